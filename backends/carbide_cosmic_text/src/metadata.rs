@@ -1,9 +1,10 @@
 use carbide_core::draw::{Position, Scalar};
-use carbide_core::text::TextStyle;
+use carbide_core::text::{TextSpan, TextStyle};
 
 pub struct Metadata {
     pub scale_factor: Scalar,
     pub position: Position,
     pub text: String,
     pub style: TextStyle,
+    pub spans: Vec<TextSpan>,
 }
\ No newline at end of file
@@ -1,8 +1,12 @@
+use std::hash::{Hash, Hasher};
+
 use cosmic_text::Buffer;
-use carbide_core::color::Color;
+use fxhash::FxHasher;
+use carbide_core::color::{Color, ColorExt};
 use carbide_core::draw::{Dimension, Scalar};
-use carbide_core::text::{FontSize, FontStyle, FontWeight, TextDecoration, TextStyle};
+use carbide_core::text::{FontSize, FontStyle, FontWeight, TextDecoration, TextSpan, TextStyle};
 use carbide_core::text::text_wrap::Wrap;
+use carbide_core::widget::LayoutDirection;
 
 pub struct TextEntry {
     pub buffer: Buffer,
@@ -14,6 +18,8 @@ pub struct TextEntry {
 pub struct TextKey {
     pub text: String,
     pub style: CachableTextStyle,
+    /// A hash of the spans of the text, since the spans contain floats that can not be hashed directly.
+    pub spans: u64,
     pub width: u32,
     pub scale_factor: u64,
 }
@@ -34,4 +40,25 @@ pub struct CachableTextStyle {
     /// The primary color for the text
     pub color: Option<(u32, u32, u32, u32)>,
     pub wrap: Wrap,
-}
\ No newline at end of file
+    pub direction: LayoutDirection,
+}
+
+pub fn spans_hash(spans: &[TextSpan]) -> u64 {
+    let mut hasher = FxHasher::default();
+
+    for span in spans {
+        let attributes = &span.attributes;
+
+        span.range.hash(&mut hasher);
+        attributes.family.hash(&mut hasher);
+        attributes.font_size.hash(&mut hasher);
+        attributes.font_style.hash(&mut hasher);
+        attributes.font_weight.hash(&mut hasher);
+        attributes.text_decoration.hash(&mut hasher);
+        attributes.color.map(|color| color.to_fsa().map(f32::to_bits)).hash(&mut hasher);
+        attributes.baseline_offset.to_bits().hash(&mut hasher);
+        attributes.link.hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
@@ -1,4 +1,4 @@
-use cosmic_text::{Attrs, Buffer, Family, FontSystem, LayoutRun, Metrics, Shaping, Style, SwashImage, Weight};
+use cosmic_text::{Align, Attrs, BorrowedWithFontSystem, Buffer, Family, FontSystem, LayoutRun, Metrics, Shaping, Style, SwashImage, Weight};
 use fxhash::FxHashMap;
 use std::path::PathBuf;
 use swash::scale::image::Content;
@@ -7,7 +7,7 @@ use swash::zeno::{Format, Vector};
 
 use crate::atlas::texture_atlas::{AtlasId, TextureAtlas};
 use crate::metadata::Metadata;
-use crate::text_cache::{CachableTextStyle, TextEntry, TextKey};
+use crate::text_cache::{spans_hash, CachableTextStyle, TextEntry, TextKey};
 use carbide_cache::Cache;
 use carbide_core::color::ColorExt;
 use carbide_core::draw::{Dimension, Position, Rect, Scalar};
@@ -18,7 +18,8 @@ use carbide_core::scene::SceneManager;
 use carbide_core::text::glyph::{Glyph, GlyphRenderMode};
 use carbide_core::text::text_wrap::Wrap;
 use carbide_core::text::{FontStyle, TextId};
-use carbide_core::text::{TextContext, TextStyle};
+use carbide_core::text::{AttributedText, TextContext, TextSpan, TextStyle};
+use carbide_core::widget::LayoutDirection;
 use unicode_segmentation::UnicodeSegmentation;
 
 pub struct CosmicTextContext {
@@ -133,10 +134,8 @@ impl CosmicTextContext {
         let dimension = Dimension::new(width as f64, height as f64);
         dimension
     }
-}
 
-impl TextContext for CosmicTextContext {
-    fn calculate_size_new(&mut self, text: &str, style: &TextStyle, requested_size: Option<Dimension>, env: &mut Environment) -> Dimension {
+    fn calculate_size_spans(&mut self, text: &str, spans: &[TextSpan], style: &TextStyle, requested_size: Option<Dimension>, env: &mut Environment) -> Dimension {
         let scale_factor = env.get_mut::<SceneManager>()
             .map(|a| a.scale_factor())
             .unwrap_or(1.0);
@@ -157,7 +156,9 @@ impl TextContext for CosmicTextContext {
                     (fsa[0].to_bits(), fsa[1].to_bits(), fsa[2].to_bits(), fsa[3].to_bits())
                 }),
                 wrap: style.wrap.clone(),
+                direction: style.direction,
             },
+            spans: spans_hash(spans),
             width: width.to_bits(),
             scale_factor: scale_factor.to_bits(),
         };
@@ -170,12 +171,7 @@ impl TextContext for CosmicTextContext {
             {
                 let mut buffer = buffer.borrow_with(&mut self.font_system);
 
-                let attributes = Attrs::new()
-                    .family(Family::Name(&style.family))
-                    .style(convert_style(style))
-                    .weight(convert_weight(style));
-
-                buffer.set_text(text, &attributes, Shaping::Advanced, None);
+                set_buffer_text(&mut buffer, text, spans, style);
                 buffer.set_wrap(convert_wrap(style));
                 buffer.set_size(Some(width), None);
             }
@@ -200,7 +196,7 @@ impl TextContext for CosmicTextContext {
         }
     }
 
-    fn render_new(&mut self, text: &str, style: &TextStyle, position: Position, requested_size: Option<Dimension>, env: &mut Environment, f: &mut dyn FnMut(&Glyph)) {
+    fn render_spans(&mut self, text: &str, spans: &[TextSpan], style: &TextStyle, position: Position, requested_size: Option<Dimension>, env: &mut Environment, f: &mut dyn FnMut(&Glyph)) {
         let scale_factor = env.get_mut::<SceneManager>()
             .map(|a| a.scale_factor())
             .unwrap_or(1.0);
@@ -223,7 +219,9 @@ impl TextContext for CosmicTextContext {
                     (fsa[0].to_bits(), fsa[1].to_bits(), fsa[2].to_bits(), fsa[3].to_bits())
                 }),
                 wrap: style.wrap.clone(),
+                direction: style.direction,
             },
+            spans: spans_hash(spans),
             width: width.to_bits(),
             scale_factor: scale_factor.to_bits(),
         };
@@ -242,12 +240,7 @@ impl TextContext for CosmicTextContext {
             {
                 let mut buffer = buffer.borrow_with(&mut self.font_system);
 
-                let attributes = Attrs::new()
-                    .family(Family::Name(&style.family))
-                    .style(convert_style(style))
-                    .weight(convert_weight(style));
-
-                buffer.set_text(text, &attributes, Shaping::Advanced, None);
+                set_buffer_text(&mut buffer, text, spans, style);
                 buffer.set_wrap(convert_wrap(style));
                 buffer.set_size(Some(width), None);
             }
@@ -284,10 +277,12 @@ impl TextContext for CosmicTextContext {
                 let book = self.atlas.book(&AtlasId::Glyph(physical_glyph.cache_key));
 
                 if let Some(book) = book {
+                    let span = span_of(glyph, spans);
+
                     let bb = Rect::new(
                         Position::new(physical_glyph.x as f64 + book.left as f64, run.line_y as f64 * scale_factor + physical_glyph.y as f64 - book.top as f64),
                         Dimension::new(book.width as f64, book.height as f64)
-                    ) / scale_factor + position;
+                    ) / scale_factor + position + Position::new(0.0, -baseline_offset(span));
 
                     /*ctx.style(DrawStyle::Color(LIGHT_RED));
                     ctx.rect(bb);
@@ -296,29 +291,57 @@ impl TextContext for CosmicTextContext {
                     f(&Glyph {
                         bounding_box: bb,
                         texture_coords: book.tex_coords,
-                        mode: if book.has_color { GlyphRenderMode::Colored } else { GlyphRenderMode::Plain }
+                        mode: if book.has_color { GlyphRenderMode::Colored } else { GlyphRenderMode::Plain },
+                        color: span.and_then(|span| span.attributes.color),
                     });
                 }
             }
         }
     }
 
+}
+
+impl TextContext for CosmicTextContext {
+    fn calculate_size_new(&mut self, text: &str, style: &TextStyle, requested_size: Option<Dimension>, env: &mut Environment) -> Dimension {
+        self.calculate_size_spans(text, &[], style, requested_size, env)
+    }
+
+    fn render_new(&mut self, text: &str, style: &TextStyle, position: Position, requested_size: Option<Dimension>, env: &mut Environment, f: &mut dyn FnMut(&Glyph)) {
+        self.render_spans(text, &[], style, position, requested_size, env, f)
+    }
+
+    fn calculate_size_attributed(&mut self, text: &AttributedText, style: &TextStyle, requested_size: Option<Dimension>, env: &mut Environment) -> Dimension {
+        self.calculate_size_spans(&text.text, &text.spans, style, requested_size, env)
+    }
+
+    fn render_attributed(&mut self, text: &AttributedText, style: &TextStyle, position: Position, requested_size: Option<Dimension>, env: &mut Environment, f: &mut dyn FnMut(&Glyph)) {
+        self.render_spans(&text.text, &text.spans, style, position, requested_size, env, f)
+    }
+
     fn calculate_size(&mut self, id: TextId, requested_size: Dimension, env: &mut Environment) -> Dimension {
-        let (buffer, _) = self.map.get_mut(&id).unwrap_or_else(|| panic!("Expected the text context to contain an entry with id: {:?}", id));
+        let (buffer, metadata) = self.map.get_mut(&id).unwrap_or_else(|| panic!("Expected the text context to contain an entry with id: {:?}", id));
 
         buffer.set_size(&mut self.font_system, Some(requested_size.width as f32), None);
 
         let mut width: f32 = 0.0;
         let mut height: f32 = 0.0;
 
+        for run in buffer.layout_runs() {
+            width = width.max(run.line_w);
+            height = height.max(run.line_top + buffer.metrics().line_height);
+        }
+
+        // Right aligned lines are aligned within the width of the text, rather than within
+        // the proposed width, which might be much wider or infinite.
+        if metadata.style.direction.is_right_to_left() {
+            buffer.set_size(&mut self.font_system, Some(width), None);
+        }
+
         let scale_factor = env.get_mut::<SceneManager>()
             .map(|a| a.scale_factor())
             .unwrap_or(1.0);
 
         for run in buffer.layout_runs() {
-            width = width.max(run.line_w);
-            height = height.max(run.line_top + buffer.metrics().line_height);
-
             for glyph in run.glyphs.iter() {
                 let physical_glyph = glyph.physical((0., 0.), scale_factor as f32);
 
@@ -360,20 +383,20 @@ impl TextContext for CosmicTextContext {
     }
 
     fn update(&mut self, id: TextId, text: &str, style: &TextStyle) {
+        self.update_attributed(id, text, &[], style)
+    }
+
+    fn update_attributed(&mut self, id: TextId, text: &str, spans: &[TextSpan], style: &TextStyle) {
         if let Some((buffer, metadata)) = self.map.get_mut(&id) {
 
-            if &metadata.text != text || &metadata.style != style {
+            if &metadata.text != text || &metadata.style != style || &metadata.spans[..] != spans {
                 metadata.style = style.clone();
                 metadata.text = text.to_string();
+                metadata.spans = spans.to_vec();
 
                 let mut buffer = buffer.borrow_with(&mut self.font_system);
 
-                let attributes = Attrs::new()
-                    .family(Family::Name(&style.family))
-                    .style(convert_style(style))
-                    .weight(convert_weight(style));
-
-                buffer.set_text(text, &attributes, Shaping::Advanced, None);
+                set_buffer_text(&mut buffer, text, spans, style);
                 buffer.set_wrap(convert_wrap(style));
                 buffer.set_metrics(Metrics::new(style.font_size as f32, style.font_size as f32 * style.line_height as f32));
             }
@@ -383,12 +406,7 @@ impl TextContext for CosmicTextContext {
             {
                 let mut buffer = buffer.borrow_with(&mut self.font_system);
 
-                let attributes = Attrs::new()
-                    .family(Family::Name(&style.family))
-                    .style(convert_style(style))
-                    .weight(convert_weight(style));
-
-                buffer.set_text(text, &attributes, Shaping::Advanced, None);
+                set_buffer_text(&mut buffer, text, spans, style);
                 buffer.set_wrap(convert_wrap(style));
             }
 
@@ -398,6 +416,7 @@ impl TextContext for CosmicTextContext {
                 position: Default::default(),
                 text: text.to_string(),
                 style: style.clone(),
+                spans: spans.to_vec(),
             }));
         }
     }
@@ -426,10 +445,12 @@ impl TextContext for CosmicTextContext {
                 let book = self.atlas.book(&AtlasId::Glyph(physical_glyph.cache_key));
 
                 if let Some(book) = book {
+                    let span = span_of(glyph, &metadata.spans);
+
                     let bb = Rect::new(
                         Position::new(physical_glyph.x as f64 + book.left as f64, run.line_y as f64 * metadata.scale_factor + physical_glyph.y as f64 - book.top as f64),
                         Dimension::new(book.width as f64, book.height as f64)
-                    ) / metadata.scale_factor + metadata.position;
+                    ) / metadata.scale_factor + metadata.position + Position::new(0.0, -baseline_offset(span));
 
                     /*ctx.style(DrawStyle::Color(LIGHT_RED));
                     ctx.rect(bb);
@@ -438,7 +459,8 @@ impl TextContext for CosmicTextContext {
                     f(&Glyph {
                         bounding_box: bb,
                         texture_coords: book.tex_coords,
-                        mode: if book.has_color { GlyphRenderMode::Colored } else { GlyphRenderMode::Plain }
+                        mode: if book.has_color { GlyphRenderMode::Colored } else { GlyphRenderMode::Plain },
+                        color: span.and_then(|span| span.attributes.color),
                     });
                 }
             }
@@ -527,6 +549,74 @@ impl TextContext for CosmicTextContext {
         unreachable!()
     }
 
+    fn hit_offset(&self, id: TextId, position: Position) -> usize {
+        let (buffer, meta) = self.map.get(&id).unwrap();
+
+        match buffer.hit(position.x as f32, position.y as f32) {
+            Some(cursor) => line_start(&meta.text, cursor.line) + cursor.index,
+            None => 0,
+        }
+    }
+
+    fn caret_rect(&self, id: TextId, offset: usize) -> Rect {
+        let (buffer, meta) = self.map.get(&id).unwrap();
+
+        let offset = offset.min(meta.text.len());
+        let line = meta.text.as_bytes()[..offset].iter().filter(|byte| **byte == b'\n').count();
+        let index = offset - line_start(&meta.text, line);
+
+        let line_height = buffer.metrics().line_height as Scalar;
+        let mut caret = Position::new(0.0, line as Scalar * line_height);
+
+        // A line can be wrapped into multiple runs, so we look for the run containing the index.
+        // If no run contains it, the index is at the end of the line.
+        for run in buffer.layout_runs().filter(|run| run.line_i == line) {
+            let end = run.glyphs.last().map(|glyph| glyph.x + glyph.w).unwrap_or(0.0);
+            caret = Position::new(end as Scalar, run.line_top as Scalar);
+
+            if let Some(glyph) = run.glyphs.iter().find(|glyph| glyph.start <= index && index < glyph.end) {
+                let (_, internal_offset) = Self::partial_glyph_offset(index, &run);
+
+                let x = if glyph.level.is_rtl() {
+                    glyph.x + glyph.w - internal_offset as f32
+                } else {
+                    glyph.x + internal_offset as f32
+                };
+
+                caret = Position::new(x as Scalar, run.line_top as Scalar);
+                break;
+            }
+        }
+
+        Rect::new(caret, Dimension::new(0.0, line_height))
+    }
+
+    fn span_at(&self, id: TextId, position: Position) -> Option<usize> {
+        let (buffer, metadata) = self.map.get(&id)?;
+
+        let line_height = buffer.metrics().line_height;
+        let (x, y) = (position.x as f32, position.y as f32);
+
+        for run in buffer.layout_runs() {
+            if y < run.line_top || y >= run.line_top + line_height {
+                continue;
+            }
+
+            return run.glyphs.iter()
+                .find(|glyph| x >= glyph.x && x < glyph.x + glyph.w)
+                .and_then(|glyph| glyph.metadata.checked_sub(1))
+                .filter(|index| *index < metadata.spans.len());
+        }
+
+        None
+    }
+
+    fn first_baseline(&self, id: TextId) -> Option<Scalar> {
+        let (buffer, _) = self.map.get(&id)?;
+
+        buffer.layout_runs().next().map(|run| run.line_y as Scalar)
+    }
+
     fn remove(&mut self, id: TextId) {
         self.map.remove(&id);
     }
@@ -583,10 +673,104 @@ fn convert_weight(weight: &TextStyle) -> Weight {
     Weight(weight.font_weight.weight())
 }
 
+/// Map the generic family names to the generic cosmic text families, and all other names to
+/// the font family with that name.
+fn convert_family(family: &str) -> Family<'_> {
+    match family {
+        "serif" => Family::Serif,
+        "sans-serif" => Family::SansSerif,
+        "cursive" => Family::Cursive,
+        "fantasy" => Family::Fantasy,
+        "monospace" => Family::Monospace,
+        _ => Family::Name(family),
+    }
+}
+
 fn convert_wrap(wrap: &TextStyle) -> cosmic_text::Wrap {
     match wrap.wrap {
         Wrap::Character => cosmic_text::Wrap::Glyph,
         Wrap::Whitespace => cosmic_text::Wrap::Word,
         Wrap::None => cosmic_text::Wrap::None,
     }
-}
\ No newline at end of file
+}
+
+fn convert_align(style: &TextStyle) -> Option<Align> {
+    match style.direction {
+        LayoutDirection::LeftToRight => None,
+        LayoutDirection::RightToLeft => Some(Align::Right),
+    }
+}
+
+/// Set the text of the buffer, with the attributes of the spans applied on top of the style.
+/// The metadata of each glyph is the index of its span plus one, or zero if it is not part
+/// of a span.
+pub fn set_buffer_text(buffer: &mut BorrowedWithFontSystem<Buffer>, text: &str, spans: &[TextSpan], style: &TextStyle) {
+    let attributes = Attrs::new()
+        .family(convert_family(&style.family))
+        .style(convert_style(style))
+        .weight(convert_weight(style));
+
+    if spans.is_empty() {
+        buffer.set_text(text, &attributes, Shaping::Advanced, convert_align(style));
+        return;
+    }
+
+    let mut rich_text = vec![];
+    let mut end = 0;
+
+    for (index, span) in spans.iter().enumerate() {
+        // Spans are clamped to the text, and overlapping parts of later spans are ignored.
+        let span_start = span.range.start.clamp(end, text.len());
+        let span_end = span.range.end.clamp(span_start, text.len());
+
+        if !text.is_char_boundary(span_start) || !text.is_char_boundary(span_end) {
+            continue;
+        }
+
+        if span_start > end {
+            rich_text.push((&text[end..span_start], attributes.clone()));
+        }
+
+        let span_style = span.attributes.resolve(style);
+
+        let span_attributes = Attrs::new()
+            .family(convert_family(span.attributes.family.as_deref().unwrap_or(&style.family)))
+            .style(convert_style(&span_style))
+            .weight(convert_weight(&span_style))
+            .metrics(Metrics::new(span_style.font_size as f32, span_style.font_size as f32 * span_style.line_height as f32))
+            .metadata(index + 1);
+
+        rich_text.push((&text[span_start..span_end], span_attributes));
+
+        end = span_end;
+    }
+
+    if end < text.len() {
+        rich_text.push((&text[end..], attributes.clone()));
+    }
+
+    buffer.set_rich_text(rich_text, &attributes, Shaping::Advanced, convert_align(style));
+}
+
+/// The byte offset of the start of the line in the text. Lines are separated by line feeds, like
+/// the lines of a buffer.
+fn line_start(text: &str, line: usize) -> usize {
+    if line == 0 {
+        return 0;
+    }
+
+    text.match_indices('\n')
+        .nth(line - 1)
+        .map(|(index, _)| index + 1)
+        .unwrap_or(text.len())
+}
+
+/// The span the glyph is part of, given the spans used when setting the text of its buffer.
+pub fn span_of<'a>(glyph: &cosmic_text::LayoutGlyph, spans: &'a [TextSpan]) -> Option<&'a TextSpan> {
+    glyph.metadata.checked_sub(1).and_then(|index| spans.get(index))
+}
+
+/// The offset of the baseline of the span, where positive values raise the text.
+pub fn baseline_offset(span: Option<&TextSpan>) -> Scalar {
+    span.map(|span| span.attributes.baseline_offset).unwrap_or(0.0)
+}
@@ -15,7 +15,7 @@ pub use localized_number::*;
 pub use localizable::Localizable;
 pub use args::Arg;
 pub use args::LocalizedArg;
-pub use locale_ext::LocaleExt;
+pub use locale_ext::{LocaleExt, layout_direction};
 pub use icu::locid::locale;
 use carbide_core::locate_folder;
 
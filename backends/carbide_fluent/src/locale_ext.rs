@@ -1,7 +1,7 @@
 use icu::locid::{Locale, locale};
 use carbide_core::environment::{Environment, EnvironmentKey};
 use carbide_core::state::{EnvMap1, IntoReadState, Map1, ReadState};
-use carbide_core::widget::{EnvUpdatingNew2, WidgetExt};
+use carbide_core::widget::{EnvUpdatingNew2, LayoutDirection, WidgetExt};
 
 type WithLocale<C, K, V> = EnvUpdatingNew2<C, K, V>;
 type LocaleState = EnvMap1<fn(&mut Environment, &i32) -> Locale, i32, Locale, i32>;
@@ -17,6 +17,13 @@ pub trait LocaleExt: WidgetExt {
     fn locale<L: IntoReadState<Locale>>(self, locale: L) -> WithLocale<Self, impl EnvironmentKey<Value=Locale>, impl ReadState<T=Locale>> {
         EnvUpdatingNew2::<Self, LocaleKey, L::Output>::new(locale.into_read_state(), self)
     }
+
+    /// Lay out the widget in the direction of the locale in the environment, such that it is
+    /// laid out right to left for locales like Arabic and Hebrew. The locale is usually set on
+    /// a parent using [LocaleExt::locale].
+    fn layout_direction_from_locale(self) -> EnvUpdatingNew2<Self, impl EnvironmentKey<Value=LayoutDirection>, impl ReadState<T=LayoutDirection>> {
+        self.layout_direction(Map1::read_map(locale_state(), layout_direction))
+    }
 }
 
 impl<T> LocaleExt for T where T: WidgetExt {}
@@ -27,4 +34,25 @@ pub fn locale_state() -> LocaleState {
         // Look up enabled in the environment, or default to true of nothing is specified
         env.get::<LocaleKey>().cloned().unwrap_or_else(|| locale!("en"))
     })
-}
\ No newline at end of file
+}
+
+/// The languages that are written right to left, when no script is specified in the locale.
+const RIGHT_TO_LEFT_LANGUAGES: [&str; 10] = ["ar", "ckb", "dv", "fa", "he", "ps", "sd", "ug", "ur", "yi"];
+
+/// The scripts that are written right to left.
+const RIGHT_TO_LEFT_SCRIPTS: [&str; 7] = ["Adlm", "Arab", "Hebr", "Nkoo", "Rohg", "Syrc", "Thaa"];
+
+/// Returns the direction text in the locale is written in. The script of the locale is used if
+/// specified, such that for example "az-Arab" is right to left while "az" is left to right.
+pub fn layout_direction(locale: &Locale) -> LayoutDirection {
+    let right_to_left = match locale.id.script {
+        Some(script) => RIGHT_TO_LEFT_SCRIPTS.contains(&script.as_str()),
+        None => RIGHT_TO_LEFT_LANGUAGES.contains(&locale.id.language.as_str()),
+    };
+
+    if right_to_left {
+        LayoutDirection::RightToLeft
+    } else {
+        LayoutDirection::LeftToRight
+    }
+}
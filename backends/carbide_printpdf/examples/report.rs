@@ -0,0 +1,43 @@
+use carbide_core::color::{BLUE, GREEN, RED};
+use carbide_core::widget::*;
+use carbide_printpdf::{PageLayout, PageSize, Pdf};
+
+fn main() {
+    let mut pdf = Pdf::new("report")
+        .page_size(PageSize::A4Portrait);
+
+    pdf.add_page(VStack::new((
+        Text::new("Quarterly report")
+            .font_size(32u32),
+        Text::new("This document is rendered from the same widgets as shown on screen. Shapes are written as paths, text is written as text and images are embedded.")
+            .font_size(13u32),
+        HStack::new((
+            Rectangle::new().fill(RED).frame(100.0, 100.0),
+            Circle::new().fill(GREEN).frame(100.0, 100.0),
+            RoundedRectangle::new(CornerRadii::all(25.0))
+                .stroke(BLUE)
+                .stroke_style(4.0)
+                .frame(100.0, 100.0),
+            Image::new("images/landscape.png")
+                .resizeable()
+                .scaled_to_fill()
+                .frame(100.0, 100.0)
+                .clip(),
+        )),
+    )));
+
+    let rows = (1..=120)
+        .map(|row| {
+            HStack::new((
+                Text::new(format!("Row {}", row)),
+                Spacer::new(),
+                Text::new(format!("{:.2}", row as f64 * 1.25)),
+            )).boxed()
+        })
+        .collect::<Vec<_>>();
+
+    pdf.add_page(PageLayout::new(rows).spacing(4.0));
+
+    pdf.save("target/report.pdf").unwrap();
+    println!("Output pdf at: target/report.pdf");
+}
@@ -1,51 +1,88 @@
-// use std::cell::RefCell;
-// use std::collections::HashMap;
-// use carbide_core::draw::{ImageId, ImageMetrics};
-// use carbide_core::draw::{ImageContext, Texture, TextureFormat};
-// use printpdf::Image as PdfImage;
-// use carbide_core::environment::Environment;
-// use carbide_core::image::{DynamicImage, RgbaImage};
-//
-// thread_local! {
-//     pub(crate) static IMAGES: RefCell<HashMap<ImageId, (PdfImage, u32, u32)>> = RefCell::new(HashMap::new());
-// }
-//
-// pub struct PDFImageContext;
-//
-// impl ImageContext for PDFImageContext {
-//     fn exist(&self, id: &ImageId, env: &mut Environment) -> bool {
-//         IMAGES.with(|images| {
-//             images.borrow().contains_key(id)
-//         })
-//     }
-//
-//     fn metrics(&self, id: &ImageId, env: &mut Environment) -> ImageMetrics {
-//         IMAGES.with(|images| {
-//             let borrow = images.borrow();
-//             let (_, width, height) = borrow.get(id)?;
-//
-//             Some((*width, *height))
-//         })
-//     }
-//
-//     fn update_texture(&mut self, id: &ImageId, texture: Texture, env: &mut Environment) -> bool {
-//         IMAGES.with(|images| {
-//             let width = texture.width;
-//             let height = texture.height;
-//
-//             let dynamic_image = match texture.format {
-//                 TextureFormat::RGBA8 => RgbaImage::from_raw(texture.width, texture.height, texture.data.to_vec()).unwrap(),
-//                 TextureFormat::BGRA8 => todo!(),
-//             };
-//
-//             // Image with alpha support waiting for merge and release of: https://github.com/fschutt/printpdf/pull/158
-//             //let pdf_image = PdfImage::from_dynamic_image(&DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(dynamic_image).to_rgb8()));
-//
-//             //images.borrow_mut().insert(id, (pdf_image, width, height));
-//
-//             todo!();
-//             true
-//         })
-//
-//     }
-// }
\ No newline at end of file
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use carbide_core::draw::{Dimension, ImageContext, ImageFormat, ImageId, ImageMetrics, Texture, TextureFormat};
+use carbide_core::environment::Environment;
+use carbide_core::image::{Rgba, RgbaImage};
+use carbide_core::render::{RenderInstruction, RenderInstructionCache};
+
+thread_local! {
+    pub(crate) static IMAGES: RefCell<HashMap<ImageId, RgbaImage>> = RefCell::new(HashMap::new());
+}
+
+/// Keeps the images used when rendering a PDF. Raster images are stored with straight alpha,
+/// and embedded into the document when drawn. Vector images are kept as render instructions,
+/// such that they stay vector graphics in the document.
+pub struct PdfImageContext;
+
+impl ImageContext for PdfImageContext {
+    fn exist(&self, id: &ImageId, env: &mut Environment) -> bool {
+        match id.format() {
+            ImageFormat::Unknown => false,
+            ImageFormat::Svg => {
+                env.get::<RenderInstructionCache>()
+                    .map(|cache| cache.contains_key(id))
+                    .unwrap_or(false)
+            }
+            _ => IMAGES.with(|images| images.borrow().contains_key(id))
+        }
+    }
+
+    fn metrics(&self, id: &ImageId, env: &mut Environment) -> ImageMetrics {
+        match id.format() {
+            ImageFormat::Unknown => ImageMetrics::Unknown,
+            ImageFormat::Svg => {
+                env.get::<RenderInstructionCache>()
+                    .and_then(|cache| cache.get(id))
+                    .map(|vector| ImageMetrics::Vector { dimension: vector.0 })
+                    .unwrap_or(ImageMetrics::Unknown)
+            }
+            _ => {
+                IMAGES.with(|images| {
+                    images.borrow()
+                        .get(id)
+                        .map(|image| ImageMetrics::Raster { width: image.width(), height: image.height() })
+                        .unwrap_or(ImageMetrics::Unknown)
+                })
+            }
+        }
+    }
+
+    fn update_texture(&mut self, id: &ImageId, texture: Texture, _env: &mut Environment) -> bool {
+        if texture.width == 0 || texture.height == 0 {
+            return false;
+        }
+
+        let image = RgbaImage::from_fn(texture.width, texture.height, |x, y| {
+            let offset = y as usize * texture.bytes_per_row as usize + x as usize * 4;
+
+            let [c0, c1, c2, a] = [
+                texture.data[offset],
+                texture.data[offset + 1],
+                texture.data[offset + 2],
+                texture.data[offset + 3],
+            ];
+
+            match texture.format {
+                TextureFormat::RGBA8 => Rgba([c0, c1, c2, a]),
+                TextureFormat::BGRA8 => Rgba([c2, c1, c0, a]),
+            }
+        });
+
+        IMAGES.with(|images| {
+            images.borrow_mut().insert(id.clone(), image);
+        });
+
+        true
+    }
+
+    fn update_vector(&mut self, id: &ImageId, description: Vec<RenderInstruction>, size: Dimension, env: &mut Environment) -> bool {
+        let Some(cache) = env.get_mut::<RenderInstructionCache>() else {
+            return false;
+        };
+
+        cache.insert(id.clone(), Rc::new((size, description)));
+
+        true
+    }
+}
@@ -1,6 +1,18 @@
+//! Render widgets into PDF documents. Shapes are written as vector paths, text is written as
+//! text with the fonts embedded, and images are embedded into the document. This makes it
+//! possible to generate printable documents from the same widgets shown on screen.
+
+extern crate carbide_core as carbide;
+
 mod pdf;
 mod page_layout;
 mod render_context;
 mod image_context;
+mod pdf_gradient;
+mod pdf_layer;
 
-pub use pdf::Pdf;
\ No newline at end of file
+pub use pdf::Pdf;
+pub use page_layout::{PageLayout, PageSize, PdfPage};
+pub use render_context::PdfRenderContext;
+pub use image_context::PdfImageContext;
+pub use pdf_layer::PdfLayer;
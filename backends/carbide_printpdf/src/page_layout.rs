@@ -1,138 +1,185 @@
-// use carbide_core::draw::{Dimension, Position};
-// use carbide_core::environment::Environment;
-// use carbide_core::flags::Flags;
-// use carbide_core::layout::Layout;
-// use carbide_core::widget::{CommonWidget, CrossAxisAlignment, EdgeInsets, Spacer, Widget, WidgetExt, WidgetId, WidgetIter, WidgetIterMut};
-//
-// type NewPage = Spacer;
-//
-// struct Page {
-//     dimension: Dimension,
-//     children: Vec<Box<dyn Widget>>,
-//     insets: EdgeInsets,
-// }
-//
-// pub enum PageSize {
-//     A4Portrait
-// }
-//
-// impl From<PageSize> for Dimension {
-//     fn from(size: PageSize) -> Self {
-//         match size {
-//             PageSize::A4Portrait => Dimension::new(210.0, 297.0)
-//         }
-//     }
-// }
-//
-// #[derive(Debug, Clone, Widget)]
-// #[carbide_exclude(Layout)]
-// pub struct PageLayout {
-//     id: WidgetId,
-//     pages: Vec<Page>,
-//     //cross_axis_alignment: CrossAxisAlignment,
-// }
-//
-// impl PageLayout {
-//     pub fn new(size: impl Into<Dimension>, children: Vec<Box<dyn Widget>>) -> Box<Self> {
-//         Box::new(PageLayout {
-//             id: WidgetId::new(),
-//             pages: vec![
-//                 Page {
-//                     dimension: size.into(),
-//                     children,
-//                     insets: EdgeInsets::all(10.0)
-//                 }
-//             ]
-//         })
-//     }
-// }
-//
-// impl Layout for PageLayout {
-//     fn calculate_size(&mut self, requested_size: Dimension, env: &mut Environment) -> Dimension {
-//         let spacing = self.spacing;
-//         calculate_size_vstack(self, spacing, requested_size, env);
-//         self.dimension
-//     }
-//
-//     fn position_children(&mut self) {
-//         let spacing = self.spacing;
-//         let cross_axis_alignment = self.cross_axis_alignment;
-//         position_children_vstack(self, spacing, cross_axis_alignment)
-//     }
-// }
-//
-// impl CommonWidget for PageLayout {
-//     fn id(&self) -> WidgetId {
-//         self.id
-//     }
-//
-//     fn children(&self) -> WidgetIter {
-//         let contains_proxy_or_ignored = self.children.iter().fold(false, |a, b| {
-//             a || (b.flag() == Flags::PROXY || b.flag() == Flags::IGNORE)
-//         });
-//         if !contains_proxy_or_ignored {
-//             WidgetIter::Vec(self.children.iter())
-//         } else {
-//             self.children
-//                 .iter()
-//                 .filter(|x| x.flag() != Flags::IGNORE)
-//                 .rfold(WidgetIter::Empty, |acc, x| {
-//                     if x.flag() == Flags::PROXY {
-//                         WidgetIter::Multi(Box::new(x.children()), Box::new(acc))
-//                     } else {
-//                         WidgetIter::Single(x, Box::new(acc))
-//                     }
-//                 })
-//         }
-//     }
-//
-//     fn children_mut(&mut self) -> WidgetIterMut {
-//         let contains_proxy_or_ignored = self.children.iter().fold(false, |a, b| {
-//             a || (b.flag() == Flags::PROXY || b.flag() == Flags::IGNORE)
-//         });
-//         if !contains_proxy_or_ignored {
-//             WidgetIterMut::Vec(self.children.iter_mut())
-//         } else {
-//             self.children
-//                 .iter_mut()
-//                 .filter(|x| x.flag() != Flags::IGNORE)
-//                 .rfold(WidgetIterMut::Empty, |acc, x| {
-//                     if x.flag() == Flags::PROXY {
-//                         WidgetIterMut::Multi(Box::new(x.children_mut()), Box::new(acc))
-//                     } else {
-//                         WidgetIterMut::Single(x, Box::new(acc))
-//                     }
-//                 })
-//         }
-//     }
-//
-//     fn children_direct(&mut self) -> WidgetIterMut {
-//         WidgetIterMut::Vec(self.children.iter_mut())
-//     }
-//
-//     fn children_direct_rev(&mut self) -> WidgetIterMut {
-//         WidgetIterMut::VecRev(self.children.iter_mut().rev())
-//     }
-//
-//     fn position(&self) -> Position {
-//         self.position
-//     }
-//
-//     fn set_position(&mut self, position: Position) {
-//         self.position = position;
-//     }
-//
-//     fn flexibility(&self) -> u32 {
-//         1
-//     }
-//
-//     fn dimension(&self) -> Dimension {
-//         self.dimension
-//     }
-//
-//     fn set_dimension(&mut self, dimension: Dimension) {
-//         self.dimension = dimension
-//     }
-// }
-//
-// impl WidgetExt for PageLayout {}
+use carbide_core::CommonWidgetImpl;
+use carbide_core::draw::{Dimension, Position, Rect, Scalar};
+use carbide_core::environment::EnvironmentKey;
+use carbide_core::layout::{Layout, LayoutContext};
+use carbide_core::render::{Render, RenderContext};
+use carbide_core::widget::{AnySequence, AnyWidget, CommonWidget, CrossAxisAlignment, Sequence, Widget, WidgetId};
+
+/// The size of a page. The dimensions are given in millimeters.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PageSize {
+    A3Portrait,
+    A3Landscape,
+    A4Portrait,
+    A4Landscape,
+    A5Portrait,
+    A5Landscape,
+    LetterPortrait,
+    LetterLandscape,
+    Custom(Dimension),
+}
+
+impl From<PageSize> for Dimension {
+    fn from(size: PageSize) -> Self {
+        match size {
+            PageSize::A3Portrait => Dimension::new(297.0, 420.0),
+            PageSize::A3Landscape => Dimension::new(420.0, 297.0),
+            PageSize::A4Portrait => Dimension::new(210.0, 297.0),
+            PageSize::A4Landscape => Dimension::new(297.0, 210.0),
+            PageSize::A5Portrait => Dimension::new(148.0, 210.0),
+            PageSize::A5Landscape => Dimension::new(210.0, 148.0),
+            PageSize::LetterPortrait => Dimension::new(215.9, 279.4),
+            PageSize::LetterLandscape => Dimension::new(279.4, 215.9),
+            PageSize::Custom(dimension) => dimension,
+        }
+    }
+}
+
+/// The page currently being rendered, and the number of pages needed by the widgets. When
+/// rendering a PDF, the count is updated during layout by the page layouts, and the index is
+/// set for each page during rendering.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct PdfPage {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl EnvironmentKey for PdfPage {
+    type Value = PdfPage;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Placement {
+    page: usize,
+    offset: Scalar,
+}
+
+/// # Page layout
+/// Lays out its children vertically, similar to a [VStack](carbide_core::widget::VStack), but
+/// splits them into pages. A child that does not fit on the remaining part of a page is moved
+/// to the top of the next page. Children taller than a page are placed on a page of their own.
+///
+/// The layout takes up the size of a single page, and only renders the children on the page
+/// being rendered. When not rendered as part of a PDF, the first page is shown.
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Layout, Render)]
+pub struct PageLayout<W> where W: Sequence {
+    #[id] id: WidgetId,
+    children: W,
+    position: Position,
+    dimension: Dimension,
+    spacing: Scalar,
+    cross_axis_alignment: CrossAxisAlignment,
+    placements: Vec<Placement>,
+}
+
+impl<W: Sequence> PageLayout<W> {
+    pub fn new(children: W) -> PageLayout<W> {
+        PageLayout {
+            id: WidgetId::new(),
+            children,
+            position: Position::new(0.0, 0.0),
+            dimension: Dimension::new(100.0, 100.0),
+            spacing: 10.0,
+            cross_axis_alignment: CrossAxisAlignment::Start,
+            placements: vec![],
+        }
+    }
+
+    pub fn cross_axis_alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.cross_axis_alignment = alignment;
+        self
+    }
+
+    pub fn spacing(mut self, spacing: f64) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// The number of pages needed to show all the children. This is known after layout.
+    pub fn page_count(&self) -> usize {
+        self.placements.last().map(|placement| placement.page + 1).unwrap_or(1)
+    }
+}
+
+impl<W: Sequence> Layout for PageLayout<W> {
+    fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        let spacing = self.spacing;
+        let page_height = requested_size.height;
+
+        let mut placements = vec![];
+        let mut page = 0;
+        let mut empty = true;
+        let mut y = 0.0;
+
+        self.children.foreach(&mut |child: &mut dyn AnyWidget| {
+            let dimension = child.process_calculate_size(requested_size, ctx);
+
+            let mut offset = if empty { 0.0 } else { y + spacing };
+
+            if !empty && offset + dimension.height > page_height {
+                page += 1;
+                offset = 0.0;
+            }
+
+            placements.push(Placement { page, offset });
+
+            empty = false;
+            y = offset + dimension.height;
+        });
+
+        self.placements = placements;
+
+        let page_count = self.page_count();
+
+        if let Some(page) = ctx.env.get_mut::<PdfPage>() {
+            page.count = page.count.max(page_count);
+        }
+
+        self.dimension = requested_size;
+        self.dimension
+    }
+
+    fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
+        let position = self.position;
+        let width = self.dimension.width;
+        let cross_axis_alignment = self.cross_axis_alignment;
+        let placements = &self.placements;
+
+        let mut index = 0;
+
+        self.children.foreach(&mut |child: &mut dyn AnyWidget| {
+            let x = match cross_axis_alignment {
+                CrossAxisAlignment::Start => position.x,
+                CrossAxisAlignment::Center => position.x + (width - child.width()) / 2.0,
+                CrossAxisAlignment::End => position.x + width - child.width(),
+            };
+
+            child.set_position(Position::new(x, position.y + placements[index].offset));
+            child.position_children(bounding_box, ctx);
+
+            index += 1;
+        });
+    }
+}
+
+impl<W: Sequence> Render for PageLayout<W> {
+    fn render(&mut self, ctx: &mut RenderContext) {
+        let current = ctx.env.get::<PdfPage>().map(|page| page.index).unwrap_or(0);
+        let placements = &self.placements;
+
+        let mut index = 0;
+
+        self.children.foreach(&mut |child: &mut dyn AnyWidget| {
+            if placements.get(index).is_some_and(|placement| placement.page == current) {
+                child.render(ctx);
+            }
+
+            index += 1;
+        });
+    }
+}
+
+impl<W: Sequence> CommonWidget for PageLayout<W> {
+    CommonWidgetImpl!(self, child: self.children, position: self.position, dimension: self.dimension, flexibility: 1);
+}
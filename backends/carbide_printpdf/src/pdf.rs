@@ -1,349 +1,218 @@
-use printpdf::{Mm, PdfDocumentReference, PdfLayerIndex, PdfPageIndex, Point};
-use carbide_core::draw::{Dimension, Position};
-use carbide_core::render::Render;
-use carbide_core::widget::AnyWidget;
-
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::rc::Rc;
+
+use printpdf::{Mm, PdfDocument, PdfDocumentReference};
+
+use carbide_core::draw::{Alignment, Dimension, ImageId, Position, Rect};
+use carbide_core::draw::theme::Theme;
+use carbide_core::environment::Environment;
+use carbide_core::layout::{Layout, LayoutContext};
+use carbide_core::lifecycle::{Initialize, InitializationContext, Update, UpdateContext};
+use carbide_core::render::{Render, RenderContext, RenderInstruction, RenderInstructionCache};
+use carbide_core::scene::SceneManager;
+use carbide_core::text::TextContext;
+use carbide_core::widget::{AnyWidget, CommonWidget, EdgeInsets};
+use carbide_cosmic_text::text_context::CosmicTextContext;
+
+use crate::image_context::PdfImageContext;
+use crate::page_layout::{PageSize, PdfPage};
+use crate::render_context::PdfRenderContext;
+
+/// The number of points in a millimeter. Widgets are laid out in points, such that a widget
+/// with a width of 72 is one inch wide on the page.
+const POINTS_PER_MM: f64 = 72.0 / 25.4;
+
+/// # Pdf
+/// A document rendered from widgets. Each widget added starts on a new page, and spans
+/// multiple pages if it contains a [PageLayout](crate::PageLayout) with more content than
+/// fits on a single page.
+///
+/// ```ignore
+/// let mut pdf = Pdf::new("report")
+///     .page_size(PageSize::A4Portrait);
+///
+/// pdf.add_page(PageLayout::new(rows));
+/// pdf.save("target/report.pdf")?;
+/// ```
 pub struct Pdf {
-    widgets: Box<dyn AnyWidget>,
     pub title: String,
-    pub document: PdfDocumentReference,
+    page_size: PageSize,
+    margin: EdgeInsets,
+    theme: Theme,
+    fonts: Vec<Vec<u8>>,
+    pages: Vec<Box<dyn AnyWidget>>,
 }
 
 impl Pdf {
-    /*pub fn new(title: impl Into<String>) -> Self {
-        let title = title.into();
-
-        /*let mut environment = Environment::new(
-            Dimension::new(210.0, 297.0),
-            0.5,
-            Box::new(NoopEventSink)
-        );
-
-        environment.set_root_alignment(Alignment::Top);
-
-        let (document, _, _) =
-            PdfDocument::new(&title, Mm(210.0), Mm(297.0), "Layer 1");
-
+    /// Create a new A4 portrait document with margins of half an inch. The document uses
+    /// the light theme by default, since it is meant for paper.
+    pub fn new(title: impl Into<String>) -> Self {
         Pdf {
-            environment,
-            widgets: Empty::new().boxed(),
-            title,
-            document
-        }*/
-    }*/
-/*
-    /// Locates the default asset folder and tries to load fonts from a subfolder called /fonts.
-    /// For each sub folder in the fonts folder will create a new family with the name of that folder
-    /// and load in any fonts within it.
-    pub fn with_asset_fonts(mut self) -> Self {
-        let assets = locate_folder::Search::KidsThenParents(3, 5)
-            .for_folder("assets")
-            .unwrap();
-
-        let fonts_path = assets.join("fonts");
-
-        let directories = fs::read_dir(fonts_path).unwrap();
-
-        for entry in directories.filter_map(|a| a.ok()) {
-            let path = entry.path();
-            if path.is_dir() {
-
-                let name = path.file_name()
-                    .map(|name| name.to_string_lossy().to_string())
-                    .unwrap();
-
-                let mut fonts = vec![];
-
-                for font_path in fs::read_dir(path).unwrap().filter_map(|a| a.ok()) {
-                    if font_path.path().extension() == Some(OsStr::new("ttf")) {
-                        fonts.push(font_path.path());
-                    }
-                }
-
-                self.add_font_family(FontFamily::new_from_paths(&name, fonts));
-            }
+            title: title.into(),
+            page_size: PageSize::A4Portrait,
+            margin: EdgeInsets::all(36.0),
+            theme: Theme::Light,
+            fonts: vec![],
+            pages: vec![],
         }
+    }
 
+    pub fn page_size(mut self, page_size: PageSize) -> Self {
+        self.page_size = page_size;
         self
     }
 
-    pub fn add_font_family(&mut self, family: FontFamily) -> String {
-        let family_name = family.name.clone();
-        self.environment.add_font_family(family);
-        family_name
+    /// The margin around the content of each page, given in points.
+    pub fn margin(mut self, margin: EdgeInsets) -> Self {
+        self.margin = margin;
+        self
     }
 
-    fn add_font<P: AsRef<Path>>(&mut self, path: P) -> FontId {
-        let assets = locate_folder::Search::KidsThenParents(3, 5)
-            .for_folder("assets")
-            .unwrap();
-        let font_path = assets.join(path.as_ref());
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
 
-        self.environment.insert_font_from_file(font_path).0
-    }*/
+    /// Add a font that can be used by the text in the document, in addition to the fonts
+    /// installed on the system.
+    pub fn add_font_from_bytes(&mut self, bytes: Vec<u8>) {
+        self.fonts.push(bytes);
+    }
 
-    pub fn set_widgets(&mut self, widgets: Box<dyn AnyWidget>) {
-        self.widgets = widgets;
+    /// Add a widget starting on a new page.
+    pub fn add_page(&mut self, widget: impl AnyWidget) {
+        self.pages.push(Box::new(widget));
     }
 
-    pub fn render(mut self) -> String {
-        let current_layer = self.document.get_page(PdfPageIndex(0)).get_layer(PdfLayerIndex(0));
-        let page_dimensions = Dimension::new(210.0, 297.0);
+    /// Replace all the pages of the document with the widget.
+    pub fn set_widgets(&mut self, widgets: Box<dyn AnyWidget>) {
+        self.pages = vec![widgets];
+    }
 
-        //self.environment.capture_time();
-        //self.environment.set_root_alignment(BasicLayouter::Top);
+    /// The dimension of the pages in points.
+    pub fn page_dimension(&self) -> Dimension {
+        Dimension::from(self.page_size) * POINTS_PER_MM
+    }
 
-        /*let primitives = Primitives::new(
-            page_dimensions/self.environment.scale_factor(),
-            &mut self.widgets,
-            &mut self.environment,
+    /// Lay out and render the widgets into a new PDF document.
+    pub fn render(mut self) -> PdfDocumentReference {
+        let page_size = Dimension::from(self.page_size);
+        let page_dimension = self.page_dimension();
+
+        let content = Rect::new(
+            Position::new(self.margin.left, self.margin.top),
+            Dimension::new(
+                (page_dimension.width - self.margin.left - self.margin.right).max(0.0),
+                (page_dimension.height - self.margin.top - self.margin.bottom).max(0.0),
+            ),
         );
 
-        for primitive in primitives {
-            let rectangle = primitive.bounding_box;
-            match primitive.kind {
-                PrimitiveKind::Geometry { color, triangles } => {
-                    current_layer.set_fill_color(Color::Rgb(Rgb::new(
-                        color.red() as f64,
-                        color.green() as f64,
-                        color.blue() as f64,
-                        None
-                    )));
-
-                    for triangle in triangles {
-                        let point1 = convert_position_to_point(triangle.points()[0], page_dimensions);
-                        let point2 = convert_position_to_point(triangle.points()[1], page_dimensions);
-                        let point3 = convert_position_to_point(triangle.points()[2], page_dimensions);
-
-                        current_layer.add_operation(Operation::new(
-                            OP_PATH_CONST_MOVE_TO,
-                            vec![point1.x.into(), point1.y.into()],
-                        ));
-
-                        current_layer.add_operation(Operation::new(
-                            OP_PATH_CONST_LINE_TO,
-                            vec![point2.x.into(), point2.y.into()],
-                        ));
-                        current_layer.add_operation(Operation::new(
-                            OP_PATH_CONST_LINE_TO,
-                            vec![point3.x.into(), point3.y.into()],
-                        ));
-                        current_layer.add_operation(Operation::new(
-                            OP_PATH_CONST_LINE_TO,
-                            vec![point1.x.into(), point1.y.into()],
-                        ));
-                    }
-
-                    current_layer.add_operation(Operation::new(OP_PATH_PAINT_FILL_NZ, vec![]));
-                }
-                PrimitiveKind::Image { image_id, color: _, source_rect: _, mode: _ } => {
-                    println!("Image");
-                    let image = self.environment.image_map.get(&image_id).unwrap();
-                    dbg!(image.color());
-                    let pdf_image = PdfImage::from_dynamic_image(image);
-
-                    let width_px = Px(image.width() as usize * 2);
-                    let height_px = Px(image.height() as usize * 2);
-
-                    let width = Mm::from(width_px.into_pt(300.0));
-                    let height = Mm::from(height_px.into_pt(300.0));
-
-                    let requested_width = rectangle.width();
-                    let requested_height = rectangle.height();
-
-                    let scaling_width = requested_width / width.0;
-                    let scaling_height = requested_height / height.0;
-
-                    dbg!(width, height);
-                    dbg!(requested_width, requested_height);
-                    dbg!(scaling_width, scaling_height);
-
-                    let x = rectangle.left() / 2.0;
-                    let y = page_dimensions.height - rectangle.top() / 2.0;
-
-                    pdf_image.add_to_layer(current_layer.clone(), ImageTransform {
-                        translate_x: Some(Mm(x)),
-                        translate_y: Some(Mm(y)),
-                        scale_x: Some(scaling_width),
-                        scale_y: Some(scaling_height),
-                        ..Default::default()
-                    })
-                }
-                PrimitiveKind::Text { color, text } => {
-                    /*current_layer.set_fill_color(Color::Rgb(Rgb::new(1.0, 1.0, 0.0, None)));
-
-                    for glyph in &text {
-                        if let Some(bb) = glyph.bb() {
-                            let point1 = convert_position_to_point(bb.top_left(), page_dimensions);
-                            let point2 = convert_position_to_point(bb.top_right(), page_dimensions);
-                            let point3 = convert_position_to_point(bb.bottom_right(), page_dimensions);
-                            let point4 = convert_position_to_point(bb.bottom_left(), page_dimensions);
-                            current_layer.add_operation(Operation::new(
-                                OP_PATH_CONST_MOVE_TO,
-                                vec![point1.x.into(), point1.y.into()],
-                            ));
-
-                            current_layer.add_operation(Operation::new(
-                                OP_PATH_CONST_LINE_TO,
-                                vec![point2.x.into(), point2.y.into()],
-                            ));
-                            current_layer.add_operation(Operation::new(
-                                OP_PATH_CONST_LINE_TO,
-                                vec![point3.x.into(), point3.y.into()],
-                            ));
-                            current_layer.add_operation(Operation::new(
-                                OP_PATH_CONST_LINE_TO,
-                                vec![point4.x.into(), point3.y.into()],
-                            ));
-                            current_layer.add_operation(Operation::new(
-                                OP_PATH_CONST_LINE_TO,
-                                vec![point1.x.into(), point1.y.into()],
-                            ));
-                        }
-                    }
-
-                    current_layer.add_operation(Operation::new(OP_PATH_PAINT_FILL_NZ, vec![]));*/
-
-
-                    let inner_font = self.environment.get_font(0);
-                    let font_path = inner_font.path();
-                    let path = Path::new(&font_path);
-                    let mut file = std::fs::File::open(path).unwrap();
-
-                    let font = self.document.add_external_font(&mut file).unwrap();
-
-                    current_layer.set_fill_color(Color::Rgb(Rgb::new(
-                        color.red() as f64,
-                        color.green() as f64,
-                        color.blue() as f64,
-                        None
-                    )));
+        let (document, first_page, first_layer) = PdfDocument::new(
+            &self.title,
+            Mm(page_size.width as f32),
+            Mm(page_size.height as f32),
+            "Layer 1",
+        );
 
-                    current_layer.begin_text_section();
+        let mut render_context = PdfRenderContext::new(document);
+        let mut text_context = CosmicTextContext::new();
+        let mut image_context = PdfImageContext;
 
-                    for glyph in text.iter() {
-                        let point_x = glyph.position().x();
-                        let point_y = page_dimensions.height - glyph.position().y();
-                        //dbg!(glyph.font_size());
-                        current_layer.set_font(&font, (glyph.font_size()) as f64);
-                        current_layer.set_text_cursor(Mm(point_x), Mm(point_y));
-                        current_layer.write_text(glyph.character(), &font);
-                        current_layer.set_text_cursor(Mm(-point_x), Mm(-point_y));
-                    }
+        for font in &self.fonts {
+            render_context.add_font_from_bytes(font.clone());
+            text_context.add_font_from_bytes(font.clone());
+        }
 
-                    current_layer.end_text_section();
-                }
-                PrimitiveKind::Clip => {
-                    println!("Clip");
-                    let point1 = convert_position_to_point(rectangle.top_left(), page_dimensions);
-                    let point2 = convert_position_to_point(rectangle.top_right(), page_dimensions);
-                    let point3 = convert_position_to_point(rectangle.bottom_right(), page_dimensions);
-                    let point4 = convert_position_to_point(rectangle.bottom_left(), page_dimensions);
-
-                    current_layer.add_operation(Operation::new(
-                        OP_PATH_CONST_MOVE_TO,
-                        vec![point1.x.into(), point1.y.into()],
-                    ));
-                    current_layer.add_operation(Operation::new(
-                        OP_PATH_CONST_LINE_TO,
-                        vec![point2.x.into(), point2.y.into()],
-                    ));
-                    current_layer.add_operation(Operation::new(
-                        OP_PATH_CONST_LINE_TO,
-                        vec![point3.x.into(), point3.y.into()],
-                    ));
-                    current_layer.add_operation(Operation::new(
-                        OP_PATH_CONST_LINE_TO,
-                        vec![point4.x.into(), point4.y.into()],
-                    ));
-                    current_layer.add_operation(Operation::new(
-                        OP_PATH_CONST_LINE_TO,
-                        vec![point1.x.into(), point1.y.into()],
-                    ));
-
-                    //current_layer.add_operation(Operation::new(OP_PATH_CONST_RECT, vec![100.0.into(), 100.0.into(), 100.0.into(), 100.0.into()]));
-                    current_layer.add_operation(Operation::new("q W n", vec![]));
-
-                }
-                PrimitiveKind::UnClip => {
-                    current_layer.add_operation(Operation::new("Q", vec![]));
-                }
-                PrimitiveKind::Stencil(triangles) => {
-                    for triangle in triangles {
-                        let point1 = convert_position_to_point(triangle.points()[0], page_dimensions);
-                        let point2 = convert_position_to_point(triangle.points()[1], page_dimensions);
-                        let point3 = convert_position_to_point(triangle.points()[2], page_dimensions);
-
-                        current_layer.add_operation(Operation::new(
-                            OP_PATH_CONST_MOVE_TO,
-                            vec![point1.x.into(), point1.y.into()],
-                        ));
-
-                        current_layer.add_operation(Operation::new(
-                            OP_PATH_CONST_LINE_TO,
-                            vec![point2.x.into(), point2.y.into()],
-                        ));
-                        current_layer.add_operation(Operation::new(
-                            OP_PATH_CONST_LINE_TO,
-                            vec![point3.x.into(), point3.y.into()],
-                        ));
-                        current_layer.add_operation(Operation::new(
-                            OP_PATH_CONST_LINE_TO,
-                            vec![point1.x.into(), point1.y.into()],
-                        ));
+        let mut render_instruction_cache: HashMap<ImageId, Rc<(Dimension, Vec<RenderInstruction>)>> = HashMap::new();
+        let mut scene_manager = SceneManager::new(1.0, page_dimension);
+        let mut next_page = Some((first_page, first_layer));
+
+        let mut env = Environment::new();
+        let theme = self.theme;
+
+        env.with::<Theme>(&theme, |env| {
+            env.with_mut::<SceneManager>(&mut scene_manager, |env| {
+                env.with_mut::<RenderInstructionCache>(&mut render_instruction_cache, |env| {
+                    for widget in &mut self.pages {
+                        widget.process_initialization(&mut InitializationContext {
+                            env,
+                        });
+
+                        widget.process_update(&mut UpdateContext {
+                            text: &mut text_context,
+                            image: &mut image_context,
+                            env,
+                        });
+
+                        let mut pages = PdfPage { index: 0, count: 1 };
+
+                        env.with_mut::<PdfPage>(&mut pages, |env| {
+                            widget.process_calculate_size(content.dimension, &mut LayoutContext {
+                                text: &mut text_context,
+                                image: &mut image_context,
+                                env,
+                            });
+                        });
+
+                        widget.set_position(Alignment::Top.position(content.position, content.dimension, widget.dimension()));
+
+                        widget.position_children(content, &mut LayoutContext {
+                            text: &mut text_context,
+                            image: &mut image_context,
+                            env,
+                        });
+
+                        for index in 0..pages.count {
+                            let (page, layer) = next_page.take().unwrap_or_else(|| {
+                                render_context.document().add_page(
+                                    Mm(page_size.width as f32),
+                                    Mm(page_size.height as f32),
+                                    "Layer 1",
+                                )
+                            });
+
+                            let layer = render_context.document().get_page(page).get_layer(layer);
+                            render_context.begin_page(layer, page_dimension);
+
+                            let mut current = PdfPage { index, count: pages.count };
+
+                            env.with_mut::<PdfPage>(&mut current, |env| {
+                                text_context.prepare_render();
+
+                                widget.render(&mut RenderContext {
+                                    render: &mut render_context,
+                                    text: &mut text_context,
+                                    image: &mut image_context,
+                                    env,
+                                });
+                            });
+
+                            render_context.end_page();
+                        }
                     }
-
-                    current_layer.add_operation(Operation::new("q W n", vec![]));
-                }
-                PrimitiveKind::DeStencil => {
-                    current_layer.add_operation(Operation::new("Q", vec![]));
-                }
-                _ => ()
-            }
-        }*/
-
-        todo!()
-
-        /*let font_id = self.environment.get_font_family("system-font").get_best_fit(FontWeight::Normal, FontStyle::Normal);
-        let inner_font = self.environment.get_font(font_id);
-        let font_path = inner_font.path();
-        let path = Path::new(&font_path);
-        let mut file = File::open(path).unwrap();
-        let font = self.document.add_external_font(&mut file).unwrap();
-
-        let mut context = PDFRenderContext {
-            pdf_layer_reference: current_layer,
-            color_stack: vec![],
-            page_dimensions,
-            font
-        };
-
-        let image_context = ImageContext::new(PDFImageContext);
-
-        self.environment.with_image_context(image_context, move |environment| {
-            self.widgets.calculate_size(page_dimensions / environment.scale_factor(), environment);
-            let layout = environment.root_alignment();
-            (layout.positioner())(Position::new(0.0, 0.0), page_dimensions / environment.scale_factor(), &mut self.widgets);
-
-            self.widgets.position_children(environment);
-            self.widgets.render(&mut RenderContext::new(&mut context), environment.env);
+                })
+            })
         });
 
+        render_context.into_document()
+    }
 
+    /// Render the document and write it to the file at the path.
+    pub fn save(self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let file = File::create(path)?;
 
-        let path = format!("target/{}.pdf", self.title);
-
-        self.document.save(&mut BufWriter::new(File::create(path.clone()).unwrap()))
-            .unwrap();
+        self.render()
+            .save(&mut BufWriter::new(file))
+            .map_err(|err| std::io::Error::other(err.to_string()))
+    }
 
-        path*/
+    /// Render the document and return the bytes of the PDF file.
+    pub fn to_bytes(self) -> std::io::Result<Vec<u8>> {
+        self.render()
+            .save_to_bytes()
+            .map_err(|err| std::io::Error::other(err.to_string()))
     }
 }
-
-fn convert_position_to_point(position: Position, page_dimensions: Dimension) -> Point {
-    let x = Mm(position.x / 2.0);
-    let y = Mm(page_dimensions.height - position.y / 2.0);
-
-    println!("{:?}, {:?}", x, y);
-    Point::new(x, y)
-}
\ No newline at end of file
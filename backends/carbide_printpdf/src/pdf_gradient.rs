@@ -0,0 +1,196 @@
+use std::f64::consts::TAU;
+
+use carbide_core::color::ColorExt;
+use carbide_core::draw::{DrawGradient, Position, Rect, Scalar};
+use carbide_core::draw::gradient::{GradientRepeat, GradientType};
+
+/// The maximum number of bands a gradient is split into.
+const MAX_BANDS: usize = 256;
+
+/// The number of segments used when approximating the circles of a radial gradient.
+const CIRCLE_SEGMENTS: usize = 64;
+
+/// A gradient converted into bands of solid colors. PDF shadings are not available through
+/// printpdf, so gradients are drawn as a series of polygons clipped to the shape. The
+/// evaluation of the colors mirrors the gradient function in the wgpu shader.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfGradient {
+    colors: Vec<[f32; 4]>,
+    ratios: Vec<f32>,
+    gradient_type: GradientType,
+    gradient_repeat: GradientRepeat,
+    start: Position,
+    end: Position,
+}
+
+impl PdfGradient {
+    pub fn convert(gradient: &DrawGradient) -> PdfGradient {
+        PdfGradient {
+            colors: gradient.colors.iter().map(|color| color.to_fsa()).collect(),
+            ratios: gradient.ratios.clone(),
+            gradient_type: gradient.gradient_type.clone(),
+            gradient_repeat: gradient.gradient_repeat.clone(),
+            start: gradient.start,
+            end: gradient.end,
+        }
+    }
+
+    /// The color used when a single color is needed, for example when drawing text.
+    pub fn first_color(&self) -> [f32; 4] {
+        self.colors.first().copied().unwrap_or([0.0, 0.0, 0.0, 0.0])
+    }
+
+    /// Split the gradient into polygons covering the bounds. The polygons are returned in the
+    /// order they should be drawn, each with the color of its band.
+    pub fn bands(&self, bounds: Rect) -> Vec<(Vec<Position>, [f32; 4])> {
+        let length = self.start.dist(&self.end);
+
+        if length <= 0.0 || self.colors.is_empty() {
+            let color = self.colors.last().copied().unwrap_or([0.0, 0.0, 0.0, 0.0]);
+            return vec![(corners(bounds).to_vec(), color)];
+        }
+
+        let corners = corners(bounds);
+
+        // The distance that is guaranteed to reach outside the bounds from the start.
+        let reach = corners.iter()
+            .map(|corner| corner.dist(&self.start))
+            .fold(0.0, Scalar::max) + 1.0;
+
+        let angle = (self.end.y - self.start.y).atan2(self.end.x - self.start.x);
+
+        match self.gradient_type {
+            GradientType::Linear => {
+                let direction = (self.end - self.start) / length;
+                let normal = Position::new(-direction.y, direction.x) * reach;
+
+                let projected = corners.iter()
+                    .map(|corner| {
+                        let offset = *corner - self.start;
+                        direction.x * offset.x + direction.y * offset.y
+                    })
+                    .collect::<Vec<_>>();
+
+                let from = projected.iter().copied().fold(Scalar::MAX, Scalar::min);
+                let to = projected.iter().copied().fold(Scalar::MIN, Scalar::max);
+
+                let count = band_count(to - from);
+                let step = (to - from) / count as Scalar;
+
+                (0..count).map(|index| {
+                    let a = from + step * index as Scalar;
+                    // Overlap the bands slightly to avoid hairline gaps between them.
+                    let b = a + step * 1.05;
+
+                    let polygon = vec![
+                        self.start + direction * a - normal,
+                        self.start + direction * b - normal,
+                        self.start + direction * b + normal,
+                        self.start + direction * a + normal,
+                    ];
+
+                    (polygon, self.color_at((a + step / 2.0) / length))
+                }).collect()
+            }
+            GradientType::Radial | GradientType::Diamond => {
+                let count = band_count(reach);
+                let step = reach / count as Scalar;
+
+                // Draw the outermost band first, such that the inner bands are drawn on top.
+                (0..count).rev().map(|index| {
+                    let radius = step * (index + 1) as Scalar;
+
+                    let polygon = if self.gradient_type == GradientType::Radial {
+                        (0..CIRCLE_SEGMENTS).map(|segment| {
+                            let angle = segment as Scalar / CIRCLE_SEGMENTS as Scalar * TAU;
+                            self.start + Position::new(angle.cos(), angle.sin()) * radius
+                        }).collect()
+                    } else {
+                        (0..4).map(|corner| {
+                            let angle = angle + corner as Scalar * TAU / 4.0;
+                            self.start + Position::new(angle.cos(), angle.sin()) * radius
+                        }).collect()
+                    };
+
+                    (polygon, self.color_at((radius - step / 2.0) / length))
+                }).collect()
+            }
+            GradientType::Conic => {
+                let count = band_count(reach * TAU).min(MAX_BANDS);
+                let step = TAU / count as Scalar;
+
+                (0..count).map(|index| {
+                    let a = angle + step * index as Scalar;
+                    let b = a + step * 1.05;
+                    // The wedge is extended such that its outer edge is outside the bounds.
+                    let radius = reach / (step / 2.0).cos().max(0.5);
+
+                    let polygon = vec![
+                        self.start,
+                        self.start + Position::new(a.cos(), a.sin()) * radius,
+                        self.start + Position::new(b.cos(), b.sin()) * radius,
+                    ];
+
+                    (polygon, self.color_at((index as Scalar + 0.5) / count as Scalar))
+                }).collect()
+            }
+        }
+    }
+
+    /// Evaluate the straight alpha color of the gradient at the given ratio.
+    fn color_at(&self, t: Scalar) -> [f32; 4] {
+        let last = self.colors.len() - 1;
+
+        let mut t = match self.gradient_repeat {
+            GradientRepeat::Clamp => (t as f32).clamp(0.0, 1.0),
+            GradientRepeat::Repeat => (t as f32).rem_euclid(1.0),
+            GradientRepeat::Mirror => {
+                let t = (t as f32).abs();
+                if (t as i32) & 1 == 0 {
+                    t.fract()
+                } else {
+                    1.0 - t.fract()
+                }
+            }
+        };
+
+        if last == 0 || t.is_nan() {
+            return self.colors[last];
+        }
+
+        t = t.clamp(self.ratios[0], self.ratios[last]);
+
+        let mut j = 1;
+        while j < last && t > self.ratios[j] {
+            j += 1;
+        }
+        let i = j - 1;
+
+        let span = self.ratios[j] - self.ratios[i];
+        let a = if span > 0.0 { (t - self.ratios[i]) / span } else { 0.0 };
+
+        let from = self.colors[i];
+        let to = self.colors[j];
+
+        [
+            from[0] + (to[0] - from[0]) * a,
+            from[1] + (to[1] - from[1]) * a,
+            from[2] + (to[2] - from[2]) * a,
+            from[3] + (to[3] - from[3]) * a,
+        ]
+    }
+}
+
+/// One band per unit of distance, which is a point in the document, within reasonable limits.
+fn band_count(distance: Scalar) -> usize {
+    (distance.abs().ceil() as usize).clamp(2, MAX_BANDS)
+}
+
+fn corners(bounds: Rect) -> [Position; 4] {
+    [
+        Position::new(bounds.left(), bounds.bottom()),
+        Position::new(bounds.right(), bounds.bottom()),
+        Position::new(bounds.right(), bounds.top()),
+        Position::new(bounds.left(), bounds.top()),
+    ]
+}
@@ -0,0 +1,39 @@
+use std::fmt::{Debug, Formatter};
+use carbide_core::image::RgbaImage;
+use carbide_core::render::InnerLayer;
+
+/// A layer rendered into a PDF. Layers are raster content, and are embedded as an image when
+/// the layer is rendered onto the page.
+pub struct PdfLayer {
+    pub(crate) image: RgbaImage,
+}
+
+impl PdfLayer {
+    pub(crate) fn new(width: u32, height: u32) -> PdfLayer {
+        PdfLayer {
+            image: RgbaImage::new(width.max(1), height.max(1)),
+        }
+    }
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    pub fn image_mut(&mut self) -> &mut RgbaImage {
+        &mut self.image
+    }
+}
+
+impl InnerLayer for PdfLayer {
+    fn dimensions(&self) -> (u32, u32) {
+        self.image.dimensions()
+    }
+}
+
+impl Debug for PdfLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PdfLayer")
+            .field("dimensions", &self.dimensions())
+            .finish()
+    }
+}
@@ -1,268 +1,972 @@
-use printpdf::{Color, IndirectFontRef, PdfLayerReference};
-use carbide_core::draw::Dimension;
-use carbide_core::render::InnerRenderContext;
-use carbide_core::text::TextContext;
-
-pub struct PDFRenderContext {
-    /*style_stack: Vec<WGPUStyle>,
-    stencil_stack: Vec<Range<u32>>,
-    scissor_stack: Vec<BoundingBox>,
-    transform_stack: Vec<(Matrix4<f32>, usize)>,
-
-    transforms: Vec<Matrix4<f32>>,
-    gradients: Vec<Gradient>,
-    vertices: Vec<Vertex>,
-
-    render_pass: Vec<RenderPass>,
-    render_pass_inner: Vec<RenderPassCommand>,
-    current_bind_group: Option<WGPUBindGroup>,
-
-    state: State,
-    window_bounding_box: Rect,
-    frame_count: usize,*/
-    pub(crate) pdf_layer_reference: PdfLayerReference,
-    pub(crate) color_stack: Vec<Color>,
-    pub(crate) page_dimensions: Dimension,
-    pub(crate) font: IndirectFontRef,
+use std::collections::HashMap;
+use std::f64::consts::FRAC_PI_2;
+
+use cosmic_text::{Attrs, Buffer, Family, FontSystem, Metrics, Shaping, Style, Weight};
+use cosmic_text::fontdb::ID;
+use printpdf::{Image, ImageTransform, IndirectFontRef, Mm, PdfDocumentReference, PdfLayerReference, Pt};
+use printpdf::image_crate;
+use printpdf::lopdf::content::Operation;
+use printpdf::lopdf::Object;
+
+use carbide_core::color::{hsl_to_rgb, rgb_to_hsl, ColorExt, WHITE};
+use carbide_core::draw::{Color, CompositeDrawShape, Dimension, DrawOptions, DrawShape, DrawStyle, ImageId, ImageMode, ImageOptions, Position, Rect, Scalar};
+use carbide_core::draw::fill::FillRule;
+use carbide_core::draw::path::PathInstruction;
+use carbide_core::draw::stroke::{LineCap, LineJoin, StrokeAlignment, StrokeDashCap, StrokeDashPattern, StrokeOptions};
+use carbide_core::environment::Environment;
+use carbide_core::image::imageops::crop_imm;
+use carbide_core::image::RgbaImage;
+use carbide_core::math::Matrix4;
+use carbide_core::render::{InnerRenderContext, Layer, LayerId};
+use carbide_core::text::{AttributedText, FontStyle, TextContext, TextId, TextSpan, TextStyle};
+use carbide_core::text::text_wrap::Wrap;
+use carbide_core::widget::{CornerRadii, ImageFilter};
+use carbide_cosmic_text::text_context::{baseline_offset, set_buffer_text, span_of};
+
+use crate::image_context::IMAGES;
+use crate::pdf_gradient::PdfGradient;
+use crate::pdf_layer::PdfLayer;
+
+/// Kappa is the distance to the control points, when approximating a quarter circle with a cubic bezier.
+const KAPPA: Scalar = 0.552_284_8;
+
+#[derive(Debug, Clone, PartialEq)]
+enum PdfStyle {
+    Color([f32; 4]),
+    Gradient(PdfGradient),
 }
 
-/*impl InnerRenderContext for PDFRenderContext {
-    fn transform(&mut self, transform: CarbideTransform) {
-        todo!()
+/// The color filter currently applied, given as shifts relative to the original colors.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct PdfColorFilter {
+    /// Hue rotation given in turns.
+    hue_rotation: f32,
+    saturation_shift: f32,
+    luminance_shift: f32,
+    color_invert: bool,
+}
+
+impl PdfColorFilter {
+    fn apply(&self, [r, g, b, a]: [f32; 4]) -> [f32; 4] {
+        let (r, g, b) = if self.color_invert {
+            (1.0 - r, 1.0 - g, 1.0 - b)
+        } else {
+            (r, g, b)
+        };
+
+        let (h, s, l) = rgb_to_hsl(r, g, b);
+
+        let turn = std::f32::consts::TAU;
+        let h = (h / turn + self.hue_rotation).rem_euclid(1.0) * turn;
+        let s = (s + self.saturation_shift).clamp(0.0, 1.0);
+        let l = (l + self.luminance_shift).clamp(0.0, 1.0);
+
+        let (r, g, b) = hsl_to_rgb(h, s, l);
+
+        [r, g, b, a]
+    }
+}
+
+/// A render context that writes the rendering as vector graphics into a PDF document. Shapes
+/// are written as paths, text is written as text using the fonts embedded into the document,
+/// and raster images are embedded as images.
+///
+/// All positions are given in points, with the origin in the top left corner of the page.
+///
+/// Some effects are not expressible using the operations available, and are approximated:
+/// * Transparent colors are blended onto white paper, and fully transparent shapes are skipped.
+/// * Gradients are drawn as bands of solid colors, clipped to the shape.
+/// * Stroked shapes used as stencils clip to the filled shape.
+/// * Image filters, like blur and shadows, are not drawn. The filtered content is drawn as is.
+/// * Masks are not applied, and the content is drawn unmasked.
+pub struct PdfRenderContext {
+    document: PdfDocumentReference,
+    layer: Option<PdfLayerReference>,
+    page_dimension: Dimension,
+    style_stack: Vec<PdfStyle>,
+    stroke_dash_stack: Vec<Option<StrokeDashPattern>>,
+    color_filter_stack: Vec<PdfColorFilter>,
+    /// For each mask started, whether we are currently drawing the mask, which is not drawn.
+    mask_stack: Vec<bool>,
+    layers: HashMap<LayerId, PdfLayer>,
+    fonts: HashMap<ID, IndirectFontRef>,
+    font_system: FontSystem,
+}
+
+impl PdfRenderContext {
+    pub fn new(document: PdfDocumentReference) -> PdfRenderContext {
+        PdfRenderContext {
+            document,
+            layer: None,
+            page_dimension: Dimension::new(0.0, 0.0),
+            style_stack: vec![],
+            stroke_dash_stack: vec![],
+            color_filter_stack: vec![],
+            mask_stack: vec![],
+            layers: HashMap::new(),
+            fonts: HashMap::new(),
+            font_system: FontSystem::new(),
+        }
+    }
+
+    /// Add a font that can be used when laying out text. The font is embedded into the
+    /// document when used.
+    pub fn add_font_from_bytes(&mut self, bytes: Vec<u8>) {
+        self.font_system.db_mut().load_font_data(bytes);
+    }
+
+    pub fn document(&self) -> &PdfDocumentReference {
+        &self.document
+    }
+
+    pub fn into_document(self) -> PdfDocumentReference {
+        self.document
+    }
+
+    /// Start rendering onto the layer of a page with the given dimension in points. This resets
+    /// all the stacks.
+    pub fn begin_page(&mut self, layer: PdfLayerReference, page_dimension: Dimension) {
+        self.layer = Some(layer);
+        self.page_dimension = page_dimension;
+
+        self.style_stack.clear();
+        self.stroke_dash_stack.clear();
+        self.color_filter_stack.clear();
+        self.mask_stack.clear();
+
+        // PDF has the origin in the bottom left corner with the y-axis pointing up, so we
+        // flip the page such that we can use the same coordinates as on screen.
+        self.operation("q", vec![]);
+        self.operation("cm", reals(&[1.0, 0.0, 0.0, -1.0, 0.0, page_dimension.height]));
+    }
+
+    /// Finish the page currently being rendered.
+    pub fn end_page(&mut self) {
+        self.operation("Q", vec![]);
+        self.layer = None;
+    }
+
+    pub fn page_dimension(&self) -> Dimension {
+        self.page_dimension
+    }
+
+    fn layer_reference(&self) -> &PdfLayerReference {
+        self.layer.as_ref().expect("A page to be started before rendering")
+    }
+
+    /// Whether drawing is currently skipped, because the content is part of a mask.
+    fn suppressed(&self) -> bool {
+        self.mask_stack.iter().any(|drawing_mask| *drawing_mask)
+    }
+
+    fn operation(&self, operator: &str, operands: Vec<Object>) {
+        self.layer_reference().add_operation(Operation::new(operator, operands));
+    }
+
+    fn operations(&self, operations: Vec<Operation>) {
+        let layer = self.layer_reference();
+
+        for operation in operations {
+            layer.add_operation(operation);
+        }
+    }
+
+    fn current_style(&self) -> PdfStyle {
+        self.style_stack.last()
+            .cloned()
+            .unwrap_or(PdfStyle::Color(WHITE.to_fsa()))
+    }
+
+    /// The color after applying the color filters. Colors are blended onto white, since
+    /// transparency is not available. Returns `None` for fully transparent colors.
+    fn filtered_color(&self, color: [f32; 4]) -> Option<[f32; 3]> {
+        let [r, g, b, a] = self.color_filter_stack.iter()
+            .rev()
+            .fold(color, |color, filter| filter.apply(color));
+
+        if a <= 1.0 / 255.0 {
+            return None;
+        }
+
+        let a = a.min(1.0);
+        let blend = |c: f32| c.clamp(0.0, 1.0) * a + (1.0 - a);
+
+        Some([blend(r), blend(g), blend(b)])
+    }
+
+    /// The single color of the current style. Gradients use their first color.
+    fn current_color(&self) -> Option<[f32; 3]> {
+        match self.current_style() {
+            PdfStyle::Color(color) => self.filtered_color(color),
+            PdfStyle::Gradient(gradient) => self.filtered_color(gradient.first_color()),
+        }
+    }
+
+    fn fill_color(&self, [r, g, b]: [f32; 3]) {
+        self.operation("rg", reals(&[r as Scalar, g as Scalar, b as Scalar]));
+    }
+
+    fn stroke_color(&self, [r, g, b]: [f32; 3]) {
+        self.operation("RG", reals(&[r as Scalar, g as Scalar, b as Scalar]));
+    }
+
+    fn fill(&mut self, shape: &DrawShape, rule: FillRule) {
+        let fill = match rule {
+            FillRule::EvenOdd => "f*",
+            FillRule::NonZero => "f",
+        };
+
+        match self.current_style() {
+            PdfStyle::Color(color) => {
+                let Some(color) = self.filtered_color(color) else {
+                    return;
+                };
+
+                self.fill_color(color);
+                self.operations(shape_operations(shape));
+                self.operation(fill, vec![]);
+            }
+            PdfStyle::Gradient(gradient) => {
+                let clip = match rule {
+                    FillRule::EvenOdd => "W*",
+                    FillRule::NonZero => "W",
+                };
+
+                self.operation("q", vec![]);
+                self.operations(shape_operations(shape));
+                self.operation(clip, vec![]);
+                self.operation("n", vec![]);
+
+                for (polygon, color) in gradient.bands(shape_bounds(shape)) {
+                    let Some(color) = self.filtered_color(color) else {
+                        continue;
+                    };
+
+                    self.fill_color(color);
+                    self.operations(polygon_operations(&polygon));
+                    self.operation("f", vec![]);
+                }
+
+                self.operation("Q", vec![]);
+            }
+        }
+    }
+
+    fn stroke(&mut self, shape: &DrawShape, options: &StrokeOptions) {
+        let Some(color) = self.current_color() else {
+            return;
+        };
+
+        // Strokes aligned to one side of the path are drawn with the double width, and
+        // clipped to the inside or outside of the path respectively.
+        let width = match options.stroke_alignment {
+            StrokeAlignment::Center => options.stroke_width,
+            StrokeAlignment::Positive | StrokeAlignment::Negative => options.stroke_width * 2.0,
+        };
+
+        self.operation("q", vec![]);
+
+        match options.stroke_alignment {
+            StrokeAlignment::Center => {}
+            StrokeAlignment::Positive => {
+                self.operations(shape_operations(shape));
+                self.operation("W", vec![]);
+                self.operation("n", vec![]);
+            }
+            StrokeAlignment::Negative => {
+                let page = Rect::new(Position::origin(), self.page_dimension);
+                let outside = Rect::new(page.position - Position::new(width, width), page.dimension + Dimension::new(width * 2.0, width * 2.0));
+
+                self.operations(shape_operations(&DrawShape::Rectangle(outside)));
+                self.operations(shape_operations(shape));
+                self.operation("W*", vec![]);
+                self.operation("n", vec![]);
+            }
+        }
+
+        self.stroke_color(color);
+        self.operation("w", reals(&[width]));
+        self.operation("J", vec![Object::Integer(line_cap(options.start_cap))]);
+
+        match options.stroke_join {
+            LineJoin::Miter => {
+                self.operation("j", vec![Object::Integer(0)]);
+            }
+            LineJoin::MiterClip { miter_limit } => {
+                self.operation("j", vec![Object::Integer(0)]);
+                self.operation("M", reals(&[miter_limit]));
+            }
+            LineJoin::Round => {
+                self.operation("j", vec![Object::Integer(1)]);
+            }
+            LineJoin::Bevel => {
+                self.operation("j", vec![Object::Integer(2)]);
+            }
+        }
+
+        if let Some(Some(pattern)) = self.stroke_dash_stack.last() {
+            let mut dashes = pattern.pattern.clone();
+
+            // An odd number of dashes is repeated to get an even number, similar to svg.
+            if dashes.len() % 2 == 1 {
+                dashes.extend_from_within(..);
+            }
+
+            self.operation("d", vec![Object::Array(reals(&dashes)), real(pattern.offset)]);
+
+            let cap = match pattern.start_cap {
+                StrokeDashCap::None => 0,
+                StrokeDashCap::Round => 1,
+                StrokeDashCap::Square => 2,
+                StrokeDashCap::TriangleIn |
+                StrokeDashCap::TriangleOut => 0,
+            };
+
+            self.operation("J", vec![Object::Integer(cap)]);
+        }
+
+        self.operations(shape_operations(shape));
+        self.operation("S", vec![]);
+        self.operation("Q", vec![]);
+    }
+
+    /// Draw an area of the image within the bounding box. The source rect is given in normalized
+    /// coordinates of the image. If a tint is given, the image is drawn in the tint color,
+    /// using the alpha of the image.
+    fn draw_image(&mut self, image: &RgbaImage, bounding_box: Rect, source_rect: Rect, tint: Option<[f32; 4]>) {
+        if bounding_box.width() <= 0.0 || bounding_box.height() <= 0.0 {
+            return;
+        }
+
+        let (width, height) = (image.width() as Scalar, image.height() as Scalar);
+
+        let left = (source_rect.position.x * width).round().clamp(0.0, width) as u32;
+        let top = (source_rect.position.y * height).round().clamp(0.0, height) as u32;
+        let right = ((source_rect.position.x + source_rect.width()) * width).round().clamp(0.0, width) as u32;
+        let bottom = ((source_rect.position.y + source_rect.height()) * height).round().clamp(0.0, height) as u32;
+
+        if right <= left || bottom <= top {
+            return;
+        }
+
+        let mut source = crop_imm(image, left, top, right - left, bottom - top).to_image();
+
+        for pixel in source.pixels_mut() {
+            let [r, g, b, a] = pixel.0.map(|channel| channel as f32 / 255.0);
+
+            let color = match tint {
+                Some(tint) => [tint[0], tint[1], tint[2], tint[3] * a],
+                None => [r, g, b, a],
+            };
+
+            let [r, g, b, a] = self.color_filter_stack.iter()
+                .rev()
+                .fold(color, |color, filter| filter.apply(color));
+
+            pixel.0 = [r, g, b, a].map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+        }
+
+        let (pixel_width, pixel_height) = source.dimensions();
+
+        let Some(source) = image_crate::RgbaImage::from_raw(pixel_width, pixel_height, source.into_raw()) else {
+            return;
+        };
+
+        let pdf_image = Image::from_dynamic_image(&image_crate::DynamicImage::ImageRgba8(source));
+
+        // Images are placed with their bottom left corner at the position, in the coordinate
+        // system of the PDF. We flip the area of the image back, to not draw it upside down.
+        self.operation("q", vec![]);
+        self.operation("cm", reals(&[1.0, 0.0, 0.0, -1.0, 0.0, bounding_box.bottom() + bounding_box.top()]));
+
+        pdf_image.add_to_layer(self.layer_reference().clone(), ImageTransform {
+            translate_x: Some(Mm::from(Pt(bounding_box.left() as f32))),
+            translate_y: Some(Mm::from(Pt(bounding_box.bottom() as f32))),
+            scale_x: Some((bounding_box.width() / pixel_width as Scalar) as f32),
+            scale_y: Some((bounding_box.height() / pixel_height as Scalar) as f32),
+            // At 72 dpi a pixel of the image is exactly a point.
+            dpi: Some(72.0),
+            ..Default::default()
+        });
+
+        self.operation("Q", vec![]);
+    }
+
+    /// Get the font embedded into the document for the font in the font system. The font is
+    /// embedded the first time it is used.
+    fn font(&mut self, id: ID) -> Option<IndirectFontRef> {
+        if let Some(font) = self.fonts.get(&id) {
+            return Some(font.clone());
+        }
+
+        let data = self.font_system.db().with_face_data(id, |data, _| data.to_vec())?;
+        let font = self.document.add_external_font(&data[..]).ok()?;
+
+        self.fonts.insert(id, font.clone());
+
+        Some(font)
+    }
+
+    /// Write the glyphs of the laid out buffer as text. Glyphs of spans with their own color
+    /// are written using that color instead of the given color.
+    fn write_glyphs(&mut self, buffer: &Buffer, spans: &[TextSpan], position: Position, color: [f32; 3]) {
+        let mut glyphs = vec![];
+
+        for run in buffer.layout_runs() {
+            let mut previous = None;
+
+            for glyph in run.glyphs.iter() {
+                // A cluster that is shaped into multiple glyphs, is written once.
+                if previous == Some((glyph.start, glyph.end)) {
+                    continue;
+                }
+                previous = Some((glyph.start, glyph.end));
+
+                let span = span_of(glyph, spans);
+
+                let color = match span.and_then(|span| span.attributes.color) {
+                    Some(color) => match self.filtered_color(color.to_fsa()) {
+                        Some(color) => color,
+                        None => continue,
+                    },
+                    None => color,
+                };
+
+                glyphs.push((
+                    glyph.font_id,
+                    run.text[glyph.start..glyph.end].to_string(),
+                    Position::new(position.x + glyph.x as Scalar, position.y + (run.line_y + glyph.y) as Scalar - baseline_offset(span)),
+                    glyph.font_size,
+                    color,
+                ));
+            }
+        }
+
+        let layer = self.layer_reference().clone();
+
+        layer.begin_text_section();
+
+        let mut current_color = None;
+
+        for (font_id, characters, position, font_size, color) in glyphs {
+            let Some(font) = self.font(font_id) else {
+                continue;
+            };
+
+            if current_color != Some(color) {
+                self.fill_color(color);
+                current_color = Some(color);
+            }
+
+            layer.set_font(&font, font_size);
+            // The page is flipped, so we flip the text back to not draw it upside down.
+            self.operation("Tm", reals(&[1.0, 0.0, 0.0, -1.0, position.x, position.y]));
+            layer.write_text(characters, &font);
+        }
+
+        layer.end_text_section();
+    }
+}
+
+impl InnerRenderContext for PdfRenderContext {
+    fn transform(&mut self, transform: &Matrix4<f32>) {
+        self.operation("q", vec![]);
+        self.operation("cm", reals(&[
+            transform[0][0] as Scalar,
+            transform[0][1] as Scalar,
+            transform[1][0] as Scalar,
+            transform[1][1] as Scalar,
+            transform[3][0] as Scalar,
+            transform[3][1] as Scalar,
+        ]));
     }
 
     fn pop_transform(&mut self) {
-        todo!()
+        self.operation("Q", vec![]);
     }
 
     fn color_filter(&mut self, hue_rotation: f32, saturation_shift: f32, luminance_shift: f32, color_invert: bool) {
-        todo!()
+        self.color_filter_stack.push(PdfColorFilter {
+            hue_rotation,
+            saturation_shift,
+            luminance_shift,
+            color_invert,
+        });
     }
 
     fn pop_color_filter(&mut self) {
-        todo!()
+        assert!(self.color_filter_stack.pop().is_some(), "A color filter was popped, when no color filter is present.");
     }
 
     fn clip(&mut self, bounding_box: Rect) {
-        println!("Clip");
-        let point1 = convert_position_to_point(bounding_box.top_left(), self.page_dimensions);
-        let point2 = convert_position_to_point(bounding_box.top_right(), self.page_dimensions);
-        let point3 = convert_position_to_point(bounding_box.bottom_right(), self.page_dimensions);
-        let point4 = convert_position_to_point(bounding_box.bottom_left(), self.page_dimensions);
-
-        self.pdf_layer_reference.add_operation(Operation::new(
-            OP_PATH_CONST_MOVE_TO,
-            vec![point1.x.into(), point1.y.into()],
-        ));
-        self.pdf_layer_reference.add_operation(Operation::new(
-            OP_PATH_CONST_LINE_TO,
-            vec![point2.x.into(), point2.y.into()],
-        ));
-        self.pdf_layer_reference.add_operation(Operation::new(
-            OP_PATH_CONST_LINE_TO,
-            vec![point3.x.into(), point3.y.into()],
-        ));
-        self.pdf_layer_reference.add_operation(Operation::new(
-            OP_PATH_CONST_LINE_TO,
-            vec![point4.x.into(), point4.y.into()],
-        ));
-        self.pdf_layer_reference.add_operation(Operation::new(
-            OP_PATH_CONST_LINE_TO,
-            vec![point1.x.into(), point1.y.into()],
-        ));
-
-        //current_layer.add_operation(Operation::new(OP_PATH_CONST_RECT, vec![100.0.into(), 100.0.into(), 100.0.into(), 100.0.into()]));
-        self.pdf_layer_reference.add_operation(Operation::new("q W n", vec![]));
+        self.operation("q", vec![]);
+        self.operation("re", reals(&[
+            bounding_box.position.x,
+            bounding_box.position.y,
+            bounding_box.width(),
+            bounding_box.height(),
+        ]));
+        self.operation("W", vec![]);
+        self.operation("n", vec![]);
     }
 
     fn pop_clip(&mut self) {
-        println!("Pop clip");
-        self.pdf_layer_reference.add_operation(Operation::new("Q", vec![]));
+        self.operation("Q", vec![]);
     }
 
-    fn filter(&mut self, id: FilterId, bounding_box: Rect) {
-        todo!()
-    }
+    fn filter(&mut self, _filter: &ImageFilter, _bounding_box: Rect) {}
 
-    fn filter2d(&mut self, id1: FilterId, bounding_box1: Rect, id2: FilterId, bounding_box2: Rect) {
-        todo!()
-    }
+    fn filter2d(&mut self, _filter1: &ImageFilter, _bounding_box1: Rect, _filter2: &ImageFilter, _bounding_box2: Rect) {}
 
-    fn stencil(&mut self, geometry: &[Triangle<Position>]) {
-        println!("Stencil");
-        for triangle in geometry {
-            let point1 = convert_position_to_point(triangle.points()[0], self.page_dimensions);
-            let point2 = convert_position_to_point(triangle.points()[1], self.page_dimensions);
-            let point3 = convert_position_to_point(triangle.points()[2], self.page_dimensions);
+    fn stencil(&mut self, shape: CompositeDrawShape) {
+        self.operation("q", vec![]);
 
-            self.pdf_layer_reference.add_operation(Operation::new(
-                OP_PATH_CONST_MOVE_TO,
-                vec![point1.x.into(), point1.y.into()],
-            ));
+        let shapes = match shape {
+            CompositeDrawShape::Zero => vec![],
+            CompositeDrawShape::One(shape, options) => vec![(shape, options)],
+            CompositeDrawShape::Many(shapes) => shapes,
+        };
+
+        if shapes.is_empty() {
+            // An empty stencil hides everything drawn within it.
+            self.operation("re", reals(&[0.0, 0.0, 0.0, 0.0]));
+        }
 
-            self.pdf_layer_reference.add_operation(Operation::new(
-                OP_PATH_CONST_LINE_TO,
-                vec![point2.x.into(), point2.y.into()],
-            ));
-            self.pdf_layer_reference.add_operation(Operation::new(
-                OP_PATH_CONST_LINE_TO,
-                vec![point3.x.into(), point3.y.into()],
-            ));
-            self.pdf_layer_reference.add_operation(Operation::new(
-                OP_PATH_CONST_LINE_TO,
-                vec![point1.x.into(), point1.y.into()],
-            ));
+        for (shape, _) in &shapes {
+            self.operations(shape_operations(shape));
         }
 
-        self.pdf_layer_reference.add_operation(Operation::new("q W n", vec![]));
+        self.operation("W", vec![]);
+        self.operation("n", vec![]);
     }
 
     fn pop_stencil(&mut self) {
-        println!("DeStencil");
-        self.pdf_layer_reference.add_operation(Operation::new("Q", vec![]));
+        self.operation("Q", vec![]);
     }
 
-    fn geometry(&mut self, geometry: &[Triangle<Position>]) {
-        println!("Draw geometry: {:#?}", geometry);
-        for triangle in geometry {
-            let point1 = convert_position_to_point(triangle.points()[0], self.page_dimensions);
-            let point2 = convert_position_to_point(triangle.points()[1], self.page_dimensions);
-            let point3 = convert_position_to_point(triangle.points()[2], self.page_dimensions);
-
-            self.pdf_layer_reference.add_operation(Operation::new(
-                OP_PATH_CONST_MOVE_TO,
-                vec![point1.x.into(), point1.y.into()],
-            ));
-
-            self.pdf_layer_reference.add_operation(Operation::new(
-                OP_PATH_CONST_LINE_TO,
-                vec![point2.x.into(), point2.y.into()],
-            ));
-            self.pdf_layer_reference.add_operation(Operation::new(
-                OP_PATH_CONST_LINE_TO,
-                vec![point3.x.into(), point3.y.into()],
-            ));
-            self.pdf_layer_reference.add_operation(Operation::new(
-                OP_PATH_CONST_LINE_TO,
-                vec![point1.x.into(), point1.y.into()],
-            ));
+    fn shape(&mut self, shape: &DrawShape, option: &DrawOptions) {
+        if self.suppressed() {
+            return;
         }
 
-        self.pdf_layer_reference.add_operation(Operation::new(OP_PATH_PAINT_FILL_NZ, vec![]));
+        match option {
+            DrawOptions::Fill(fill) => self.fill(shape, fill.fill_rule),
+            DrawOptions::Stroke(stroke) => self.stroke(shape, stroke),
+        }
     }
 
-    fn style(&mut self, style: DrawStyle) {
-        println!("Set style to: {:?}", style);
+    fn style(&mut self, style: &DrawStyle) {
         match style {
             DrawStyle::Color(color) => {
-                let color = Color::Rgb(Rgb::new(
-                    color.red() as f64,
-                    color.green() as f64,
-                    color.blue() as f64,
-                    None
-                ));
-                self.color_stack.push(color.clone());
-
-                self.pdf_layer_reference.set_fill_color(color);
+                self.style_stack.push(PdfStyle::Color(color.to_fsa()));
+            }
+            DrawStyle::Gradient(gradient) => {
+                self.style_stack.push(PdfStyle::Gradient(PdfGradient::convert(gradient)));
+            }
+            DrawStyle::MultiGradient(gradients) => {
+                // Only the top most gradient is drawn.
+                match gradients.last() {
+                    Some(gradient) => self.style_stack.push(PdfStyle::Gradient(PdfGradient::convert(gradient))),
+                    None => self.style_stack.push(PdfStyle::Color([0.0, 0.0, 0.0, 0.0])),
+                }
             }
-            DrawStyle::Gradient(_) => todo!(),
-            DrawStyle::MultiGradient(_) => todo!(),
         }
     }
 
     fn pop_style(&mut self) {
-        println!("Pop style");
-        self.color_stack.pop();
-        if let Some(color) = self.color_stack.last() {
-            self.pdf_layer_reference.set_fill_color(color.clone());
-        }
+        assert!(self.style_stack.pop().is_some(), "A style was popped, when no style is present.")
     }
 
-    fn image(&mut self, id: Option<ImageId>, bounding_box: Rect, source_rect: Rect, mode: u32) {
-        /*IMAGES.with(|images| {
-            let images = &*images.borrow();
-            let (image, width, height) = images.get(&id).unwrap();
+    fn stroke_dash_pattern(&mut self, pattern: Option<StrokeDashPattern>) {
+        self.stroke_dash_stack.push(pattern);
+    }
 
-            let width_px = Px(*width as usize * 2);
-            let height_px = Px(*height as usize * 2);
+    fn pop_stroke_dash_pattern(&mut self) {
+        self.stroke_dash_stack.pop();
+    }
 
-            let width = Mm::from(width_px.into_pt(300.0));
-            let height = Mm::from(height_px.into_pt(300.0));
+    fn raster_image(&mut self, id: &ImageId, bounding_box: Rect, options: ImageOptions) {
+        if self.suppressed() {
+            return;
+        }
 
-            let requested_width = bounding_box.width();
-            let requested_height = bounding_box.height();
+        let source_rect = options.source_rect.unwrap_or_else(|| Rect::new(Position::new(0.0, 0.0), Dimension::new(1.0, 1.0)));
 
-            let scaling_width = requested_width / width.0;
-            let scaling_height = requested_height / height.0;
+        let tint = match options.mode {
+            ImageMode::Image => None,
+            ImageMode::Icon => match self.current_style() {
+                PdfStyle::Color(color) => Some(color),
+                PdfStyle::Gradient(gradient) => Some(gradient.first_color()),
+            },
+        };
 
-            /*dbg!(width, height);
-            dbg!(requested_width, requested_height);
-            dbg!(scaling_width, scaling_height);*/
+        let Some(image) = IMAGES.with(|images| images.borrow().get(id).cloned()) else {
+            return;
+        };
 
-            let x = bounding_box.left() / 2.0;
-            let y = self.page_dimensions.height - bounding_box.top() / 2.0;
+        self.draw_image(&image, bounding_box, source_rect, tint);
+    }
+
+    fn text(&mut self, text: &str, style: &TextStyle, position: Position, requested_size: Option<Dimension>, _env: &mut Environment, _ctx: &mut dyn TextContext) {
+        if self.suppressed() {
+            return;
+        }
 
-            let owned_image = PdfImage { image: image.image.clone() };
+        let Some(color) = self.current_color() else {
+            return;
+        };
 
-            /*dbg!(
-                Mm(x), Mm(y), scaling_width, scaling_height
-            );*/
+        // The text is laid out again using our own font system, because we need to know the
+        // fonts and characters of each glyph, and not only the rasterized glyphs.
+        let width = requested_size.map(|x| x.width as f32).unwrap_or(f32::MAX);
 
-            owned_image.add_to_layer(self.pdf_layer_reference.clone(), ImageTransform {
-                translate_x: Some(Mm(x)),
-                translate_y: Some(Mm(y)),
-                scale_x: Some(scaling_width),
-                scale_y: Some(scaling_height),
-                ..Default::default()
-            });
-        })*/
-    }
+        let mut buffer = Buffer::new(&mut self.font_system, Metrics::new(style.font_size as f32, style.font_size as f32 * style.line_height as f32));
 
-    fn text(&mut self, text: TextId, ctx: &mut dyn InnerTextContext) {
-        /*self.pdf_layer_reference.begin_text_section();
+        {
+            let mut buffer = buffer.borrow_with(&mut self.font_system);
 
-        for glyph in text.iter() {
-            let point_x = glyph.position().x();
-            let point_y = self.page_dimensions.height - glyph.position().y();
-            //dbg!(glyph.font_size(), Mm::from(point1.x), Mm::from(point1.y));
-            self.pdf_layer_reference.set_font(&self.font, (glyph.font_size()) as f64);
-            self.pdf_layer_reference.set_text_cursor(Mm(point_x), Mm(point_y));
-            self.pdf_layer_reference.write_text(glyph.character(), &self.font);
-            self.pdf_layer_reference.set_text_cursor(Mm(-point_x), Mm(-point_y));
+            let attributes = Attrs::new()
+                .family(Family::Name(&style.family))
+                .style(convert_style(style))
+                .weight(convert_weight(style));
+
+            buffer.set_text(text, &attributes, Shaping::Advanced, None);
+            buffer.set_wrap(convert_wrap(style));
+            buffer.set_size(Some(width), None);
         }
 
-        self.pdf_layer_reference.end_text_section();*/
+        self.write_glyphs(&buffer, &[], position, color);
     }
 
-    fn filter_new(&mut self) {
-        todo!()
-    }
+    fn attributed_text(&mut self, text: &AttributedText, style: &TextStyle, position: Position, requested_size: Option<Dimension>, _env: &mut Environment, _ctx: &mut dyn TextContext) {
+        if self.suppressed() {
+            return;
+        }
 
-    fn filter_new_pop(&mut self, id: FilterId, color: carbide_core::color::Color, post_draw: bool) {
-        todo!()
+        let Some(color) = self.current_color() else {
+            return;
+        };
+
+        let width = requested_size.map(|x| x.width as f32).unwrap_or(f32::MAX);
+
+        let mut buffer = Buffer::new(&mut self.font_system, Metrics::new(style.font_size as f32, style.font_size as f32 * style.line_height as f32));
+
+        {
+            let mut buffer = buffer.borrow_with(&mut self.font_system);
+
+            set_buffer_text(&mut buffer, &text.text, &text.spans, style);
+            buffer.set_wrap(convert_wrap(style));
+            buffer.set_size(Some(width), None);
+        }
+
+        self.write_glyphs(&buffer, &text.spans, position, color);
     }
 
-    fn filter_new_pop2d(&mut self, id: FilterId, id2: FilterId, color: carbide_core::color::Color, post_draw: bool) {
-        todo!()
+    fn text_old(&mut self, _text: TextId, _ctx: &mut dyn TextContext) {
+        // The old text api only provides the rasterized glyphs, which can not be written as text.
     }
 
+    fn filter_new(&mut self) {}
+
+    fn filter_new_pop(&mut self, _filter: &ImageFilter, _color: Color, _post_draw: bool) {}
+
+    fn filter_new_pop2d(&mut self, _filter: &ImageFilter, _filter2: &ImageFilter, _color: Color, _post_draw: bool) {}
+
     fn mask_start(&mut self) {
-        todo!()
+        self.mask_stack.push(true);
     }
 
     fn mask_in(&mut self) {
-        todo!()
+        if let Some(drawing_mask) = self.mask_stack.last_mut() {
+            *drawing_mask = false;
+        }
     }
 
     fn mask_end(&mut self) {
-        todo!()
+        assert!(self.mask_stack.pop().is_some(), "A mask was ended, when no mask is present.");
+    }
+
+    fn layer(&mut self, layer_id: LayerId, dimensions: Dimension, _env: &mut Environment) -> Layer<'_> {
+        let width = dimensions.width.floor().max(1.0) as u32;
+        let height = dimensions.height.floor().max(1.0) as u32;
+
+        let layer = self.layers.entry(layer_id).or_insert_with(|| PdfLayer::new(width, height));
+
+        if layer.image.dimensions() != (width, height) {
+            *layer = PdfLayer::new(width, height);
+        }
+
+        Layer {
+            inner: layer,
+            inner2: layer,
+        }
+    }
+
+    fn render_layer(&mut self, layer_id: LayerId, bounding_box: Rect) {
+        if self.suppressed() {
+            return;
+        }
+
+        let Some(layer) = self.layers.remove(&layer_id) else {
+            return;
+        };
+
+        self.draw_image(&layer.image, bounding_box, Rect::new(Position::new(0.0, 0.0), Dimension::new(1.0, 1.0)), None);
+
+        self.layers.insert(layer_id, layer);
+    }
+}
+
+fn real(value: Scalar) -> Object {
+    Object::Real((value as f32).into())
+}
+
+fn reals(values: &[Scalar]) -> Vec<Object> {
+    values.iter().map(|value| real(*value)).collect()
+}
+
+fn line_cap(cap: LineCap) -> i64 {
+    match cap {
+        LineCap::Butt => 0,
+        LineCap::Round => 1,
+        LineCap::Square => 2,
     }
 }
 
+fn convert_style(style: &TextStyle) -> Style {
+    match style.font_style {
+        FontStyle::Normal => Style::Normal,
+        FontStyle::Italic => Style::Italic,
+    }
+}
 
-fn convert_position_to_point(position: Position, page_dimensions: Dimension) -> Point {
-    let x = Mm(position.x / 2.0);
-    let y = Mm(page_dimensions.height - position.y / 2.0);
-    //println!("{:?}, {:?}", x, y);
-    Point::new(x, y)
-}*/
\ No newline at end of file
+fn convert_weight(style: &TextStyle) -> Weight {
+    Weight(style.font_weight.weight())
+}
+
+fn convert_wrap(style: &TextStyle) -> cosmic_text::Wrap {
+    match style.wrap {
+        Wrap::Character => cosmic_text::Wrap::Glyph,
+        Wrap::Whitespace => cosmic_text::Wrap::Word,
+        Wrap::None => cosmic_text::Wrap::None,
+    }
+}
+
+/// A builder of the path construction operations of a PDF content stream.
+struct PathOperations {
+    operations: Vec<Operation>,
+    current: Option<Position>,
+}
+
+impl PathOperations {
+    fn new() -> PathOperations {
+        PathOperations {
+            operations: vec![],
+            current: None,
+        }
+    }
+
+    fn move_to(&mut self, to: Position) {
+        self.operations.push(Operation::new("m", reals(&[to.x, to.y])));
+        self.current = Some(to);
+    }
+
+    fn line_to(&mut self, to: Position) {
+        self.operations.push(Operation::new("l", reals(&[to.x, to.y])));
+        self.current = Some(to);
+    }
+
+    fn cubic_to(&mut self, ctrl1: Position, ctrl2: Position, to: Position) {
+        self.operations.push(Operation::new("c", reals(&[ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y])));
+        self.current = Some(to);
+    }
+
+    fn quad_to(&mut self, ctrl: Position, to: Position) {
+        let from = self.current.unwrap_or(ctrl);
+
+        // A quadratic bezier is exactly represented by a cubic bezier, with the control
+        // points two thirds of the way towards the quadratic control point.
+        let ctrl1 = from + (ctrl - from) * (2.0 / 3.0);
+        let ctrl2 = to + (ctrl - to) * (2.0 / 3.0);
+
+        self.cubic_to(ctrl1, ctrl2, to);
+    }
+
+    fn close(&mut self) {
+        self.operations.push(Operation::new("h", vec![]));
+    }
+
+    /// Append an elliptical arc to the path, approximated by cubic beziers of at most 90 degrees each.
+    /// If the path has a current point, a line is drawn to the start of the arc.
+    fn arc(&mut self, center: Position, radius: Dimension, start: Scalar, end: Scalar) {
+        let point = |angle: Scalar| Position::new(
+            center.x + radius.width * angle.cos(),
+            center.y + radius.height * angle.sin(),
+        );
+
+        if self.current.is_some() {
+            self.line_to(point(start));
+        } else {
+            self.move_to(point(start));
+        }
+
+        let sweep = end - start;
+        let segments = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+        let step = sweep / segments as Scalar;
+        let k = 4.0 / 3.0 * (step / 4.0).tan();
+
+        for i in 0..segments {
+            let from = start + step * i as Scalar;
+            let to = from + step;
+
+            let ctrl1 = Position::new(
+                center.x + radius.width * (from.cos() - k * from.sin()),
+                center.y + radius.height * (from.sin() + k * from.cos()),
+            );
+            let ctrl2 = Position::new(
+                center.x + radius.width * (to.cos() + k * to.sin()),
+                center.y + radius.height * (to.sin() - k * to.cos()),
+            );
+
+            self.cubic_to(ctrl1, ctrl2, point(to));
+        }
+    }
+
+    fn ellipse(&mut self, center: Position, radius: Dimension) {
+        self.current = None;
+        self.arc(center, radius, 0.0, std::f64::consts::TAU);
+        self.close();
+    }
+}
+
+/// Convert a shape into the operations constructing the path of the shape.
+fn shape_operations(shape: &DrawShape) -> Vec<Operation> {
+    let mut path = PathOperations::new();
+
+    match shape {
+        DrawShape::Rectangle(rect) => {
+            path.operations.push(Operation::new("re", reals(&[rect.position.x, rect.position.y, rect.width(), rect.height()])));
+        }
+        DrawShape::Capsule(rect) => {
+            let radius = rect.width().min(rect.height()) / 2.0;
+            rounded_rectangle(&mut path, rect, &CornerRadii::all(radius));
+        }
+        DrawShape::RoundedRectangle(rect, corners) => {
+            rounded_rectangle(&mut path, rect, corners);
+        }
+        DrawShape::Circle(center, radius) => {
+            path.ellipse(*center, Dimension::new(*radius, *radius));
+        }
+        DrawShape::Ellipse(rect) => {
+            path.ellipse(rect.center(), rect.dimension / 2.0);
+        }
+        DrawShape::Line(from, to) => {
+            path.move_to(*from);
+            path.line_to(*to);
+        }
+        DrawShape::Path(shape_path) => {
+            for instruction in &shape_path.instructions {
+                match instruction {
+                    PathInstruction::MoveTo { to } => path.move_to(*to),
+                    PathInstruction::Close => path.close(),
+                    PathInstruction::LineTo { to } => path.line_to(*to),
+                    PathInstruction::QuadraticBezierTo { ctrl, to } => path.quad_to(*ctrl, *to),
+                    PathInstruction::CubicBezierTo { ctrl1, ctrl2, to } => path.cubic_to(*ctrl1, *ctrl2, *to),
+                    PathInstruction::Arc { center, radius, start_angle, end_angle } => {
+                        path.arc(*center, *radius, start_angle.radians(), end_angle.radians());
+                    }
+                }
+            }
+        }
+    }
+
+    path.operations
+}
+
+fn polygon_operations(polygon: &[Position]) -> Vec<Operation> {
+    let mut path = PathOperations::new();
+
+    for (index, point) in polygon.iter().enumerate() {
+        if index == 0 {
+            path.move_to(*point);
+        } else {
+            path.line_to(*point);
+        }
+    }
+
+    path.close();
+    path.operations
+}
+
+fn rounded_rectangle(path: &mut PathOperations, rect: &Rect, corners: &CornerRadii) {
+    let (left, right, top, bottom) = (
+        rect.left(),
+        rect.right(),
+        rect.bottom(),
+        rect.top(),
+    );
+
+    let max = rect.width().min(rect.height()) / 2.0;
+    let clamp = |radius: Scalar| radius.clamp(0.0, max);
+
+    let top_left = clamp(corners.top_left);
+    let top_right = clamp(corners.top_right);
+    let bottom_left = clamp(corners.bottom_left);
+    let bottom_right = clamp(corners.bottom_right);
+
+    let point = |x: Scalar, y: Scalar| Position::new(x, y);
+
+    path.move_to(point(left + top_left, top));
+    path.line_to(point(right - top_right, top));
+    path.cubic_to(point(right - top_right * (1.0 - KAPPA), top), point(right, top + top_right * (1.0 - KAPPA)), point(right, top + top_right));
+    path.line_to(point(right, bottom - bottom_right));
+    path.cubic_to(point(right, bottom - bottom_right * (1.0 - KAPPA)), point(right - bottom_right * (1.0 - KAPPA), bottom), point(right - bottom_right, bottom));
+    path.line_to(point(left + bottom_left, bottom));
+    path.cubic_to(point(left + bottom_left * (1.0 - KAPPA), bottom), point(left, bottom - bottom_left * (1.0 - KAPPA)), point(left, bottom - bottom_left));
+    path.line_to(point(left, top + top_left));
+    path.cubic_to(point(left, top + top_left * (1.0 - KAPPA)), point(left + top_left * (1.0 - KAPPA), top), point(left + top_left, top));
+    path.close();
+}
+
+/// The bounds of the shape, used to cover the shape when drawing gradients.
+fn shape_bounds(shape: &DrawShape) -> Rect {
+    let from_points = |points: &mut dyn Iterator<Item=Position>| {
+        let mut min = Position::new(Scalar::MAX, Scalar::MAX);
+        let mut max = Position::new(Scalar::MIN, Scalar::MIN);
+
+        for point in points {
+            min = Position::new(min.x.min(point.x), min.y.min(point.y));
+            max = Position::new(max.x.max(point.x), max.y.max(point.y));
+        }
+
+        if min.x > max.x {
+            return Rect::default();
+        }
+
+        Rect::new(min, Dimension::new(max.x - min.x, max.y - min.y))
+    };
+
+    match shape {
+        DrawShape::Rectangle(rect) |
+        DrawShape::Capsule(rect) |
+        DrawShape::RoundedRectangle(rect, _) |
+        DrawShape::Ellipse(rect) => *rect,
+        DrawShape::Circle(center, radius) => {
+            Rect::new(*center - Position::new(*radius, *radius), Dimension::new(radius * 2.0, radius * 2.0))
+        }
+        DrawShape::Line(from, to) => from_points(&mut [*from, *to].into_iter()),
+        DrawShape::Path(path) => {
+            from_points(&mut path.instructions.iter().flat_map(|instruction| match instruction {
+                PathInstruction::MoveTo { to } |
+                PathInstruction::LineTo { to } => vec![*to],
+                PathInstruction::Close => vec![],
+                PathInstruction::QuadraticBezierTo { ctrl, to } => vec![*ctrl, *to],
+                PathInstruction::CubicBezierTo { ctrl1, ctrl2, to } => vec![*ctrl1, *ctrl2, *to],
+                PathInstruction::Arc { center, radius, .. } => vec![
+                    *center - Position::new(radius.width, radius.height),
+                    *center + Position::new(radius.width, radius.height),
+                ],
+            }))
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use carbide_core::color::{BLUE, ORANGE};
+use carbide_core::draw::Dimension;
+use carbide_core::environment::Environment;
+use carbide_core::widget::*;
+use carbide_svg::SvgRenderer;
+
+fn main() {
+    let mut env = Environment::new();
+    let mut renderer = SvgRenderer::new();
+
+    let mut widget = VStack::new((
+        Text::new("Hello world!"),
+        Circle::new().fill(ORANGE).frame(50.0, 50.0),
+    )).padding(10.0)
+        .background(RoundedRectangle::new(8.0).fill(BLUE));
+
+    renderer.save(&mut widget, Dimension::new(200.0, 150.0), &mut env, "target/export.svg").unwrap();
+}
@@ -0,0 +1,13 @@
+//! A vector renderer for carbide, writing the widgets as svg documents. This can be used to
+//! export widgets as scalable graphics, for example for illustrations or printing.
+
+pub use image_context::SvgImageContext;
+pub use render_context::SvgRenderContext;
+pub use renderer::SvgRenderer;
+pub use svg_layer::SvgLayer;
+
+mod image_context;
+mod render_context;
+mod renderer;
+mod svg_element;
+mod svg_layer;
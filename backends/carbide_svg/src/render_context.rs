@@ -0,0 +1,912 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::io::Cursor;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use cosmic_text::{Attrs, Buffer, Family, FontSystem, Metrics, Shaping, Style, Weight};
+use image::{ImageFormat, RgbaImage};
+use image::imageops::crop_imm;
+
+use carbide_core::color::{hsl_to_rgb, rgb_to_hsl, ColorExt, WHITE};
+use carbide_core::draw::{Color, CompositeDrawShape, Dimension, DrawGradient, DrawOptions, DrawShape, DrawStyle, ImageId, ImageMode, ImageOptions, Position, Rect, Scalar};
+use carbide_core::draw::fill::FillRule;
+use carbide_core::draw::gradient::{GradientRepeat, GradientType};
+use carbide_core::draw::stroke::{LineCap, LineJoin, StrokeAlignment, StrokeDashCap, StrokeDashPattern, StrokeOptions};
+use carbide_core::environment::Environment;
+use carbide_core::math::Matrix4;
+use carbide_core::render::{InnerRenderContext, Layer, LayerId};
+use carbide_core::text::{AttributedText, FontStyle, TextContext, TextDecoration, TextId, TextSpan, TextStyle};
+use carbide_core::text::text_wrap::Wrap;
+use carbide_core::widget::ImageFilter;
+use carbide_cosmic_text::text_context::{baseline_offset, set_buffer_text, span_of};
+
+use crate::image_context::IMAGES;
+use crate::svg_element::{color_attributes, escape, number, shape_element};
+use crate::svg_layer::SvgLayer;
+
+/// The region in user space covered by masks. Masks have no natural bounds, so the region is
+/// chosen to be larger than any reasonable document.
+const MASK_REGION: &str = r#"maskUnits="userSpaceOnUse" x="-100000" y="-100000" width="200000" height="200000""#;
+
+#[derive(Debug, Clone, PartialEq)]
+enum SvgStyle {
+    Color([f32; 4]),
+    Gradient(DrawGradient),
+}
+
+/// The color filter currently applied, given as shifts relative to the original colors.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct SvgColorFilter {
+    /// Hue rotation given in turns.
+    hue_rotation: f32,
+    saturation_shift: f32,
+    luminance_shift: f32,
+    color_invert: bool,
+}
+
+impl SvgColorFilter {
+    fn apply(&self, [r, g, b, a]: [f32; 4]) -> [f32; 4] {
+        let (r, g, b) = if self.color_invert {
+            (1.0 - r, 1.0 - g, 1.0 - b)
+        } else {
+            (r, g, b)
+        };
+
+        let (h, s, l) = rgb_to_hsl(r, g, b);
+
+        let turn = std::f32::consts::TAU;
+        let h = (h / turn + self.hue_rotation).rem_euclid(1.0) * turn;
+        let s = (s + self.saturation_shift).clamp(0.0, 1.0);
+        let l = (l + self.luminance_shift).clamp(0.0, 1.0);
+
+        let (r, g, b) = hsl_to_rgb(h, s, l);
+
+        [r, g, b, a]
+    }
+}
+
+/// A render context that writes the rendering as an svg document. Shapes are written as
+/// shape and path elements, gradients as gradient definitions, clips and stencils as clip
+/// paths, masks as masks and text as text elements using the font attributes of the style.
+/// Raster images and layers are embedded as png images.
+///
+/// Some effects are not expressible in svg, and are approximated:
+/// * Conic gradients are drawn using their first color, and diamond gradients as radial gradients.
+/// * Color filters are applied to the colors and images when written, and not as filters.
+/// * Backdrop filters are not drawn.
+pub struct SvgRenderContext {
+    dimension: Dimension,
+    defs: String,
+    /// The bodies currently being written. The first is the body of the document, and the
+    /// others are the contents of masks and filters being drawn.
+    bodies: Vec<String>,
+    next_id: usize,
+    gradients: HashMap<String, String>,
+    style_stack: Vec<SvgStyle>,
+    stroke_dash_stack: Vec<Option<StrokeDashPattern>>,
+    color_filter_stack: Vec<SvgColorFilter>,
+    layers: HashMap<LayerId, SvgLayer>,
+    font_system: FontSystem,
+}
+
+impl SvgRenderContext {
+    pub fn new() -> SvgRenderContext {
+        SvgRenderContext {
+            dimension: Dimension::new(0.0, 0.0),
+            defs: String::new(),
+            bodies: vec![String::new()],
+            next_id: 0,
+            gradients: HashMap::new(),
+            style_stack: vec![],
+            stroke_dash_stack: vec![],
+            color_filter_stack: vec![],
+            layers: HashMap::new(),
+            font_system: FontSystem::new(),
+        }
+    }
+
+    /// Add a font that can be used when laying out text. The documents refer to fonts by
+    /// family name, so the font should also be available where the document is shown.
+    pub fn add_font_from_bytes(&mut self, bytes: Vec<u8>) {
+        self.font_system.db_mut().load_font_data(bytes);
+    }
+
+    /// Start a new document with the given dimension. This clears everything written before.
+    pub fn start(&mut self, dimension: Dimension) {
+        self.dimension = dimension;
+        self.defs.clear();
+        self.bodies = vec![String::new()];
+        self.next_id = 0;
+        self.gradients.clear();
+        self.style_stack.clear();
+        self.stroke_dash_stack.clear();
+        self.color_filter_stack.clear();
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.dimension
+    }
+
+    /// The document written since the last call to start.
+    pub fn document(&self) -> String {
+        let width = number(self.dimension.width);
+        let height = number(self.dimension.height);
+
+        let mut document = String::new();
+
+        let _ = write!(
+            document,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width,
+            height,
+        );
+
+        if !self.defs.is_empty() {
+            let _ = write!(document, "<defs>{}</defs>", self.defs);
+        }
+
+        document.push_str(&self.bodies[0]);
+        document.push_str("</svg>\n");
+
+        document
+    }
+
+    fn write(&mut self, element: &str) {
+        self.bodies.last_mut().expect("The document body to be present").push_str(element);
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}{}", prefix, self.next_id)
+    }
+
+    fn current_style(&self) -> SvgStyle {
+        self.style_stack.last()
+            .cloned()
+            .unwrap_or(SvgStyle::Color(WHITE.to_fsa()))
+    }
+
+    fn filtered_color(&self, color: [f32; 4]) -> [f32; 4] {
+        self.color_filter_stack.iter()
+            .rev()
+            .fold(color, |color, filter| filter.apply(color))
+    }
+
+    /// The paint of the current style and its opacity. Returns `None` if nothing would be
+    /// visible when painting.
+    fn paint(&mut self) -> Option<(String, f32)> {
+        match self.current_style() {
+            SvgStyle::Color(color) => {
+                let (hex, opacity) = color_attributes(self.filtered_color(color));
+
+                if opacity <= 0.0 {
+                    return None;
+                }
+
+                Some((hex, opacity))
+            }
+            SvgStyle::Gradient(gradient) => {
+                if gradient.gradient_type == GradientType::Conic {
+                    let first = gradient.colors.first().map(|color| color.to_fsa()).unwrap_or([0.0, 0.0, 0.0, 0.0]);
+                    let (hex, opacity) = color_attributes(self.filtered_color(first));
+
+                    return Some((hex, opacity));
+                }
+
+                Some((format!("url(#{})", self.gradient(&gradient)), 1.0))
+            }
+        }
+    }
+
+    /// Get the id of the definition of the gradient with the current color filters applied.
+    /// Equal gradients share a single definition.
+    fn gradient(&mut self, gradient: &DrawGradient) -> String {
+        let spread = match gradient.gradient_repeat {
+            GradientRepeat::Clamp => "pad",
+            GradientRepeat::Repeat => "repeat",
+            GradientRepeat::Mirror => "reflect",
+        };
+
+        let mut definition = match gradient.gradient_type {
+            GradientType::Linear => format!(
+                r#"gradientUnits="userSpaceOnUse" spreadMethod="{}" x1="{}" y1="{}" x2="{}" y2="{}">"#,
+                spread,
+                number(gradient.start.x),
+                number(gradient.start.y),
+                number(gradient.end.x),
+                number(gradient.end.y),
+            ),
+            GradientType::Radial | GradientType::Diamond | GradientType::Conic => format!(
+                r#"gradientUnits="userSpaceOnUse" spreadMethod="{}" cx="{1}" cy="{2}" fx="{1}" fy="{2}" r="{3}">"#,
+                spread,
+                number(gradient.start.x),
+                number(gradient.start.y),
+                number(gradient.start.dist(&gradient.end)),
+            ),
+        };
+
+        for (color, ratio) in gradient.colors.iter().zip(gradient.ratios.iter()) {
+            let (hex, opacity) = color_attributes(self.filtered_color(color.to_fsa()));
+
+            let _ = write!(
+                definition,
+                r#"<stop offset="{}" stop-color="{}" stop-opacity="{}"/>"#,
+                number(*ratio as Scalar),
+                hex,
+                number(opacity as Scalar),
+            );
+        }
+
+        if let Some(id) = self.gradients.get(&definition) {
+            return id.clone();
+        }
+
+        let id = self.next_id("gradient");
+
+        match gradient.gradient_type {
+            GradientType::Linear => {
+                let _ = write!(self.defs, r#"<linearGradient id="{}" {}</linearGradient>"#, id, definition);
+            }
+            GradientType::Radial | GradientType::Diamond | GradientType::Conic => {
+                let _ = write!(self.defs, r#"<radialGradient id="{}" {}</radialGradient>"#, id, definition);
+            }
+        }
+
+        self.gradients.insert(definition, id.clone());
+
+        id
+    }
+
+    fn fill(&mut self, shape: &DrawShape, rule: FillRule) {
+        let Some((paint, opacity)) = self.paint() else {
+            return;
+        };
+
+        let mut element = shape_element(shape);
+        let _ = write!(element, r#" fill="{}""#, paint);
+
+        if opacity < 1.0 {
+            let _ = write!(element, r#" fill-opacity="{}""#, number(opacity as Scalar));
+        }
+
+        if rule == FillRule::EvenOdd {
+            element.push_str(r#" fill-rule="evenodd""#);
+        }
+
+        element.push_str("/>");
+
+        self.write(&element);
+    }
+
+    fn stroke(&mut self, shape: &DrawShape, options: &StrokeOptions) {
+        let Some((paint, opacity)) = self.paint() else {
+            return;
+        };
+
+        // Strokes aligned to one side of the path are drawn with the double width, and
+        // clipped or masked to the inside or outside of the path respectively.
+        let width = match options.stroke_alignment {
+            StrokeAlignment::Center => options.stroke_width,
+            StrokeAlignment::Positive | StrokeAlignment::Negative => options.stroke_width * 2.0,
+        };
+
+        let mut element = shape_element(shape);
+
+        let _ = write!(element, r#" fill="none" stroke="{}" stroke-width="{}""#, paint, number(width));
+
+        if opacity < 1.0 {
+            let _ = write!(element, r#" stroke-opacity="{}""#, number(opacity as Scalar));
+        }
+
+        let mut cap = line_cap(options.start_cap);
+
+        match options.stroke_join {
+            LineJoin::Miter => {}
+            LineJoin::MiterClip { miter_limit } => {
+                let _ = write!(element, r#" stroke-miterlimit="{}""#, number(miter_limit));
+            }
+            LineJoin::Round => element.push_str(r#" stroke-linejoin="round""#),
+            LineJoin::Bevel => element.push_str(r#" stroke-linejoin="bevel""#),
+        }
+
+        if let Some(Some(pattern)) = self.stroke_dash_stack.last() {
+            let mut dashes = pattern.pattern.clone();
+
+            // An odd number of dashes is repeated by svg, but we write the even list to be explicit.
+            if dashes.len() % 2 == 1 {
+                dashes.extend_from_within(..);
+            }
+
+            let dashes = dashes.iter().map(|dash| number(*dash)).collect::<Vec<_>>().join(" ");
+
+            let _ = write!(element, r#" stroke-dasharray="{}" stroke-dashoffset="{}""#, dashes, number(pattern.offset));
+
+            cap = match pattern.start_cap {
+                StrokeDashCap::None => "butt",
+                StrokeDashCap::Round => "round",
+                StrokeDashCap::Square => "square",
+                StrokeDashCap::TriangleIn |
+                StrokeDashCap::TriangleOut => "butt",
+            };
+        }
+
+        if cap != "butt" {
+            let _ = write!(element, r#" stroke-linecap="{}""#, cap);
+        }
+
+        element.push_str("/>");
+
+        match options.stroke_alignment {
+            StrokeAlignment::Center => {
+                self.write(&element);
+            }
+            StrokeAlignment::Positive => {
+                let id = self.next_id("clip");
+                let _ = write!(self.defs, r#"<clipPath id="{}">{}/></clipPath>"#, id, shape_element(shape));
+
+                self.write(&format!(r#"<g clip-path="url(#{})">{}</g>"#, id, element));
+            }
+            StrokeAlignment::Negative => {
+                let id = self.next_id("mask");
+                let _ = write!(
+                    self.defs,
+                    r##"<mask id="{}" {}><rect x="-100000" y="-100000" width="200000" height="200000" fill="#ffffff"/>{} fill="#000000"/></mask>"##,
+                    id,
+                    MASK_REGION,
+                    shape_element(shape),
+                );
+
+                self.write(&format!(r#"<g mask="url(#{})">{}</g>"#, id, element));
+            }
+        }
+    }
+
+    /// Write an area of the image within the bounding box as an embedded png. The source rect
+    /// is given in normalized coordinates of the image. If a tint is given, the image is drawn
+    /// in the tint color, using the alpha of the image.
+    fn draw_image(&mut self, image: &RgbaImage, bounding_box: Rect, source_rect: Rect, tint: Option<[f32; 4]>) {
+        if bounding_box.width() <= 0.0 || bounding_box.height() <= 0.0 {
+            return;
+        }
+
+        let (width, height) = (image.width() as Scalar, image.height() as Scalar);
+
+        let left = (source_rect.position.x * width).round().clamp(0.0, width) as u32;
+        let top = (source_rect.position.y * height).round().clamp(0.0, height) as u32;
+        let right = ((source_rect.position.x + source_rect.width()) * width).round().clamp(0.0, width) as u32;
+        let bottom = ((source_rect.position.y + source_rect.height()) * height).round().clamp(0.0, height) as u32;
+
+        if right <= left || bottom <= top {
+            return;
+        }
+
+        let mut source = crop_imm(image, left, top, right - left, bottom - top).to_image();
+
+        if tint.is_some() || !self.color_filter_stack.is_empty() {
+            for pixel in source.pixels_mut() {
+                let [r, g, b, a] = pixel.0.map(|channel| channel as f32 / 255.0);
+
+                let color = match tint {
+                    Some(tint) => [tint[0], tint[1], tint[2], tint[3] * a],
+                    None => [r, g, b, a],
+                };
+
+                pixel.0 = self.filtered_color(color).map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+        }
+
+        let mut png = vec![];
+
+        if source.write_to(&mut Cursor::new(&mut png), ImageFormat::Png).is_err() {
+            return;
+        }
+
+        let element = format!(
+            r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none" href="data:image/png;base64,{}"/>"#,
+            number(bounding_box.position.x),
+            number(bounding_box.position.y),
+            number(bounding_box.width()),
+            number(bounding_box.height()),
+            STANDARD.encode(png),
+        );
+
+        self.write(&element);
+    }
+
+    /// Write the kernel of the filter as a convolution matrix primitive, reading from the input
+    /// and storing the result under the given name.
+    fn convolve_primitive(filter: &ImageFilter, input: &str, result: &str) -> String {
+        if filter.filter.is_empty() {
+            return format!(r#"<feOffset in="{}" result="{}"/>"#, input, result);
+        }
+
+        let min_x = filter.filter.iter().map(|value| value.offset_x).min().unwrap_or(0);
+        let max_x = filter.filter.iter().map(|value| value.offset_x).max().unwrap_or(0);
+        let min_y = filter.filter.iter().map(|value| value.offset_y).min().unwrap_or(0);
+        let max_y = filter.filter.iter().map(|value| value.offset_y).max().unwrap_or(0);
+
+        let order_x = (max_x - min_x + 1) as usize;
+        let order_y = (max_y - min_y + 1) as usize;
+
+        let mut kernel = vec![0.0f32; order_x * order_y];
+
+        // The kernel of a convolution matrix is rotated half a turn relative to the offsets
+        // of our filters, which sample the source at the offset from the pixel.
+        for value in &filter.filter {
+            let column = order_x - 1 - (value.offset_x - min_x) as usize;
+            let row = order_y - 1 - (value.offset_y - min_y) as usize;
+
+            kernel[row * order_x + column] += value.weight;
+        }
+
+        let kernel = kernel.iter().map(|weight| number(*weight as Scalar)).collect::<Vec<_>>().join(" ");
+
+        format!(
+            r#"<feConvolveMatrix in="{}" result="{}" order="{} {}" kernelMatrix="{}" divisor="1" targetX="{}" targetY="{}" edgeMode="none"/>"#,
+            input,
+            result,
+            order_x,
+            order_y,
+            kernel,
+            -min_x,
+            -min_y,
+        )
+    }
+
+    fn finish_filter(&mut self, filters: &[&ImageFilter], color: Color, post_draw: bool) {
+        assert!(self.bodies.len() > 1, "A filter was popped, when no filter is started.");
+
+        let content = self.bodies.pop().unwrap();
+        let id = self.next_id("filter");
+
+        let _ = write!(self.defs, r#"<filter id="{}" x="-50%" y="-50%" width="200%" height="200%">"#, id);
+
+        let mut input = "SourceGraphic".to_string();
+
+        for (index, filter) in filters.iter().enumerate() {
+            let result = format!("convolved{}", index);
+            let primitive = Self::convolve_primitive(filter, &input, &result);
+
+            self.defs.push_str(&primitive);
+            input = result;
+        }
+
+        if post_draw {
+            // The filtered content is tinted with the color, and the source is drawn on top.
+            let (hex, opacity) = color_attributes(self.filtered_color(color.to_fsa()));
+
+            let _ = write!(
+                self.defs,
+                r#"<feFlood flood-color="{}" flood-opacity="{}"/><feComposite in2="{}" operator="in"/><feMerge><feMergeNode/><feMergeNode in="SourceGraphic"/></feMerge>"#,
+                hex,
+                number(opacity as Scalar),
+                input,
+            );
+        }
+
+        self.defs.push_str("</filter>");
+
+        self.write(&format!(r#"<g filter="url(#{})">{}</g>"#, id, content));
+    }
+
+    /// Lay out the text using our own font system, because we need to know the characters of
+    /// each line, and not only the rasterized glyphs.
+    fn text_lines(&mut self, text: &str, style: &TextStyle, requested_size: Option<Dimension>) -> Vec<(Scalar, Scalar, String)> {
+        let width = requested_size.map(|x| x.width as f32).unwrap_or(f32::MAX);
+
+        let mut buffer = Buffer::new(&mut self.font_system, Metrics::new(style.font_size as f32, style.font_size as f32 * style.line_height as f32));
+
+        {
+            let mut buffer = buffer.borrow_with(&mut self.font_system);
+
+            let attributes = Attrs::new()
+                .family(Family::Name(&style.family))
+                .style(convert_style(style))
+                .weight(Weight(style.font_weight.weight()));
+
+            buffer.set_text(text, &attributes, Shaping::Advanced, None);
+            buffer.set_wrap(convert_wrap(style));
+            buffer.set_size(Some(width), None);
+        }
+
+        buffer.layout_runs()
+            .filter_map(|run| {
+                let first = run.glyphs.first()?;
+                let last = run.glyphs.last()?;
+
+                let line = run.text[first.start.min(last.start)..first.end.max(last.end)].to_string();
+
+                Some((first.x as Scalar, run.line_y as Scalar, line))
+            })
+            .collect()
+    }
+
+    /// Lay out the attributed text, and get the consecutive glyphs of each line that are part
+    /// of the same span, with the offset of their first glyph and their baseline.
+    fn text_segments<'a>(&mut self, text: &'a AttributedText, style: &TextStyle, requested_size: Option<Dimension>) -> Vec<(Scalar, Scalar, Option<&'a TextSpan>, String)> {
+        let width = requested_size.map(|x| x.width as f32).unwrap_or(f32::MAX);
+
+        let mut buffer = Buffer::new(&mut self.font_system, Metrics::new(style.font_size as f32, style.font_size as f32 * style.line_height as f32));
+
+        {
+            let mut buffer = buffer.borrow_with(&mut self.font_system);
+
+            set_buffer_text(&mut buffer, &text.text, &text.spans, style);
+            buffer.set_wrap(convert_wrap(style));
+            buffer.set_size(Some(width), None);
+        }
+
+        let mut segments = vec![];
+
+        for run in buffer.layout_runs() {
+            // The offset, span and byte range of the segment currently being extended
+            let mut current: Option<(f32, Option<&TextSpan>, usize, usize)> = None;
+            let mut metadata = None;
+
+            for glyph in run.glyphs.iter() {
+                if metadata == Some(glyph.metadata) {
+                    if let Some((_, _, start, end)) = &mut current {
+                        *start = (*start).min(glyph.start);
+                        *end = (*end).max(glyph.end);
+                    }
+
+                    continue;
+                }
+
+                if let Some((x, span, start, end)) = current.take() {
+                    segments.push((x as Scalar, run.line_y as Scalar, span, run.text[start..end].to_string()));
+                }
+
+                metadata = Some(glyph.metadata);
+                current = Some((glyph.x, span_of(glyph, &text.spans), glyph.start, glyph.end));
+            }
+
+            if let Some((x, span, start, end)) = current {
+                segments.push((x as Scalar, run.line_y as Scalar, span, run.text[start..end].to_string()));
+            }
+        }
+
+        segments
+    }
+}
+
+impl Default for SvgRenderContext {
+    fn default() -> Self {
+        SvgRenderContext::new()
+    }
+}
+
+impl InnerRenderContext for SvgRenderContext {
+    fn transform(&mut self, transform: &Matrix4<f32>) {
+        let element = format!(
+            r#"<g transform="matrix({} {} {} {} {} {})">"#,
+            number(transform[0][0] as Scalar),
+            number(transform[0][1] as Scalar),
+            number(transform[1][0] as Scalar),
+            number(transform[1][1] as Scalar),
+            number(transform[3][0] as Scalar),
+            number(transform[3][1] as Scalar),
+        );
+
+        self.write(&element);
+    }
+
+    fn pop_transform(&mut self) {
+        self.write("</g>");
+    }
+
+    fn color_filter(&mut self, hue_rotation: f32, saturation_shift: f32, luminance_shift: f32, color_invert: bool) {
+        self.color_filter_stack.push(SvgColorFilter {
+            hue_rotation,
+            saturation_shift,
+            luminance_shift,
+            color_invert,
+        });
+    }
+
+    fn pop_color_filter(&mut self) {
+        assert!(self.color_filter_stack.pop().is_some(), "A color filter was popped, when no color filter is present.");
+    }
+
+    fn clip(&mut self, bounding_box: Rect) {
+        let id = self.next_id("clip");
+
+        let _ = write!(self.defs, r#"<clipPath id="{}">{}/></clipPath>"#, id, shape_element(&DrawShape::Rectangle(bounding_box)));
+
+        self.write(&format!(r#"<g clip-path="url(#{})">"#, id));
+    }
+
+    fn pop_clip(&mut self) {
+        self.write("</g>");
+    }
+
+    fn filter(&mut self, _filter: &ImageFilter, _bounding_box: Rect) {}
+
+    fn filter2d(&mut self, _filter1: &ImageFilter, _bounding_box1: Rect, _filter2: &ImageFilter, _bounding_box2: Rect) {}
+
+    fn stencil(&mut self, shape: CompositeDrawShape) {
+        let shapes = match shape {
+            CompositeDrawShape::Zero => vec![],
+            CompositeDrawShape::One(shape, options) => vec![(shape, options)],
+            CompositeDrawShape::Many(shapes) => shapes,
+        };
+
+        let stroked = shapes.iter().any(|(_, options)| matches!(options, DrawOptions::Stroke(_)));
+
+        if stroked {
+            // Clip paths only use the geometry of their children, so stencils containing
+            // strokes are written as masks instead.
+            let id = self.next_id("mask");
+            let _ = write!(self.defs, r#"<mask id="{}" {}>"#, id, MASK_REGION);
+
+            for (shape, options) in &shapes {
+                let mut element = shape_element(shape);
+
+                match options {
+                    DrawOptions::Fill(fill) => {
+                        element.push_str(r##" fill="#ffffff""##);
+
+                        if fill.fill_rule == FillRule::EvenOdd {
+                            element.push_str(r#" fill-rule="evenodd""#);
+                        }
+                    }
+                    DrawOptions::Stroke(stroke) => {
+                        let _ = write!(element, r##" fill="none" stroke="#ffffff" stroke-width="{}""##, number(stroke.stroke_width));
+                    }
+                }
+
+                element.push_str("/>");
+                self.defs.push_str(&element);
+            }
+
+            self.defs.push_str("</mask>");
+            self.write(&format!(r#"<g mask="url(#{})">"#, id));
+        } else {
+            // An empty clip path hides everything drawn within it.
+            let id = self.next_id("clip");
+            let _ = write!(self.defs, r#"<clipPath id="{}">"#, id);
+
+            for (shape, options) in &shapes {
+                let mut element = shape_element(shape);
+
+                if let DrawOptions::Fill(fill) = options {
+                    if fill.fill_rule == FillRule::EvenOdd {
+                        element.push_str(r#" clip-rule="evenodd""#);
+                    }
+                }
+
+                element.push_str("/>");
+                self.defs.push_str(&element);
+            }
+
+            self.defs.push_str("</clipPath>");
+            self.write(&format!(r#"<g clip-path="url(#{})">"#, id));
+        }
+    }
+
+    fn pop_stencil(&mut self) {
+        self.write("</g>");
+    }
+
+    fn shape(&mut self, shape: &DrawShape, option: &DrawOptions) {
+        match option {
+            DrawOptions::Fill(fill) => self.fill(shape, fill.fill_rule),
+            DrawOptions::Stroke(stroke) => self.stroke(shape, stroke),
+        }
+    }
+
+    fn style(&mut self, style: &DrawStyle) {
+        match style {
+            DrawStyle::Color(color) => {
+                self.style_stack.push(SvgStyle::Color(color.to_fsa()));
+            }
+            DrawStyle::Gradient(gradient) => {
+                self.style_stack.push(SvgStyle::Gradient(gradient.clone()));
+            }
+            DrawStyle::MultiGradient(gradients) => {
+                // Only the top most gradient is drawn.
+                match gradients.last() {
+                    Some(gradient) => self.style_stack.push(SvgStyle::Gradient(gradient.clone())),
+                    None => self.style_stack.push(SvgStyle::Color([0.0, 0.0, 0.0, 0.0])),
+                }
+            }
+        }
+    }
+
+    fn pop_style(&mut self) {
+        assert!(self.style_stack.pop().is_some(), "A style was popped, when no style is present.")
+    }
+
+    fn stroke_dash_pattern(&mut self, pattern: Option<StrokeDashPattern>) {
+        self.stroke_dash_stack.push(pattern);
+    }
+
+    fn pop_stroke_dash_pattern(&mut self) {
+        self.stroke_dash_stack.pop();
+    }
+
+    fn raster_image(&mut self, id: &ImageId, bounding_box: Rect, options: ImageOptions) {
+        let source_rect = options.source_rect.unwrap_or_else(|| Rect::new(Position::new(0.0, 0.0), Dimension::new(1.0, 1.0)));
+
+        let tint = match options.mode {
+            ImageMode::Image => None,
+            ImageMode::Icon => match self.current_style() {
+                SvgStyle::Color(color) => Some(color),
+                SvgStyle::Gradient(gradient) => Some(gradient.colors.first().map(|color| color.to_fsa()).unwrap_or([0.0, 0.0, 0.0, 0.0])),
+            },
+        };
+
+        let Some(image) = IMAGES.with(|images| images.borrow().get(id).cloned()) else {
+            return;
+        };
+
+        self.draw_image(&image, bounding_box, source_rect, tint);
+    }
+
+    fn text(&mut self, text: &str, style: &TextStyle, position: Position, requested_size: Option<Dimension>, _env: &mut Environment, _ctx: &mut dyn TextContext) {
+        let Some((paint, opacity)) = self.paint() else {
+            return;
+        };
+
+        let attributes = text_attributes(style, &paint, opacity);
+
+        for (x, baseline, line) in self.text_lines(text, style, requested_size) {
+            let element = format!(
+                r#"<text xml:space="preserve" x="{}" y="{}"{}>{}</text>"#,
+                number(position.x + x),
+                number(position.y + baseline),
+                attributes,
+                escape(&line),
+            );
+
+            self.write(&element);
+        }
+    }
+
+    fn attributed_text(&mut self, text: &AttributedText, style: &TextStyle, position: Position, requested_size: Option<Dimension>, _env: &mut Environment, _ctx: &mut dyn TextContext) {
+        let Some((paint, opacity)) = self.paint() else {
+            return;
+        };
+
+        for (x, baseline, span, segment) in self.text_segments(text, style, requested_size) {
+            let span_style = span.map(|span| span.attributes.resolve(style)).unwrap_or_else(|| style.clone());
+
+            // Spans with their own color are written using that color instead of the current style.
+            let attributes = match span.and_then(|span| span.attributes.color) {
+                Some(color) => {
+                    let (paint, opacity) = color_attributes(self.filtered_color(color.to_fsa()));
+                    text_attributes(&span_style, &paint, opacity)
+                }
+                None => text_attributes(&span_style, &paint, opacity),
+            };
+
+            let element = format!(
+                r#"<text xml:space="preserve" x="{}" y="{}"{}>{}</text>"#,
+                number(position.x + x),
+                number(position.y + baseline - baseline_offset(span)),
+                attributes,
+                escape(&segment),
+            );
+
+            self.write(&element);
+        }
+    }
+
+    fn text_old(&mut self, _text: TextId, _ctx: &mut dyn TextContext) {
+        // The old text api only provides the rasterized glyphs, which can not be written as text.
+    }
+
+    fn filter_new(&mut self) {
+        self.bodies.push(String::new());
+    }
+
+    fn filter_new_pop(&mut self, filter: &ImageFilter, color: Color, post_draw: bool) {
+        self.finish_filter(&[filter], color, post_draw);
+    }
+
+    fn filter_new_pop2d(&mut self, filter: &ImageFilter, filter2: &ImageFilter, color: Color, post_draw: bool) {
+        self.finish_filter(&[filter, filter2], color, post_draw);
+    }
+
+    fn mask_start(&mut self) {
+        self.bodies.push(String::new());
+    }
+
+    fn mask_in(&mut self) {
+        assert!(self.bodies.len() > 1, "A mask was applied, when no mask is started.");
+
+        let content = self.bodies.pop().unwrap();
+        let id = self.next_id("mask");
+
+        let _ = write!(self.defs, r#"<mask id="{}" {} mask-type="alpha">{}</mask>"#, id, MASK_REGION, content);
+
+        self.write(&format!(r#"<g mask="url(#{})">"#, id));
+    }
+
+    fn mask_end(&mut self) {
+        self.write("</g>");
+    }
+
+    fn layer(&mut self, layer_id: LayerId, dimensions: Dimension, _env: &mut Environment) -> Layer<'_> {
+        let width = dimensions.width.floor().max(1.0) as u32;
+        let height = dimensions.height.floor().max(1.0) as u32;
+
+        let layer = self.layers.entry(layer_id).or_insert_with(|| SvgLayer::new(width, height));
+
+        if layer.image.dimensions() != (width, height) {
+            *layer = SvgLayer::new(width, height);
+        }
+
+        Layer {
+            inner: layer,
+            inner2: layer,
+        }
+    }
+
+    fn render_layer(&mut self, layer_id: LayerId, bounding_box: Rect) {
+        let Some(layer) = self.layers.remove(&layer_id) else {
+            return;
+        };
+
+        self.draw_image(&layer.image, bounding_box, Rect::new(Position::new(0.0, 0.0), Dimension::new(1.0, 1.0)), None);
+
+        self.layers.insert(layer_id, layer);
+    }
+}
+
+fn line_cap(cap: LineCap) -> &'static str {
+    match cap {
+        LineCap::Butt => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square",
+    }
+}
+
+/// The attributes of a text element written with the style and paint.
+fn text_attributes(style: &TextStyle, paint: &str, opacity: f32) -> String {
+    let mut attributes = format!(
+        r#" font-family="{}, sans-serif" font-size="{}""#,
+        escape(&style.family),
+        style.font_size,
+    );
+
+    let weight = style.font_weight.weight();
+    if weight != 400 {
+        let _ = write!(attributes, r#" font-weight="{}""#, weight);
+    }
+
+    if style.font_style == FontStyle::Italic {
+        attributes.push_str(r#" font-style="italic""#);
+    }
+
+    match style.text_decoration {
+        TextDecoration::None => {}
+        TextDecoration::StrikeThrough => attributes.push_str(r#" text-decoration="line-through""#),
+        TextDecoration::Overline => attributes.push_str(r#" text-decoration="overline""#),
+        TextDecoration::Underline => attributes.push_str(r#" text-decoration="underline""#),
+    }
+
+    let _ = write!(attributes, r#" fill="{}""#, paint);
+
+    if opacity < 1.0 {
+        let _ = write!(attributes, r#" fill-opacity="{}""#, number(opacity as Scalar));
+    }
+
+    attributes
+}
+
+fn convert_style(style: &TextStyle) -> Style {
+    match style.font_style {
+        FontStyle::Normal => Style::Normal,
+        FontStyle::Italic => Style::Italic,
+    }
+}
+
+fn convert_wrap(style: &TextStyle) -> cosmic_text::Wrap {
+    match style.wrap {
+        Wrap::Character => cosmic_text::Wrap::Glyph,
+        Wrap::Whitespace => cosmic_text::Wrap::Word,
+        Wrap::None => cosmic_text::Wrap::None,
+    }
+}
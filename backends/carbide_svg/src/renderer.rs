@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use carbide_core::draw::{Alignment, Dimension, ImageId, Position, Rect};
+use carbide_core::draw::theme::Theme;
+use carbide_core::environment::Environment;
+use carbide_core::layout::{Layout, LayoutContext};
+use carbide_core::lifecycle::{Update, UpdateContext};
+use carbide_core::render::{Render, RenderContext, RenderInstruction, RenderInstructionCache};
+use carbide_core::scene::SceneManager;
+use carbide_core::text::TextContext;
+use carbide_core::widget::{AnyWidget, CommonWidget};
+use carbide_cosmic_text::text_context::CosmicTextContext;
+
+use crate::image_context::SvgImageContext;
+use crate::render_context::SvgRenderContext;
+
+/// Renders a widget tree into an svg document without needing a window. The renderer runs
+/// update, layout and render on the widget, similar to what a window does each frame.
+///
+/// ```ignore
+/// let mut renderer = SvgRenderer::new();
+/// let svg = renderer.render(&mut widget, Dimension::new(400.0, 300.0), &mut env);
+/// ```
+pub struct SvgRenderer {
+    render_context: SvgRenderContext,
+    text_context: CosmicTextContext,
+    image_context: SvgImageContext,
+    render_instruction_cache: HashMap<ImageId, Rc<(Dimension, Vec<RenderInstruction>)>>,
+    theme: Theme,
+}
+
+impl SvgRenderer {
+    pub fn new() -> SvgRenderer {
+        SvgRenderer {
+            render_context: SvgRenderContext::new(),
+            text_context: CosmicTextContext::new(),
+            image_context: SvgImageContext,
+            render_instruction_cache: HashMap::new(),
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> SvgRenderer {
+        self.theme = theme;
+        self
+    }
+
+    /// Add a font that can be used by the text in the document, in addition to the fonts
+    /// installed on the system.
+    pub fn add_font_from_bytes(&mut self, bytes: Vec<u8>) {
+        self.render_context.add_font_from_bytes(bytes.clone());
+        self.text_context.add_font_from_bytes(bytes);
+    }
+
+    pub fn text_context(&mut self) -> &mut dyn TextContext {
+        &mut self.text_context
+    }
+
+    pub fn render_context(&self) -> &SvgRenderContext {
+        &self.render_context
+    }
+
+    /// Render the widget within the given dimension, and return the svg document. One unit
+    /// in the document corresponds to one logical pixel.
+    pub fn render(&mut self, widget: &mut dyn AnyWidget, dimension: Dimension, env: &mut Environment) -> String {
+        let mut scene_manager = SceneManager::new(1.0, dimension);
+
+        let theme = self.theme;
+
+        env.with::<Theme>(&theme, |env| {
+            env.with_mut::<SceneManager>(&mut scene_manager, |env| {
+                env.with_mut::<RenderInstructionCache>(&mut self.render_instruction_cache, |env| {
+                    widget.process_update(&mut UpdateContext {
+                        text: &mut self.text_context,
+                        image: &mut self.image_context,
+                        env,
+                    });
+
+                    widget.process_calculate_size(dimension, &mut LayoutContext {
+                        text: &mut self.text_context,
+                        image: &mut self.image_context,
+                        env,
+                    });
+
+                    widget.set_position(Alignment::Center.position(Position::origin(), dimension, widget.dimension()));
+
+                    widget.position_children(Rect::new(Position::origin(), dimension), &mut LayoutContext {
+                        text: &mut self.text_context,
+                        image: &mut self.image_context,
+                        env,
+                    });
+
+                    self.render_context.start(dimension);
+
+                    self.text_context.prepare_render();
+
+                    widget.render(&mut RenderContext {
+                        render: &mut self.render_context,
+                        text: &mut self.text_context,
+                        image: &mut self.image_context,
+                        env,
+                    });
+                })
+            })
+        });
+
+        self.render_context.document()
+    }
+
+    /// Render the widget and write the document to the file at the path.
+    pub fn save(&mut self, widget: &mut dyn AnyWidget, dimension: Dimension, env: &mut Environment, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let document = self.render(widget, dimension, env);
+        fs::write(path, document)
+    }
+}
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        SvgRenderer::new()
+    }
+}
@@ -0,0 +1,231 @@
+use std::f64::consts::PI;
+use std::fmt::Write;
+
+use carbide_core::draw::{DrawShape, Position, Scalar};
+use carbide_core::draw::path::PathInstruction;
+
+/// Format a number for an attribute, with at most three decimals and without trailing zeros.
+pub(crate) fn number(value: Scalar) -> String {
+    if !value.is_finite() {
+        return "0".to_string();
+    }
+
+    let formatted = format!("{:.3}", value);
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    match trimmed {
+        "-0" | "" => "0".to_string(),
+        _ => trimmed.to_string(),
+    }
+}
+
+/// Escape the text such that it can be used as content or attribute value in the document.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Convert a straight alpha color into a hex color and an opacity.
+pub(crate) fn color_attributes([r, g, b, a]: [f32; 4]) -> (String, f32) {
+    let byte = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    (format!("#{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b)), a.clamp(0.0, 1.0))
+}
+
+/// The start of an element describing the geometry of the shape, without the closing of the
+/// tag. This allows the caller to append the attributes for the style.
+pub(crate) fn shape_element(shape: &DrawShape) -> String {
+    match shape {
+        DrawShape::Rectangle(rect) => {
+            format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}""#,
+                number(rect.position.x),
+                number(rect.position.y),
+                number(rect.width()),
+                number(rect.height()),
+            )
+        }
+        DrawShape::Capsule(rect) => {
+            let radius = rect.width().min(rect.height()) / 2.0;
+
+            format!(
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}""#,
+                number(rect.position.x),
+                number(rect.position.y),
+                number(rect.width()),
+                number(rect.height()),
+                number(radius),
+            )
+        }
+        DrawShape::RoundedRectangle(rect, corners) => {
+            let max = rect.width().min(rect.height()) / 2.0;
+            let clamp = |radius: Scalar| radius.clamp(0.0, max);
+
+            let top_left = clamp(corners.top_left);
+            let top_right = clamp(corners.top_right);
+            let bottom_left = clamp(corners.bottom_left);
+            let bottom_right = clamp(corners.bottom_right);
+
+            if top_left == top_right && top_left == bottom_left && top_left == bottom_right {
+                return format!(
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}""#,
+                    number(rect.position.x),
+                    number(rect.position.y),
+                    number(rect.width()),
+                    number(rect.height()),
+                    number(top_left),
+                );
+            }
+
+            let (left, right, top, bottom) = (rect.left(), rect.right(), rect.bottom(), rect.top());
+
+            let mut data = String::new();
+            let _ = write!(data, "M{} {}", number(left + top_left), number(top));
+            let _ = write!(data, "H{}", number(right - top_right));
+            let _ = write!(data, "A{0} {0} 0 0 1 {1} {2}", number(top_right), number(right), number(top + top_right));
+            let _ = write!(data, "V{}", number(bottom - bottom_right));
+            let _ = write!(data, "A{0} {0} 0 0 1 {1} {2}", number(bottom_right), number(right - bottom_right), number(bottom));
+            let _ = write!(data, "H{}", number(left + bottom_left));
+            let _ = write!(data, "A{0} {0} 0 0 1 {1} {2}", number(bottom_left), number(left), number(bottom - bottom_left));
+            let _ = write!(data, "V{}", number(top + top_left));
+            let _ = write!(data, "A{0} {0} 0 0 1 {1} {2}", number(top_left), number(left + top_left), number(top));
+            data.push('Z');
+
+            format!(r#"<path d="{}""#, data)
+        }
+        DrawShape::Circle(center, radius) => {
+            format!(
+                r#"<circle cx="{}" cy="{}" r="{}""#,
+                number(center.x),
+                number(center.y),
+                number(*radius),
+            )
+        }
+        DrawShape::Ellipse(rect) => {
+            let center = rect.center();
+
+            format!(
+                r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}""#,
+                number(center.x),
+                number(center.y),
+                number(rect.width() / 2.0),
+                number(rect.height() / 2.0),
+            )
+        }
+        DrawShape::Line(from, to) => {
+            format!(
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}""#,
+                number(from.x),
+                number(from.y),
+                number(to.x),
+                number(to.y),
+            )
+        }
+        DrawShape::Path(path) => {
+            format!(r#"<path d="{}""#, path_data(&path.instructions))
+        }
+    }
+}
+
+/// Convert the path instructions into the data of a path element.
+pub(crate) fn path_data(instructions: &[PathInstruction]) -> String {
+    let mut data = String::new();
+    let mut current: Option<Position> = None;
+
+    for instruction in instructions {
+        match instruction {
+            PathInstruction::MoveTo { to } => {
+                let _ = write!(data, "M{} {}", number(to.x), number(to.y));
+                current = Some(*to);
+            }
+            PathInstruction::LineTo { to } => {
+                let _ = write!(data, "L{} {}", number(to.x), number(to.y));
+                current = Some(*to);
+            }
+            PathInstruction::QuadraticBezierTo { ctrl, to } => {
+                let _ = write!(data, "Q{} {} {} {}", number(ctrl.x), number(ctrl.y), number(to.x), number(to.y));
+                current = Some(*to);
+            }
+            PathInstruction::CubicBezierTo { ctrl1, ctrl2, to } => {
+                let _ = write!(
+                    data,
+                    "C{} {} {} {} {} {}",
+                    number(ctrl1.x), number(ctrl1.y),
+                    number(ctrl2.x), number(ctrl2.y),
+                    number(to.x), number(to.y),
+                );
+                current = Some(*to);
+            }
+            PathInstruction::Close => {
+                data.push('Z');
+            }
+            PathInstruction::Arc { center, radius, start_angle, end_angle } => {
+                let start = start_angle.radians();
+                let end = end_angle.radians();
+
+                let point = |angle: Scalar| Position::new(
+                    center.x + radius.width * angle.cos(),
+                    center.y + radius.height * angle.sin(),
+                );
+
+                let from = point(start);
+
+                // If the path has a current point, a line is drawn to the start of the arc.
+                let command = if current.is_some() { 'L' } else { 'M' };
+                let _ = write!(data, "{}{} {}", command, number(from.x), number(from.y));
+
+                // Split the arc into segments of at most half a turn, such that the large arc
+                // flag is never needed, and full circles can be described.
+                let sweep = end - start;
+                let segments = (sweep.abs() / PI).ceil().max(1.0) as usize;
+                let step = sweep / segments as Scalar;
+                let sweep_flag = if sweep >= 0.0 { 1 } else { 0 };
+
+                for i in 1..=segments {
+                    let to = point(start + step * i as Scalar);
+
+                    let _ = write!(
+                        data,
+                        "A{} {} 0 0 {} {} {}",
+                        number(radius.width),
+                        number(radius.height),
+                        sweep_flag,
+                        number(to.x),
+                        number(to.y),
+                    );
+
+                    current = Some(to);
+                }
+            }
+        }
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use carbide_core::draw::Scalar;
+    use super::number;
+
+    #[test]
+    fn number_formatting() {
+        assert_eq!(number(1.0), "1");
+        assert_eq!(number(1.5), "1.5");
+        assert_eq!(number(0.12345), "0.123");
+        assert_eq!(number(-0.0001), "0");
+        assert_eq!(number(Scalar::NAN), "0");
+    }
+}
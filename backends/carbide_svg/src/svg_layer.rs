@@ -0,0 +1,39 @@
+use std::fmt::{Debug, Formatter};
+use carbide_core::image::RgbaImage;
+use carbide_core::render::InnerLayer;
+
+/// A layer rendered into an svg document. Layers are raster content, and are embedded as an
+/// image when the layer is rendered.
+pub struct SvgLayer {
+    pub(crate) image: RgbaImage,
+}
+
+impl SvgLayer {
+    pub(crate) fn new(width: u32, height: u32) -> SvgLayer {
+        SvgLayer {
+            image: RgbaImage::new(width.max(1), height.max(1)),
+        }
+    }
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    pub fn image_mut(&mut self) -> &mut RgbaImage {
+        &mut self.image
+    }
+}
+
+impl InnerLayer for SvgLayer {
+    fn dimensions(&self) -> (u32, u32) {
+        self.image.dimensions()
+    }
+}
+
+impl Debug for SvgLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SvgLayer")
+            .field("dimensions", &self.dimensions())
+            .finish()
+    }
+}
@@ -0,0 +1,72 @@
+use carbide_core::draw::{Color, CompositeDrawShape, Dimension, DrawGradient, DrawOptions, DrawShape, DrawStyle, Position, Rect};
+use carbide_core::draw::color_space::ColorSpace;
+use carbide_core::draw::fill::FillOptions;
+use carbide_core::draw::gradient::{GradientRepeat, GradientType};
+use carbide_core::draw::stroke::{StrokeAlignment, StrokeOptions};
+use carbide_core::render::InnerRenderContext;
+use carbide_svg::SvgRenderContext;
+use carbide_usvg::{Node, Options, Tree};
+
+fn parse(document: &str) -> Tree {
+    Tree::from_str(document, &Options::default()).expect("The document to be valid svg")
+}
+
+#[test]
+fn shapes_round_trip() {
+    let mut context = SvgRenderContext::new();
+    context.start(Dimension::new(200.0, 100.0));
+
+    context.style(&DrawStyle::Color(Color::new_rgb(255, 0, 0)));
+    context.shape(&DrawShape::Rectangle(Rect::new(Position::new(10.0, 10.0), Dimension::new(50.0, 30.0))), &DrawOptions::Fill(FillOptions::default()));
+    context.shape(&DrawShape::Circle(Position::new(100.0, 50.0), 20.0), &DrawOptions::Stroke(StrokeOptions::default().with_stroke_width(4.0)));
+    context.pop_style();
+
+    let tree = parse(&context.document());
+
+    assert_eq!(tree.size().width(), 200.0);
+    assert_eq!(tree.size().height(), 100.0);
+
+    let paths = tree.root().children().iter()
+        .filter(|node| matches!(node, Node::Path(_)))
+        .count();
+
+    assert_eq!(paths, 2);
+}
+
+#[test]
+fn gradients_clips_and_masks_round_trip() {
+    let mut context = SvgRenderContext::new();
+    context.start(Dimension::new(100.0, 100.0));
+
+    context.style(&DrawStyle::Gradient(DrawGradient {
+        colors: vec![Color::new_rgb(255, 0, 0), Color::new_rgb(0, 0, 255)],
+        ratios: vec![0.0, 1.0],
+        gradient_type: GradientType::Linear,
+        gradient_repeat: GradientRepeat::Clamp,
+        start: Position::new(0.0, 0.0),
+        end: Position::new(100.0, 0.0),
+        color_space: ColorSpace::Linear,
+    }));
+
+    context.clip(Rect::new(Position::new(0.0, 0.0), Dimension::new(50.0, 50.0)));
+    context.shape(&DrawShape::Rectangle(Rect::new(Position::new(0.0, 0.0), Dimension::new(100.0, 100.0))), &DrawOptions::Fill(FillOptions::default()));
+    context.pop_clip();
+
+    context.stencil(CompositeDrawShape::One(DrawShape::Circle(Position::new(50.0, 50.0), 25.0), DrawOptions::Fill(FillOptions::default())));
+    context.shape(&DrawShape::Rectangle(Rect::new(Position::new(0.0, 0.0), Dimension::new(100.0, 100.0))), &DrawOptions::Fill(FillOptions::default()));
+    context.pop_stencil();
+
+    context.mask_start();
+    context.shape(&DrawShape::Ellipse(Rect::new(Position::new(20.0, 20.0), Dimension::new(60.0, 40.0))), &DrawOptions::Fill(FillOptions::default()));
+    context.mask_in();
+    context.shape(&DrawShape::Rectangle(Rect::new(Position::new(0.0, 0.0), Dimension::new(100.0, 100.0))), &DrawOptions::Stroke(StrokeOptions::default().with_alignment(StrokeAlignment::Positive)));
+    context.mask_end();
+
+    context.pop_style();
+
+    let tree = parse(&context.document());
+
+    assert_eq!(tree.linear_gradients().len(), 1);
+    assert_eq!(tree.clip_paths().len(), 3);
+    assert_eq!(tree.masks().len(), 1);
+}
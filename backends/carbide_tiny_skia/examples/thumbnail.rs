@@ -0,0 +1,24 @@
+use carbide_core::color::{BLUE, ORANGE};
+use carbide_core::draw::Dimension;
+use carbide_core::environment::Environment;
+use carbide_core::widget::*;
+use carbide_core::widget::managers::{FontSizeManager, ThemeManager};
+use carbide_tiny_skia::TinySkiaRenderer;
+
+fn main() {
+    let mut env = Environment::new();
+    let mut renderer = TinySkiaRenderer::new(2.0);
+
+    let widget = VStack::new((
+        Text::new("Hello world!"),
+        Circle::new().fill(ORANGE).frame(50.0, 50.0),
+    )).padding(10.0)
+        .background(RoundedRectangle::new(8.0).fill(BLUE));
+
+    // Provide the theme colors and font sizes, like a window does for its content.
+    let mut widget = FontSizeManager::new(ThemeManager::new(widget));
+
+    let image = renderer.render(&mut widget, Dimension::new(200.0, 150.0), &mut env);
+
+    image.save("target/thumbnail.png").unwrap();
+}
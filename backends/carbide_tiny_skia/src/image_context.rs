@@ -0,0 +1,92 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use carbide_core::draw::{Dimension, ImageContext, ImageFormat, ImageId, ImageMetrics, Texture, TextureFormat};
+use carbide_core::environment::Environment;
+use carbide_core::render::{RenderInstruction, RenderInstructionCache};
+use tiny_skia::Pixmap;
+use crate::tiny_skia_gradient::premultiplied;
+
+thread_local! {
+    pub(crate) static IMAGES: RefCell<HashMap<ImageId, Pixmap>> = RefCell::new(HashMap::new());
+}
+
+pub struct TinySkiaImageContext;
+
+impl ImageContext for TinySkiaImageContext {
+    fn exist(&self, id: &ImageId, env: &mut Environment) -> bool {
+        match id.format() {
+            ImageFormat::Unknown => false,
+            ImageFormat::Svg => {
+                env.get::<RenderInstructionCache>()
+                    .map(|cache| cache.contains_key(id))
+                    .unwrap_or(false)
+            }
+            _ => IMAGES.with(|images| images.borrow().contains_key(id))
+        }
+    }
+
+    fn metrics(&self, id: &ImageId, env: &mut Environment) -> ImageMetrics {
+        match id.format() {
+            ImageFormat::Unknown => ImageMetrics::Unknown,
+            ImageFormat::Svg => {
+                env.get::<RenderInstructionCache>()
+                    .and_then(|cache| cache.get(id))
+                    .map(|vector| ImageMetrics::Vector { dimension: vector.0 })
+                    .unwrap_or(ImageMetrics::Unknown)
+            }
+            _ => {
+                IMAGES.with(|images| {
+                    images.borrow()
+                        .get(id)
+                        .map(|pixmap| ImageMetrics::Raster { width: pixmap.width(), height: pixmap.height() })
+                        .unwrap_or(ImageMetrics::Unknown)
+                })
+            }
+        }
+    }
+
+    fn update_texture(&mut self, id: &ImageId, texture: Texture, _env: &mut Environment) -> bool {
+        let Some(mut pixmap) = Pixmap::new(texture.width, texture.height) else {
+            return false;
+        };
+
+        let width = texture.width as usize;
+
+        for (index, pixel) in pixmap.pixels_mut().iter_mut().enumerate() {
+            let x = index % width;
+            let y = index / width;
+            let offset = y * texture.bytes_per_row as usize + x * 4;
+
+            let [c0, c1, c2, a] = [
+                texture.data[offset],
+                texture.data[offset + 1],
+                texture.data[offset + 2],
+                texture.data[offset + 3],
+            ];
+
+            let (r, g, b) = match texture.format {
+                TextureFormat::RGBA8 => (c0, c1, c2),
+                TextureFormat::BGRA8 => (c2, c1, c0),
+            };
+
+            *pixel = premultiplied(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0);
+        }
+
+        IMAGES.with(|images| {
+            images.borrow_mut().insert(id.clone(), pixmap);
+        });
+
+        true
+    }
+
+    fn update_vector(&mut self, id: &ImageId, description: Vec<RenderInstruction>, size: Dimension, env: &mut Environment) -> bool {
+        let Some(cache) = env.get_mut::<RenderInstructionCache>() else {
+            return false;
+        };
+
+        cache.insert(id.clone(), Rc::new((size, description)));
+
+        true
+    }
+}
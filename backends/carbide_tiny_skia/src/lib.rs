@@ -0,0 +1,16 @@
+//! A software renderer for carbide, using tiny-skia to rasterize on the CPU. This can be used
+//! to render widgets without a window or a GPU, for example for thumbnails, exports or tests.
+
+pub use image_context::TinySkiaImageContext;
+pub use render_context::TinySkiaRenderContext;
+pub use renderer::TinySkiaRenderer;
+pub use snapshot::*;
+pub use tiny_skia_layer::TinySkiaLayer;
+
+mod image_context;
+mod render_context;
+mod renderer;
+mod snapshot;
+mod tiny_skia_filter;
+mod tiny_skia_gradient;
+mod tiny_skia_layer;
@@ -0,0 +1,1078 @@
+use std::collections::HashMap;
+use std::f64::consts::FRAC_PI_2;
+
+use image::{DynamicImage, RgbaImage};
+use tiny_skia::{FillRule as SkiaFillRule, FilterQuality, IntRect, LineCap as SkiaLineCap, LineJoin as SkiaLineJoin, Mask, MaskType, Paint, PathBuilder, Pattern, Pixmap, PixmapPaint, SpreadMode, Stroke, StrokeDash, Transform};
+
+use carbide_core::color::{ColorExt, WHITE};
+use carbide_core::draw::{Color, CompositeDrawShape, Dimension, DrawOptions, DrawShape, DrawStyle, ImageId, ImageMode, ImageOptions, Position, Rect, Scalar};
+use carbide_core::draw::fill::FillRule;
+use carbide_core::draw::path::PathInstruction;
+use carbide_core::draw::stroke::{LineCap, LineJoin, StrokeAlignment, StrokeDashCap, StrokeDashPattern, StrokeOptions};
+use carbide_core::environment::Environment;
+use carbide_core::math::Matrix4;
+use carbide_core::render::{InnerRenderContext, Layer, LayerId};
+use carbide_core::text::{AttributedText, TextContext, TextId, TextStyle};
+use carbide_core::text::glyph::{Glyph, GlyphRenderMode};
+use carbide_core::widget::{CornerRadii, ImageFilter};
+
+use crate::image_context::IMAGES;
+use crate::tiny_skia_filter::{convolve, tint, TinySkiaColorFilter};
+use crate::tiny_skia_gradient::{premultiplied, TinySkiaGradient};
+use crate::tiny_skia_layer::TinySkiaLayer;
+
+/// Kappa is the distance to the control points, when approximating a quarter circle with a cubic bezier.
+const KAPPA: f32 = 0.552_284_8;
+
+#[derive(Debug, Clone, PartialEq)]
+enum TinySkiaStyle {
+    Color([f32; 4]),
+    Gradient(TinySkiaGradient),
+}
+
+/// The way an image is drawn onto the target.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum ImageDrawMode {
+    /// Draw the colors of the image as is.
+    Image,
+    /// Draw the current style, using the alpha of the image.
+    Tinted,
+}
+
+/// A render context that rasterizes everything on the CPU using tiny-skia. This makes it possible
+/// to render a widget tree without a window or a GPU, for example to create thumbnails or to
+/// compare the rendering against reference images in tests.
+///
+/// All positions are given in logical coordinates, and the scale factor is applied as the base
+/// transform, such that the resulting image is in physical pixels.
+pub struct TinySkiaRenderContext {
+    /// The stack of render targets. The first target is the final image, and additional targets
+    /// are pushed when rendering filters, masks and color filters.
+    targets: Vec<Pixmap>,
+    transform_stack: Vec<Transform>,
+    /// The clip mask for the current target. A value of `None` means no clipping.
+    clip_stack: Vec<Option<Mask>>,
+    style_stack: Vec<TinySkiaStyle>,
+    stroke_dash_stack: Vec<Option<StrokeDashPattern>>,
+    color_filter_stack: Vec<TinySkiaColorFilter>,
+    mask_stack: Vec<Mask>,
+    layers: HashMap<LayerId, TinySkiaLayer>,
+    atlas: Option<Pixmap>,
+    window_bounding_box: Rect,
+    scale_factor: Scalar,
+}
+
+impl TinySkiaRenderContext {
+    pub fn new(width: u32, height: u32, scale_factor: Scalar) -> TinySkiaRenderContext {
+        let mut context = TinySkiaRenderContext {
+            targets: vec![],
+            transform_stack: vec![],
+            clip_stack: vec![],
+            style_stack: vec![],
+            stroke_dash_stack: vec![],
+            color_filter_stack: vec![],
+            mask_stack: vec![],
+            layers: HashMap::new(),
+            atlas: None,
+            window_bounding_box: Rect::default(),
+            scale_factor,
+        };
+
+        context.start(width, height, scale_factor);
+        context
+    }
+
+    /// Start a new frame of the given size in physical pixels. This clears the target and
+    /// resets all the stacks.
+    pub fn start(&mut self, width: u32, height: u32, scale_factor: Scalar) {
+        self.scale_factor = scale_factor;
+        self.window_bounding_box = Rect::new(
+            Position::origin(),
+            Dimension::new(width as Scalar / scale_factor, height as Scalar / scale_factor),
+        );
+
+        self.targets.clear();
+        self.targets.push(Pixmap::new(width.max(1), height.max(1)).expect("The render size to be non-zero"));
+
+        self.transform_stack.clear();
+        self.transform_stack.push(Transform::from_scale(scale_factor as f32, scale_factor as f32));
+
+        self.clip_stack.clear();
+        self.clip_stack.push(None);
+
+        self.style_stack.clear();
+        self.stroke_dash_stack.clear();
+        self.color_filter_stack.clear();
+        self.mask_stack.clear();
+    }
+
+    /// The bounding box of the frame in logical coordinates.
+    pub fn window_bounding_box(&self) -> Rect {
+        self.window_bounding_box
+    }
+
+    pub fn scale_factor(&self) -> Scalar {
+        self.scale_factor
+    }
+
+    /// The rendered frame with premultiplied colors.
+    pub fn pixmap(&self) -> &Pixmap {
+        &self.targets[0]
+    }
+
+    /// The rendered frame as an image with straight alpha.
+    pub fn image(&self) -> RgbaImage {
+        let pixmap = &self.targets[0];
+
+        let data = pixmap.pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect::<Vec<_>>();
+
+        RgbaImage::from_raw(pixmap.width(), pixmap.height(), data)
+            .expect("The pixmap to contain width * height pixels")
+    }
+
+    /// Get the pixmap of a layer, such that other renderers can draw into it.
+    pub fn layer_pixmap_mut(&mut self, layer_id: LayerId) -> Option<&mut Pixmap> {
+        self.layers.get_mut(&layer_id).map(|layer| &mut layer.pixmap)
+    }
+
+    /// Update the glyph atlas used when rendering text.
+    pub fn update_atlas(&mut self, image: &DynamicImage) {
+        let image = image.to_rgba8();
+
+        let Some(mut pixmap) = Pixmap::new(image.width(), image.height()) else {
+            return;
+        };
+
+        for (pixel, source) in pixmap.pixels_mut().iter_mut().zip(image.pixels()) {
+            let [r, g, b, a] = source.0;
+            *pixel = premultiplied(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0);
+        }
+
+        self.atlas = Some(pixmap);
+    }
+
+    fn current_transform(&self) -> Transform {
+        *self.transform_stack.last().unwrap()
+    }
+
+    fn current_clip(&self) -> Option<&Mask> {
+        self.clip_stack.last().and_then(|clip| clip.as_ref())
+    }
+
+    fn current_style(&self) -> TinySkiaStyle {
+        self.style_stack.last()
+            .cloned()
+            .unwrap_or(TinySkiaStyle::Color(WHITE.to_fsa()))
+    }
+
+    fn push_target(&mut self) {
+        let (width, height) = (self.targets[0].width(), self.targets[0].height());
+        self.targets.push(Pixmap::new(width, height).unwrap());
+    }
+
+    fn pop_target(&mut self) -> Pixmap {
+        assert!(self.targets.len() > 1, "The main target can not be popped.");
+        self.targets.pop().unwrap()
+    }
+
+    /// Draw the pixmap on top of the current target, using the current clip and optionally a mask.
+    fn composite(&mut self, pixmap: &Pixmap, mask: Option<&Mask>) {
+        let combined = match (mask, self.current_clip()) {
+            (Some(mask), Some(clip)) => Some(intersect(mask.clone(), clip)),
+            (Some(mask), None) => Some(mask.clone()),
+            (None, Some(clip)) => Some(clip.clone()),
+            (None, None) => None,
+        };
+
+        self.targets.last_mut().unwrap().draw_pixmap(
+            0,
+            0,
+            pixmap.as_ref(),
+            &PixmapPaint::default(),
+            Transform::identity(),
+            combined.as_ref(),
+        );
+    }
+
+    /// Create a clip mask for the given shape using the current transform, intersected with the
+    /// current clip.
+    fn shape_mask(&self, shape: &DrawShape, options: &DrawOptions) -> Mask {
+        let target = &self.targets[0];
+        let mut mask = Mask::new(target.width(), target.height()).unwrap();
+
+        let Some(path) = shape_path(shape) else {
+            return mask;
+        };
+
+        let transform = self.current_transform();
+
+        match options {
+            DrawOptions::Fill(fill) => {
+                mask.fill_path(&path, fill_rule(fill.fill_rule), true, transform);
+            }
+            DrawOptions::Stroke(stroke) => {
+                let stroke = self.stroke(stroke);
+
+                if let Some(stroked) = stroke_path(&path, &stroke, transform.get_scale().0.max(transform.get_scale().1)) {
+                    mask.fill_path(&stroked, SkiaFillRule::Winding, true, transform);
+                }
+            }
+        }
+
+        mask
+    }
+
+    fn stroke(&self, options: &StrokeOptions) -> Stroke {
+        let mut stroke = Stroke {
+            width: options.stroke_width as f32,
+            line_cap: line_cap(options.start_cap),
+            ..Stroke::default()
+        };
+
+        match options.stroke_join {
+            LineJoin::Miter => {
+                stroke.line_join = SkiaLineJoin::Miter;
+            }
+            LineJoin::MiterClip { miter_limit } => {
+                stroke.line_join = SkiaLineJoin::MiterClip;
+                stroke.miter_limit = miter_limit as f32;
+            }
+            LineJoin::Round => {
+                stroke.line_join = SkiaLineJoin::Round;
+            }
+            LineJoin::Bevel => {
+                stroke.line_join = SkiaLineJoin::Bevel;
+            }
+        }
+
+        if let Some(Some(pattern)) = self.stroke_dash_stack.last() {
+            let mut dashes = pattern.pattern.iter().map(|dash| *dash as f32).collect::<Vec<_>>();
+
+            // An odd number of dashes is repeated to get an even number, similar to svg.
+            if dashes.len() % 2 == 1 {
+                dashes.extend_from_within(..);
+            }
+
+            stroke.dash = StrokeDash::new(dashes, pattern.offset as f32);
+
+            stroke.line_cap = match pattern.start_cap {
+                StrokeDashCap::None => SkiaLineCap::Butt,
+                StrokeDashCap::Round => SkiaLineCap::Round,
+                StrokeDashCap::Square => SkiaLineCap::Square,
+                StrokeDashCap::TriangleIn |
+                StrokeDashCap::TriangleOut => SkiaLineCap::Butt,
+            };
+        }
+
+        stroke
+    }
+
+    /// Fill the path on the current target using the current style. The pattern is used for
+    /// styles that can not be expressed as a tiny-skia shader, and needs to outlive the paint.
+    fn fill_path(&mut self, path: &tiny_skia::Path, rule: SkiaFillRule, mask: Option<&Mask>) {
+        let transform = self.current_transform();
+        let bounds = path.bounds();
+        let bounds = Rect::new(
+            Position::new(bounds.x() as Scalar, bounds.y() as Scalar),
+            Dimension::new(bounds.width() as Scalar, bounds.height() as Scalar),
+        );
+
+        let (scale_x, scale_y) = transform.get_scale();
+        let scale = scale_x.max(scale_y).max(f32::EPSILON);
+
+        let mut pattern = None;
+
+        let mut paint = Paint::default();
+        paint.anti_alias = true;
+
+        match self.current_style() {
+            TinySkiaStyle::Color(color) => {
+                let color = tiny_skia::Color::from_rgba(color[0], color[1], color[2], color[3])
+                    .unwrap_or(tiny_skia::Color::TRANSPARENT);
+                paint.set_color(color);
+            }
+            TinySkiaStyle::Gradient(gradient) => {
+                match gradient.shader(bounds, scale, &mut pattern) {
+                    Some(shader) => paint.shader = shader,
+                    None => {
+                        let Some(pattern) = &pattern else {
+                            return;
+                        };
+
+                        paint.shader = Pattern::new(
+                            pattern.as_ref(),
+                            SpreadMode::Pad,
+                            FilterQuality::Bilinear,
+                            1.0,
+                            Transform::from_scale(1.0 / scale, 1.0 / scale)
+                                .post_translate(bounds.position.x as f32, bounds.position.y as f32),
+                        );
+                    }
+                }
+            }
+        }
+
+        let clip = self.clip_stack.last().and_then(|clip| clip.as_ref());
+        let combined = mask.map(|mask| match clip {
+            Some(clip) => intersect(mask.clone(), clip),
+            None => mask.clone(),
+        });
+
+        self.targets.last_mut().unwrap().fill_path(path, &paint, rule, transform, combined.as_ref().or(clip));
+    }
+
+    /// Draw an area of the image within the bounding box. The source rect is given in normalized
+    /// coordinates of the image.
+    fn draw_image(&mut self, image: &Pixmap, bounding_box: Rect, source_rect: Rect, mode: ImageDrawMode) {
+        if bounding_box.width() <= 0.0 || bounding_box.height() <= 0.0 {
+            return;
+        }
+
+        let (width, height) = (image.width() as Scalar, image.height() as Scalar);
+
+        let left = (source_rect.position.x * width).round().max(0.0) as i32;
+        let top = (source_rect.position.y * height).round().max(0.0) as i32;
+        let right = ((source_rect.position.x + source_rect.width()) * width).round().min(width) as i32;
+        let bottom = ((source_rect.position.y + source_rect.height()) * height).round().min(height) as i32;
+
+        let Some(area) = IntRect::from_ltrb(left, top, right, bottom) else {
+            return;
+        };
+
+        let Some(mut source) = image.clone_rect(area) else {
+            return;
+        };
+
+        if mode == ImageDrawMode::Tinted {
+            match self.current_style() {
+                TinySkiaStyle::Color(color) => tint(&mut source, color),
+                TinySkiaStyle::Gradient(gradient) => {
+                    let (source_width, source_height) = (source.width(), source.height());
+
+                    for (index, pixel) in source.pixels_mut().iter_mut().enumerate() {
+                        let x = (index as u32 % source_width) as Scalar + 0.5;
+                        let y = (index as u32 / source_width) as Scalar + 0.5;
+
+                        let position = Position::new(
+                            bounding_box.position.x + x / source_width as Scalar * bounding_box.width(),
+                            bounding_box.position.y + y / source_height as Scalar * bounding_box.height(),
+                        );
+
+                        let [r, g, b, a] = gradient.color_at(position);
+                        *pixel = premultiplied(r, g, b, a * pixel.alpha() as f32 / 255.0);
+                    }
+                }
+            }
+        }
+
+        let Some(rect) = tiny_skia::Rect::from_xywh(
+            bounding_box.position.x as f32,
+            bounding_box.position.y as f32,
+            bounding_box.width() as f32,
+            bounding_box.height() as f32,
+        ) else {
+            return;
+        };
+
+        let mut paint = Paint::default();
+        paint.shader = Pattern::new(
+            source.as_ref(),
+            SpreadMode::Pad,
+            FilterQuality::Bilinear,
+            1.0,
+            Transform::from_scale(
+                rect.width() / source.width() as f32,
+                rect.height() / source.height() as f32,
+            ).post_translate(rect.x(), rect.y()),
+        );
+
+        let transform = self.current_transform();
+        let clip = self.clip_stack.last().and_then(|clip| clip.as_ref());
+
+        self.targets.last_mut().unwrap().fill_rect(rect, &paint, transform, clip);
+    }
+
+    fn draw_glyph(&mut self, glyph: &Glyph) {
+        let Some(atlas) = self.atlas.take() else {
+            return;
+        };
+
+        let mode = match glyph.mode {
+            GlyphRenderMode::Plain => ImageDrawMode::Tinted,
+            GlyphRenderMode::Colored => ImageDrawMode::Image,
+        };
+
+        // Glyphs of spans with their own color are drawn using that color instead of the current style.
+        if let Some(color) = glyph.color {
+            self.style(&DrawStyle::Color(color));
+            self.draw_image(&atlas, glyph.bounding_box, glyph.texture_coords, mode);
+            self.pop_style();
+        } else {
+            self.draw_image(&atlas, glyph.bounding_box, glyph.texture_coords, mode);
+        }
+
+        self.atlas = Some(atlas);
+    }
+
+    /// The area of the target covered by the bounding box, in physical pixels.
+    fn physical_area(&self, bounding_box: Rect) -> Option<IntRect> {
+        let rect = tiny_skia::Rect::from_xywh(
+            bounding_box.position.x as f32,
+            bounding_box.position.y as f32,
+            bounding_box.width() as f32,
+            bounding_box.height() as f32,
+        )?;
+
+        let rect = rect.transform(self.current_transform())?;
+
+        IntRect::from_ltrb(
+            rect.left().floor() as i32,
+            rect.top().floor() as i32,
+            rect.right().ceil() as i32,
+            rect.bottom().ceil() as i32,
+        )
+    }
+
+    fn apply_filter(&mut self, filter: &ImageFilter, bounding_box: Rect) {
+        let Some(area) = self.physical_area(bounding_box) else {
+            return;
+        };
+
+        let source = self.targets.last().unwrap().clone();
+        let mut filtered = source.clone();
+
+        convolve(&source, &mut filtered, filter, area);
+
+        let mut mask = Mask::new(source.width(), source.height()).unwrap();
+        mask.fill_path(&PathBuilder::from_rect(area.to_rect()), SkiaFillRule::Winding, false, Transform::identity());
+
+        if let Some(clip) = self.current_clip() {
+            mask = intersect(mask, clip);
+        }
+
+        // Replace the pixels within the area by the filtered pixels.
+        let target = self.targets.last_mut().unwrap();
+        for ((pixel, filtered), coverage) in target.pixels_mut().iter_mut().zip(filtered.pixels()).zip(mask.data()) {
+            if *coverage > 0 {
+                *pixel = *filtered;
+            }
+        }
+    }
+
+    fn finish_filter(&mut self, filters: &[&ImageFilter], color: Color, post_draw: bool) {
+        let source = self.pop_target();
+        let area = IntRect::from_xywh(0, 0, source.width(), source.height()).unwrap();
+
+        let mut filtered = source.clone();
+
+        for filter in filters {
+            let input = filtered.clone();
+            convolve(&input, &mut filtered, filter, area);
+        }
+
+        if post_draw {
+            tint(&mut filtered, color.to_fsa());
+        }
+
+        self.composite(&filtered, None);
+
+        if post_draw {
+            self.composite(&source, None);
+        }
+    }
+}
+
+impl InnerRenderContext for TinySkiaRenderContext {
+    fn transform(&mut self, transform: &Matrix4<f32>) {
+        let transform = Transform::from_row(
+            transform[0][0],
+            transform[0][1],
+            transform[1][0],
+            transform[1][1],
+            transform[3][0],
+            transform[3][1],
+        );
+
+        let current = self.current_transform();
+        self.transform_stack.push(current.pre_concat(transform));
+    }
+
+    fn pop_transform(&mut self) {
+        assert!(self.transform_stack.len() > 1, "A transform was popped, when no transform is present.");
+        self.transform_stack.pop();
+    }
+
+    fn color_filter(&mut self, hue_rotation: f32, saturation_shift: f32, luminance_shift: f32, color_invert: bool) {
+        self.color_filter_stack.push(TinySkiaColorFilter {
+            hue_rotation,
+            saturation_shift,
+            luminance_shift,
+            color_invert,
+        });
+
+        self.push_target();
+    }
+
+    fn pop_color_filter(&mut self) {
+        let filter = self.color_filter_stack.pop().expect("A color filter was popped, when no color filter is present.");
+
+        let mut pixmap = self.pop_target();
+        filter.apply(&mut pixmap);
+
+        self.composite(&pixmap, None);
+    }
+
+    fn clip(&mut self, bounding_box: Rect) {
+        let corrected = bounding_box.within_bounding_box(&self.window_bounding_box);
+
+        let target = &self.targets[0];
+        let mut mask = Mask::new(target.width(), target.height()).unwrap();
+
+        if let Some(rect) = tiny_skia::Rect::from_xywh(
+            corrected.position.x as f32,
+            corrected.position.y as f32,
+            corrected.width() as f32,
+            corrected.height() as f32,
+        ) {
+            let scale = self.scale_factor as f32;
+            mask.fill_path(&PathBuilder::from_rect(rect), SkiaFillRule::Winding, false, Transform::from_scale(scale, scale));
+        }
+
+        if let Some(outer) = self.current_clip() {
+            mask = intersect(mask, outer);
+        }
+
+        self.clip_stack.push(Some(mask));
+    }
+
+    fn pop_clip(&mut self) {
+        assert!(self.clip_stack.len() > 1, "A clip was popped, when no clip is present.");
+        self.clip_stack.pop();
+    }
+
+    fn filter(&mut self, filter: &ImageFilter, bounding_box: Rect) {
+        self.apply_filter(filter, bounding_box);
+    }
+
+    fn filter2d(&mut self, filter1: &ImageFilter, bounding_box1: Rect, filter2: &ImageFilter, bounding_box2: Rect) {
+        self.apply_filter(filter1, bounding_box1);
+        self.apply_filter(filter2, bounding_box2);
+    }
+
+    fn stencil(&mut self, shape: CompositeDrawShape) {
+        let target = &self.targets[0];
+        let mut mask = Mask::new(target.width(), target.height()).unwrap();
+
+        let shapes = match shape {
+            CompositeDrawShape::Zero => vec![],
+            CompositeDrawShape::One(shape, options) => vec![(shape, options)],
+            CompositeDrawShape::Many(shapes) => shapes,
+        };
+
+        for (shape, options) in &shapes {
+            let shape_mask = self.shape_mask(shape, options);
+
+            for (value, shape_value) in mask.data_mut().iter_mut().zip(shape_mask.data()) {
+                *value = (*value).max(*shape_value);
+            }
+        }
+
+        if let Some(outer) = self.current_clip() {
+            mask = intersect(mask, outer);
+        }
+
+        self.clip_stack.push(Some(mask));
+    }
+
+    fn pop_stencil(&mut self) {
+        assert!(self.clip_stack.len() > 1, "A stencil was popped, when no stencil is present.");
+        self.clip_stack.pop();
+    }
+
+    fn shape(&mut self, shape: &DrawShape, option: &DrawOptions) {
+        let Some(path) = shape_path(shape) else {
+            return;
+        };
+
+        match option {
+            DrawOptions::Fill(fill) => {
+                self.fill_path(&path, fill_rule(fill.fill_rule), None);
+            }
+            DrawOptions::Stroke(options) => {
+                let mut stroke = self.stroke(options);
+
+                let transform = self.current_transform();
+                let resolution = transform.get_scale().0.max(transform.get_scale().1);
+
+                // Strokes aligned to one side of the path are drawn with the double width, and
+                // masked to the inside or outside of the path respectively.
+                let mask = match options.stroke_alignment {
+                    StrokeAlignment::Center => None,
+                    StrokeAlignment::Positive | StrokeAlignment::Negative => {
+                        stroke.width *= 2.0;
+
+                        let mut mask = self.shape_mask(shape, &DrawOptions::Fill(Default::default()));
+
+                        if options.stroke_alignment == StrokeAlignment::Negative {
+                            mask.invert();
+                        }
+
+                        Some(mask)
+                    }
+                };
+
+                let Some(stroked) = stroke_path(&path, &stroke, resolution) else {
+                    return;
+                };
+
+                self.fill_path(&stroked, SkiaFillRule::Winding, mask.as_ref());
+            }
+        }
+    }
+
+    fn style(&mut self, style: &DrawStyle) {
+        match style {
+            DrawStyle::Color(color) => {
+                self.style_stack.push(TinySkiaStyle::Color(color.to_fsa()));
+            }
+            DrawStyle::Gradient(gradient) => {
+                self.style_stack.push(TinySkiaStyle::Gradient(TinySkiaGradient::convert(gradient)));
+            }
+            DrawStyle::MultiGradient(gradients) => {
+                // Only the top most gradient is drawn.
+                match gradients.last() {
+                    Some(gradient) => self.style_stack.push(TinySkiaStyle::Gradient(TinySkiaGradient::convert(gradient))),
+                    None => self.style_stack.push(TinySkiaStyle::Color([0.0, 0.0, 0.0, 0.0])),
+                }
+            }
+        }
+    }
+
+    fn pop_style(&mut self) {
+        assert!(self.style_stack.pop().is_some(), "A style was popped, when no style is present.")
+    }
+
+    fn stroke_dash_pattern(&mut self, pattern: Option<StrokeDashPattern>) {
+        self.stroke_dash_stack.push(pattern);
+    }
+
+    fn pop_stroke_dash_pattern(&mut self) {
+        self.stroke_dash_stack.pop();
+    }
+
+    fn raster_image(&mut self, id: &ImageId, bounding_box: Rect, options: ImageOptions) {
+        let source_rect = options.source_rect.unwrap_or_else(|| Rect::new(Position::new(0.0, 0.0), Dimension::new(1.0, 1.0)));
+
+        let mode = match options.mode {
+            ImageMode::Image => ImageDrawMode::Image,
+            ImageMode::Icon => ImageDrawMode::Tinted,
+        };
+
+        let Some(image) = IMAGES.with(|images| images.borrow().get(id).cloned()) else {
+            return;
+        };
+
+        self.draw_image(&image, bounding_box, source_rect, mode);
+    }
+
+    fn text(&mut self, text: &str, style: &TextStyle, position: Position, requested_size: Option<Dimension>, env: &mut Environment, ctx: &mut dyn TextContext) {
+        // Glyphs not yet in the atlas are skipped when rendering. To render the text within a single
+        // frame, we enqueue the glyphs first, and update the atlas before rendering.
+        ctx.render_new(text, style, position, requested_size, env, &mut |_| {});
+        ctx.prepare_render();
+        ctx.update_cache(&mut |image| self.update_atlas(image));
+
+        let mut glyphs = vec![];
+        ctx.render_new(text, style, position, requested_size, env, &mut |glyph| glyphs.push(glyph.clone()));
+
+        for glyph in &glyphs {
+            self.draw_glyph(glyph);
+        }
+    }
+
+    fn attributed_text(&mut self, text: &AttributedText, style: &TextStyle, position: Position, requested_size: Option<Dimension>, env: &mut Environment, ctx: &mut dyn TextContext) {
+        ctx.render_attributed(text, style, position, requested_size, env, &mut |_| {});
+        ctx.prepare_render();
+        ctx.update_cache(&mut |image| self.update_atlas(image));
+
+        let mut glyphs = vec![];
+        ctx.render_attributed(text, style, position, requested_size, env, &mut |glyph| glyphs.push(glyph.clone()));
+
+        for glyph in &glyphs {
+            self.draw_glyph(glyph);
+        }
+    }
+
+    fn text_old(&mut self, text: TextId, ctx: &mut dyn TextContext) {
+        ctx.prepare_render();
+        ctx.update_cache(&mut |image| self.update_atlas(image));
+
+        let mut glyphs = vec![];
+        ctx.render(text, &mut |glyph| glyphs.push(glyph.clone()));
+
+        for glyph in &glyphs {
+            self.draw_glyph(glyph);
+        }
+    }
+
+    fn filter_new(&mut self) {
+        self.push_target();
+    }
+
+    fn filter_new_pop(&mut self, filter: &ImageFilter, color: Color, post_draw: bool) {
+        self.finish_filter(&[filter], color, post_draw);
+    }
+
+    fn filter_new_pop2d(&mut self, filter: &ImageFilter, filter2: &ImageFilter, color: Color, post_draw: bool) {
+        self.finish_filter(&[filter, filter2], color, post_draw);
+    }
+
+    fn mask_start(&mut self) {
+        self.push_target();
+    }
+
+    fn mask_in(&mut self) {
+        let mask = self.pop_target();
+        self.mask_stack.push(Mask::from_pixmap(mask.as_ref(), MaskType::Alpha));
+        self.push_target();
+    }
+
+    fn mask_end(&mut self) {
+        let content = self.pop_target();
+        let mask = self.mask_stack.pop().expect("A mask was ended, when no mask is present.");
+
+        self.composite(&content, Some(&mask));
+    }
+
+    fn layer(&mut self, layer_id: LayerId, dimensions: Dimension, _env: &mut Environment) -> Layer<'_> {
+        let width = dimensions.width.floor().max(1.0) as u32;
+        let height = dimensions.height.floor().max(1.0) as u32;
+
+        let layer = self.layers.entry(layer_id).or_insert_with(|| TinySkiaLayer::new(width, height));
+
+        if layer.pixmap.width() != width || layer.pixmap.height() != height {
+            *layer = TinySkiaLayer::new(width, height);
+        }
+
+        Layer {
+            inner: layer,
+            inner2: layer,
+        }
+    }
+
+    fn render_layer(&mut self, layer_id: LayerId, bounding_box: Rect) {
+        let Some(layer) = self.layers.remove(&layer_id) else {
+            return;
+        };
+
+        self.draw_image(&layer.pixmap, bounding_box, Rect::new(Position::new(0.0, 0.0), Dimension::new(1.0, 1.0)), ImageDrawMode::Image);
+
+        self.layers.insert(layer_id, layer);
+    }
+}
+
+/// Convert the path to the outline of its stroke. Stroking a path in tiny-skia ignores the dash
+/// pattern, so the path is dashed first.
+fn stroke_path(path: &tiny_skia::Path, stroke: &Stroke, resolution: f32) -> Option<tiny_skia::Path> {
+    match &stroke.dash {
+        Some(dash) => path.dash(dash, resolution)?.stroke(stroke, resolution),
+        None => path.stroke(stroke, resolution),
+    }
+}
+
+/// Multiply the coverage of the mask with the coverage of the other mask.
+fn intersect(mut mask: Mask, other: &Mask) -> Mask {
+    for (value, other) in mask.data_mut().iter_mut().zip(other.data()) {
+        *value = ((*value as u16 * *other as u16 + 127) / 255) as u8;
+    }
+
+    mask
+}
+
+fn fill_rule(rule: FillRule) -> SkiaFillRule {
+    match rule {
+        FillRule::EvenOdd => SkiaFillRule::EvenOdd,
+        FillRule::NonZero => SkiaFillRule::Winding,
+    }
+}
+
+fn line_cap(cap: LineCap) -> SkiaLineCap {
+    match cap {
+        LineCap::Butt => SkiaLineCap::Butt,
+        LineCap::Square => SkiaLineCap::Square,
+        LineCap::Round => SkiaLineCap::Round,
+    }
+}
+
+/// Convert a shape into a tiny-skia path. Returns `None` if the shape is empty.
+fn shape_path(shape: &DrawShape) -> Option<tiny_skia::Path> {
+    match shape {
+        DrawShape::Rectangle(rect) => {
+            Some(PathBuilder::from_rect(skia_rect(rect)?))
+        }
+        DrawShape::Capsule(rect) => {
+            let radius = rect.width().min(rect.height()) / 2.0;
+            rounded_rectangle(rect, &CornerRadii::all(radius))
+        }
+        DrawShape::RoundedRectangle(rect, corners) => {
+            rounded_rectangle(rect, corners)
+        }
+        DrawShape::Circle(center, radius) => {
+            PathBuilder::from_circle(center.x as f32, center.y as f32, *radius as f32)
+        }
+        DrawShape::Ellipse(rect) => {
+            PathBuilder::from_oval(skia_rect(rect)?)
+        }
+        DrawShape::Line(from, to) => {
+            let mut builder = PathBuilder::new();
+            builder.move_to(from.x as f32, from.y as f32);
+            builder.line_to(to.x as f32, to.y as f32);
+            builder.finish()
+        }
+        DrawShape::Path(path) => {
+            let mut builder = PathBuilder::new();
+
+            for instruction in &path.instructions {
+                match instruction {
+                    PathInstruction::MoveTo { to } => {
+                        builder.move_to(to.x as f32, to.y as f32);
+                    }
+                    PathInstruction::Close => {
+                        builder.close();
+                    }
+                    PathInstruction::LineTo { to } => {
+                        builder.line_to(to.x as f32, to.y as f32);
+                    }
+                    PathInstruction::QuadraticBezierTo { ctrl, to } => {
+                        builder.quad_to(ctrl.x as f32, ctrl.y as f32, to.x as f32, to.y as f32);
+                    }
+                    PathInstruction::CubicBezierTo { ctrl1, ctrl2, to } => {
+                        builder.cubic_to(ctrl1.x as f32, ctrl1.y as f32, ctrl2.x as f32, ctrl2.y as f32, to.x as f32, to.y as f32);
+                    }
+                    PathInstruction::Arc { center, radius, start_angle, end_angle } => {
+                        arc(&mut builder, *center, *radius, start_angle.radians(), end_angle.radians());
+                    }
+                }
+            }
+
+            builder.finish()
+        }
+    }
+}
+
+fn skia_rect(rect: &Rect) -> Option<tiny_skia::Rect> {
+    tiny_skia::Rect::from_xywh(
+        rect.position.x as f32,
+        rect.position.y as f32,
+        rect.width() as f32,
+        rect.height() as f32,
+    )
+}
+
+fn rounded_rectangle(rect: &Rect, corners: &CornerRadii) -> Option<tiny_skia::Path> {
+    let (left, right, top, bottom) = (
+        rect.left() as f32,
+        rect.right() as f32,
+        rect.bottom() as f32,
+        rect.top() as f32,
+    );
+
+    let max = (rect.width().min(rect.height()) / 2.0) as f32;
+    let clamp = |radius: Scalar| (radius as f32).clamp(0.0, max);
+
+    let top_left = clamp(corners.top_left);
+    let top_right = clamp(corners.top_right);
+    let bottom_left = clamp(corners.bottom_left);
+    let bottom_right = clamp(corners.bottom_right);
+
+    let mut builder = PathBuilder::new();
+
+    builder.move_to(left + top_left, top);
+    builder.line_to(right - top_right, top);
+    builder.cubic_to(right - top_right * (1.0 - KAPPA), top, right, top + top_right * (1.0 - KAPPA), right, top + top_right);
+    builder.line_to(right, bottom - bottom_right);
+    builder.cubic_to(right, bottom - bottom_right * (1.0 - KAPPA), right - bottom_right * (1.0 - KAPPA), bottom, right - bottom_right, bottom);
+    builder.line_to(left + bottom_left, bottom);
+    builder.cubic_to(left + bottom_left * (1.0 - KAPPA), bottom, left, bottom - bottom_left * (1.0 - KAPPA), left, bottom - bottom_left);
+    builder.line_to(left, top + top_left);
+    builder.cubic_to(left, top + top_left * (1.0 - KAPPA), left + top_left * (1.0 - KAPPA), top, left + top_left, top);
+    builder.close();
+
+    builder.finish()
+}
+
+/// Append an elliptical arc to the path, approximated by cubic beziers of at most 90 degrees each.
+/// If the path has a current point, a line is drawn to the start of the arc.
+fn arc(builder: &mut PathBuilder, center: Position, radius: Dimension, start: Scalar, end: Scalar) {
+    let point = |angle: Scalar| (
+        (center.x + radius.width * angle.cos()) as f32,
+        (center.y + radius.height * angle.sin()) as f32,
+    );
+
+    let (x, y) = point(start);
+
+    if builder.last_point().is_some() {
+        builder.line_to(x, y);
+    } else {
+        builder.move_to(x, y);
+    }
+
+    let sweep = end - start;
+    let segments = (sweep.abs() / FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / segments as Scalar;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+
+    for i in 0..segments {
+        let from = start + step * i as Scalar;
+        let to = from + step;
+
+        let ctrl1 = (
+            (center.x + radius.width * (from.cos() - k * from.sin())) as f32,
+            (center.y + radius.height * (from.sin() + k * from.cos())) as f32,
+        );
+        let ctrl2 = (
+            (center.x + radius.width * (to.cos() + k * to.sin())) as f32,
+            (center.y + radius.height * (to.sin() - k * to.cos())) as f32,
+        );
+        let (x, y) = point(to);
+
+        builder.cubic_to(ctrl1.0, ctrl1.1, ctrl2.0, ctrl2.1, x, y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::RgbaImage;
+
+    use carbide_core::color::RED;
+    use carbide_core::draw::{CompositeDrawShape, Dimension, DrawOptions, DrawShape, DrawStyle, Position, Rect};
+    use carbide_core::draw::stroke::{StrokeDashCap, StrokeDashMode, StrokeDashPattern, StrokeOptions};
+    use carbide_core::render::InnerRenderContext;
+
+    use crate::render_context::TinySkiaRenderContext;
+
+    fn rect(x: f64, y: f64, width: f64, height: f64) -> Rect {
+        Rect::new(Position::new(x, y), Dimension::new(width, height))
+    }
+
+    /// Render into a 40x40 logical canvas with the given scale factor.
+    fn render(scale_factor: f64, f: impl FnOnce(&mut TinySkiaRenderContext)) -> RgbaImage {
+        let size = (40.0 * scale_factor) as u32;
+        let mut ctx = TinySkiaRenderContext::new(size, size, scale_factor);
+        ctx.start(size, size, scale_factor);
+
+        ctx.style(&DrawStyle::Color(RED));
+        f(&mut ctx);
+        ctx.pop_style();
+
+        ctx.image()
+    }
+
+    fn fill_all(ctx: &mut TinySkiaRenderContext) {
+        ctx.shape(&DrawShape::Rectangle(rect(0.0, 0.0, 40.0, 40.0)), &DrawOptions::Fill(Default::default()));
+    }
+
+    fn is_drawn(image: &RgbaImage, x: u32, y: u32) -> bool {
+        image.get_pixel(x, y).0[3] == 255
+    }
+
+    fn is_empty(image: &RgbaImage, x: u32, y: u32) -> bool {
+        image.get_pixel(x, y).0[3] == 0
+    }
+
+    #[test]
+    fn clip_limits_drawing_to_the_scaled_bounding_box() {
+        let image = render(2.0, |ctx| {
+            ctx.clip(rect(10.0, 10.0, 10.0, 10.0));
+            fill_all(ctx);
+            ctx.pop_clip();
+        });
+
+        assert!(is_drawn(&image, 21, 21));
+        assert!(is_drawn(&image, 38, 38));
+        assert!(is_empty(&image, 18, 30));
+        assert!(is_empty(&image, 41, 30));
+    }
+
+    #[test]
+    fn clips_are_intersected_and_restored() {
+        let image = render(1.0, |ctx| {
+            ctx.clip(rect(0.0, 0.0, 20.0, 40.0));
+            ctx.clip(rect(0.0, 0.0, 40.0, 20.0));
+            fill_all(ctx);
+            ctx.pop_clip();
+            ctx.pop_clip();
+
+            ctx.shape(&DrawShape::Rectangle(rect(30.0, 30.0, 10.0, 10.0)), &DrawOptions::Fill(Default::default()));
+        });
+
+        assert!(is_drawn(&image, 5, 5));
+        assert!(is_empty(&image, 25, 5));
+        assert!(is_empty(&image, 5, 25));
+        assert!(is_drawn(&image, 35, 35));
+    }
+
+    #[test]
+    fn stencil_limits_drawing_to_the_union_of_the_shapes() {
+        let image = render(1.0, |ctx| {
+            ctx.stencil(CompositeDrawShape::Many(vec![
+                (DrawShape::Circle(Position::new(10.0, 10.0), 8.0), DrawOptions::Fill(Default::default())),
+                (DrawShape::Rectangle(rect(20.0, 20.0, 20.0, 20.0)), DrawOptions::Fill(Default::default())),
+            ]));
+            fill_all(ctx);
+            ctx.pop_stencil();
+        });
+
+        assert!(is_drawn(&image, 10, 10));
+        assert!(is_drawn(&image, 30, 30));
+        assert!(is_empty(&image, 1, 1));
+        assert!(is_empty(&image, 30, 10));
+    }
+
+    #[test]
+    fn mask_uses_the_alpha_of_the_mask_content() {
+        let image = render(1.0, |ctx| {
+            ctx.mask_start();
+            ctx.shape(&DrawShape::Rectangle(rect(0.0, 0.0, 20.0, 40.0)), &DrawOptions::Fill(Default::default()));
+            ctx.mask_in();
+            fill_all(ctx);
+            ctx.mask_end();
+        });
+
+        assert!(is_drawn(&image, 5, 20));
+        assert!(is_empty(&image, 30, 20));
+    }
+
+    #[test]
+    fn dashes_leave_gaps_in_strokes() {
+        let line = DrawShape::Line(Position::new(0.0, 20.0), Position::new(40.0, 20.0));
+        let options = DrawOptions::Stroke(StrokeOptions::default().with_stroke_width(4.0));
+
+        let image = render(1.0, |ctx| {
+            ctx.stroke_dash_pattern(Some(StrokeDashPattern {
+                pattern: vec![10.0],
+                offset: 0.0,
+                start_cap: StrokeDashCap::None,
+                end_cap: StrokeDashCap::None,
+                dash_type: StrokeDashMode::Fast,
+            }));
+            ctx.shape(&line, &options);
+            ctx.pop_stroke_dash_pattern();
+        });
+
+        assert!(is_drawn(&image, 5, 20));
+        assert!(is_empty(&image, 15, 20));
+        assert!(is_drawn(&image, 25, 20));
+        assert!(is_empty(&image, 35, 20));
+
+        let solid = render(1.0, |ctx| ctx.shape(&line, &options));
+
+        assert!(is_drawn(&solid, 15, 20));
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use carbide_core::draw::{Alignment, Dimension, ImageId, Position, Rect, Scalar};
+use carbide_core::draw::theme::Theme;
+use carbide_core::environment::Environment;
+use carbide_core::layout::{Layout, LayoutContext};
+use carbide_core::lifecycle::{Update, UpdateContext};
+use carbide_core::render::{Render, RenderContext, RenderInstruction, RenderInstructionCache};
+use carbide_core::scene::SceneManager;
+use carbide_core::text::TextContext;
+use carbide_core::widget::{AnyWidget, CommonWidget};
+use carbide_cosmic_text::text_context::CosmicTextContext;
+use image::RgbaImage;
+
+use crate::image_context::TinySkiaImageContext;
+use crate::render_context::TinySkiaRenderContext;
+
+/// Renders a widget tree into an image without needing a window or a GPU. The renderer runs
+/// update, layout and render on the widget, similar to what a window does each frame.
+pub struct TinySkiaRenderer {
+    render_context: TinySkiaRenderContext,
+    text_context: CosmicTextContext,
+    image_context: TinySkiaImageContext,
+    render_instruction_cache: HashMap<ImageId, Rc<(Dimension, Vec<RenderInstruction>)>>,
+    scale_factor: Scalar,
+    theme: Theme,
+}
+
+impl TinySkiaRenderer {
+    pub fn new(scale_factor: Scalar) -> TinySkiaRenderer {
+        TinySkiaRenderer {
+            render_context: TinySkiaRenderContext::new(1, 1, scale_factor),
+            text_context: CosmicTextContext::new(),
+            image_context: TinySkiaImageContext,
+            render_instruction_cache: HashMap::new(),
+            scale_factor,
+            theme: Theme::default(),
+        }
+    }
+
+    pub fn with_theme(mut self, theme: Theme) -> TinySkiaRenderer {
+        self.theme = theme;
+        self
+    }
+
+    pub fn text_context(&mut self) -> &mut dyn TextContext {
+        &mut self.text_context
+    }
+
+    pub fn render_context(&self) -> &TinySkiaRenderContext {
+        &self.render_context
+    }
+
+    /// Render the widget within the given logical dimension. The resulting image has the size
+    /// of the dimension multiplied by the scale factor. The widget is rendered as is, so widgets
+    /// using theme colors or font sizes should be wrapped in a
+    /// [ThemeManager](carbide_core::widget::managers::ThemeManager) and a
+    /// [FontSizeManager](carbide_core::widget::managers::FontSizeManager), like the content of a window.
+    pub fn render(&mut self, widget: &mut dyn AnyWidget, dimension: Dimension, env: &mut Environment) -> RgbaImage {
+        let width = (dimension.width * self.scale_factor).round().max(1.0) as u32;
+        let height = (dimension.height * self.scale_factor).round().max(1.0) as u32;
+
+        let mut scene_manager = SceneManager::new(
+            self.scale_factor,
+            Dimension::new(width as Scalar, height as Scalar),
+        );
+
+        let theme = self.theme;
+
+        env.with::<Theme>(&theme, |env| {
+            env.with_mut::<SceneManager>(&mut scene_manager, |env| {
+                env.with_mut::<RenderInstructionCache>(&mut self.render_instruction_cache, |env| {
+                    widget.process_update(&mut UpdateContext {
+                        text: &mut self.text_context,
+                        image: &mut self.image_context,
+                        env,
+                    });
+
+                    widget.process_calculate_size(dimension, &mut LayoutContext {
+                        text: &mut self.text_context,
+                        image: &mut self.image_context,
+                        env,
+                    });
+
+                    widget.set_position(Alignment::Center.position(Position::origin(), dimension, widget.dimension()));
+
+                    widget.position_children(Rect::new(Position::origin(), dimension), &mut LayoutContext {
+                        text: &mut self.text_context,
+                        image: &mut self.image_context,
+                        env,
+                    });
+
+                    self.render_context.start(width, height, self.scale_factor);
+
+                    self.text_context.prepare_render();
+
+                    widget.render(&mut RenderContext {
+                        render: &mut self.render_context,
+                        text: &mut self.text_context,
+                        image: &mut self.image_context,
+                        env,
+                    });
+                })
+            })
+        });
+
+        self.render_context.image()
+    }
+}
@@ -0,0 +1,201 @@
+use std::path::PathBuf;
+
+use carbide_core::draw::{Dimension, Scalar};
+use carbide_core::environment::Environment;
+use carbide_core::lifecycle::{Initialize, InitializationContext};
+use carbide_core::widget::managers::{FontSizeManager, ThemeManager};
+use carbide_core::widget::Widget;
+use image::{Rgba, RgbaImage};
+
+use crate::renderer::TinySkiaRenderer;
+
+/// The environment variable that can be set to overwrite existing snapshots with the
+/// current rendering, instead of comparing against them.
+pub const UPDATE_SNAPSHOTS_VARIABLE: &str = "CARBIDE_UPDATE_SNAPSHOTS";
+
+/// Assert that a widget renders the same as the stored golden image with the given name.
+/// The snapshots are stored in `tests/snapshots` of the crate calling the macro, and should be
+/// committed together with the tests.
+///
+/// Missing snapshots fail the assertion, unless `CARBIDE_UPDATE_SNAPSHOTS` is set, in which case
+/// the current rendering is written as the new snapshot. This departs from writing the snapshot
+/// on the first run, because a test would then pass on CI without ever comparing anything, when
+/// its snapshot was not committed. The rendering is instead written next to the missing snapshot
+/// with the extension `.new.png`, to be reviewed before storing it.
+///
+/// ```ignore
+/// assert_snapshot!(Rectangle::new().fill(RED), Dimension::new(100.0, 100.0), "red_rectangle");
+/// assert_snapshot!(Circle::new(), Dimension::new(100.0, 100.0), "circle", tolerance = 2);
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($widget:expr, $dimension:expr, $name:expr) => {
+        $crate::Snapshot::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"), $name)
+            .assert($widget, $dimension)
+    };
+    ($widget:expr, $dimension:expr, $name:expr, tolerance = $tolerance:expr) => {
+        $crate::Snapshot::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots"), $name)
+            .tolerance($tolerance)
+            .assert($widget, $dimension)
+    };
+}
+
+/// # Snapshot
+/// A golden image test of a widget. The widget is rendered using the software renderer and
+/// compared pixel by pixel against the stored image. Two pixels are considered equal if no
+/// channel differs by more than the tolerance.
+///
+/// When the comparison fails, the current rendering is written next to the snapshot with the
+/// extension `.new.png`, and an image highlighting the differing pixels in red is written with
+/// the extension `.diff.png`.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    directory: PathBuf,
+    name: String,
+    tolerance: u8,
+    scale_factor: Scalar,
+}
+
+impl Snapshot {
+    pub fn new(directory: impl Into<PathBuf>, name: impl Into<String>) -> Snapshot {
+        Snapshot {
+            directory: directory.into(),
+            name: name.into(),
+            tolerance: 0,
+            scale_factor: 1.0,
+        }
+    }
+
+    /// The maximum difference allowed per channel, before a pixel is considered changed.
+    pub fn tolerance(mut self, tolerance: u8) -> Snapshot {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn scale_factor(mut self, scale_factor: Scalar) -> Snapshot {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.directory.join(format!("{}.png", self.name))
+    }
+
+    fn new_path(&self) -> PathBuf {
+        self.directory.join(format!("{}.new.png", self.name))
+    }
+
+    fn diff_path(&self) -> PathBuf {
+        self.directory.join(format!("{}.diff.png", self.name))
+    }
+
+    /// Render the widget within the dimension, and compare it to the stored snapshot. The widget
+    /// is given the theme colors and font sizes, like the content of a window.
+    /// Panics if the rendering differs from the snapshot.
+    pub fn assert(&self, widget: impl Widget, dimension: Dimension) {
+        let mut widget = FontSizeManager::new(ThemeManager::new(widget));
+        let mut env = Environment::new();
+
+        widget.process_initialization(&mut InitializationContext {
+            env: &mut env,
+        });
+
+        let image = TinySkiaRenderer::new(self.scale_factor).render(&mut widget, dimension, &mut env);
+
+        if let Err(message) = self.compare(&image) {
+            panic!("Snapshot '{}' failed: {}", self.name, message);
+        }
+    }
+
+    /// Compare the image to the stored snapshot. If the update environment variable is set, the
+    /// image is stored as the new snapshot instead. A missing snapshot is an error, such that
+    /// snapshots that have not been committed do not pass silently.
+    pub fn compare(&self, image: &RgbaImage) -> Result<(), String> {
+        let path = self.path();
+
+        let update = std::env::var(UPDATE_SNAPSHOTS_VARIABLE).is_ok_and(|value| value != "0");
+
+        if update {
+            std::fs::create_dir_all(&self.directory)
+                .map_err(|err| format!("Could not create the snapshot directory: {}", err))?;
+
+            image.save(&path)
+                .map_err(|err| format!("Could not write the snapshot: {}", err))?;
+
+            return Ok(());
+        }
+
+        if !path.exists() {
+            let _ = std::fs::create_dir_all(&self.directory);
+            let _ = image.save(self.new_path());
+
+            return Err(format!(
+                "No snapshot exists at {}. The new rendering is written to {}. Run the tests with {}=1 to store it as the snapshot",
+                path.display(),
+                self.new_path().display(),
+                UPDATE_SNAPSHOTS_VARIABLE
+            ));
+        }
+
+        let expected = image::open(&path)
+            .map_err(|err| format!("Could not read the snapshot at {}: {}", path.display(), err))?
+            .to_rgba8();
+
+        if expected.dimensions() != image.dimensions() {
+            let _ = image.save(self.new_path());
+
+            return Err(format!(
+                "The dimensions differ, expected {:?} but got {:?}. The new rendering is written to {}",
+                expected.dimensions(),
+                image.dimensions(),
+                self.new_path().display()
+            ));
+        }
+
+        let (diff, count) = self.diff(&expected, image);
+
+        if count == 0 {
+            let _ = std::fs::remove_file(self.new_path());
+            let _ = std::fs::remove_file(self.diff_path());
+            return Ok(());
+        }
+
+        let _ = image.save(self.new_path());
+        let _ = diff.save(self.diff_path());
+
+        Err(format!(
+            "{} pixels differ by more than the tolerance of {}. The new rendering is written to {} and the difference to {}",
+            count,
+            self.tolerance,
+            self.new_path().display(),
+            self.diff_path().display()
+        ))
+    }
+
+    /// Create an image where the differing pixels are red, and the others are a faded
+    /// grayscale version of the expected image. Returns the image and the number of
+    /// differing pixels.
+    fn diff(&self, expected: &RgbaImage, actual: &RgbaImage) -> (RgbaImage, usize) {
+        let mut count = 0;
+
+        let diff = RgbaImage::from_fn(expected.width(), expected.height(), |x, y| {
+            let a = expected.get_pixel(x, y);
+            let b = actual.get_pixel(x, y);
+
+            let differs = a.0.iter()
+                .zip(b.0.iter())
+                .any(|(a, b)| a.abs_diff(*b) > self.tolerance);
+
+            if differs {
+                count += 1;
+                Rgba([255, 0, 0, 255])
+            } else {
+                let luminance = (a[0] as u32 * 3 + a[1] as u32 * 6 + a[2] as u32) / 10;
+                let gray = (luminance * a[3] as u32 / 255 / 4 + 192) as u8;
+                Rgba([gray, gray, gray, 255])
+            }
+        });
+
+        (diff, count)
+    }
+}
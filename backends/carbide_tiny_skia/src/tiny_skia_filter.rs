@@ -0,0 +1,122 @@
+use carbide_core::color::{hsl_to_rgb, rgb_to_hsl};
+use carbide_core::widget::ImageFilter;
+use tiny_skia::{IntRect, Pixmap, PremultipliedColorU8};
+use crate::tiny_skia_gradient::premultiplied;
+
+/// The color filter currently applied, given as shifts relative to the original colors.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TinySkiaColorFilter {
+    /// Hue rotation given in turns.
+    pub hue_rotation: f32,
+    pub saturation_shift: f32,
+    pub luminance_shift: f32,
+    pub color_invert: bool,
+}
+
+impl TinySkiaColorFilter {
+    pub fn is_identity(&self) -> bool {
+        self.hue_rotation == 0.0 &&
+            self.saturation_shift == 0.0 &&
+            self.luminance_shift == 0.0 &&
+            !self.color_invert
+    }
+
+    /// Apply the color filter to every pixel of the pixmap.
+    pub fn apply(&self, pixmap: &mut Pixmap) {
+        if self.is_identity() {
+            return;
+        }
+
+        for pixel in pixmap.pixels_mut() {
+            if pixel.alpha() == 0 {
+                continue;
+            }
+
+            let a = pixel.alpha() as f32 / 255.0;
+            let mut r = pixel.red() as f32 / 255.0 / a;
+            let mut g = pixel.green() as f32 / 255.0 / a;
+            let mut b = pixel.blue() as f32 / 255.0 / a;
+
+            if self.color_invert {
+                r = 1.0 - r;
+                g = 1.0 - g;
+                b = 1.0 - b;
+            }
+
+            let (h, s, l) = rgb_to_hsl(r, g, b);
+
+            let turn = std::f32::consts::TAU;
+            let h = (h / turn + self.hue_rotation).rem_euclid(1.0) * turn;
+            let s = (s + self.saturation_shift).clamp(0.0, 1.0);
+            let l = (l + self.luminance_shift).clamp(0.0, 1.0);
+
+            let (r, g, b) = hsl_to_rgb(h, s, l);
+
+            *pixel = premultiplied(r, g, b, a);
+        }
+    }
+}
+
+impl Default for TinySkiaColorFilter {
+    fn default() -> Self {
+        TinySkiaColorFilter {
+            hue_rotation: 0.0,
+            saturation_shift: 0.0,
+            luminance_shift: 0.0,
+            color_invert: false,
+        }
+    }
+}
+
+/// Convolve the source within the area using the filter, and write the result into the target.
+/// Samples outside the source are clamped to the nearest edge pixel, similar to the sampler
+/// used by the wgpu backend. Pixels outside the area are left untouched in the target.
+pub fn convolve(source: &Pixmap, target: &mut Pixmap, filter: &ImageFilter, area: IntRect) {
+    let width = source.width() as i32;
+    let height = source.height() as i32;
+
+    let left = area.left().max(0);
+    let top = area.top().max(0);
+    let right = area.right().min(width).min(target.width() as i32);
+    let bottom = area.bottom().min(height).min(target.height() as i32);
+
+    let source_pixels = source.pixels();
+    let target_width = target.width() as i32;
+    let target_pixels = target.pixels_mut();
+
+    for y in top..bottom {
+        for x in left..right {
+            let mut sum = [0.0f32; 4];
+
+            for value in &filter.filter {
+                let sx = (x + value.offset_x).clamp(0, width - 1);
+                let sy = (y + value.offset_y).clamp(0, height - 1);
+                let sample = source_pixels[(sy * width + sx) as usize];
+
+                sum[0] += sample.red() as f32 * value.weight;
+                sum[1] += sample.green() as f32 * value.weight;
+                sum[2] += sample.blue() as f32 * value.weight;
+                sum[3] += sample.alpha() as f32 * value.weight;
+            }
+
+            let alpha = sum[3].round().clamp(0.0, 255.0) as u8;
+            let channel = |c: f32| (c.round().clamp(0.0, 255.0) as u8).min(alpha);
+
+            target_pixels[(y * target_width + x) as usize] = PremultipliedColorU8::from_rgba(
+                channel(sum[0]),
+                channel(sum[1]),
+                channel(sum[2]),
+                alpha,
+            ).unwrap_or(PremultipliedColorU8::TRANSPARENT);
+        }
+    }
+}
+
+/// Replace the color of every pixel by the given straight alpha color, keeping the alpha.
+/// This is used to tint the result of a filter, for example when drawing shadows.
+pub fn tint(pixmap: &mut Pixmap, color: [f32; 4]) {
+    for pixel in pixmap.pixels_mut() {
+        let a = pixel.alpha() as f32 / 255.0 * color[3];
+        *pixel = premultiplied(color[0], color[1], color[2], a);
+    }
+}
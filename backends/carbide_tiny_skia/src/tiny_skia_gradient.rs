@@ -0,0 +1,171 @@
+use carbide_core::color::ColorExt;
+use carbide_core::draw::gradient::{GradientRepeat, GradientType};
+use carbide_core::draw::{DrawGradient, Position, Rect};
+use tiny_skia::{Color, GradientStop, LinearGradient, Pixmap, PremultipliedColorU8, RadialGradient, Shader, SpreadMode, Transform};
+
+/// A gradient converted into a form that can be evaluated on the CPU. The evaluation mirrors
+/// the gradient function in the wgpu shader, so both backends produce the same colors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TinySkiaGradient {
+    colors: Vec<[f32; 4]>,
+    ratios: Vec<f32>,
+    gradient_type: GradientType,
+    gradient_repeat: GradientRepeat,
+    start: Position,
+    end: Position,
+}
+
+impl TinySkiaGradient {
+    pub fn convert(gradient: &DrawGradient) -> TinySkiaGradient {
+        TinySkiaGradient {
+            colors: gradient.colors.iter().map(|color| color.to_fsa()).collect(),
+            ratios: gradient.ratios.clone(),
+            gradient_type: gradient.gradient_type.clone(),
+            gradient_repeat: gradient.gradient_repeat.clone(),
+            start: gradient.start,
+            end: gradient.end,
+        }
+    }
+
+    /// Create a tiny-skia shader for the gradient. Linear and radial gradients are supported
+    /// natively. Diamond and conic gradients are rasterized into a pattern covering `bounds`,
+    /// which is given in the local coordinate space of the shape being drawn.
+    pub fn shader(&self, bounds: Rect, scale: f32, pattern: &mut Option<Pixmap>) -> Option<Shader<'static>> {
+        let stops = self.colors.iter()
+            .zip(self.ratios.iter())
+            .filter_map(|(color, ratio)| {
+                Color::from_rgba(color[0], color[1], color[2], color[3])
+                    .map(|color| GradientStop::new(*ratio, color))
+            })
+            .collect::<Vec<_>>();
+
+        let spread_mode = match self.gradient_repeat {
+            GradientRepeat::Clamp => SpreadMode::Pad,
+            GradientRepeat::Repeat => SpreadMode::Repeat,
+            GradientRepeat::Mirror => SpreadMode::Reflect,
+        };
+
+        let start = tiny_skia::Point::from_xy(self.start.x as f32, self.start.y as f32);
+        let end = tiny_skia::Point::from_xy(self.end.x as f32, self.end.y as f32);
+
+        match self.gradient_type {
+            GradientType::Linear => {
+                LinearGradient::new(start, end, stops, spread_mode, Transform::identity())
+            }
+            GradientType::Radial => {
+                let radius = self.start.dist(&self.end) as f32;
+                RadialGradient::new(start, start, radius, stops, spread_mode, Transform::identity())
+            }
+            GradientType::Diamond | GradientType::Conic => {
+                *pattern = self.rasterize(bounds, scale);
+                None
+            }
+        }
+    }
+
+    /// Rasterize the gradient into a pixmap covering the bounds, with `scale` pixels per unit.
+    pub fn rasterize(&self, bounds: Rect, scale: f32) -> Option<Pixmap> {
+        let width = (bounds.width() as f32 * scale).ceil().max(1.0) as u32;
+        let height = (bounds.height() as f32 * scale).ceil().max(1.0) as u32;
+
+        let mut pixmap = Pixmap::new(width, height)?;
+
+        for y in 0..height {
+            for x in 0..width {
+                let position = Position::new(
+                    bounds.position.x + (x as f64 + 0.5) / scale as f64,
+                    bounds.position.y + (y as f64 + 0.5) / scale as f64,
+                );
+
+                let [r, g, b, a] = self.color_at(position);
+
+                pixmap.pixels_mut()[(y * width + x) as usize] = premultiplied(r, g, b, a);
+            }
+        }
+
+        Some(pixmap)
+    }
+
+    /// Evaluate the straight alpha color of the gradient at the given position.
+    pub fn color_at(&self, position: Position) -> [f32; 4] {
+        let last = self.colors.len() - 1;
+
+        let start = self.start;
+        let end = self.end;
+        let length = start.dist(&end);
+
+        let mut t = match self.gradient_type {
+            GradientType::Linear => {
+                let direction = (end - start) / length;
+                let offset = position - start;
+                (direction.x * offset.x + direction.y * offset.y) / length
+            }
+            GradientType::Radial => {
+                position.dist(&start) / length
+            }
+            GradientType::Diamond => {
+                let angle = (end.y - start.y).atan2(end.x - start.x);
+                let offset = position - start;
+                let x = angle.cos() * offset.x + angle.sin() * offset.y;
+                let y = -angle.sin() * offset.x + angle.cos() * offset.y;
+                (x.abs() + y.abs()) / length
+            }
+            GradientType::Conic => {
+                let angle = (end.y - start.y).atan2(end.x - start.x) - std::f64::consts::PI;
+                let offset = position - start;
+                let x = angle.cos() * offset.x + angle.sin() * offset.y;
+                let y = -angle.sin() * offset.x + angle.cos() * offset.y;
+                (y.atan2(x).to_degrees() + 180.0) / 360.0
+            }
+        } as f32;
+
+        t = match self.gradient_repeat {
+            GradientRepeat::Clamp => t.clamp(0.0, 1.0),
+            GradientRepeat::Repeat => t.rem_euclid(1.0),
+            GradientRepeat::Mirror => {
+                let t = t.abs();
+                if (t as i32) & 1 == 0 {
+                    t.fract()
+                } else {
+                    1.0 - t.fract()
+                }
+            }
+        };
+
+        if last == 0 || t.is_nan() {
+            return self.colors[last];
+        }
+
+        t = t.clamp(self.ratios[0], self.ratios[last]);
+
+        let mut j = 1;
+        while j < last && t > self.ratios[j] {
+            j += 1;
+        }
+        let i = j - 1;
+
+        let span = self.ratios[j] - self.ratios[i];
+        let a = if span > 0.0 { (t - self.ratios[i]) / span } else { 0.0 };
+
+        let from = self.colors[i];
+        let to = self.colors[j];
+
+        [
+            from[0] + (to[0] - from[0]) * a,
+            from[1] + (to[1] - from[1]) * a,
+            from[2] + (to[2] - from[2]) * a,
+            from[3] + (to[3] - from[3]) * a,
+        ]
+    }
+}
+
+/// Convert a straight alpha color into a premultiplied tiny-skia pixel.
+pub(crate) fn premultiplied(r: f32, g: f32, b: f32, a: f32) -> PremultipliedColorU8 {
+    let a = a.clamp(0.0, 1.0);
+    let alpha = (a * 255.0).round() as u8;
+
+    let channel = |c: f32| ((c.clamp(0.0, 1.0) * a * 255.0).round() as u8).min(alpha);
+
+    PremultipliedColorU8::from_rgba(channel(r), channel(g), channel(b), alpha)
+        .unwrap_or(PremultipliedColorU8::TRANSPARENT)
+}
@@ -0,0 +1,34 @@
+use std::fmt::{Debug, Formatter};
+use carbide_core::render::InnerLayer;
+use tiny_skia::Pixmap;
+
+/// A layer rendered by the CPU renderer. The layer is backed by a pixmap in premultiplied RGBA.
+pub struct TinySkiaLayer {
+    pub(crate) pixmap: Pixmap,
+}
+
+impl TinySkiaLayer {
+    pub(crate) fn new(width: u32, height: u32) -> TinySkiaLayer {
+        TinySkiaLayer {
+            pixmap: Pixmap::new(width.max(1), height.max(1)).expect("The layer size to be non-zero"),
+        }
+    }
+
+    pub fn pixmap(&self) -> &Pixmap {
+        &self.pixmap
+    }
+}
+
+impl InnerLayer for TinySkiaLayer {
+    fn dimensions(&self) -> (u32, u32) {
+        (self.pixmap.width(), self.pixmap.height())
+    }
+}
+
+impl Debug for TinySkiaLayer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TinySkiaLayer")
+            .field("dimensions", &self.dimensions())
+            .finish()
+    }
+}
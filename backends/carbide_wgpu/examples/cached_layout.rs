@@ -0,0 +1,35 @@
+use carbide_core::draw::Dimension;
+use carbide_core::environment::EnvironmentColor;
+use carbide_core::widget::*;
+use carbide_wgpu::{Application, Window};
+
+fn main() {
+    let mut application = Application::new();
+
+    // Each row contains a paragraph of wrapping text, which is expensive to measure. The layout
+    // of every widget is cached, so resizing the window vertically stays smooth: the rows are
+    // proposed the same width and are not measured again.
+    application.set_scene(
+        Window::new(
+            "Cached layout - Carbide",
+            Dimension::new(600.0, 600.0),
+            Scroll::new(
+                LazyVStack::new(
+                    ForEach::new(0..10_000, |_, idx| {
+                        VStack::new((
+                            Text::new(idx),
+                            Text::new("Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat.")
+                                .foreground_color(EnvironmentColor::SecondaryLabel),
+                        )).spacing(4.0)
+                            .padding(10.0)
+                            .background(RoundedRectangle::new(6.0).fill(EnvironmentColor::SecondarySystemBackground))
+                    })
+                ).spacing(6.0)
+            )
+                .clip()
+                .padding(20.0)
+        )
+    );
+
+    application.launch()
+}
@@ -0,0 +1,62 @@
+use std::f64::consts::PI;
+
+use carbide_core::draw::{Dimension, Position, Rect};
+use carbide_core::environment::EnvironmentColor;
+use carbide_core::widget::*;
+use carbide_wgpu::{Application, Window};
+
+/// Places the children evenly on a circle, like the items of a radial menu.
+#[derive(Debug, Clone)]
+struct Radial {
+    radius: f64,
+}
+
+impl LayoutAlgorithm for Radial {
+    fn size_that_fits(&mut self, _proposal: Dimension, subviews: &mut Subviews) -> Dimension {
+        let mut largest = Dimension::new(0.0, 0.0);
+
+        for index in 0..subviews.len() {
+            let size = subviews.size_that_fits(index, Dimension::new(f64::INFINITY, f64::INFINITY));
+            largest = Dimension::new(largest.width.max(size.width), largest.height.max(size.height));
+        }
+
+        Dimension::new(self.radius * 2.0 + largest.width, self.radius * 2.0 + largest.height)
+    }
+
+    fn place_subviews(&mut self, bounds: Rect, subviews: &mut Subviews) {
+        let center = bounds.position + Position::new(bounds.dimension.width / 2.0, bounds.dimension.height / 2.0);
+        let count = subviews.len();
+
+        for index in 0..count {
+            let angle = 2.0 * PI * index as f64 / count as f64 - PI / 2.0;
+            let dimension = subviews.dimension(index);
+
+            let position = center
+                + Position::new(angle.cos() * self.radius, angle.sin() * self.radius)
+                - Position::new(dimension.width / 2.0, dimension.height / 2.0);
+
+            subviews.place(index, position, dimension);
+        }
+    }
+}
+
+fn main() {
+    let mut application = Application::new();
+
+    let items = (1..=8)
+        .map(|item| {
+            ZStack::new((
+                Circle::new().fill(EnvironmentColor::Accent),
+                Text::new(format!("{}", item)),
+            )).frame(40.0, 40.0)
+        })
+        .collect::<Vec<_>>();
+
+    application.set_scene(Window::new(
+        "Custom layout - Carbide",
+        Dimension::new(400.0, 400.0),
+        CustomLayout::new(Radial { radius: 120.0 }, items)
+    ));
+
+    application.launch();
+}
@@ -0,0 +1,47 @@
+use carbide_core::draw::Dimension;
+use carbide_core::environment::*;
+use carbide_core::text::text_wrap::Wrap;
+use carbide_core::widget::*;
+use carbide_wgpu::{Application, Window};
+
+fn tags() -> Vec<impl Widget> {
+    ["Rust", "Layout", "Flow", "Wrapping", "Tags", "Chips", "Toolbars", "Carbide", "Narrow windows", "GPU"]
+        .into_iter()
+        .map(|tag| {
+            Text::new(tag)
+                .wrap(Wrap::None)
+                .padding(8.0)
+                .background(RoundedRectangle::new(8.0).fill(EnvironmentColor::Accent))
+        })
+        .collect()
+}
+
+fn main() {
+    let mut application = Application::new();
+
+    application.set_scene(
+        Window::new(
+            "HWrap - Carbide",
+            Dimension::new(600.0, 600.0),
+            VStack::new((
+                Text::new("HWrap with start justification").wrap(Wrap::None),
+                HWrap::new(tags())
+                    .spacing(8.0)
+                    .border(),
+                Text::new("HWrap with center justification").wrap(Wrap::None),
+                HWrap::new(tags())
+                    .spacing(8.0)
+                    .justification(LineJustification::Center)
+                    .border(),
+                Text::new("HWrap with space between justification").wrap(Wrap::None),
+                HWrap::new(tags())
+                    .spacing(8.0)
+                    .justification(LineJustification::SpaceBetween)
+                    .border(),
+            )).spacing(20.0)
+                .padding(50.0)
+        )
+    );
+
+    application.launch()
+}
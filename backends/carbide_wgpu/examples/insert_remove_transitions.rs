@@ -0,0 +1,50 @@
+use carbide_controls::button::{BorderedProminentStyle, Button};
+use carbide_controls::ControlsExt;
+use carbide_core::animation::Transition;
+use carbide_core::closure;
+use carbide_core::draw::Dimension;
+use carbide_core::environment::EnvironmentColor;
+use carbide_core::state::{AnyState, LocalState, Functor};
+use carbide_core::widget::*;
+use carbide_wgpu::{Application, Window};
+
+fn main() {
+    let mut application = Application::new();
+
+    let show = LocalState::new(true);
+    let items = LocalState::new(vec![1u32, 2, 3]);
+    let next = LocalState::new(4u32);
+
+    application.set_scene(Window::new(
+        "Insert and remove transitions - Carbide",
+        Dimension::new(400.0, 600.0),
+        VStack::new((
+            IfElse::new(show.clone())
+                .when_true(Rectangle::new().fill(EnvironmentColor::Accent).frame(200.0, 60.0))
+                .transition(Transition::opacity().combined(Transition::scale(0.8))),
+            ForEach::new(items.clone(), |item: Box<dyn AnyState<T=u32>>, _| {
+                ZStack::new((
+                    RoundedRectangle::new(4.0).fill(EnvironmentColor::SecondarySystemBackground),
+                    Text::new(item.map(|item: &u32| format!("Item {}", item))),
+                )).frame(200.0, 30.0)
+            }).transition(Transition::offset(-40.0, 0.0).combined(Transition::opacity())),
+            HStack::new((
+                Button::new("Toggle", closure!(|_| {
+                    *$show = !*$show;
+                })).frame(96.0, 22.0),
+                Button::new("Add", closure!(|_| {
+                    $items.push(*$next);
+                    *$next += 1;
+                })).frame(96.0, 22.0),
+                Button::new("Remove", closure!(|_| {
+                    if !$items.is_empty() {
+                        $items.remove(0);
+                    }
+                })).frame(96.0, 22.0),
+            )).spacing(10.0),
+        )).spacing(10.0)
+            .button_style(BorderedProminentStyle)
+    ));
+
+    application.launch();
+}
@@ -0,0 +1,50 @@
+use carbide_core::draw::Dimension;
+use carbide_core::environment::EnvironmentColor;
+use carbide_core::widget::*;
+use carbide_wgpu::{Application, Window};
+
+fn main() {
+    let mut application = Application::new();
+
+    // The same content laid out left to right and right to left. In the right to left layout
+    // the stack starts from the right, the leading padding is on the right side, the leading
+    // side of the split is on the right, and the text is aligned to the right.
+    application.set_scene(Window::new(
+        "Layout direction - Carbide",
+        Dimension::new(500.0, 600.0),
+        VStack::new((
+            content(),
+            content().layout_direction(LayoutDirection::RightToLeft),
+        )).spacing(20.0)
+            .padding(20.0)
+    ));
+
+    application.launch();
+}
+
+fn content() -> impl Widget {
+    VStack::new((
+        HStack::new((
+            item("1"),
+            item("2"),
+            item("3"),
+            Spacer::new(),
+        )).spacing(10.0)
+            .padding(EdgeInsets::single(0.0, 0.0, 40.0, 0.0))
+            .background(Rectangle::new().fill(EnvironmentColor::SecondarySystemBackground)),
+        HSplit::new(
+            Rectangle::new().fill(EnvironmentColor::Green),
+            Rectangle::new().fill(EnvironmentColor::Accent),
+        ).percent(0.3)
+            .frame_fixed_height(60.0),
+        Text::new("The lines of wrapping text are aligned to the leading edge of the text, which is the right edge in right to left layouts.")
+            .frame_fixed_width(300.0),
+    )).spacing(10.0)
+}
+
+fn item(label: &'static str) -> impl Widget {
+    ZStack::new((
+        RoundedRectangle::new(6.0).fill(EnvironmentColor::Accent),
+        Text::new(label),
+    )).frame(40.0, 40.0)
+}
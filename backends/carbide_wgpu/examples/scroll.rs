@@ -15,6 +15,8 @@ fn main() {
                 .clip()
                 .frame(500.0, 500.0),
         )
+            // Keep scrolling after the scroll wheel stops, slowing down over time.
+            .fling(4.0)
             .clip()
             .frame(250.0, 250.0)
             .border()
@@ -0,0 +1,45 @@
+use carbide_controls::button::{BorderedProminentStyle, Button};
+use carbide_controls::ControlsExt;
+use carbide_core::animation::Spring;
+use carbide_core::closure;
+use carbide_core::draw::Dimension;
+use carbide_core::state::{LocalState, ReadStateExtTransition, State};
+use carbide_core::widget::*;
+use carbide_wgpu::{Application, Window};
+
+fn main() {
+    let mut application = Application::new();
+
+    let offset = LocalState::new(-120.0);
+
+    // Pressing the buttons while the rectangle is moving keeps its velocity.
+    let smooth = offset.transition().spring(Spring::smooth());
+    let bouncy = offset.transition().spring(Spring::bouncy());
+
+    application.set_scene(Window::new(
+        "Spring - Carbide",
+        Dimension::new(400.0, 300.0),
+        VStack::new((
+            Rectangle::new()
+                .frame(60.0, 60.0)
+                .offset(smooth, 0.0),
+            Rectangle::new()
+                .frame(60.0, 60.0)
+                .offset(bouncy, 0.0),
+            HStack::new((
+                Button::new("Left", closure!(|_| {
+                    *$offset = -120.0;
+                }))
+                    .frame(96.0, 22.0),
+                Button::new("Right", closure!(|_| {
+                    *$offset = 120.0;
+                }))
+                    .frame(96.0, 22.0),
+            )).spacing(10.0),
+        )).spacing(10.0)
+            .button_style(BorderedProminentStyle)
+    ));
+
+
+    application.launch();
+}
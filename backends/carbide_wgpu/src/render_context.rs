@@ -16,8 +16,8 @@ use carbide_core::draw::{Dimension, DrawOptions, CompositeDrawShape, DrawStyle,
 use carbide_core::environment::Environment;
 use carbide_core::math::{Matrix4, SquareMatrix, Vector3};
 use carbide_core::render::{InnerRenderContext, Layer, LayerId, RenderInstruction};
-use carbide_core::text::glyph::GlyphRenderMode;
-use carbide_core::text::{TextContext, TextId, TextStyle};
+use carbide_core::text::glyph::{Glyph, GlyphRenderMode};
+use carbide_core::text::{AttributedText, TextContext, TextId, TextStyle};
 use carbide_core::widget::{AnyShape, FilterId, ImageFilter};
 use carbide_lyon::stroke_vertex::StrokeVertex;
 use carbide_lyon::triangle::Triangle;
@@ -267,6 +267,22 @@ impl WGPURenderContext {
         }
     }
 
+    fn draw_glyph(&mut self, glyph: &Glyph) {
+        let mode = match glyph.mode {
+            GlyphRenderMode::Plain => MODE_TEXT,
+            GlyphRenderMode::Colored => MODE_TEXT_COLOR,
+        };
+
+        // Glyphs of spans with their own color are drawn using that color instead of the current style.
+        if let Some(color) = glyph.color {
+            self.style(&DrawStyle::Color(color));
+            self.draw_raster_image(None, glyph.bounding_box, glyph.texture_coords, mode);
+            self.pop_style();
+        } else {
+            self.draw_raster_image(None, glyph.bounding_box, glyph.texture_coords, mode);
+        }
+    }
+
     fn draw_raster_image(&mut self, id: Option<WGPUBindGroup>, bounding_box: Rect, source_rect: Rect, mut mode: u32) {
         if self.skip_rendering {
             return;
@@ -886,12 +902,17 @@ impl InnerRenderContext for WGPURenderContext {
         }
 
         ctx.render_new(text, style, position, requested_size, env, &mut |glyph| {
-            let mode = match glyph.mode {
-                GlyphRenderMode::Plain => MODE_TEXT,
-                GlyphRenderMode::Colored => MODE_TEXT_COLOR,
-            };
+            self.draw_glyph(glyph);
+        });
+    }
 
-            self.draw_raster_image(None, glyph.bounding_box, glyph.texture_coords, mode);
+    fn attributed_text(&mut self, text: &AttributedText, style: &TextStyle, position: Position, requested_size: Option<Dimension>, env: &mut Environment, ctx: &mut dyn TextContext) {
+        if self.skip_rendering {
+            return;
+        }
+
+        ctx.render_attributed(text, style, position, requested_size, env, &mut |glyph| {
+            self.draw_glyph(glyph);
         });
     }
 
@@ -901,12 +922,7 @@ impl InnerRenderContext for WGPURenderContext {
         }
 
         ctx.render(text, &mut |glyph| {
-            let mode = match glyph.mode {
-                GlyphRenderMode::Plain => MODE_TEXT,
-                GlyphRenderMode::Colored => MODE_TEXT_COLOR,
-            };
-
-            self.draw_raster_image(None, glyph.bounding_box, glyph.texture_coords, mode);
+            self.draw_glyph(glyph);
         });
     }
 
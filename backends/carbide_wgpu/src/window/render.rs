@@ -72,7 +72,7 @@ impl<T: ReadState<T=String>, C: Widget> InitializedWindow<T, C> {
             });
 
             // Calculate size
-            initialized.child.calculate_size(dimensions, &mut LayoutContext {
+            initialized.child.process_calculate_size(dimensions, &mut LayoutContext {
                 text: ctx.text,
                 image: ctx.image,
                 env,
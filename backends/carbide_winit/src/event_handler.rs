@@ -13,7 +13,7 @@ use carbide_core::accessibility::AccessibilityContext;
 use carbide_core::asynchronous::{AsyncContext, check_tasks};
 use carbide_core::draw::{Dimension, ImageContext, Position, Scalar};
 use carbide_core::environment::{Environment};
-use carbide_core::event::{AccessibilityEvent, AccessibilityEventContext, ApplicationEvent, ApplicationEventContext, EventId, KeyboardEvent, KeyboardEventContext, ModifierKey, MouseEvent, MouseEventContext, OtherEvent, OtherEventContext, OtherEventHandler, WindowEventContext};
+use carbide_core::event::{AccessibilityEvent, AccessibilityEventContext, ApplicationEvent, ApplicationEventContext, EventId, KeyboardEvent, KeyboardEventContext, ModifierKey, MOUSE_CLICK_MAX_DISTANCE, MouseEvent, MouseEventContext, OtherEvent, OtherEventContext, OtherEventHandler, WindowEventContext};
 use carbide_core::focus::{FocusContext, FocusManager, Refocus};
 use carbide_core::render::{NoopRenderContext, RenderContext};
 use carbide_core::scene::AnyScene;
@@ -24,8 +24,6 @@ use carbide_core::widget::WidgetId;
 use crate::{convert_key, convert_mouse_button, convert_touch_phase};
 use crate::custom_event::CustomEvent;
 
-const N_CLICK_THRESHOLD: Duration = Duration::from_millis(500);
-const MOUSE_CLICK_MAX_DISTANCE: f64 = 3.0;
 const ARBITRARY_POINTS_PER_LINE_FACTOR: f64 = 10.0;
 
 static SCALE_FACTORS: Lazy<DashMap<WindowId, Scalar>> = Lazy::new(|| DashMap::new());
@@ -459,26 +457,7 @@ impl NewEventHandler {
                 // A click should be emitted if within a threshold distance of the press.
                 let is_click = self.mouse_position.dist(&pressed_event.get_current_mouse_position()) < MOUSE_CLICK_MAX_DISTANCE;
 
-                // A click will become a double click, if and only if it is within the
-                // double click threshold time based on the latest click of the same button.
-                let click_number = match self.last_click {
-                    // Too long since last click, so we emit a click and not a double click
-                    Some((time, _)) if Instant::now().duration_since(time) > N_CLICK_THRESHOLD => 1,
-
-                    // Our previous click was a normal click of the same button within the
-                    // same location as the previous click. The time is checked in a previous case.
-                    Some((_, MouseEvent::Click(b, location, _))) if convert_mouse_button(button) == b && self.mouse_position.dist(&location) < MOUSE_CLICK_MAX_DISTANCE => 2,
-
-                    // Our previous click was a double click within time and with the same button
-                    // and within range of the previous click.
-                    Some((_, MouseEvent::NClick(b, location, _, n))) if convert_mouse_button(button) == b && self.mouse_position.dist(&location) < MOUSE_CLICK_MAX_DISTANCE => n + 1,
-
-                    // Either the previous click was not in range or not with the same button
-                    Some((_, _)) => 1,
-
-                    // No previous click, so we emit a click and not a double click
-                    None => 1,
-                };
+                let click_number = MouseEvent::click_number(self.last_click.as_ref(), convert_mouse_button(button), self.mouse_position);
 
                 if is_click {
                     if click_number == 1 {
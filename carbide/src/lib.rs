@@ -32,6 +32,11 @@ pub mod dialogs {
     pub use carbide_dialogs::*;
 }
 
+#[cfg(feature = "carbide_markdown")]
+pub mod markdown {
+    pub use carbide_markdown::*;
+}
+
 #[cfg(feature = "carbide_media")]
 pub mod media {
     pub use carbide_media::*;
@@ -0,0 +1,47 @@
+use carbide_controls::button::{BorderedProminentStyle, Button};
+use carbide_controls::{ControlsExt, Form, TextInput};
+use carbide_core::draw::Dimension;
+use carbide_core::state::{FormState, LocalState, ReadState, ValidatedState};
+use carbide_core::widget::*;
+use carbide_wgpu::{Application, Window};
+
+fn main() {
+    let form = FormState::new();
+
+    let name = ValidatedState::new(LocalState::new("".to_string()))
+        .required("A name is required")
+        .validator(|name: &String| {
+            if name.len() > 20 {
+                return Err("The name can be at most 20 characters".to_string());
+            }
+
+            Ok(())
+        });
+
+    let age = ValidatedState::new(LocalState::new(Ok::<u32, String>(30)))
+        .required("An age is required")
+        .parsed("The age must be a whole number")
+        .in_range(18..=120);
+
+    let mut application = Application::new()
+        .with_asset_fonts();
+
+    application.set_scene(Window::new(
+        "Form Example - Carbide",
+        Dimension::new(400.0, 600.0),
+        Form::new(form.clone(), VStack::new((
+            TextInput::new(name.clone())
+                .label("Name"),
+            TextInput::new(age.clone())
+                .label("Age"),
+            Button::new("Save", move |_ctx| {
+                println!("Saved {} aged {:?}", *name.value(), *age.value());
+            }).frame(120.0, 22.0)
+                .button_style(BorderedProminentStyle)
+                .enabled(form.is_valid()),
+        )).spacing(10.0))
+            .padding(EdgeInsets::all(40.0)),
+    ));
+
+    application.launch();
+}
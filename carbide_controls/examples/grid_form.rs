@@ -0,0 +1,44 @@
+use carbide_controls::TextInput;
+use carbide_core::draw::Dimension;
+use carbide_core::environment::EnvironmentColor;
+use carbide_core::state::LocalState;
+use carbide_core::text::text_wrap::Wrap;
+use carbide_core::widget::*;
+use carbide_wgpu::{Application, Window};
+
+fn main() {
+    let name = LocalState::new("Carbide".to_string());
+    let email = LocalState::new("hello@carbide.rs".to_string());
+    let notes = LocalState::new("A form laid out in a grid.".to_string());
+
+    let mut application = Application::new()
+        .with_asset_fonts();
+
+    application.set_scene(
+        Window::new(
+            "Grid Example - Carbide",
+            Dimension::new(500.0, 400.0),
+            Grid::new(vec![GridTrack::Auto, GridTrack::Flexible(1.0)], (
+                Text::new("Name").wrap(Wrap::None),
+                TextInput::new(name),
+                Text::new("Email").wrap(Wrap::None),
+                TextInput::new(email),
+                Text::new("Notes").wrap(Wrap::None),
+                TextInput::new(notes),
+                Rectangle::new()
+                    .fill(EnvironmentColor::Accent)
+                    .frame_fixed_height(4.0)
+                    .grid_cell(2, 1),
+            ))
+                .spacing(Dimension::new(12.0, 10.0))
+                .column_alignment(0, CrossAxisAlignment::End)
+                .column_alignment(1, CrossAxisAlignment::Start)
+                .row_alignment(0, GridRowAlignment::FirstBaseline)
+                .row_alignment(1, GridRowAlignment::FirstBaseline)
+                .row_alignment(2, GridRowAlignment::FirstBaseline)
+                .padding(EdgeInsets::all(40.0)),
+        )
+    );
+
+    application.launch();
+}
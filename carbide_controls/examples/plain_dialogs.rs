@@ -0,0 +1,58 @@
+use carbide_controls::button::{BorderedProminentStyle, BorderedStyle, Button};
+use carbide_controls::dialog::PlainStyle;
+use carbide_controls::ControlsExt;
+use carbide_core::color::RED;
+use carbide_core::draw::Dimension;
+use carbide_core::state::LocalState;
+use carbide_core::widget::*;
+use carbide_dialogs::color_dialog::ColorDialog;
+use carbide_dialogs::open_dialog::OpenDialog;
+use carbide_dialogs::save_dialog::SaveDialog;
+use carbide_dialogs::{DialogsExt, FileType};
+use carbide_wgpu::{Application, Window};
+
+fn main() {
+    let mut application = Application::new()
+        .with_asset_fonts();
+
+    let color = LocalState::new(RED);
+
+    application.set_scene(Window::new(
+        "Plain Dialogs Example - Carbide",
+        Dimension::new(800.0, 600.0),
+        VStack::new((
+            Rectangle::new()
+                .fill(color.clone())
+                .frame(120.0, 60.0),
+            Button::new("Open file", |ctx| {
+                OpenDialog::new()
+                    .set_title(Some("Open a file".to_string()))
+                    .set_multiple_selection(true)
+                    .set_file_types(vec![FileType::new("Rust source", vec!["rs"])])
+                    .open(ctx.env, |res, _| {
+                        println!("Received open paths: {:?}", res.unwrap());
+                    });
+            }).frame(120.0, 22.0)
+                .button_style(BorderedProminentStyle),
+            Button::new("Save file", |ctx| {
+                SaveDialog::new()
+                    .set_default_file_name(Some("main.rs".to_string()))
+                    .set_file_types(vec![FileType::new("Rust source", vec!["rs"])])
+                    .open(ctx.env, |res, _| {
+                        println!("Received save path: {:?}", res.unwrap());
+                    });
+            }).frame(120.0, 22.0)
+                .button_style(BorderedStyle),
+            Button::new("Pick color", move |ctx| {
+                ColorDialog::new(color.clone(), true)
+                    .open(ctx.env);
+            }).frame(120.0, 22.0)
+                .button_style(BorderedStyle),
+        )).spacing(10.0)
+            .open_dialog_style(PlainStyle)
+            .save_dialog_style(PlainStyle)
+            .color_dialog_style(PlainStyle),
+    ));
+
+    application.launch();
+}
@@ -0,0 +1,32 @@
+use carbide_controls::TextEditor;
+use carbide_core::draw::Dimension;
+use carbide_core::state::LocalState;
+use carbide_core::text::highlight::Language;
+use carbide_core::text::text_wrap::Wrap;
+use carbide_core::widget::*;
+use carbide_wgpu::{Application, Window};
+
+fn main() {
+    let notes = LocalState::new("Notes\n\nWrite your notes here. Long lines are wrapped to the width of the editor.".to_string());
+    let config = LocalState::new("[window]\nwidth = 800\nheight = 600\n\n[theme]\nname = \"dark\"".to_string());
+
+    let mut application = Application::new()
+        .with_asset_fonts();
+
+    application.set_scene(
+        Window::new(
+            "Text Editor Example - Carbide",
+            Dimension::new(500.0, 600.0),
+            VStack::new((
+                TextEditor::new(notes),
+                TextEditor::new(config)
+                    .wrap(Wrap::None)
+                    .line_numbers()
+                    .highlight(Language::Toml),
+            ))
+                .spacing(10.0)
+                .padding(EdgeInsets::all(40.0)),
+        ));
+
+    application.launch();
+}
@@ -0,0 +1,129 @@
+use carbide::color::{rgba, Color, ColorExt, Rgba};
+use carbide::draw::Scalar;
+use carbide::environment::{EnvironmentColor, EnvironmentFontSize, EnvironmentKeyable};
+use carbide::state::{AnyState, Map1, ReadState, State, StateExtNew};
+use carbide::text::text_wrap::Wrap;
+use carbide::widget::{AnyWidget, CornerRadii, CrossAxisAlignment, EdgeInsets, HStack, MouseArea, RoundedRectangle, Spacer, Text, VStack, WidgetExt};
+
+use crate::button::{BorderedProminentStyle, Button};
+use crate::dialog::close;
+use crate::slider::Slider;
+use crate::ControlsExt;
+
+const LABEL_WIDTH: Scalar = 60.0;
+const VALUE_WIDTH: Scalar = 40.0;
+const SWATCH_SIZE: Scalar = 22.0;
+
+/// The colors that can be picked with a single click.
+const SWATCHES: [EnvironmentColor; 10] = [
+    EnvironmentColor::Red,
+    EnvironmentColor::Orange,
+    EnvironmentColor::Yellow,
+    EnvironmentColor::Green,
+    EnvironmentColor::Teal,
+    EnvironmentColor::Blue,
+    EnvironmentColor::Indigo,
+    EnvironmentColor::Purple,
+    EnvironmentColor::Pink,
+    EnvironmentColor::Gray,
+];
+
+/// The dialog used to pick colors. The color is changed while the sliders are dragged, like the
+/// native color panels, and the dialog is closed by the done button.
+pub(crate) fn color_dialog(color: Box<dyn AnyState<T=Color>>, show_alpha: bool) -> Box<dyn AnyWidget> {
+    let hex = Map1::read_map(color.clone(), |color: &Color| {
+        let Rgba(r, g, b, _) = color.to_rgb();
+        format!("#{:02X}{:02X}{:02X}", byte(r), byte(g), byte(b))
+    });
+
+    let mut content: Vec<Box<dyn AnyWidget>> = vec![
+        Text::new("Colors".to_string())
+            .font_size(EnvironmentFontSize::Title3)
+            .bold()
+            .boxed(),
+        HStack::new((
+            RoundedRectangle::new(CornerRadii::all(5.0))
+                .fill(color.clone())
+                .stroke(EnvironmentColor::OpaqueSeparator)
+                .stroke_style(1.0)
+                .frame(64.0, 64.0),
+            Text::new(hex).wrap(Wrap::None),
+            Spacer::new(),
+        )).spacing(12.0)
+            .boxed(),
+        component("Red", color.clone(), 0),
+        component("Green", color.clone(), 1),
+        component("Blue", color.clone(), 2),
+    ];
+
+    if show_alpha {
+        content.push(component("Opacity", color.clone(), 3));
+    }
+
+    let swatches = SWATCHES.iter().map(|swatch| {
+        let swatch = *swatch;
+        let color = color.clone();
+
+        MouseArea::new(
+            RoundedRectangle::new(CornerRadii::all(4.0))
+                .fill(swatch)
+                .frame(SWATCH_SIZE, SWATCH_SIZE)
+        ).on_click(move |ctx| {
+            let mut color = color.clone();
+
+            // Keep the opacity of the current color
+            if let Some(Rgba(r, g, b, _)) = swatch.get(ctx.env).map(|swatch| swatch.to_rgb()) {
+                let Rgba(_, _, _, a) = color.value().to_rgb();
+                color.set_value(rgba(r, g, b, a));
+            }
+        }).boxed()
+    }).collect::<Vec<_>>();
+
+    content.push(HStack::new(swatches).spacing(6.0).boxed());
+
+    content.push(HStack::new((
+        Spacer::new(),
+        Button::new("Done", |ctx| {
+            close(ctx.env);
+        }).frame(80.0, 22.0)
+            .button_style(BorderedProminentStyle),
+    )).boxed());
+
+    VStack::new(content)
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .spacing(12.0)
+        .padding(EdgeInsets::all(16.0))
+        .frame_fixed_width(320.0)
+        .boxed()
+}
+
+/// A slider row that changes a single RGBA component of the color.
+fn component(label: &str, color: Box<dyn AnyState<T=Color>>, index: usize) -> Box<dyn AnyWidget> {
+    let value = Map1::map(color, move |color: &Color| {
+        let Rgba(r, g, b, a) = color.to_rgb();
+        [r, g, b, a][index]
+    }, move |new, mut color| {
+        let Rgba(r, g, b, a) = color.to_rgb();
+        let mut components = [r, g, b, a];
+        components[index] = new;
+        *color = rgba(components[0], components[1], components[2], components[3]);
+    }).as_dyn();
+
+    let text = Map1::read_map(value.clone(), |value: &f32| format!("{}", byte(*value)));
+
+    HStack::new((
+        Text::new(label.to_string())
+            .wrap(Wrap::None)
+            .frame_fixed_width(LABEL_WIDTH),
+        Slider::new(value, 0.0f32, 1.0f32),
+        Text::new(text)
+            .wrap(Wrap::None)
+            .foreground_color(EnvironmentColor::SecondaryLabel)
+            .frame_fixed_width(VALUE_WIDTH),
+    )).spacing(8.0)
+        .boxed()
+}
+
+fn byte(component: f32) -> u8 {
+    (component.clamp(0.0, 1.0) * 255.0).round() as u8
+}
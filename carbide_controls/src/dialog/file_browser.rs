@@ -0,0 +1,548 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use carbide::event::ModifierKey;
+use carbide_dialogs::FileType;
+use carbide_dialogs::open_dialog::OpenPanelSelectionType;
+
+/// Clicks on the same entry within this duration opens the entry.
+const DOUBLE_CLICK: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum SortColumn {
+    Name,
+    Size,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Entry {
+    pub path: PathBuf,
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// The state of a file browser: the current directory, how its entries are filtered and sorted,
+/// and which of them are selected. The entries are read when the directory changes or
+/// [FileBrowser::reload] is called.
+///
+/// Every change increments the version, such that the widgets showing the browser know when to
+/// rebuild.
+#[derive(Debug, Clone)]
+pub(crate) struct FileBrowser {
+    directory: PathBuf,
+    /// All the entries of the directory, including hidden and filtered entries.
+    listing: Vec<Entry>,
+    /// The visible entries in the order they are shown.
+    entries: Vec<Entry>,
+    selection: BTreeSet<PathBuf>,
+    /// The index of the entry that shift-clicks extend the selection from.
+    anchor: Option<usize>,
+    last_click: Option<(usize, Instant)>,
+    expanded: BTreeSet<PathBuf>,
+    sort: SortColumn,
+    ascending: bool,
+    show_hidden: bool,
+    file_types: Vec<FileType>,
+    /// Zero shows all the file types, otherwise only the file type at filter - 1 is shown.
+    filter: usize,
+    multiple_selection: bool,
+    selection_type: OpenPanelSelectionType,
+    error: Option<String>,
+    version: u64,
+}
+
+impl FileBrowser {
+    pub fn new(directory: Option<PathBuf>, file_types: Vec<FileType>, show_hidden: bool, multiple_selection: bool, selection_type: OpenPanelSelectionType) -> FileBrowser {
+        let directory = directory
+            .or_else(home_directory)
+            .or_else(|| std::env::current_dir().ok())
+            .unwrap_or_else(|| PathBuf::from("/"));
+
+        let mut browser = FileBrowser {
+            directory: PathBuf::new(),
+            listing: vec![],
+            entries: vec![],
+            selection: BTreeSet::new(),
+            anchor: None,
+            last_click: None,
+            expanded: BTreeSet::new(),
+            sort: SortColumn::Name,
+            ascending: true,
+            show_hidden,
+            file_types,
+            filter: 0,
+            multiple_selection,
+            selection_type,
+            error: None,
+            version: 0,
+        };
+
+        browser.navigate(directory);
+        browser
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn file_types(&self) -> &[FileType] {
+        &self.file_types
+    }
+
+    pub fn show_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    pub fn filter(&self) -> usize {
+        self.filter
+    }
+
+    pub fn sort(&self) -> (SortColumn, bool) {
+        (self.sort, self.ascending)
+    }
+
+    pub fn is_selected(&self, entry: &Entry) -> bool {
+        self.selection.contains(&entry.path)
+    }
+
+    pub fn is_expanded(&self, path: &Path) -> bool {
+        self.expanded.contains(path)
+    }
+
+    /// Change the current directory and read its entries. The directory and its ancestors are
+    /// expanded in the directory tree.
+    pub fn navigate(&mut self, directory: PathBuf) {
+        for ancestor in directory.ancestors() {
+            self.expanded.insert(ancestor.to_path_buf());
+        }
+
+        self.directory = directory;
+        self.selection.clear();
+        self.anchor = None;
+        self.last_click = None;
+        self.reload();
+    }
+
+    /// Read the entries of the current directory again.
+    pub fn reload(&mut self) {
+        match read_entries(&self.directory) {
+            Ok(listing) => {
+                self.listing = listing;
+                self.error = None;
+            }
+            Err(error) => {
+                self.listing = vec![];
+                self.error = Some(error.to_string());
+            }
+        }
+
+        self.refresh();
+    }
+
+    pub fn set_show_hidden(&mut self, show_hidden: bool) {
+        if self.show_hidden != show_hidden {
+            self.show_hidden = show_hidden;
+            self.refresh();
+        }
+    }
+
+    pub fn set_filter(&mut self, filter: usize) {
+        if self.filter != filter {
+            self.filter = filter;
+            self.refresh();
+        }
+    }
+
+    /// Sort by the column. Sorting by the current column again reverses the order.
+    pub fn sort_by(&mut self, column: SortColumn) {
+        if self.sort == column {
+            self.ascending = !self.ascending;
+        } else {
+            self.sort = column;
+            self.ascending = true;
+        }
+
+        self.refresh();
+    }
+
+    pub fn toggle_expanded(&mut self, path: &Path) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.to_path_buf());
+        }
+
+        self.version += 1;
+    }
+
+    /// Handle a click on the entry at the index. Control or command toggles the entry and shift
+    /// selects the range from the last clicked entry, when multiple selection is allowed.
+    ///
+    /// Clicking the same entry twice opens it. Directories are navigated into, and the function
+    /// returns true when a file is opened, such that the dialog can be confirmed.
+    pub fn click(&mut self, index: usize, modifiers: ModifierKey, now: Instant) -> bool {
+        let Some(entry) = self.entries.get(index).cloned() else {
+            return false;
+        };
+
+        let double_click = matches!(self.last_click, Some((last, time)) if last == index && now.duration_since(time) < DOUBLE_CLICK);
+
+        if double_click {
+            if entry.is_dir {
+                self.navigate(entry.path);
+                return false;
+            }
+
+            self.last_click = None;
+            return self.is_selectable(&entry);
+        }
+
+        self.last_click = Some((index, now));
+
+        let extend = modifiers.contains(ModifierKey::SHIFT);
+        let toggle = modifiers.intersects(ModifierKey::CONTROL | ModifierKey::SUPER);
+
+        match self.anchor {
+            Some(anchor) if extend && self.multiple_selection => {
+                let range = if anchor <= index { anchor..=index } else { index..=anchor };
+
+                self.selection = self.entries[range].iter()
+                    .filter(|entry| self.is_selectable(entry))
+                    .map(|entry| entry.path.clone())
+                    .collect();
+            }
+            _ if toggle && self.multiple_selection => {
+                if self.is_selectable(&entry) && !self.selection.remove(&entry.path) {
+                    self.selection.insert(entry.path);
+                }
+
+                self.anchor = Some(index);
+            }
+            _ => {
+                self.selection.clear();
+
+                if self.is_selectable(&entry) {
+                    self.selection.insert(entry.path);
+                }
+
+                self.anchor = Some(index);
+            }
+        }
+
+        self.version += 1;
+        false
+    }
+
+    /// The paths chosen by the selection. When directories can be chosen and nothing is
+    /// selected, the current directory is chosen.
+    pub fn chosen(&self) -> Vec<PathBuf> {
+        if self.selection.is_empty() && self.selection_type != OpenPanelSelectionType::File {
+            return vec![self.directory.clone()];
+        }
+
+        self.entries.iter()
+            .filter(|entry| self.selection.contains(&entry.path))
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+
+    /// The path to save the file name to, within the current directory. When file types are
+    /// given and the name does not have one of their extensions, the first extension of the
+    /// selected file type is appended.
+    pub fn save_path(&self, name: &str) -> Option<PathBuf> {
+        let name = name.trim();
+
+        if name.is_empty() {
+            return None;
+        }
+
+        let allowed = self.file_types.iter()
+            .flat_map(|file_type| file_type.extensions())
+            .any(|extension| has_extension(name, extension));
+
+        let default = self.file_types.get(self.filter.saturating_sub(1))
+            .and_then(|file_type| file_type.extensions().first());
+
+        match default {
+            Some(extension) if !allowed => Some(self.directory.join(format!("{}.{}", name, extension))),
+            _ => Some(self.directory.join(name)),
+        }
+    }
+
+    /// The sub directories of the path, sorted by name, used for the directory tree.
+    pub fn directories(&self, path: &Path) -> Vec<Entry> {
+        let mut directories = read_entries(path).unwrap_or_default()
+            .into_iter()
+            .filter(|entry| entry.is_dir && (self.show_hidden || !is_hidden(entry)))
+            .collect::<Vec<_>>();
+
+        directories.sort_by(|a, b| compare_names(&a.name, &b.name));
+        directories
+    }
+
+    fn is_selectable(&self, entry: &Entry) -> bool {
+        match self.selection_type {
+            OpenPanelSelectionType::File => !entry.is_dir,
+            OpenPanelSelectionType::Dictionary => entry.is_dir,
+            OpenPanelSelectionType::FileAndDictionary => true,
+        }
+    }
+
+    fn is_shown(&self, entry: &Entry) -> bool {
+        if !self.show_hidden && is_hidden(entry) {
+            return false;
+        }
+
+        if entry.is_dir || self.file_types.is_empty() {
+            return true;
+        }
+
+        let mut file_types = match self.filter {
+            0 => self.file_types.iter().collect::<Vec<_>>(),
+            filter => self.file_types.get(filter - 1).into_iter().collect(),
+        };
+
+        // When filtering by a file type that does not exist, all the files are shown.
+        if file_types.is_empty() {
+            file_types = self.file_types.iter().collect();
+        }
+
+        file_types.iter()
+            .flat_map(|file_type| file_type.extensions())
+            .any(|extension| has_extension(&entry.name, extension))
+    }
+
+    /// Filter and sort the listing into the visible entries, and drop the selected entries that
+    /// are no longer visible.
+    fn refresh(&mut self) {
+        let mut entries = self.listing.iter()
+            .filter(|entry| self.is_shown(entry))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let (sort, ascending) = (self.sort, self.ascending);
+
+        // Directories are always listed before files
+        entries.sort_by(|a, b| {
+            b.is_dir.cmp(&a.is_dir).then_with(|| {
+                let ordering = match sort {
+                    SortColumn::Name => Ordering::Equal,
+                    SortColumn::Size => a.size.cmp(&b.size),
+                    SortColumn::Modified => a.modified.cmp(&b.modified),
+                }.then_with(|| compare_names(&a.name, &b.name));
+
+                if ascending { ordering } else { ordering.reverse() }
+            })
+        });
+
+        self.selection.retain(|path| entries.iter().any(|entry| &entry.path == path));
+        self.anchor = self.anchor.filter(|anchor| *anchor < entries.len());
+        self.entries = entries;
+        self.version += 1;
+    }
+}
+
+fn read_entries(directory: &Path) -> std::io::Result<Vec<Entry>> {
+    let mut entries = vec![];
+
+    for entry in fs::read_dir(directory)? {
+        let Ok(entry) = entry else { continue };
+        let path = entry.path();
+
+        // Follow symbolic links, such that links to directories can be navigated into
+        let metadata = fs::metadata(&path).or_else(|_| entry.metadata());
+        let Ok(metadata) = metadata else { continue };
+
+        entries.push(Entry {
+            name: entry.file_name().to_string_lossy().to_string(),
+            path,
+            is_dir: metadata.is_dir(),
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: metadata.modified().ok(),
+        });
+    }
+
+    Ok(entries)
+}
+
+fn home_directory() -> Option<PathBuf> {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+fn is_hidden(entry: &Entry) -> bool {
+    entry.name.starts_with('.')
+}
+
+fn has_extension(name: &str, extension: &str) -> bool {
+    Path::new(name).extension()
+        .is_some_and(|actual| actual.to_string_lossy().eq_ignore_ascii_case(extension))
+}
+
+fn compare_names(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase()).then_with(|| a.cmp(b))
+}
+
+/// The places shown at the top of the directory tree: the home directory and the root of the
+/// file system containing the directory.
+pub(crate) fn places(directory: &Path) -> Vec<PathBuf> {
+    let mut places = vec![];
+
+    if let Some(home) = home_directory() {
+        places.push(home);
+    }
+
+    if let Some(root) = directory.ancestors().last() {
+        places.push(root.to_path_buf());
+    }
+
+    places
+}
+
+/// The display name of a path in the path bar and the directory tree.
+pub(crate) fn display_name(path: &Path) -> String {
+    match path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => path.display().to_string(),
+    }
+}
+
+/// Format a size in bytes, like "12.3 KB".
+pub(crate) fn format_size(size: u64) -> String {
+    const UNITS: [&str; 5] = ["bytes", "KB", "MB", "GB", "TB"];
+
+    let mut value = size as f64;
+    let mut unit = 0;
+
+    while value >= 1000.0 && unit < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", size, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    use carbide::event::ModifierKey;
+    use carbide_dialogs::FileType;
+    use carbide_dialogs::open_dialog::OpenPanelSelectionType;
+
+    use super::{format_size, FileBrowser, SortColumn};
+
+    fn directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("carbide_file_browser_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+
+        fs::create_dir_all(directory.join("src")).unwrap();
+        fs::write(directory.join("main.rs"), "fn main() {}").unwrap();
+        fs::write(directory.join("README.md"), "# Readme with a longer text").unwrap();
+        fs::write(directory.join(".hidden.rs"), "").unwrap();
+
+        directory
+    }
+
+    fn names(browser: &FileBrowser) -> Vec<&str> {
+        browser.entries().iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[test]
+    fn filter_and_sort() {
+        let directory = directory("filter");
+        let mut browser = FileBrowser::new(Some(directory.clone()), vec![], false, false, OpenPanelSelectionType::File);
+
+        assert_eq!(names(&browser), vec!["src", "main.rs", "README.md"]);
+
+        browser.set_show_hidden(true);
+        assert_eq!(names(&browser), vec!["src", ".hidden.rs", "main.rs", "README.md"]);
+
+        browser.sort_by(SortColumn::Size);
+        assert_eq!(names(&browser), vec!["src", ".hidden.rs", "main.rs", "README.md"]);
+
+        browser.sort_by(SortColumn::Size);
+        assert_eq!(names(&browser), vec!["src", "README.md", "main.rs", ".hidden.rs"]);
+
+        let mut browser = FileBrowser::new(Some(directory.clone()), vec![FileType::new("Markdown", vec!["md"]), FileType::new("Rust source", vec!["rs"])], false, false, OpenPanelSelectionType::File);
+        assert_eq!(names(&browser), vec!["src", "main.rs", "README.md"]);
+
+        browser.set_filter(2);
+        assert_eq!(names(&browser), vec!["src", "main.rs"]);
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn selection() {
+        let directory = directory("selection");
+        let mut browser = FileBrowser::new(Some(directory.clone()), vec![], true, true, OpenPanelSelectionType::File);
+        let now = Instant::now();
+
+        // Directories can not be selected when choosing files
+        browser.click(0, ModifierKey::EMPTY, now);
+        assert!(browser.chosen().is_empty());
+
+        browser.click(1, ModifierKey::EMPTY, now);
+        browser.click(3, ModifierKey::SHIFT, now);
+        assert_eq!(browser.chosen(), vec![directory.join(".hidden.rs"), directory.join("main.rs"), directory.join("README.md")]);
+
+        browser.click(2, ModifierKey::CONTROL, now);
+        assert_eq!(browser.chosen(), vec![directory.join(".hidden.rs"), directory.join("README.md")]);
+
+        // Clicking twice opens the file, and clicking a directory twice navigates into it
+        assert!(!browser.click(3, ModifierKey::EMPTY, now));
+        assert!(browser.click(3, ModifierKey::EMPTY, now + Duration::from_millis(100)));
+        assert!(!browser.click(0, ModifierKey::EMPTY, now));
+        assert!(!browser.click(0, ModifierKey::EMPTY, now));
+        assert_eq!(browser.directory(), directory.join("src"));
+        assert!(browser.entries().is_empty());
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn save_path() {
+        let directory = directory("save");
+        let mut browser = FileBrowser::new(Some(directory.clone()), vec![FileType::new("Text", vec!["txt", "text"]), FileType::new("Markdown", vec!["md"])], false, false, OpenPanelSelectionType::File);
+
+        assert_eq!(browser.save_path("  "), None);
+        assert_eq!(browser.save_path("notes"), Some(directory.join("notes.txt")));
+        assert_eq!(browser.save_path("notes.text"), Some(directory.join("notes.text")));
+
+        browser.set_filter(2);
+        assert_eq!(browser.save_path("notes.rs"), Some(directory.join("notes.rs.md")));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(format_size(12), "12 bytes");
+        assert_eq!(format_size(12_345), "12.3 KB");
+        assert_eq!(format_size(5_000_000), "5.0 MB");
+    }
+}
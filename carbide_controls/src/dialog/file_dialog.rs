@@ -0,0 +1,443 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Instant;
+
+use chrono::{DateTime, Local};
+use oneshot::{Receiver, Sender};
+
+use carbide::CommonWidgetImpl;
+use carbide::draw::{Dimension, Position, Scalar};
+use carbide::environment::{Environment, EnvironmentColor, EnvironmentFontSize};
+use carbide::lifecycle::{Update, UpdateContext};
+use carbide::state::{LocalState, Map1, ReadState, State};
+use carbide::text::text_wrap::Wrap;
+use carbide::widget::{AnyWidget, CommonWidget, CrossAxisAlignment, EdgeInsets, Empty, HStack, MouseArea, Rectangle, Scroll, Spacer, Text, VStack, Widget, WidgetExt, WidgetId};
+
+use crate::button::{BorderedProminentStyle, BorderedStyle, Button};
+use crate::dialog::file_browser::{display_name, format_size, places, Entry, FileBrowser, SortColumn};
+use crate::toggle::{CheckboxStyle, Toggle};
+use crate::picker::Picker;
+use crate::dialog::close;
+use crate::{ControlsExt, TextInput};
+
+const ROW_HEIGHT: Scalar = 22.0;
+const TREE_WIDTH: Scalar = 180.0;
+const TREE_INDENT: Scalar = 12.0;
+const SIZE_WIDTH: Scalar = 80.0;
+const MODIFIED_WIDTH: Scalar = 130.0;
+
+/// The sender of the result of a dialog. The result is sent once, when the dialog is either
+/// confirmed or cancelled.
+pub(crate) struct Completion<T>(Rc<RefCell<Option<Sender<T>>>>);
+
+impl<T> Completion<T> {
+    pub fn new() -> (Completion<T>, Receiver<T>) {
+        let (sender, receiver) = oneshot::channel();
+        (Completion(Rc::new(RefCell::new(Some(sender)))), receiver)
+    }
+
+    pub fn complete(&self, value: T) {
+        if let Some(sender) = self.0.borrow_mut().take() {
+            let _ = sender.send(value);
+        }
+    }
+}
+
+impl<T> Clone for Completion<T> {
+    fn clone(&self) -> Self {
+        Completion(self.0.clone())
+    }
+}
+
+impl<T> Debug for Completion<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Completion")
+            .field("completed", &self.0.borrow().is_none())
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum FileDialogKind {
+    Open(Completion<Option<Vec<PathBuf>>>),
+    Save(Completion<Option<PathBuf>>),
+}
+
+impl FileDialogKind {
+    /// Send the result of the dialog and close it. A save dialog is not confirmed without a
+    /// file name.
+    fn confirm(&self, browser: &FileBrowser, file_name: &str, env: &mut Environment) {
+        match self {
+            FileDialogKind::Open(completion) => {
+                completion.complete(Some(browser.chosen()));
+            }
+            FileDialogKind::Save(completion) => {
+                let Some(path) = browser.save_path(file_name) else { return };
+                completion.complete(Some(path));
+            }
+        }
+
+        close(env);
+    }
+
+    fn cancel(&self, env: &mut Environment) {
+        match self {
+            FileDialogKind::Open(completion) => completion.complete(None),
+            FileDialogKind::Save(completion) => completion.complete(None),
+        }
+
+        close(env);
+    }
+}
+
+/// The dialog used to open and save files: a directory tree next to the sortable list of the
+/// entries of the current directory, a path bar, and controls for the file name, the file type
+/// filter and whether hidden files are shown.
+pub(crate) fn file_dialog(
+    kind: FileDialogKind,
+    title: Option<String>,
+    message: Option<String>,
+    prompt: Option<String>,
+    default_file_name: Option<String>,
+    browser: FileBrowser,
+) -> Box<dyn AnyWidget> {
+    let is_save = matches!(kind, FileDialogKind::Save(_));
+    let file_types = browser.file_types().to_vec();
+
+    let browser = LocalState::new(browser);
+
+    let file_name = LocalState::new(default_file_name.unwrap_or_default());
+
+    let mut content: Vec<Box<dyn AnyWidget>> = vec![];
+
+    if let Some(title) = title {
+        content.push(Text::new(title).font_size(EnvironmentFontSize::Title3).bold().boxed());
+    }
+
+    if let Some(message) = message {
+        content.push(Text::new(message).foreground_color(EnvironmentColor::SecondaryLabel).boxed());
+    }
+
+    content.push(FileBrowserView::new(browser.clone(), file_name.clone(), kind.clone()).boxed());
+
+    if is_save {
+        content.push(HStack::new((
+            Text::new("Save as:".to_string()).wrap(Wrap::None),
+            TextInput::new(file_name.clone()),
+        )).spacing(8.0).boxed());
+    }
+
+    let show_hidden = Map1::map(browser.clone(), |browser: &FileBrowser| browser.show_hidden(), |new, mut browser| {
+        browser.set_show_hidden(new);
+    });
+
+    let mut controls: Vec<Box<dyn AnyWidget>> = vec![
+        Toggle::new("Show hidden files", show_hidden).boxed(),
+    ];
+
+    if !file_types.is_empty() {
+        let filter = Map1::map(browser.clone(), |browser: &FileBrowser| browser.filter(), |new, mut browser| {
+            browser.set_filter(new);
+        });
+
+        let mut options = vec![];
+
+        // Only open dialogs can show all the supported file types at once
+        if !is_save {
+            options.push(Text::new("All supported types".to_string()).tag(0usize));
+        }
+
+        for (index, file_type) in file_types.iter().enumerate() {
+            options.push(Text::new(file_type.name().to_string()).tag(index + 1));
+        }
+
+        controls.push(Picker::new("Type", filter, options).frame_fixed_width(200.0).boxed());
+    }
+
+    let cancel = kind.clone();
+    let confirm = kind.clone();
+    let confirm_browser = browser.clone();
+    let confirm_file_name = file_name.clone();
+
+    let default_prompt = if is_save { "Save" } else { "Open" };
+
+    controls.push(Spacer::new().boxed());
+    controls.push(
+        Button::new("Cancel", move |ctx| {
+            cancel.cancel(ctx.env);
+        }).button_style(BorderedStyle)
+            .frame(80.0, 22.0)
+            .boxed()
+    );
+    controls.push(
+        Button::new(prompt.unwrap_or(default_prompt.to_string()), move |ctx| {
+            confirm.confirm(&confirm_browser.value(), &confirm_file_name.value(), ctx.env);
+        }).button_style(BorderedProminentStyle)
+            .frame(80.0, 22.0)
+            .boxed()
+    );
+
+    content.push(HStack::new(controls).spacing(10.0).boxed());
+
+    VStack::new(content)
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .spacing(10.0)
+        .toggle_style(CheckboxStyle)
+        .padding(EdgeInsets::all(16.0))
+        .frame(720.0, 480.0)
+        .boxed()
+}
+
+/// The path bar, directory tree and entry list of the file dialog. The widgets are rebuilt when
+/// the browser changes.
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Update)]
+struct FileBrowserView {
+    #[id] id: WidgetId,
+    position: Position,
+    dimension: Dimension,
+    #[state] browser: LocalState<FileBrowser>,
+    #[state] file_name: LocalState<String>,
+    kind: FileDialogKind,
+    /// The version of the browser the content was built from.
+    built: Option<u64>,
+    content: Box<dyn AnyWidget>,
+}
+
+impl FileBrowserView {
+    fn new(browser: LocalState<FileBrowser>, file_name: LocalState<String>, kind: FileDialogKind) -> FileBrowserView {
+        FileBrowserView {
+            id: WidgetId::new(),
+            position: Position::origin(),
+            dimension: Dimension::new(0.0, 0.0),
+            browser,
+            file_name,
+            kind,
+            built: None,
+            content: Empty::new().boxed(),
+        }
+    }
+
+    fn build(&self) -> Box<dyn AnyWidget> {
+        let browser = self.browser.value();
+
+        let mut tree = vec![];
+
+        for place in places(browser.directory()) {
+            self.tree_rows(&browser, place, 0, &mut tree);
+        }
+
+        let list = match browser.error() {
+            Some(error) => Text::new(error.to_string())
+                .foreground_color(EnvironmentColor::SecondaryLabel)
+                .padding(EdgeInsets::all(8.0))
+                .boxed(),
+            None => VStack::new(
+                browser.entries().iter().enumerate()
+                    .map(|(index, entry)| self.entry_row(&browser, index, entry))
+                    .collect::<Vec<_>>()
+            ).spacing(0.0).boxed(),
+        };
+
+        VStack::new((
+            self.path_bar(browser.directory()),
+            HStack::new((
+                Scroll::new(VStack::new(tree).spacing(0.0).cross_axis_alignment(CrossAxisAlignment::Start))
+                    .clip()
+                    .frame_fixed_width(TREE_WIDTH),
+                Rectangle::new()
+                    .fill(EnvironmentColor::Separator)
+                    .frame_fixed_width(1.0),
+                VStack::new((
+                    self.header(&browser),
+                    Rectangle::new()
+                        .fill(EnvironmentColor::Separator)
+                        .frame_fixed_height(1.0),
+                    Scroll::new(list).clip(),
+                )).spacing(0.0),
+            )).spacing(0.0)
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .background(
+                    Rectangle::new()
+                        .fill(EnvironmentColor::SystemBackground)
+                )
+                .border()
+                .color(EnvironmentColor::OpaqueSeparator),
+        )).spacing(8.0)
+            .boxed()
+    }
+
+    /// The ancestors of the directory, from the root. Clicking an ancestor navigates to it.
+    fn path_bar(&self, directory: &Path) -> Box<dyn AnyWidget> {
+        let mut components: Vec<Box<dyn AnyWidget>> = vec![];
+
+        for (index, ancestor) in directory.ancestors().collect::<Vec<_>>().into_iter().rev().enumerate() {
+            if index > 0 {
+                components.push(Text::new("›").foreground_color(EnvironmentColor::TertiaryLabel).boxed());
+            }
+
+            let browser = self.browser.clone();
+            let path = ancestor.to_path_buf();
+
+            components.push(
+                MouseArea::new(Text::new(display_name(ancestor)).wrap(Wrap::None))
+                    .on_click(move |_| {
+                        browser.clone().value_mut().navigate(path.clone());
+                    })
+                    .boxed()
+            );
+        }
+
+        components.push(Spacer::new().boxed());
+
+        HStack::new(components)
+            .spacing(4.0)
+            .boxed()
+    }
+
+    fn tree_rows(&self, browser: &FileBrowser, path: PathBuf, depth: usize, rows: &mut Vec<Box<dyn AnyWidget>>) {
+        let expanded = browser.is_expanded(&path);
+        let current = path == browser.directory();
+
+        let disclosure_browser = self.browser.clone();
+        let disclosure_path = path.clone();
+        let navigate_browser = self.browser.clone();
+        let navigate_path = path.clone();
+
+        rows.push(
+            HStack::new((
+                MouseArea::new(
+                    Text::new(if expanded { "▾" } else { "▸" })
+                        .foreground_color(EnvironmentColor::SecondaryLabel)
+                        .frame(TREE_INDENT, ROW_HEIGHT)
+                ).on_click(move |_| {
+                    disclosure_browser.clone().value_mut().toggle_expanded(&disclosure_path);
+                }),
+                MouseArea::new(
+                    HStack::new((
+                        Text::new(display_name(&path)).wrap(Wrap::None),
+                        Spacer::new(),
+                    ))
+                ).on_click(move |_| {
+                    navigate_browser.clone().value_mut().navigate(navigate_path.clone());
+                }),
+            )).spacing(2.0)
+                .padding(EdgeInsets::single(0.0, 0.0, depth as Scalar * TREE_INDENT + 4.0, 4.0))
+                .frame_fixed_height(ROW_HEIGHT)
+                .background(highlight(current, EnvironmentColor::SystemFill))
+                .boxed()
+        );
+
+        if expanded {
+            for directory in browser.directories(&path) {
+                self.tree_rows(browser, directory.path, depth + 1, rows);
+            }
+        }
+    }
+
+    /// The column titles of the list. Clicking a title sorts by the column, and clicking it again
+    /// reverses the order.
+    fn header(&self, browser: &FileBrowser) -> Box<dyn AnyWidget> {
+        let (sort, ascending) = browser.sort();
+
+        let title = |name: &str, column: SortColumn| {
+            let arrow = match (sort == column, ascending) {
+                (true, true) => " ▲",
+                (true, false) => " ▼",
+                (false, _) => "",
+            };
+
+            let browser = self.browser.clone();
+
+            MouseArea::new(
+                Text::new(format!("{}{}", name, arrow))
+                    .font_size(EnvironmentFontSize::Footnote)
+                    .wrap(Wrap::None)
+                    .foreground_color(EnvironmentColor::SecondaryLabel)
+            ).on_click(move |_| {
+                browser.clone().value_mut().sort_by(column);
+            })
+        };
+
+        HStack::new((
+            HStack::new((title("Name", SortColumn::Name), Spacer::new())),
+            title("Size", SortColumn::Size).frame_fixed_width(SIZE_WIDTH),
+            title("Modified", SortColumn::Modified).frame_fixed_width(MODIFIED_WIDTH),
+        )).padding(EdgeInsets::vertical_horizontal(4.0, 8.0))
+            .boxed()
+    }
+
+    fn entry_row(&self, browser: &FileBrowser, index: usize, entry: &Entry) -> Box<dyn AnyWidget> {
+        let size = if entry.is_dir { "--".to_string() } else { format_size(entry.size) };
+
+        let modified = entry.modified
+            .map(|modified| DateTime::<Local>::from(modified).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+
+        let name = if entry.is_dir { format!("{}/", entry.name) } else { entry.name.clone() };
+
+        let selected = browser.is_selected(entry);
+
+        let browser = self.browser.clone();
+        let file_name = self.file_name.clone();
+        let kind = self.kind.clone();
+
+        MouseArea::new(
+            HStack::new((
+                HStack::new((Text::new(name).wrap(Wrap::None), Spacer::new())),
+                Text::new(size)
+                    .wrap(Wrap::None)
+                    .foreground_color(EnvironmentColor::SecondaryLabel)
+                    .frame_fixed_width(SIZE_WIDTH),
+                Text::new(modified)
+                    .wrap(Wrap::None)
+                    .foreground_color(EnvironmentColor::SecondaryLabel)
+                    .frame_fixed_width(MODIFIED_WIDTH),
+            )).padding(EdgeInsets::vertical_horizontal(0.0, 8.0))
+                .frame_fixed_height(ROW_HEIGHT)
+                .background(highlight(selected, EnvironmentColor::Accent))
+        ).on_click(move |ctx| {
+            let mut browser = browser.clone();
+            let mut file_name = file_name.clone();
+
+            let open = browser.value_mut().click(index, ctx.modifier_key, Instant::now());
+
+            let entry = browser.value().entries().get(index).cloned();
+
+            // Clicking a file in a save dialog uses its name, such that the file can be replaced
+            if let (FileDialogKind::Save(_), Some(entry)) = (&kind, &entry) && !entry.is_dir {
+                file_name.set_value(entry.name.clone());
+            }
+
+            if open {
+                kind.confirm(&browser.value(), &file_name.value(), ctx.env);
+            }
+        }).boxed()
+    }
+}
+
+/// The background of a row, filled with the color when the row is highlighted.
+fn highlight(highlighted: bool, color: EnvironmentColor) -> Box<dyn AnyWidget> {
+    if highlighted {
+        Rectangle::new().fill(color).boxed()
+    } else {
+        Empty::new().boxed()
+    }
+}
+
+impl Update for FileBrowserView {
+    fn update(&mut self, _ctx: &mut UpdateContext) {
+        let version = self.browser.value().version();
+
+        if self.built != Some(version) {
+            self.content = self.build();
+            self.built = Some(version);
+        }
+    }
+}
+
+impl CommonWidget for FileBrowserView {
+    CommonWidgetImpl!(self, child: self.content, position: self.position, dimension: self.dimension, flexibility: 1);
+}
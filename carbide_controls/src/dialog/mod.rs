@@ -0,0 +1,39 @@
+//! Dialogs drawn by carbide, for platforms without native open, save and color dialogs.
+//! The dialogs are shown in the dialog overlay of the window, see [controls_overlay](crate::controls_overlay).
+
+use carbide::color::{ColorExt, BLACK};
+use carbide::environment::{Environment, EnvironmentColor};
+use carbide::widget::{AnyWidget, CornerRadii, OverlayManager, Rectangle, RoundedRectangle, WidgetExt, ZStack};
+
+use crate::DialogOverlayKey;
+
+mod color_dialog;
+mod file_browser;
+mod file_dialog;
+mod plain;
+
+pub use plain::PlainStyle;
+
+/// Show the dialog in the dialog overlay. The dialog covers the content of the window until it
+/// is closed.
+fn present(dialog: Box<dyn AnyWidget>, env: &mut Environment) {
+    let dialog = ZStack::new((
+        Rectangle::new().fill(BLACK.with_opacity(0.3)),
+        dialog.background(
+            RoundedRectangle::new(CornerRadii::all(8.0))
+                .fill(EnvironmentColor::SecondarySystemBackground)
+                .stroke(EnvironmentColor::OpaqueSeparator)
+                .stroke_style(1.0)
+        ),
+    ));
+
+    OverlayManager::get::<DialogOverlayKey>(env, |manager| {
+        manager.insert(dialog)
+    });
+}
+
+fn close(env: &mut Environment) {
+    OverlayManager::get::<DialogOverlayKey>(env, |manager| {
+        manager.clear()
+    });
+}
@@ -0,0 +1,63 @@
+use std::path::PathBuf;
+
+use carbide::asynchronous::AsyncContext;
+use carbide::draw::Color;
+use carbide::environment::Environment;
+use carbide::state::{AnyReadState, AnyState, ReadState, StateSync};
+use carbide::SpawnTask;
+use carbide_dialogs::color_dialog::style::ColorDialogStyle;
+use carbide_dialogs::open_dialog::style::OpenDialogStyle;
+use carbide_dialogs::open_dialog::OpenPanelSelectionType;
+use carbide_dialogs::save_dialog::style::SaveDialogStyle;
+use carbide_dialogs::FileType;
+use oneshot::RecvError;
+
+use crate::dialog::color_dialog::color_dialog;
+use crate::dialog::file_browser::FileBrowser;
+use crate::dialog::file_dialog::{file_dialog, Completion, FileDialogKind};
+use crate::dialog::present;
+
+/// Open, save and color dialogs drawn by carbide within the window, which can be used on every
+/// platform. This is the default style on platforms without native dialogs.
+#[derive(Copy, Clone, Debug)]
+pub struct PlainStyle;
+
+impl OpenDialogStyle for PlainStyle {
+    fn open(&self, title: Option<String>, message: Option<String>, prompt: Option<String>, multiple_selection: bool, show_hidden_files: bool, selection_type: OpenPanelSelectionType, path: Option<PathBuf>, file_types: &[FileType], f: Box<dyn Fn(Result<Option<Vec<PathBuf>>, RecvError>, &mut AsyncContext) + 'static>, env: &mut Environment) {
+        let (completion, receiver) = Completion::new();
+
+        let browser = FileBrowser::new(path, file_types.to_vec(), show_hidden_files, multiple_selection, selection_type);
+
+        present(file_dialog(FileDialogKind::Open(completion), title, message, prompt, None, browser), env);
+
+        receiver.spawn(f)
+    }
+}
+
+impl SaveDialogStyle for PlainStyle {
+    fn open(&self, title: Option<String>, message: Option<String>, prompt: Option<String>, default_file_name: Option<String>, show_hidden_files: bool, path: Option<PathBuf>, file_types: &[FileType], f: Box<dyn Fn(Result<Option<PathBuf>, RecvError>, &mut AsyncContext) + 'static>, env: &mut Environment) {
+        let (completion, receiver) = Completion::new();
+
+        let mut browser = FileBrowser::new(path, file_types.to_vec(), show_hidden_files, false, OpenPanelSelectionType::File);
+
+        // Files are saved as a single type, so the first type is selected instead of all of them
+        if !file_types.is_empty() {
+            browser.set_filter(1);
+        }
+
+        present(file_dialog(FileDialogKind::Save(completion), title, message, prompt, default_file_name, browser), env);
+
+        receiver.spawn(f)
+    }
+}
+
+impl ColorDialogStyle for PlainStyle {
+    fn open(&self, mut color: Box<dyn AnyState<T=Color>>, mut show_alpha: Box<dyn AnyReadState<T=bool>>, env: &mut Environment) {
+        color.sync(env);
+        show_alpha.sync(env);
+
+        let show_alpha = *show_alpha.value();
+
+        present(color_dialog(color, show_alpha), env);
+    }
+}
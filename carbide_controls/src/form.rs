@@ -0,0 +1,23 @@
+use carbide::state::FormState;
+use carbide::widget::{EnvUpdatingNew, Widget};
+
+/// # Form
+/// A container aggregating the validity of the validated states synced within it, for example by
+/// the text inputs of the form. The validity can be read from the [FormState], typically to enable
+/// a submit button.
+///
+/// ```ignore
+/// let form = FormState::new();
+///
+/// Form::new(form.clone(), VStack::new((
+///     TextInput::new(name.clone()),
+///     Button::new("Save", |_| {}).enabled(form.is_valid()),
+/// )))
+/// ```
+pub struct Form;
+
+impl Form {
+    pub fn new<C: Widget>(form: FormState, child: C) -> EnvUpdatingNew<C, FormState> {
+        EnvUpdatingNew::<C, FormState>::new(form, child)
+    }
+}
@@ -60,10 +60,10 @@ impl<C: Widget> MouseEventHandler for Help<C> {
 
 impl<C: Widget> Layout for Help<C> {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
-        let dimension = self.child.calculate_size(requested_size, ctx);
+        let dimension = self.child.process_calculate_size(requested_size, ctx);
         self.set_dimension(dimension);
 
-        //self.help.calculate_size(Dimension::new(ctx.env.current_window_width(), ctx.env.current_window_height()), ctx);
+        //self.help.process_calculate_size(Dimension::new(ctx.env.current_window_width(), ctx.env.current_window_height()), ctx);
 
         //dimension
         todo!()
@@ -1,13 +1,17 @@
 pub use list::List;
 pub use plain::*;
 pub use text_input::*;
+pub use text_editor::*;
 pub use controls_ext::*;
 pub use help::*;
 pub use calendar::*;
+pub use form::*;
 use carbide::environment::EnvironmentKey;
 use carbide::focus::{Focus, FocusManager, Refocus};
 use carbide::state::{KeyState, ReadState, State};
-use carbide::widget::{MouseAreaAction, MouseAreaActionContext, OverlayManager, Widget};
+use carbide::widget::{MouseAreaAction, MouseAreaActionContext, OverlayManager, Widget, WidgetExt};
+#[cfg(not(target_os = "macos"))]
+use carbide_dialogs::DialogsExt;
 pub use date_picker::*;
 
 extern crate carbide_core as carbide;
@@ -48,9 +52,11 @@ macro_rules! capture {
 pub mod list;
 mod plain;
 mod text_input;
+mod text_editor;
 mod controls_ext;
 mod help;
 mod calendar;
+mod form;
 mod date_picker;
 pub mod toggle;
 pub mod picker;
@@ -61,6 +67,7 @@ pub mod button;
 pub mod slider;
 pub mod context_menu;
 pub mod color_picker;
+pub mod dialog;
 
 pub type EnabledState = KeyState<EnabledKey>;
 
@@ -92,6 +99,27 @@ impl EnvironmentKey for ControlsOverlayKey {
     type Value = OverlayManager;
 }
 
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct DialogOverlayKey;
+
+impl EnvironmentKey for DialogOverlayKey {
+    type Value = OverlayManager;
+}
+
+/// Add the overlays used by the controls, for example by the popup of a picker. Dialogs are shown
+/// below the popups, such that the controls within a dialog can show popups too.
+///
+/// On platforms without native dialogs, this also sets the dialog styles to the dialogs drawn by
+/// carbide. Without it, the `NativeStyle` of carbide_dialogs cancels open and save dialogs and
+/// does not show color dialogs on those platforms.
 pub fn controls_overlay<C: Widget>(c: C) -> impl Widget {
+    let c = c.overlay::<DialogOverlayKey>().steal_events();
+
+    // Without native dialogs on the platform, the dialogs are drawn within the window
+    #[cfg(not(target_os = "macos"))]
+    let c = c.open_dialog_style(dialog::PlainStyle)
+        .save_dialog_style(dialog::PlainStyle)
+        .color_dialog_style(dialog::PlainStyle);
+
     c.overlay::<ControlsOverlayKey>().steal_events()
 }
\ No newline at end of file
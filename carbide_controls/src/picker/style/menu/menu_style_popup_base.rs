@@ -26,7 +26,7 @@ impl Layout for MenuStylePopupBase {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
         self.sync(ctx.env);
 
-        let res = self.child.calculate_size(self.dimension(), ctx);
+        let res = self.child.process_calculate_size(self.dimension(), ctx);
 
         res
     }
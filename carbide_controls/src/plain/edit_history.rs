@@ -0,0 +1,215 @@
+use crate::plain::cursor::Cursor;
+
+/// The maximum number of edits kept for undoing.
+const HISTORY_LIMIT: usize = 1000;
+
+/// A single change of the text, where the text removed at the byte offset was replaced by the
+/// inserted text.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Edit {
+    pub offset: usize,
+    pub removed: String,
+    pub inserted: String,
+    pub cursor_before: Cursor,
+    pub cursor_after: Cursor,
+}
+
+impl Edit {
+    /// Combine the next edit into this edit, if the next edit continues this one. This makes
+    /// consecutive typing and consecutive removals undo as a single edit. Typing does not
+    /// continue past a new line.
+    fn merge(&mut self, next: &Edit) -> bool {
+        if self.inserted.ends_with('\n') || next.inserted.contains('\n') {
+            return false;
+        }
+
+        // Typing
+        if next.removed.is_empty() && !self.inserted.is_empty() && next.offset == self.offset + self.inserted.len() {
+            self.inserted.push_str(&next.inserted);
+            self.cursor_after = next.cursor_after;
+            return true;
+        }
+
+        if !self.inserted.is_empty() || !next.inserted.is_empty() {
+            return false;
+        }
+
+        // Removing to the left
+        if next.offset + next.removed.len() == self.offset {
+            self.removed.insert_str(0, &next.removed);
+            self.offset = next.offset;
+            self.cursor_after = next.cursor_after;
+            return true;
+        }
+
+        // Removing to the right
+        if next.offset == self.offset {
+            self.removed.push_str(&next.removed);
+            self.cursor_after = next.cursor_after;
+            return true;
+        }
+
+        false
+    }
+}
+
+/// The undo and redo stacks of a text editor.
+#[derive(Debug, Clone)]
+pub(crate) struct EditHistory {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+    /// If false, the next edit is not combined with the previous edit.
+    group: bool,
+}
+
+impl EditHistory {
+    pub fn new() -> EditHistory {
+        EditHistory {
+            undo: vec![],
+            redo: vec![],
+            group: false,
+        }
+    }
+
+    /// Record an edit that has been applied to the text. This clears the redo stack.
+    pub fn push(&mut self, edit: Edit) {
+        self.redo.clear();
+
+        if self.group {
+            if let Some(last) = self.undo.last_mut() {
+                if last.merge(&edit) {
+                    return;
+                }
+            }
+        }
+
+        self.undo.push(edit);
+        self.group = true;
+
+        if self.undo.len() > HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Make sure the next edit is undone separately from the previous edits, for example
+    /// because the cursor was moved in between.
+    pub fn break_group(&mut self) {
+        self.group = false;
+    }
+
+    /// Undo the last edit, and return the cursor from before the edit. If the text has been
+    /// changed by something other than the editor, the history no longer applies and is cleared.
+    pub fn undo(&mut self, text: &mut String) -> Option<Cursor> {
+        let edit = self.undo.pop()?;
+        let range = edit.offset..edit.offset + edit.inserted.len();
+
+        if text.get(range.clone()) != Some(edit.inserted.as_str()) {
+            self.clear();
+            return None;
+        }
+
+        text.replace_range(range, &edit.removed);
+
+        let cursor = edit.cursor_before;
+        self.redo.push(edit);
+        self.group = false;
+
+        Some(cursor)
+    }
+
+    /// Redo the last undone edit, and return the cursor from after the edit.
+    pub fn redo(&mut self, text: &mut String) -> Option<Cursor> {
+        let edit = self.redo.pop()?;
+        let range = edit.offset..edit.offset + edit.removed.len();
+
+        if text.get(range.clone()) != Some(edit.removed.as_str()) {
+            self.clear();
+            return None;
+        }
+
+        text.replace_range(range, &edit.inserted);
+
+        let cursor = edit.cursor_after;
+        self.undo.push(edit);
+        self.group = false;
+
+        Some(cursor)
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.group = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plain::cursor::{Cursor, CursorIndex};
+    use crate::plain::edit_history::{Edit, EditHistory};
+
+    fn cursor(index: usize) -> Cursor {
+        Cursor::Single(CursorIndex { line: 0, index })
+    }
+
+    fn insert(text: &mut String, history: &mut EditHistory, offset: usize, inserted: &str) {
+        text.insert_str(offset, inserted);
+
+        history.push(Edit {
+            offset,
+            removed: String::new(),
+            inserted: inserted.to_string(),
+            cursor_before: cursor(offset),
+            cursor_after: cursor(offset + inserted.len()),
+        });
+    }
+
+    #[test]
+    fn typing_is_undone_as_one_edit() {
+        let mut text = String::new();
+        let mut history = EditHistory::new();
+
+        insert(&mut text, &mut history, 0, "a");
+        insert(&mut text, &mut history, 1, "b");
+        insert(&mut text, &mut history, 2, "\n");
+        insert(&mut text, &mut history, 3, "c");
+
+        assert_eq!(text, "ab\nc");
+
+        assert_eq!(history.undo(&mut text), Some(cursor(3)));
+        assert_eq!(text, "ab\n");
+        assert_eq!(history.undo(&mut text), Some(cursor(2)));
+        assert_eq!(text, "ab");
+        assert_eq!(history.undo(&mut text), Some(cursor(0)));
+        assert_eq!(text, "");
+        assert_eq!(history.undo(&mut text), None);
+
+        assert_eq!(history.redo(&mut text), Some(cursor(2)));
+        assert_eq!(text, "ab");
+    }
+
+    #[test]
+    fn pushing_clears_redo() {
+        let mut text = String::new();
+        let mut history = EditHistory::new();
+
+        insert(&mut text, &mut history, 0, "a");
+        history.undo(&mut text);
+        insert(&mut text, &mut history, 0, "b");
+
+        assert_eq!(history.redo(&mut text), None);
+        assert_eq!(text, "b");
+    }
+
+    #[test]
+    fn external_changes_clear_the_history() {
+        let mut text = String::new();
+        let mut history = EditHistory::new();
+
+        insert(&mut text, &mut history, 0, "abc");
+        text = "Replaced".to_string();
+
+        assert_eq!(history.undo(&mut text), None);
+        assert_eq!(text, "Replaced");
+    }
+}
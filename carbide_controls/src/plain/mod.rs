@@ -1,9 +1,12 @@
 pub use plain_text_input::*;
+pub use plain_text_editor::*;
 pub use plain_calendar::*;
 pub use plain_date_picker::*;
 
 mod cursor;
 mod plain_text_input;
+mod plain_text_editor;
+mod edit_history;
 mod text_input_key_commands;
 pub(crate) mod plain_calendar;
 mod plain_date_picker;
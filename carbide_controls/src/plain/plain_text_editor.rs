@@ -0,0 +1,1121 @@
+use std::ops::Range;
+
+use copypasta::{ClipboardContext, ClipboardProvider};
+use unicode_segmentation::UnicodeSegmentation;
+use carbide::cursor::MouseCursor;
+use carbide::CommonWidgetImpl;
+use carbide::draw::{Color, Dimension, Position, Rect, Scalar};
+use carbide::environment::{EnvironmentColor, EnvironmentFontSize, IntoColorReadState};
+use carbide::event::{Ime, Key, KeyboardEvent, KeyboardEventContext, KeyboardEventHandler, ModifierKey, MouseEvent, MouseEventContext, MouseEventHandler};
+use carbide::flags::WidgetFlag;
+use carbide::focus::{Focus, Focusable};
+use carbide::layout::{Layout, LayoutContext};
+use carbide::render::{Render, RenderContext};
+use carbide::state::{IntoReadState, IntoState, LocalState, ReadState, State};
+use carbide::text::highlight::Highlighter;
+use carbide::text::TextContext;
+use carbide::text::text_wrap::Wrap;
+use carbide::widget::{AnyWidget, CommonWidget, Rectangle, Text, TextWidget, Widget, WidgetExt, WidgetId};
+use carbide::widget::scroll::ScrollToVisible;
+
+use crate::EnabledState;
+use crate::plain::cursor::{Cursor, CursorIndex};
+use crate::plain::edit_history::{Edit, EditHistory};
+use crate::plain::plain_text_input::TextInputKeyCommand;
+
+const GUTTER_PADDING: Scalar = 6.0;
+
+/// A plain multi-line text editor widget. Like [PlainTextInput](crate::PlainTextInput) the widget
+/// contains no specific styling, other than text color, cursor and selection widgets.
+///
+/// The text wraps within the width of the editor, unless the wrap is set to [Wrap::None]. The
+/// editor grows to fit its text, and is meant to be placed inside a `Scroll`. While editing, the
+/// scroll is adjusted to keep the cursor visible.
+///
+/// Edits can be undone and redone using the undo and redo keys, or ctrl/cmd+z and
+/// ctrl/cmd+shift+z. For an example of how to use this widget look at examples/text_editor
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(MouseEvent, KeyboardEvent, Layout, Render)]
+pub struct PlainTextEditor<F, C, S, T, E, W> where
+    F: State<T=Focus>,
+    C: ReadState<T=Color>,
+    S: ReadState<T=u32>,
+    T: State<T=String>,
+    E: ReadState<T=bool>,
+    W: ReadState<T=Wrap>,
+{
+    // Standard fields
+    #[id] id: WidgetId,
+    position: Position,
+    dimension: Dimension,
+    #[state] focus: F,
+    #[state] enabled: E,
+
+    // Widgets
+    text_widget: Box<dyn TextWidget>,
+    cursor_widget: Box<dyn AnyWidget>,
+    selection_widget: Box<dyn AnyWidget>,
+    /// The selection widgets currently shown. A selection spanning multiple lines is shown as
+    /// up to three rectangles.
+    selection_widgets: Vec<Box<dyn AnyWidget>>,
+    selection_rects: Vec<Rect>,
+    line_number_widgets: Vec<Box<dyn AnyWidget>>,
+
+    // Text styles
+    #[state] text_color: C,
+    #[state] font_size: S,
+    #[state] wrap: W,
+    line_numbers: bool,
+    highlighter: Option<Box<dyn Highlighter>>,
+    gutter_width: Scalar,
+
+    // Text
+    #[state] text: T,
+    history: EditHistory,
+
+    // Cursor
+    cursor: Cursor,
+    /// The horizontal position the cursor tries to keep when moving up and down.
+    goal_x: Option<Scalar>,
+    /// True if the cursor should be scrolled into view when the editor is positioned next.
+    cursor_moved: bool,
+    dragging: bool,
+
+    hovered: bool,
+}
+
+impl PlainTextEditor<Focus, Color, u32, String, bool, Wrap> {
+    pub fn new<T: IntoState<String>>(text: T) -> PlainTextEditor<LocalState<Focus>, impl ReadState<T=Color>, impl ReadState<T=u32>, T::Output, EnabledState, Wrap> {
+        let focus = LocalState::new(Focus::Unfocused);
+        let color = EnvironmentColor::Label.color();
+        let font_size = EnvironmentFontSize::Body.u32();
+
+        let cursor_widget = Rectangle::new().fill(EnvironmentColor::Green).boxed();
+        let selection_widget = Rectangle::new().fill(EnvironmentColor::Purple).boxed();
+
+        Self::new_internal(
+            focus,
+            color,
+            font_size,
+            text.into_state(),
+            EnabledState::new(true),
+            Wrap::Whitespace,
+            cursor_widget,
+            selection_widget,
+            false,
+            None,
+        )
+    }
+}
+
+impl<
+    F: State<T=Focus>,
+    C: ReadState<T=Color>,
+    S: ReadState<T=u32>,
+    T: State<T=String>,
+    E: ReadState<T=bool>,
+    W: ReadState<T=Wrap>,
+> PlainTextEditor<F, C, S, T, E, W> {
+    pub fn enabled<E2: IntoReadState<bool>>(self, enabled: E2) -> PlainTextEditor<F, C, S, T, E2::Output, W> {
+        Self::new_internal(
+            self.focus,
+            self.text_color,
+            self.font_size,
+            self.text,
+            enabled.into_read_state(),
+            self.wrap,
+            self.cursor_widget,
+            self.selection_widget,
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    pub fn focused<F2: IntoState<Focus>>(self, focused: F2) -> PlainTextEditor<F2::Output, C, S, T, E, W> {
+        Self::new_internal(
+            focused.into_state(),
+            self.text_color,
+            self.font_size,
+            self.text,
+            self.enabled,
+            self.wrap,
+            self.cursor_widget,
+            self.selection_widget,
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    pub fn font_size<S2: IntoReadState<u32>>(self, font_size: S2) -> PlainTextEditor<F, C, S2::Output, T, E, W> {
+        Self::new_internal(
+            self.focus,
+            self.text_color,
+            font_size.into_read_state(),
+            self.text,
+            self.enabled,
+            self.wrap,
+            self.cursor_widget,
+            self.selection_widget,
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    pub fn text_color<C2: IntoReadState<Color>>(self, text_color: C2) -> PlainTextEditor<F, C2::Output, S, T, E, W> {
+        Self::new_internal(
+            self.focus,
+            text_color.into_read_state(),
+            self.font_size,
+            self.text,
+            self.enabled,
+            self.wrap,
+            self.cursor_widget,
+            self.selection_widget,
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    /// Set how the lines of the text wrap. Use [Wrap::None] to only break lines at line feeds.
+    pub fn wrap<W2: IntoReadState<Wrap>>(self, wrap: W2) -> PlainTextEditor<F, C, S, T, E, W2::Output> {
+        Self::new_internal(
+            self.focus,
+            self.text_color,
+            self.font_size,
+            self.text,
+            self.enabled,
+            wrap.into_read_state(),
+            self.cursor_widget,
+            self.selection_widget,
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    /// Show the line numbers in a gutter to the left of the text.
+    pub fn line_numbers(self, line_numbers: bool) -> PlainTextEditor<F, C, S, T, E, W> {
+        Self::new_internal(
+            self.focus,
+            self.text_color,
+            self.font_size,
+            self.text,
+            self.enabled,
+            self.wrap,
+            self.cursor_widget,
+            self.selection_widget,
+            line_numbers,
+            self.highlighter,
+        )
+    }
+
+    /// Style the text using the highlighter, for example to color source code.
+    pub fn highlight(self, highlighter: impl Highlighter + 'static) -> PlainTextEditor<F, C, S, T, E, W> {
+        Self::new_internal(
+            self.focus,
+            self.text_color,
+            self.font_size,
+            self.text,
+            self.enabled,
+            self.wrap,
+            self.cursor_widget,
+            self.selection_widget,
+            self.line_numbers,
+            Some(Box::new(highlighter)),
+        )
+    }
+
+    pub fn selection_widget(self, selection: Box<dyn AnyWidget>) -> PlainTextEditor<F, C, S, T, E, W> {
+        Self::new_internal(
+            self.focus,
+            self.text_color,
+            self.font_size,
+            self.text,
+            self.enabled,
+            self.wrap,
+            self.cursor_widget,
+            selection,
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    pub fn cursor_widget(self, cursor: Box<dyn AnyWidget>) -> PlainTextEditor<F, C, S, T, E, W> {
+        Self::new_internal(
+            self.focus,
+            self.text_color,
+            self.font_size,
+            self.text,
+            self.enabled,
+            self.wrap,
+            cursor,
+            self.selection_widget,
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    fn new_internal<
+        F2: State<T=Focus>,
+        C2: ReadState<T=Color>,
+        S2: ReadState<T=u32>,
+        T2: State<T=String>,
+        E2: ReadState<T=bool>,
+        W2: ReadState<T=Wrap>,
+    >(focus: F2, text_color: C2, font_size: S2, text: T2, enabled: E2, wrap: W2, cursor_widget: Box<dyn AnyWidget>, selection_widget: Box<dyn AnyWidget>, line_numbers: bool, highlighter: Option<Box<dyn Highlighter>>) -> PlainTextEditor<F2, C2, S2, T2, E2, W2> {
+        let mut text_widget = Text::new(text.clone())
+            .font_size(font_size.clone())
+            .color(text_color.clone())
+            .wrap(wrap.clone());
+
+        if let Some(highlighter) = highlighter.clone() {
+            text_widget = text_widget.highlight(highlighter);
+        }
+
+        PlainTextEditor {
+            id: WidgetId::new(),
+            position: Default::default(),
+            dimension: Default::default(),
+            focus,
+            enabled,
+            text_widget: Box::new(text_widget),
+            cursor_widget,
+            selection_widget,
+            selection_widgets: vec![],
+            selection_rects: vec![],
+            line_number_widgets: vec![],
+            text_color,
+            font_size,
+            wrap,
+            line_numbers,
+            highlighter,
+            gutter_width: 0.0,
+            text,
+            history: EditHistory::new(),
+            cursor: Cursor::Single(CursorIndex { line: 0, index: 0 }),
+            goal_x: None,
+            cursor_moved: false,
+            dragging: false,
+            hovered: false,
+        }
+    }
+}
+
+impl<
+    F: State<T=Focus>,
+    C: ReadState<T=Color>,
+    S: ReadState<T=u32>,
+    T: State<T=String>,
+    E: ReadState<T=bool>,
+    W: ReadState<T=Wrap>,
+> KeyboardEventHandler for PlainTextEditor<F, C, S, T, E, W> {
+    fn handle_keyboard_event(&mut self, event: &KeyboardEvent, ctx: &mut KeyboardEventContext) {
+        if self.get_focus() != Focus::Focused || !*self.enabled.value() {
+            return;
+        }
+
+        let command = TextEditorKeyCommand::from(event);
+
+        // The horizontal position is only kept while moving up and down
+        if !matches!(command, TextEditorKeyCommand::MoveUp | TextEditorKeyCommand::MoveDown | TextEditorKeyCommand::SelectUp | TextEditorKeyCommand::SelectDown) {
+            self.goal_x = None;
+        }
+
+        match command {
+            TextEditorKeyCommand::MoveUp => self.move_vertical(false, false, ctx.text),
+            TextEditorKeyCommand::MoveDown => self.move_vertical(true, false, ctx.text),
+            TextEditorKeyCommand::SelectUp => self.move_vertical(false, true, ctx.text),
+            TextEditorKeyCommand::SelectDown => self.move_vertical(true, true, ctx.text),
+            TextEditorKeyCommand::JumpToStart => self.move_to(0, false),
+            TextEditorKeyCommand::JumpToEnd => {
+                let len = self.text.value().len();
+                self.move_to(len, false)
+            }
+            TextEditorKeyCommand::JumpSelectToStart => self.move_to(0, true),
+            TextEditorKeyCommand::JumpSelectToEnd => {
+                let len = self.text.value().len();
+                self.move_to(len, true)
+            }
+            // The text has its own history, so the undo manager of the environment should not also undo
+            TextEditorKeyCommand::Undo => {
+                self.undo();
+                ctx.prevent_default();
+            }
+            TextEditorKeyCommand::Redo => {
+                self.redo();
+                ctx.prevent_default();
+            }
+            TextEditorKeyCommand::Enter => self.text("\n"),
+            TextEditorKeyCommand::Input(command) => match command {
+                TextInputKeyCommand::MoveLeft => self.move_left(),
+                TextInputKeyCommand::MoveRight => self.move_right(),
+                TextInputKeyCommand::SelectLeft => self.move_to(self.prev_grapheme(), true),
+                TextInputKeyCommand::SelectRight => self.move_to(self.next_grapheme(), true),
+                TextInputKeyCommand::JumpWordLeft => self.move_to(self.prev_word(), false),
+                TextInputKeyCommand::JumpWordRight => self.move_to(self.next_word(), false),
+                TextInputKeyCommand::JumpSelectWordLeft => self.move_to(self.prev_word(), true),
+                TextInputKeyCommand::JumpSelectWordRight => self.move_to(self.next_word(), true),
+                // The left and right ends of the text input are the ends of the current line in the editor
+                TextInputKeyCommand::JumpToLeft => self.move_to(self.line_start(), false),
+                TextInputKeyCommand::JumpToRight => self.move_to(self.line_end(), false),
+                TextInputKeyCommand::JumpSelectToLeft => self.move_to(self.line_start(), true),
+                TextInputKeyCommand::JumpSelectToRight => self.move_to(self.line_end(), true),
+                TextInputKeyCommand::RemoveLeft => self.remove_left(),
+                TextInputKeyCommand::RemoveRight => self.remove_right(),
+                TextInputKeyCommand::RemoveWordLeft => self.remove_word_left(),
+                TextInputKeyCommand::RemoveWordRight => self.remove_word_right(),
+                TextInputKeyCommand::RemoveAll => {
+                    self.select_all();
+                    self.remove_left();
+                }
+                TextInputKeyCommand::DuplicateLeft => self.duplicate(false),
+                TextInputKeyCommand::DuplicateRight => self.duplicate(true),
+                TextInputKeyCommand::Copy => self.copy(),
+                TextInputKeyCommand::Paste => self.paste(),
+                TextInputKeyCommand::Cut => self.cut(),
+                TextInputKeyCommand::SelectAll => self.select_all(),
+                TextInputKeyCommand::Enter => self.text("\n"),
+                TextInputKeyCommand::Space => self.text(" "),
+                TextInputKeyCommand::Text(s, m) => {
+                    if s.len() == 0 || s.chars().next().unwrap().is_control() || m.contains(ModifierKey::SUPER) || m.contains(ModifierKey::CONTROL) {
+                        return;
+                    }
+
+                    self.text(s);
+                }
+                TextInputKeyCommand::Undefined => {}
+            },
+        }
+    }
+}
+
+// Cursor movement
+impl<F: State<T=Focus>, C: ReadState<T=Color>, S: ReadState<T=u32>, T: State<T=String>, E: ReadState<T=bool>, W: ReadState<T=Wrap>> PlainTextEditor<F, C, S, T, E, W> {
+    /// The byte offsets of the start and the end of the cursor. For a single cursor they are equal.
+    fn offsets(&self) -> (usize, usize) {
+        let text = self.text.value();
+
+        match self.cursor {
+            Cursor::Single(index) => {
+                let offset = offset_of(index, &text);
+                (offset, offset)
+            }
+            Cursor::Selection { start, end } => (offset_of(start, &text), offset_of(end, &text)),
+        }
+    }
+
+    /// The byte range of the selection, which is empty for a single cursor.
+    fn selection_range(&self) -> Range<usize> {
+        let (start, end) = self.offsets();
+        start.min(end)..start.max(end)
+    }
+
+    /// Move the end of the cursor to the byte offset. If select is true, the start of the cursor
+    /// is kept, to select the text in between.
+    fn move_to(&mut self, offset: usize, select: bool) {
+        let end = index_of(offset, &self.text.value());
+
+        self.cursor = match self.cursor {
+            Cursor::Single(start) | Cursor::Selection { start, .. } if select && start != end => {
+                Cursor::Selection { start, end }
+            }
+            _ => Cursor::Single(end),
+        };
+
+        self.history.break_group();
+        self.cursor_moved = true;
+    }
+
+    fn move_left(&mut self) {
+        let range = self.selection_range();
+
+        if range.is_empty() {
+            self.move_to(self.prev_grapheme(), false);
+        } else {
+            self.move_to(range.start, false);
+        }
+    }
+
+    fn move_right(&mut self) {
+        let range = self.selection_range();
+
+        if range.is_empty() {
+            self.move_to(self.next_grapheme(), false);
+        } else {
+            self.move_to(range.end, false);
+        }
+    }
+
+    /// Move the cursor to the line above or below, as it is shown. The horizontal position of
+    /// the cursor is kept when moving through shorter lines.
+    fn move_vertical(&mut self, down: bool, select: bool, ctx: &dyn TextContext) {
+        let (_, end) = self.offsets();
+        let text_id = self.text_widget.text_id();
+
+        let caret = ctx.caret_rect(text_id, end);
+        let x = *self.goal_x.get_or_insert(caret.position.x);
+        let line_height = caret.dimension.height;
+
+        let offset = if down {
+            let y = caret.position.y + line_height * 1.5;
+
+            if y > self.text_widget.height() {
+                self.text.value().len()
+            } else {
+                ctx.hit_offset(text_id, Position::new(x, y))
+            }
+        } else {
+            let y = caret.position.y - line_height * 0.5;
+
+            if y < 0.0 {
+                0
+            } else {
+                ctx.hit_offset(text_id, Position::new(x, y))
+            }
+        };
+
+        self.move_to(offset, select);
+    }
+
+    fn prev_grapheme(&self) -> usize {
+        let (_, end) = self.offsets();
+        prev_grapheme_offset(end, &self.text.value())
+    }
+
+    fn next_grapheme(&self) -> usize {
+        let (_, end) = self.offsets();
+        next_grapheme_offset(end, &self.text.value())
+    }
+
+    fn prev_word(&self) -> usize {
+        let (_, end) = self.offsets();
+        prev_word_offset(end, &self.text.value())
+    }
+
+    fn next_word(&self) -> usize {
+        let (_, end) = self.offsets();
+        next_word_offset(end, &self.text.value())
+    }
+
+    fn line_start(&self) -> usize {
+        let (_, end) = self.offsets();
+        self.text.value()[..end].rfind('\n').map_or(0, |index| index + 1)
+    }
+
+    fn line_end(&self) -> usize {
+        let (_, end) = self.offsets();
+        let text = self.text.value();
+        text[end..].find('\n').map_or(text.len(), |index| end + index)
+    }
+
+    /// Select all text, with the cursor ending at the end of the text
+    fn select_all(&mut self) {
+        let len = self.text.value().len();
+
+        self.move_to(0, false);
+        self.move_to(len, true);
+    }
+
+    /// Clamp the cursor to within the text, in case the text has been changed by something
+    /// other than the editor.
+    fn clamp_cursor(&mut self) {
+        let (start, end) = self.offsets();
+
+        let text = self.text.value();
+        let start = index_of(start.min(text.len()), &text);
+        let end = index_of(end.min(text.len()), &text);
+
+        self.cursor = if start == end {
+            Cursor::Single(end)
+        } else {
+            Cursor::Selection { start, end }
+        };
+    }
+}
+
+// Text modification
+impl<F: State<T=Focus>, C: ReadState<T=Color>, S: ReadState<T=u32>, T: State<T=String>, E: ReadState<T=bool>, W: ReadState<T=Wrap>> PlainTextEditor<F, C, S, T, E, W> {
+    /// Replace the text in the byte range with the string, and place the cursor after the
+    /// inserted string. The change is recorded in the history.
+    fn replace(&mut self, range: Range<usize>, string: &str) {
+        if range.is_empty() && string.is_empty() {
+            return;
+        }
+
+        let cursor_before = self.cursor;
+        let removed = self.text.value()[range.clone()].to_string();
+
+        self.text.value_mut().replace_range(range.clone(), string);
+
+        self.cursor = Cursor::Single(index_of(range.start + string.len(), &self.text.value()));
+        self.cursor_moved = true;
+
+        self.history.push(Edit {
+            offset: range.start,
+            removed,
+            inserted: string.to_string(),
+            cursor_before,
+            cursor_after: self.cursor,
+        });
+    }
+
+    /// Insert the string at the cursor, replacing the selection if any.
+    fn text(&mut self, s: &str) {
+        self.replace(self.selection_range(), s);
+    }
+
+    fn remove_left(&mut self) {
+        let range = self.selection_range();
+
+        if range.is_empty() {
+            self.replace(self.prev_grapheme()..range.end, "");
+        } else {
+            self.replace(range, "");
+        }
+    }
+
+    fn remove_right(&mut self) {
+        let range = self.selection_range();
+
+        if range.is_empty() {
+            self.replace(range.start..self.next_grapheme(), "");
+        } else {
+            self.replace(range, "");
+        }
+    }
+
+    fn remove_word_left(&mut self) {
+        let range = self.selection_range();
+
+        if range.is_empty() {
+            self.replace(self.prev_word()..range.end, "");
+        } else {
+            self.replace(range, "");
+        }
+    }
+
+    fn remove_word_right(&mut self) {
+        let range = self.selection_range();
+
+        if range.is_empty() {
+            self.replace(range.start..self.next_word(), "");
+        } else {
+            self.replace(range, "");
+        }
+    }
+
+    /// Insert a copy of the selection after it. When `after` is true the cursor is placed after
+    /// the copy, otherwise the original selection is kept. Nothing happens if nothing is selected.
+    fn duplicate(&mut self, after: bool) {
+        let range = self.selection_range();
+
+        if range.is_empty() {
+            return;
+        }
+
+        let cursor = self.cursor;
+        let selected = self.text.value()[range.clone()].to_string();
+        self.replace(range.end..range.end, &selected);
+
+        if !after {
+            self.cursor = cursor;
+        }
+    }
+
+    /// Copy the selected text. Nothing is copied if nothing is selected.
+    fn copy(&mut self) {
+        let range = self.selection_range();
+
+        if range.is_empty() {
+            return;
+        }
+
+        let mut ctx = ClipboardContext::new().unwrap();
+        ctx.set_contents(self.text.value()[range].to_string()).unwrap();
+    }
+
+    /// Cut the selected text. Nothing is cut if nothing is selected.
+    fn cut(&mut self) {
+        self.copy();
+        self.history.break_group();
+
+        let range = self.selection_range();
+        self.replace(range, "");
+        self.history.break_group();
+    }
+
+    /// Paste the text from the clipboard, replacing the current selection if possible
+    fn paste(&mut self) {
+        let mut ctx = ClipboardContext::new().unwrap();
+
+        let content = ctx.get_contents().unwrap().replace("\r\n", "\n");
+
+        self.history.break_group();
+        self.text(&content);
+        self.history.break_group();
+    }
+
+    fn undo(&mut self) {
+        let cursor = self.history.undo(&mut *self.text.value_mut());
+
+        if let Some(cursor) = cursor {
+            self.cursor = cursor;
+            self.cursor_moved = true;
+        }
+    }
+
+    fn redo(&mut self) {
+        let cursor = self.history.redo(&mut *self.text.value_mut());
+
+        if let Some(cursor) = cursor {
+            self.cursor = cursor;
+            self.cursor_moved = true;
+        }
+    }
+}
+
+impl<
+    F: State<T=Focus>,
+    C: ReadState<T=Color>,
+    S: ReadState<T=u32>,
+    T: State<T=String>,
+    E: ReadState<T=bool>,
+    W: ReadState<T=Wrap>,
+> MouseEventHandler for PlainTextEditor<F, C, S, T, E, W> {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, ctx: &mut MouseEventContext) {
+        let enabled = *self.enabled.value();
+        let editable = enabled && self.get_focus() == Focus::Focused;
+
+        match event {
+            MouseEvent::Move { to, .. } => {
+                self.hovered = self.is_inside(*to);
+            }
+            MouseEvent::Press { .. } if !self.is_inside(event.get_current_mouse_position()) => {
+                if self.get_focus() == Focus::Focused {
+                    self.set_focus(Focus::Unfocused);
+                }
+            }
+            MouseEvent::Press { position, modifiers: ModifierKey::EMPTY, .. } if enabled => {
+                if self.get_focus() == Focus::Unfocused {
+                    self.request_focus(ctx.env);
+                }
+
+                let offset = self.hit(position, ctx.text);
+                self.move_to(offset, false);
+                self.goal_x = None;
+                self.dragging = true;
+            }
+            MouseEvent::Release { .. } => {
+                self.dragging = false;
+            }
+            MouseEvent::Click(_, position, ModifierKey::SHIFT) if editable => {
+                let offset = self.hit(position, ctx.text);
+                self.move_to(offset, true);
+                self.goal_x = None;
+            }
+            MouseEvent::NClick(_, position, _, 2) if editable => {
+                let offset = self.hit(position, ctx.text);
+                let range = word_range(offset, &self.text.value());
+
+                self.move_to(range.start, false);
+                self.move_to(range.end, true);
+            }
+            MouseEvent::NClick(_, position, _, 3) if editable => {
+                let offset = self.hit(position, ctx.text);
+                self.move_to(offset, false);
+                self.move_to(self.line_start(), false);
+                self.move_to(self.line_end(), true);
+            }
+            MouseEvent::Drag { to, .. } if self.dragging && enabled => {
+                let offset = self.hit(to, ctx.text);
+                self.move_to(offset, true);
+                self.goal_x = None;
+            }
+            _ => (),
+        }
+    }
+}
+
+impl<F: State<T=Focus>, C: ReadState<T=Color>, S: ReadState<T=u32>, T: State<T=String>, E: ReadState<T=bool>, W: ReadState<T=Wrap>> PlainTextEditor<F, C, S, T, E, W> {
+    /// The byte offset of the text closest to the position.
+    fn hit(&self, position: &Position, ctx: &dyn TextContext) -> usize {
+        ctx.hit_offset(self.text_widget.text_id(), *position - self.text_widget.position())
+    }
+
+    /// The rectangles of the selection relative to the text. A selection within a single line is
+    /// one rectangle. Otherwise the selection covers the rest of the first line, the lines in
+    /// between and the start of the last line.
+    fn calculate_selection_rects(&self, ctx: &dyn TextContext) -> Vec<Rect> {
+        let range = self.selection_range();
+
+        if range.is_empty() {
+            return vec![];
+        }
+
+        let text_id = self.text_widget.text_id();
+        let width = self.text_widget.width();
+
+        let start = ctx.caret_rect(text_id, range.start);
+        let end = ctx.caret_rect(text_id, range.end);
+
+        if (start.position.y - end.position.y).abs() < 0.5 {
+            return vec![
+                Rect::new(start.position, Dimension::new(end.position.x - start.position.x, start.dimension.height)),
+            ];
+        }
+
+        let between = end.position.y - (start.position.y + start.dimension.height);
+
+        let mut rects = vec![
+            Rect::new(start.position, Dimension::new(width - start.position.x, start.dimension.height)),
+        ];
+
+        if between > 0.0 {
+            rects.push(Rect::new(
+                Position::new(0.0, start.position.y + start.dimension.height),
+                Dimension::new(width, between),
+            ));
+        }
+
+        rects.push(Rect::new(
+            Position::new(0.0, end.position.y),
+            Dimension::new(end.position.x, end.dimension.height),
+        ));
+
+        rects
+    }
+}
+
+impl<
+    F: State<T=Focus>,
+    C: ReadState<T=Color>,
+    S: ReadState<T=u32>,
+    T: State<T=String>,
+    E: ReadState<T=bool>,
+    W: ReadState<T=Wrap>,
+> Layout for PlainTextEditor<F, C, S, T, E, W> {
+    fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        self.clamp_cursor();
+
+        self.gutter_width = 0.0;
+
+        if self.line_numbers {
+            let lines = self.text.value().split('\n').count();
+
+            if self.line_number_widgets.len() != lines {
+                self.line_number_widgets = (1..=lines).map(|line| {
+                    Text::new(line.to_string())
+                        .font_size(self.font_size.clone())
+                        .color(EnvironmentColor::SecondaryLabel.color())
+                        .wrap(Wrap::None)
+                        .boxed()
+                }).collect();
+            }
+
+            for widget in &mut self.line_number_widgets {
+                let dimension = widget.process_calculate_size(requested_size, ctx);
+                self.gutter_width = self.gutter_width.max(dimension.width);
+            }
+
+            self.gutter_width += GUTTER_PADDING * 2.0;
+        }
+
+        let text_dimension = self.text_widget.process_calculate_size(
+            Dimension::new((requested_size.width - self.gutter_width).max(0.0), requested_size.height),
+            ctx,
+        );
+
+        let (_, end) = self.offsets();
+        let caret = ctx.text.caret_rect(self.text_widget.text_id(), end);
+        self.cursor_widget.process_calculate_size(Dimension::new(1.0, caret.dimension.height), ctx);
+
+        self.selection_rects = self.calculate_selection_rects(ctx.text);
+
+        while self.selection_widgets.len() < self.selection_rects.len() {
+            self.selection_widgets.push(self.selection_widget.clone());
+        }
+
+        self.selection_widgets.truncate(self.selection_rects.len());
+
+        for (widget, rect) in self.selection_widgets.iter_mut().zip(&self.selection_rects) {
+            widget.process_calculate_size(rect.dimension, ctx);
+        }
+
+        self.dimension = Dimension::new(
+            requested_size.width.max(self.gutter_width + text_dimension.width),
+            requested_size.height.max(text_dimension.height),
+        );
+
+        self.dimension
+    }
+
+    fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
+        let origin = self.position + Position::new(self.gutter_width, 0.0);
+
+        self.text_widget.set_position(origin);
+        self.text_widget.position_children(bounding_box, ctx);
+
+        let text_id = self.text_widget.text_id();
+
+        if self.line_numbers {
+            let mut line_start = 0;
+
+            for (widget, line) in self.line_number_widgets.iter_mut().zip(self.text.value().split('\n')) {
+                let y = ctx.text.caret_rect(text_id, line_start).position.y;
+                let x = self.position.x + self.gutter_width - GUTTER_PADDING - widget.width();
+
+                widget.set_position(Position::new(x, origin.y + y));
+                widget.position_children(bounding_box, ctx);
+
+                line_start += line.len() + 1;
+            }
+        }
+
+        for (widget, rect) in self.selection_widgets.iter_mut().zip(&self.selection_rects) {
+            widget.set_position(origin + rect.position);
+            widget.position_children(bounding_box, ctx);
+        }
+
+        let (_, end) = self.offsets();
+        let caret = ctx.text.caret_rect(text_id, end);
+
+        self.cursor_widget.set_position(origin + caret.position);
+        self.cursor_widget.position_children(bounding_box, ctx);
+
+        // Request the scroll containing the editor to show the cursor
+        if self.cursor_moved {
+            self.cursor_moved = false;
+
+            if let Some(visible) = ctx.env.get_mut::<ScrollToVisible>() {
+                *visible = Some(Rect::new(self.cursor_widget.position(), self.cursor_widget.dimension()));
+            }
+        }
+    }
+}
+
+impl<
+    F: State<T=Focus>,
+    C: ReadState<T=Color>,
+    S: ReadState<T=u32>,
+    T: State<T=String>,
+    E: ReadState<T=bool>,
+    W: ReadState<T=Wrap>,
+> Render for PlainTextEditor<F, C, S, T, E, W> {
+    fn render(&mut self, context: &mut RenderContext) {
+        if let Some(cursor) = self.cursor() {
+            if let Some(env_cursor) = context.env.get_mut::<MouseCursor>() {
+                *env_cursor = cursor;
+            }
+        }
+
+        for widget in &mut self.line_number_widgets {
+            widget.render(context);
+        }
+
+        if self.get_focus() == Focus::Focused && *self.enabled.value() {
+            for widget in &mut self.selection_widgets {
+                widget.render(context);
+            }
+
+            self.text_widget.render(context);
+            self.cursor_widget.render(context);
+        } else {
+            self.text_widget.render(context);
+        }
+    }
+}
+
+impl<
+    F: State<T=Focus>,
+    C: ReadState<T=Color>,
+    S: ReadState<T=u32>,
+    T: State<T=String>,
+    E: ReadState<T=bool>,
+    W: ReadState<T=Wrap>,
+> CommonWidget for PlainTextEditor<F, C, S, T, E, W> {
+    fn cursor(&self) -> Option<MouseCursor> {
+        if self.hovered {
+            Some(MouseCursor::Text)
+        } else {
+            None
+        }
+    }
+
+    CommonWidgetImpl!(self, child: (), position: self.position, dimension: self.dimension, flag: WidgetFlag::FOCUSABLE, flexibility: 1, focus: self.focus);
+}
+
+
+// ---------------------------------------------------
+//  Key commands
+// ---------------------------------------------------
+/// The key commands of the editor are the commands of the text input, extended with the
+/// commands that only make sense for multiple lines of text.
+enum TextEditorKeyCommand<'a> {
+    Input(TextInputKeyCommand<'a>),
+    MoveUp,
+    MoveDown,
+    SelectUp,
+    SelectDown,
+    JumpToStart,
+    JumpToEnd,
+    JumpSelectToStart,
+    JumpSelectToEnd,
+    Undo,
+    Redo,
+    Enter,
+}
+
+impl<'a> From<&'a KeyboardEvent> for TextEditorKeyCommand<'a> {
+    fn from(value: &'a KeyboardEvent) -> Self {
+        match value {
+            KeyboardEvent::Press { key: Key::ArrowUp, modifiers: ModifierKey::EMPTY, .. } => TextEditorKeyCommand::MoveUp,
+            KeyboardEvent::Press { key: Key::ArrowUp, modifiers: ModifierKey::SHIFT, .. } => TextEditorKeyCommand::SelectUp,
+            KeyboardEvent::Press { key: Key::ArrowUp, modifiers: ModifierKey::SUPER, .. } => TextEditorKeyCommand::JumpToStart,
+            KeyboardEvent::Press { key: Key::ArrowUp, modifiers: ModifierKey::SHIFT_SUPER, .. } => TextEditorKeyCommand::JumpSelectToStart,
+
+            KeyboardEvent::Press { key: Key::ArrowDown, modifiers: ModifierKey::EMPTY, .. } => TextEditorKeyCommand::MoveDown,
+            KeyboardEvent::Press { key: Key::ArrowDown, modifiers: ModifierKey::SHIFT, .. } => TextEditorKeyCommand::SelectDown,
+            KeyboardEvent::Press { key: Key::ArrowDown, modifiers: ModifierKey::SUPER, .. } => TextEditorKeyCommand::JumpToEnd,
+            KeyboardEvent::Press { key: Key::ArrowDown, modifiers: ModifierKey::SHIFT_SUPER, .. } => TextEditorKeyCommand::JumpSelectToEnd,
+
+            KeyboardEvent::Press { key: Key::Home, modifiers: ModifierKey::CONTROL, .. } => TextEditorKeyCommand::JumpToStart,
+            KeyboardEvent::Press { key: Key::Home, modifiers: ModifierKey::CTRL_SHIFT, .. } => TextEditorKeyCommand::JumpSelectToStart,
+            KeyboardEvent::Press { key: Key::End, modifiers: ModifierKey::CONTROL, .. } => TextEditorKeyCommand::JumpToEnd,
+            KeyboardEvent::Press { key: Key::End, modifiers: ModifierKey::CTRL_SHIFT, .. } => TextEditorKeyCommand::JumpSelectToEnd,
+
+            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER | ModifierKey::CONTROL, .. } if c == "z" => TextEditorKeyCommand::Undo,
+            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SHIFT_SUPER | ModifierKey::CTRL_SHIFT, .. } if c.eq_ignore_ascii_case("z") => TextEditorKeyCommand::Redo,
+            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::CONTROL, .. } if c == "y" => TextEditorKeyCommand::Redo,
+            KeyboardEvent::Press { key: Key::Undo, .. } => TextEditorKeyCommand::Undo,
+            KeyboardEvent::Press { key: Key::Redo, .. } => TextEditorKeyCommand::Redo,
+
+            KeyboardEvent::Press { key: Key::Enter, modifiers: ModifierKey::EMPTY | ModifierKey::SHIFT, .. } => TextEditorKeyCommand::Enter,
+
+            _ => TextEditorKeyCommand::Input(TextInputKeyCommand::from(value)),
+        }
+    }
+}
+
+
+// ---------------------------------------------------
+//  Utilities
+// ---------------------------------------------------
+
+/// The byte offset of the cursor index, where the index is a grapheme index within the line.
+fn offset_of(index: CursorIndex, text: &str) -> usize {
+    let start = if index.line == 0 {
+        0
+    } else {
+        text.match_indices('\n')
+            .nth(index.line - 1)
+            .map_or(text.len(), |(offset, _)| offset + 1)
+    };
+
+    let line = text[start..].split('\n').next().unwrap_or("");
+
+    line.grapheme_indices(true)
+        .nth(index.index)
+        .map_or(start + line.len(), |(offset, _)| start + offset)
+}
+
+/// The cursor index of the byte offset. Offsets within a grapheme are moved to the next grapheme.
+fn index_of(offset: usize, text: &str) -> CursorIndex {
+    let line = text[..offset].matches('\n').count();
+    let start = text[..offset].rfind('\n').map_or(0, |index| index + 1);
+
+    let index = text[start..].split('\n').next().unwrap_or("")
+        .grapheme_indices(true)
+        .take_while(|(index, _)| start + index < offset)
+        .count();
+
+    CursorIndex { line, index }
+}
+
+fn prev_grapheme_offset(offset: usize, text: &str) -> usize {
+    text[..offset].graphemes(true).next_back().map_or(0, |grapheme| offset - grapheme.len())
+}
+
+fn next_grapheme_offset(offset: usize, text: &str) -> usize {
+    text[offset..].graphemes(true).next().map_or(text.len(), |grapheme| offset + grapheme.len())
+}
+
+/// The offset of the start of the word before the offset.
+fn prev_word_offset(offset: usize, text: &str) -> usize {
+    text[..offset].split_word_bound_indices()
+        .rev()
+        .find(|(_, word)| !word.trim().is_empty())
+        .map_or(0, |(index, _)| index)
+}
+
+/// The offset of the end of the word after the offset.
+fn next_word_offset(offset: usize, text: &str) -> usize {
+    text[offset..].split_word_bound_indices()
+        .find(|(_, word)| !word.trim().is_empty())
+        .map_or(text.len(), |(index, word)| offset + index + word.len())
+}
+
+/// The byte range of the word, or the whitespace, at the offset.
+fn word_range(offset: usize, text: &str) -> Range<usize> {
+    text.split_word_bound_indices()
+        .find(|(index, word)| offset < index + word.len() && !word.contains('\n'))
+        .map_or(offset..offset, |(index, word)| index..index + word.len())
+}
+
+
+// ---------------------------------------------------
+//  Tests
+// ---------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use crate::plain::cursor::{Cursor, CursorIndex};
+    use crate::plain::plain_text_editor::{index_of, offset_of};
+    use crate::PlainTextEditor;
+
+    #[test]
+    fn cursor_index_round_trip() {
+        let text = "Hello\nধারা ১\n\nworld";
+
+        for offset in [0, 3, 5, 6, 12, 18, 19, 22, 23, 24, text.len()] {
+            assert_eq!(offset_of(index_of(offset, text), text), offset);
+        }
+
+        assert_eq!(index_of(6, text), CursorIndex { line: 1, index: 0 });
+        assert_eq!(index_of(text.len(), text), CursorIndex { line: 3, index: 5 });
+    }
+
+    #[test]
+    fn edit_lines_then_undo_and_redo() {
+        let mut editor = PlainTextEditor::new("Hello".to_string());
+        editor.move_to(5, false);
+
+        editor.text("\n");
+        editor.text("world");
+        assert_eq!(editor.text, "Hello\nworld".to_string());
+        assert_matches!(editor.cursor, Cursor::Single(CursorIndex { line: 1, index: 5 }));
+
+        editor.remove_left();
+        editor.remove_left();
+        assert_eq!(editor.text, "Hello\nwor".to_string());
+
+        editor.undo();
+        assert_eq!(editor.text, "Hello\nworld".to_string());
+
+        editor.undo();
+        assert_eq!(editor.text, "Hello\n".to_string());
+        assert_matches!(editor.cursor, Cursor::Single(CursorIndex { line: 1, index: 0 }));
+
+        editor.redo();
+        assert_eq!(editor.text, "Hello\nworld".to_string());
+    }
+
+    #[test]
+    fn remove_left_joins_lines() {
+        let mut editor = PlainTextEditor::new("Hello\nworld".to_string());
+        editor.move_to(6, false);
+
+        editor.remove_left();
+        assert_eq!(editor.text, "Helloworld".to_string());
+        assert_matches!(editor.cursor, Cursor::Single(CursorIndex { line: 0, index: 5 }));
+    }
+}
@@ -6,6 +6,7 @@ use carbide::animation::AnimationManager;
 use carbide_core::cursor::MouseCursor;
 use carbide::draw::{Alignment, Rect};
 use carbide::environment::Environment;
+use carbide::text::highlight::Highlighter;
 use carbide::text::text_wrap::Wrap;
 use carbide_core::CommonWidgetImpl;
 use carbide_core::draw::{Color, Dimension, Position};
@@ -86,6 +87,7 @@ pub struct PlainTextInput<F, C, O, S, T, E> where
     current_offset_speed: Option<f64>,
 
     hovered: bool,
+    highlighter: Option<Box<dyn Highlighter>>,
 }
 
 impl PlainTextInput<Focus, Color, Option<char>, u32, String, bool> {
@@ -107,6 +109,7 @@ impl PlainTextInput<Focus, Color, Option<char>, u32, String, bool> {
             cursor_widget,
             selection_widget,
             EnabledState::new(true),
+            None,
         )
     }
 }
@@ -129,6 +132,7 @@ impl<
             self.cursor_widget,
             self.selection_widget,
             enabled.into_read_state(),
+            self.highlighter,
         )
     }
 
@@ -142,6 +146,7 @@ impl<
             self.cursor_widget,
             self.selection_widget,
             self.enabled,
+            self.highlighter,
         )
     }
 
@@ -155,6 +160,7 @@ impl<
             self.cursor_widget,
             self.selection_widget,
             self.enabled,
+            self.highlighter,
         )
     }
 
@@ -168,6 +174,7 @@ impl<
             self.cursor_widget,
             self.selection_widget,
             self.enabled,
+            self.highlighter,
         )
     }
 
@@ -181,6 +188,7 @@ impl<
             self.cursor_widget,
             self.selection_widget,
             self.enabled,
+            self.highlighter,
         )
     }
 
@@ -194,6 +202,7 @@ impl<
             self.cursor_widget,
             selection,
             self.enabled,
+            self.highlighter,
         )
     }
 
@@ -207,6 +216,22 @@ impl<
             cursor,
             self.selection_widget,
             self.enabled,
+            self.highlighter,
+        )
+    }
+
+    /// Style the text using the highlighter, for example to color a snippet of code.
+    pub fn highlight(self, highlighter: impl Highlighter + 'static) -> PlainTextInput<F, C, O, S, T, E> {
+        Self::new_internal(
+            self.focus,
+            self.text_color,
+            self.obscure_text,
+            self.font_size,
+            self.text,
+            self.cursor_widget,
+            self.selection_widget,
+            self.enabled,
+            Some(Box::new(highlighter)),
         )
     }
 
@@ -217,7 +242,7 @@ impl<
         S2: ReadState<T=u32>,
         T2: State<T=String>,
         E2: ReadState<T=bool>,
-    >(focus: F2, text_color: C2, obscure: O2, font_size: S2, text: T2, cursor_widget: Box<dyn AnyWidget>, selection_widget: Box<dyn AnyWidget>, enabled: E2) -> PlainTextInput<F2, C2, O2, S2, T2, E2> {
+    >(focus: F2, text_color: C2, obscure: O2, font_size: S2, text: T2, cursor_widget: Box<dyn AnyWidget>, selection_widget: Box<dyn AnyWidget>, enabled: E2, highlighter: Option<Box<dyn Highlighter>>) -> PlainTextInput<F2, C2, O2, S2, T2, E2> {
 
         let display_text = Map2::read_map(text.clone(), obscure.clone(), |text, obscure| {
             if let Some(obscuring_char) = obscure {
@@ -227,10 +252,14 @@ impl<
             }
         });
 
-        let text_widget = Box::new(Text::new(display_text.clone())
+        let mut text_widget = Text::new(display_text.clone())
             .font_size(font_size.clone())
             .color(text_color.clone())
-            .wrap(Wrap::None));
+            .wrap(Wrap::None);
+
+        if let Some(highlighter) = highlighter.clone() {
+            text_widget = text_widget.highlight(highlighter);
+        }
 
         let last = usize::MAX;
 
@@ -240,7 +269,7 @@ impl<
             dimension: Default::default(),
             focus,
             enabled,
-            text_widget,
+            text_widget: Box::new(text_widget),
             cursor_widget,
             selection_widget,
             text_color,
@@ -254,6 +283,7 @@ impl<
             last_drag_position: None,
             current_offset_speed: None,
             hovered: false,
+            highlighter,
         }
     }
 }
@@ -1238,12 +1268,12 @@ impl<
             self.drag_selection(ctx.text, &position, &position);
         }
 
-        let text_dimensions = self.text_widget.calculate_size(requested_size, ctx);
+        let text_dimensions = self.text_widget.process_calculate_size(requested_size, ctx);
 
         // Calculate size for selection indicator
         match self.cursor {
             Cursor::Single(_) => {
-                self.cursor_widget.calculate_size(Dimension::new(1.0, text_dimensions.height), ctx);
+                self.cursor_widget.process_calculate_size(Dimension::new(1.0, text_dimensions.height), ctx);
             }
             Cursor::Selection { start, end } => {
                 let text_id = self.text_widget.text_id();
@@ -1253,8 +1283,8 @@ impl<
                 let min = start_x.min(end_x);
                 let max = start_x.max(end_x);
 
-                self.cursor_widget.calculate_size(Dimension::new(1.0, text_dimensions.height), ctx);
-                self.selection_widget.calculate_size(Dimension::new(max - min, text_dimensions.height), ctx);
+                self.cursor_widget.process_calculate_size(Dimension::new(1.0, text_dimensions.height), ctx);
+                self.selection_widget.process_calculate_size(Dimension::new(max - min, text_dimensions.height), ctx);
             }
         }
 
@@ -1409,37 +1439,39 @@ impl<'a> From<&'a KeyboardEvent> for TextInputKeyCommand<'a> {
         match value {
             KeyboardEvent::Press { key: Key::ArrowLeft, modifiers: ModifierKey::EMPTY, .. } => TextInputKeyCommand::MoveLeft,
             KeyboardEvent::Press { key: Key::ArrowLeft, modifiers: ModifierKey::SHIFT, .. } => TextInputKeyCommand::SelectLeft,
-            KeyboardEvent::Press { key: Key::ArrowLeft, modifiers: ModifierKey::ALT, .. } => TextInputKeyCommand::JumpWordLeft,
+            KeyboardEvent::Press { key: Key::ArrowLeft, modifiers: ModifierKey::ALT | ModifierKey::CONTROL, .. } => TextInputKeyCommand::JumpWordLeft,
             KeyboardEvent::Press { key: Key::ArrowLeft, modifiers: ModifierKey::SUPER, .. } => TextInputKeyCommand::JumpToLeft,
-            KeyboardEvent::Press { key: Key::ArrowLeft, modifiers: ModifierKey::SHIFT_ALT, .. } => TextInputKeyCommand::JumpSelectWordLeft,
+            KeyboardEvent::Press { key: Key::ArrowLeft, modifiers: ModifierKey::SHIFT_ALT | ModifierKey::CTRL_SHIFT, .. } => TextInputKeyCommand::JumpSelectWordLeft,
             KeyboardEvent::Press { key: Key::ArrowLeft, modifiers: ModifierKey::SHIFT_SUPER , ..} => TextInputKeyCommand::JumpSelectToLeft,
 
             KeyboardEvent::Press { key: Key::ArrowRight, modifiers: ModifierKey::EMPTY, .. } => TextInputKeyCommand::MoveRight,
             KeyboardEvent::Press { key: Key::ArrowRight, modifiers: ModifierKey::SHIFT, .. } => TextInputKeyCommand::SelectRight,
-            KeyboardEvent::Press { key: Key::ArrowRight, modifiers: ModifierKey::ALT, .. } => TextInputKeyCommand::JumpWordRight,
+            KeyboardEvent::Press { key: Key::ArrowRight, modifiers: ModifierKey::ALT | ModifierKey::CONTROL, .. } => TextInputKeyCommand::JumpWordRight,
             KeyboardEvent::Press { key: Key::ArrowRight, modifiers: ModifierKey::SUPER, .. } => TextInputKeyCommand::JumpToRight,
-            KeyboardEvent::Press { key: Key::ArrowRight, modifiers: ModifierKey::SHIFT_ALT, .. } => TextInputKeyCommand::JumpSelectWordRight,
+            KeyboardEvent::Press { key: Key::ArrowRight, modifiers: ModifierKey::SHIFT_ALT | ModifierKey::CTRL_SHIFT, .. } => TextInputKeyCommand::JumpSelectWordRight,
             KeyboardEvent::Press { key: Key::ArrowRight, modifiers: ModifierKey::SHIFT_SUPER, .. } => TextInputKeyCommand::JumpSelectToRight,
 
             KeyboardEvent::Press { key: Key::Backspace, modifiers: ModifierKey::EMPTY, .. } => TextInputKeyCommand::RemoveLeft,
             KeyboardEvent::Press { key: Key::Backspace, modifiers: ModifierKey::SHIFT, .. } => TextInputKeyCommand::RemoveLeft,
-            KeyboardEvent::Press { key: Key::Backspace, modifiers: ModifierKey::ALT, .. } => TextInputKeyCommand::RemoveWordLeft,
+            KeyboardEvent::Press { key: Key::Backspace, modifiers: ModifierKey::ALT | ModifierKey::CONTROL, .. } => TextInputKeyCommand::RemoveWordLeft,
 
             KeyboardEvent::Press { key: Key::Delete, modifiers: ModifierKey::EMPTY, .. } => TextInputKeyCommand::RemoveRight,
             KeyboardEvent::Press { key: Key::Delete, modifiers: ModifierKey::SHIFT, .. } => TextInputKeyCommand::RemoveAll,
-            KeyboardEvent::Press { key: Key::Delete, modifiers: ModifierKey::ALT, .. } => TextInputKeyCommand::RemoveWordRight,
+            KeyboardEvent::Press { key: Key::Delete, modifiers: ModifierKey::ALT | ModifierKey::CONTROL, .. } => TextInputKeyCommand::RemoveWordRight,
 
-            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER, .. } if c == "c" => TextInputKeyCommand::Copy,
-            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER, .. } if c == "v" => TextInputKeyCommand::Paste,
-            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER, .. } if c == "x" => TextInputKeyCommand::Cut,
-            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER, .. } if c == "a" => TextInputKeyCommand::SelectAll,
+            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER | ModifierKey::CONTROL, .. } if c == "c" => TextInputKeyCommand::Copy,
+            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER | ModifierKey::CONTROL, .. } if c == "v" => TextInputKeyCommand::Paste,
+            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER | ModifierKey::CONTROL, .. } if c == "x" => TextInputKeyCommand::Cut,
+            KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER | ModifierKey::CONTROL, .. } if c == "a" => TextInputKeyCommand::SelectAll,
             KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SUPER, .. } if c == "d" => TextInputKeyCommand::DuplicateRight,
             KeyboardEvent::Press { key: Key::Character(c), modifiers: ModifierKey::SHIFT_SUPER, .. } if c == "d" => TextInputKeyCommand::DuplicateLeft,
 
+            KeyboardEvent::Press { key: Key::Home, modifiers: ModifierKey::EMPTY, .. } => TextInputKeyCommand::JumpToLeft,
             KeyboardEvent::Press { key: Key::Home, modifiers: ModifierKey::SHIFT, .. } => TextInputKeyCommand::JumpSelectToLeft,
+            KeyboardEvent::Press { key: Key::End, modifiers: ModifierKey::EMPTY, .. } => TextInputKeyCommand::JumpToRight,
             KeyboardEvent::Press { key: Key::End, modifiers: ModifierKey::SHIFT, .. } => TextInputKeyCommand::JumpSelectToRight,
             KeyboardEvent::Press { key: Key::Enter, modifiers: ModifierKey::EMPTY, .. } => TextInputKeyCommand::Enter,
-            KeyboardEvent::Press { key: Key::Space, modifiers: ModifierKey::EMPTY, .. } => TextInputKeyCommand::Space,
+            KeyboardEvent::Press { key: Key::Space, modifiers: ModifierKey::EMPTY | ModifierKey::SHIFT, .. } => TextInputKeyCommand::Space,
 
             KeyboardEvent::Press { key: Key::Character(s), modifiers: m, .. } => TextInputKeyCommand::Text(s, *m),
             KeyboardEvent::Ime(Ime::Commit(s)) => TextInputKeyCommand::Text(s, ModifierKey::EMPTY),
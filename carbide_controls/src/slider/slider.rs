@@ -281,7 +281,7 @@ impl<
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
         let percent = self.percent.value().max(0.0).min(1.0);
 
-        let background = self.background.calculate_size(requested_size, ctx);
+        let background = self.background.process_calculate_size(requested_size, ctx);
 
         let track_width = match &*self.steps.value() {
             SliderStepping::Smooth | SliderStepping::SmoothStepped(_) => requested_size.width * percent,
@@ -291,9 +291,9 @@ impl<
         };
 
         let track_dimensions = Dimension::new(track_width, requested_size.height);
-        let track = self.track.calculate_size(track_dimensions, ctx);
+        let track = self.track.process_calculate_size(track_dimensions, ctx);
 
-        let thumb = self.thumb.calculate_size(requested_size, ctx);
+        let thumb = self.thumb.process_calculate_size(requested_size, ctx);
 
         let max_height = background.height.max(track.height).max(thumb.height);
 
@@ -0,0 +1,186 @@
+use carbide::color::ColorExt;
+use carbide::environment::IntoColorReadState;
+use carbide::text::highlight::Highlighter;
+use carbide::text::text_wrap::Wrap;
+use carbide_core::CommonWidgetImpl;
+use carbide_core::draw::{Dimension, Position};
+use carbide_core::environment::{EnvironmentColor, EnvironmentFontSize};
+use carbide_core::focus::Focus;
+use carbide_core::state::{IntoReadState, IntoState, LocalState, Map1, Map2, ReadState, State};
+use carbide_core::widget::{CommonWidget, CornerRadii, EdgeInsets, Rectangle, RoundedRectangle, AnyWidget, Scroll, WidgetExt, WidgetId, ZStack, Widget};
+
+use crate::{EnabledState, PlainTextEditor};
+
+const PADDING: f64 = 5.0;
+
+/// A multi-line text editor, styled like the [TextInput](crate::TextInput). The editor scrolls
+/// when the text does not fit, and can show line numbers in a gutter.
+#[derive(Debug, Clone, Widget)]
+pub struct TextEditor<F, T, E, W> where
+    F: State<T=Focus>,
+    T: State<T=String>,
+    E: ReadState<T=bool>,
+    W: ReadState<T=Wrap>,
+{
+    #[id] id: WidgetId,
+    position: Position,
+    dimension: Dimension,
+
+    child: Box<dyn AnyWidget>,
+    line_numbers: bool,
+    highlighter: Option<Box<dyn Highlighter>>,
+
+    #[state] text: T,
+    #[state] focus: F,
+    #[state] enabled: E,
+    #[state] wrap: W,
+}
+
+impl TextEditor<Focus, String, bool, Wrap> {
+    pub fn new<T: IntoState<String>>(text: T) -> TextEditor<LocalState<Focus>, T::Output, EnabledState, Wrap> {
+        let focus = LocalState::new(Focus::Unfocused);
+        let text = text.into_state();
+
+        Self::new_internal(text, focus, EnabledState::new(true), Wrap::Whitespace, false, None)
+    }
+}
+
+impl<F: State<T=Focus>, T: State<T=String>, E: ReadState<T=bool>, W: ReadState<T=Wrap>> TextEditor<F, T, E, W> {
+    pub fn enabled<E2: IntoReadState<bool>>(self, enabled: E2) -> TextEditor<F, T, E2::Output, W> {
+        Self::new_internal(
+            self.text,
+            self.focus,
+            enabled.into_read_state(),
+            self.wrap,
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    pub fn focused<F2: IntoState<Focus>>(self, focused: F2) -> TextEditor<F2::Output, T, E, W> {
+        Self::new_internal(
+            self.text,
+            focused.into_state(),
+            self.enabled,
+            self.wrap,
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    /// Set how the lines of the text wrap. With [Wrap::None] long lines scroll horizontally.
+    pub fn wrap<W2: IntoReadState<Wrap>>(self, wrap: W2) -> TextEditor<F, T, E, W2::Output> {
+        Self::new_internal(
+            self.text,
+            self.focus,
+            self.enabled,
+            wrap.into_read_state(),
+            self.line_numbers,
+            self.highlighter,
+        )
+    }
+
+    /// Show the line numbers in a gutter to the left of the text.
+    pub fn line_numbers(self) -> TextEditor<F, T, E, W> {
+        Self::new_internal(
+            self.text,
+            self.focus,
+            self.enabled,
+            self.wrap,
+            true,
+            self.highlighter,
+        )
+    }
+
+    /// Style the text using the highlighter, for example to color a config file.
+    pub fn highlight(self, highlighter: impl Highlighter + 'static) -> TextEditor<F, T, E, W> {
+        Self::new_internal(
+            self.text,
+            self.focus,
+            self.enabled,
+            self.wrap,
+            self.line_numbers,
+            Some(Box::new(highlighter)),
+        )
+    }
+
+    fn new_internal<F2: State<T=Focus>, T2: State<T=String>, E2: ReadState<T=bool>, W2: ReadState<T=Wrap>>(
+        text: T2,
+        focus: F2,
+        enabled: E2,
+        wrap: W2,
+        line_numbers: bool,
+        highlighter: Option<Box<dyn Highlighter>>,
+    ) -> TextEditor<F2, T2, E2, W2> {
+
+        let selection_color = EnvironmentColor::Accent.color();
+        let darkened_selection_color = Map1::read_map(selection_color, |col| col.darkened(0.2));
+
+        let stroke_color = Map2::read_map(focus.clone(), enabled.clone(), |focus: &Focus, enabled: &bool| {
+            if *focus == Focus::Focused && *enabled {
+                return EnvironmentColor::Accent;
+            }
+
+            EnvironmentColor::OpaqueSeparator
+        });
+
+        let label_color = Map1::read_map(enabled.clone(), |enabled| {
+            if *enabled {
+                EnvironmentColor::Label
+            } else {
+                EnvironmentColor::SecondaryLabel
+            }
+        });
+
+        let background_color = Map1::read_map(enabled.clone(), |enabled| {
+            if *enabled {
+                EnvironmentColor::SecondarySystemBackground
+            } else {
+                EnvironmentColor::TertiarySystemBackground
+            }
+        });
+
+        let editor = PlainTextEditor::new(text.clone())
+            .font_size(EnvironmentFontSize::Body)
+            .text_color(label_color)
+            .cursor_widget(Rectangle::new().fill(EnvironmentColor::Label).boxed())
+            .selection_widget(Rectangle::new().fill(darkened_selection_color).boxed())
+            .focused(focus.clone())
+            .enabled(enabled.clone())
+            .wrap(wrap.clone())
+            .line_numbers(line_numbers);
+
+        let editor = match highlighter.clone() {
+            Some(highlighter) => editor.highlight(highlighter),
+            None => editor,
+        }.padding(EdgeInsets::all(PADDING));
+
+        let child = ZStack::new(vec![
+            RoundedRectangle::new(CornerRadii::all(3.0))
+                .fill(background_color)
+                .stroke(stroke_color)
+                .stroke_style(1.0)
+                .boxed(),
+            Scroll::new(editor)
+                .clip()
+                .boxed()
+        ]).boxed();
+
+        TextEditor {
+            id: WidgetId::new(),
+            position: Default::default(),
+            dimension: Default::default(),
+            child,
+            line_numbers,
+            highlighter,
+            text,
+            focus,
+            enabled,
+            wrap,
+        }
+    }
+}
+
+impl<F: State<T=Focus>, T: State<T=String>, E: ReadState<T=bool>, W: ReadState<T=Wrap>> CommonWidget for TextEditor<F, T, E, W> {
+    CommonWidgetImpl!(self, child: self.child, position: self.position, dimension: self.dimension, flexibility: 1);
+}
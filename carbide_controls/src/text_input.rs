@@ -4,8 +4,8 @@ use carbide_core::CommonWidgetImpl;
 use carbide_core::draw::{Dimension, Position};
 use carbide_core::environment::{EnvironmentColor, EnvironmentFontSize};
 use carbide_core::focus::Focus;
-use carbide_core::state::{IntoReadState, IntoState, LocalState, Map1, Map2, ReadState, State};
-use carbide_core::widget::{CommonWidget, CornerRadii, EdgeInsets, Rectangle, RoundedRectangle, AnyWidget, WidgetExt, WidgetId, ZStack, Widget};
+use carbide_core::state::{FieldValidation, IntoReadState, IntoState, LocalState, Map1, Map3, ReadState, State, ValidationResult};
+use carbide_core::widget::{CommonWidget, CornerRadii, CrossAxisAlignment, EdgeInsets, EnvUpdatingNew, IfElse, Rectangle, RoundedRectangle, AnyWidget, Text, VStack, WidgetExt, WidgetId, ZStack, Widget};
 
 use crate::{EnabledState, PASSWORD_CHAR, PlainTextInput};
 
@@ -13,11 +13,12 @@ const VERTICAL_PADDING: f64 = 0.0;
 const HORIZONTAL_PADDING: f64 = 5.0;
 
 #[derive(Debug, Clone, Widget)]
-pub struct TextInput<F, O, T, E> where
+pub struct TextInput<F, O, T, E, V> where
     F: State<T=Focus>,
     O: ReadState<T=Option<char>>,
     T: State<T=Result<String, String>>,
     E: ReadState<T=bool>,
+    V: ReadState<T=ValidationResult>,
 {
     #[id] id: WidgetId,
     position: Position,
@@ -25,62 +26,91 @@ pub struct TextInput<F, O, T, E> where
 
     child: Box<dyn AnyWidget>,
     obscure: O,
+    /// The validation of the [ValidatedState](carbide_core::state::ValidatedState) given as the
+    /// text, if any. It is used as the validation unless another is given.
+    field: FieldValidation,
 
     #[state] text: T,
     #[state] focus: F,
     #[state] enabled: E,
+    #[state] validation: V,
 }
 
-impl TextInput<Focus, Option<char>, Result<String, String>, bool> {
-    pub fn new<T: IntoState<Result<String, String>>>(text: T) -> TextInput<LocalState<Focus>, Option<char>, T::Output, EnabledState> {
+impl TextInput<Focus, Option<char>, Result<String, String>, bool, ValidationResult> {
+    /// Create a text input for the text. If the text is a
+    /// [ValidatedState](carbide_core::state::ValidatedState), the result of the validation is shown.
+    pub fn new<T: IntoState<Result<String, String>>>(text: T) -> TextInput<LocalState<Focus>, Option<char>, T::Output, EnabledState, FieldValidation> {
         let focus = LocalState::new(Focus::Unfocused);
         let obscure = None;
         let text = text.into_state();
+        let field = FieldValidation::new();
 
-        Self::new_internal(text, focus, obscure, EnabledState::new(true))
+        Self::new_internal(text, focus, obscure, EnabledState::new(true), field.clone(), field)
     }
 }
 
-impl<F: State<T=Focus>, O: ReadState<T=Option<char>>, T: State<T=Result<String, String>>, E: ReadState<T=bool>> TextInput<F, O, T, E> {
-    pub fn enabled<E2: IntoReadState<bool>>(self, enabled: E2) -> TextInput<F, O, T, E2::Output> {
+impl<F: State<T=Focus>, O: ReadState<T=Option<char>>, T: State<T=Result<String, String>>, E: ReadState<T=bool>, V: ReadState<T=ValidationResult>> TextInput<F, O, T, E, V> {
+    pub fn enabled<E2: IntoReadState<bool>>(self, enabled: E2) -> TextInput<F, O, T, E2::Output, V> {
         Self::new_internal(
             self.text,
             self.focus,
             self.obscure,
             enabled.into_read_state(),
+            self.validation,
+            self.field,
         )
     }
 
-    pub fn obscure(self) -> TextInput<F, Option<char>, T, E> {
+    pub fn obscure(self) -> TextInput<F, Option<char>, T, E, V> {
         Self::new_internal(
             self.text,
             self.focus,
             Some(PASSWORD_CHAR),
             self.enabled,
+            self.validation,
+            self.field,
         )
     }
 
-    pub fn obscure_with<O2: IntoReadState<Option<char>>>(self, obscure: O2) -> TextInput<F, O2::Output, T, E> {
+    pub fn obscure_with<O2: IntoReadState<Option<char>>>(self, obscure: O2) -> TextInput<F, O2::Output, T, E, V> {
         Self::new_internal(
             self.text,
             self.focus,
             obscure.into_read_state(),
             self.enabled,
+            self.validation,
+            self.field,
         )
     }
 
-    fn new_internal<F2: State<T=Focus>, O2: ReadState<T=Option<char>>, T2: State<T=Result<String, String>>, E2: ReadState<T=bool>>(
+    /// Show the result of validating the text. An invalid input is outlined in red, with the
+    /// message shown below it. This is only needed when the result is not from a
+    /// [ValidatedState](carbide_core::state::ValidatedState) given as the text.
+    pub fn validation<V2: IntoReadState<ValidationResult>>(self, validation: V2) -> TextInput<F, O, T, E, V2::Output> {
+        Self::new_internal(
+            self.text,
+            self.focus,
+            self.obscure,
+            self.enabled,
+            validation.into_read_state(),
+            self.field,
+        )
+    }
+
+    fn new_internal<F2: State<T=Focus>, O2: ReadState<T=Option<char>>, T2: State<T=Result<String, String>>, E2: ReadState<T=bool>, V2: ReadState<T=ValidationResult>>(
         text: T2,
         focus: F2,
         obscure: O2,
         enabled: E2,
-    ) -> TextInput<F2, O2, T2, E2> {
+        validation: V2,
+        field: FieldValidation,
+    ) -> TextInput<F2, O2, T2, E2, V2> {
 
         let selection_color = EnvironmentColor::Accent.color();
         let darkened_selection_color = Map1::read_map(selection_color, |col| col.darkened(0.2));
 
-        let stroke_color = Map2::read_map(focus.clone(), text.clone(), |focus: &Focus, text: &Result<String, String>| {
-            if text.is_err() {
+        let stroke_color = Map3::read_map(focus.clone(), text.clone(), validation.clone(), |focus: &Focus, text: &Result<String, String>, validation: &ValidationResult| {
+            if text.is_err() || validation.message().is_some() {
                 return EnvironmentColor::Red;
             }
 
@@ -136,8 +166,24 @@ impl<F: State<T=Focus>, O: ReadState<T=Option<char>>, T: State<T=Result<String,
                 .stroke_style(1.0)
                 .boxed(),
             text_widget.boxed()
-        ]).frame_fixed_height(22.0)
-            .boxed();
+        ]).frame_fixed_height(22.0);
+
+        let has_message = Map1::read_map(validation.clone(), |validation| validation.message().is_some());
+        let message = Map1::read_map(validation.clone(), |validation| validation.message().unwrap_or_default().to_string());
+
+        let child = VStack::new((
+            child,
+            IfElse::new(has_message).when_true(
+                Text::new(message)
+                    .font_size(EnvironmentFontSize::Caption)
+                    .foreground_color(EnvironmentColor::Red)
+                    .padding(EdgeInsets::single(2.0, 0.0, HORIZONTAL_PADDING, 0.0))
+            ),
+        )).spacing(0.0)
+            .cross_axis_alignment(CrossAxisAlignment::Start);
+
+        // A validated state attaches its result to the field when the text is synced within it
+        let child = EnvUpdatingNew::<_, FieldValidation>::new(field.clone(), child).boxed();
 
 
         TextInput {
@@ -146,13 +192,15 @@ impl<F: State<T=Focus>, O: ReadState<T=Option<char>>, T: State<T=Result<String,
             dimension: Default::default(),
             child,
             obscure,
+            field,
             text,
             focus,
             enabled,
+            validation,
         }
     }
 }
 
-impl<F: State<T=Focus>, O: ReadState<T=Option<char>>, T: State<T=Result<String, String>>, E: ReadState<T=bool>,> CommonWidget for TextInput<F, O, T, E> {
+impl<F: State<T=Focus>, O: ReadState<T=Option<char>>, T: State<T=Result<String, String>>, E: ReadState<T=bool>, V: ReadState<T=ValidationResult>> CommonWidget for TextInput<F, O, T, E, V> {
     CommonWidgetImpl!(self, child: self.child, position: self.position, dimension: self.dimension, flexibility: 1);
 }
\ No newline at end of file
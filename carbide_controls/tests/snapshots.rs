@@ -0,0 +1,37 @@
+use carbide_controls::button::{BorderedProminentStyle, Button};
+use carbide_controls::toggle::{SwitchStyle, Toggle};
+use carbide_controls::ControlsExt;
+use carbide_core::draw::Dimension;
+use carbide_core::state::LocalState;
+use carbide_core::widget::MouseAreaActionContext;
+use carbide_tiny_skia::assert_snapshot;
+
+#[test]
+fn bordered_prominent_button() {
+    assert_snapshot!(
+        Button::new("Button", |_: MouseAreaActionContext| {}).button_style(BorderedProminentStyle),
+        Dimension::new(120.0, 40.0),
+        "bordered_prominent_button",
+        tolerance = 2
+    );
+}
+
+#[test]
+fn switch_on() {
+    assert_snapshot!(
+        Toggle::new("Switch", LocalState::new(true)).toggle_style(SwitchStyle),
+        Dimension::new(120.0, 40.0),
+        "switch_on",
+        tolerance = 2
+    );
+}
+
+#[test]
+fn switch_off() {
+    assert_snapshot!(
+        Toggle::new("Switch", LocalState::new(false)).toggle_style(SwitchStyle),
+        Dimension::new(120.0, 40.0),
+        "switch_off",
+        tolerance = 2
+    );
+}
@@ -1,5 +1,6 @@
 use crate::draw::Color;
 use crate::draw::{Dimension, Position};
+use crate::math::Vector2;
 
 /// This trait is the base for things that are animatable. To animate a value in an [Animation]
 /// you can either provide a value that is animatable or provide a value with a custom interpolation.
@@ -10,18 +11,36 @@ use crate::draw::{Dimension, Position};
 /// possibly being applied twice.
 pub trait Animatable<T> {
     fn interpolate(&self, other: &T, percentage: f64) -> T;
+
+    /// The displacement from this value to the other, as a vector. This is used to carry the
+    /// velocity of a running animation over, when the animation gets a new target, by projecting
+    /// it onto the direction of the new animation. One dimensional values only use the x
+    /// component. Values without a meaningful displacement return None, and start new
+    /// animations from rest.
+    #[allow(unused_variables)]
+    fn displacement(&self, other: &T) -> Option<Vector2<f64>> {
+        None
+    }
 }
 
 impl Animatable<f32> for f32 {
     fn interpolate(&self, other: &f32, percentage: f64) -> f32 {
         *self * (1.0 - percentage as f32) + *other * percentage as f32
     }
+
+    fn displacement(&self, other: &f32) -> Option<Vector2<f64>> {
+        Some(Vector2::new(*other as f64 - *self as f64, 0.0))
+    }
 }
 
 impl Animatable<f64> for f64 {
     fn interpolate(&self, other: &f64, percentage: f64) -> f64 {
         *self * (1.0 - percentage) + *other * percentage
     }
+
+    fn displacement(&self, other: &f64) -> Option<Vector2<f64>> {
+        Some(Vector2::new(*other - *self, 0.0))
+    }
 }
 
 impl Animatable<Color> for Color {
@@ -37,6 +56,10 @@ impl Animatable<Position> for Position {
             self.y.interpolate(&other.y, percentage),
         )
     }
+
+    fn displacement(&self, other: &Position) -> Option<Vector2<f64>> {
+        Some(Vector2::new(other.x - self.x, other.y - self.y))
+    }
 }
 
 impl Animatable<Dimension> for Dimension {
@@ -46,10 +69,18 @@ impl Animatable<Dimension> for Dimension {
             self.height.interpolate(&other.height, percentage)
         )
     }
+
+    fn displacement(&self, other: &Dimension) -> Option<Vector2<f64>> {
+        Some(Vector2::new(other.width - self.width, other.height - self.height))
+    }
 }
 
 impl Animatable<i32> for i32 {
     fn interpolate(&self, other: &i32, percentage: f64) -> i32 {
         (*self as f32 * (1.0 - percentage as f32) + *other as f32 * percentage as f32).round() as i32
     }
+
+    fn displacement(&self, other: &i32) -> Option<Vector2<f64>> {
+        Some(Vector2::new(*other as f64 - *self as f64, 0.0))
+    }
 }
\ No newline at end of file
@@ -2,7 +2,7 @@ use std::fmt::{Debug, Formatter};
 use crate::state::AnyState;
 
 use crate::animation::animatable::Animatable;
-use crate::animation::linear;
+use crate::animation::{linear, Decay, Simulation, Spring};
 use crate::state::{IntoState, RepeatMode, State, StateContract, StateExtNew};
 use crate::time::*;
 
@@ -16,6 +16,7 @@ where
     repeat_mode: RepeatMode,
     //repeat_count: Option<u32>,
     animation_curve: fn(f64) -> f64,
+    simulation: Option<Simulation>,
     custom_interpolation: fn(&T, &T, f64) -> T,
     state: Box<dyn AnyState<T=T>>,
     from: T,
@@ -30,6 +31,7 @@ impl<T: StateContract + Animatable<T>> Animation<T> {
             repeat_mode: RepeatMode::None,
             //repeat_count: None,
             animation_curve: linear,
+            simulation: None,
             custom_interpolation: T::interpolate,
             state: state.into_state().as_dyn(),
             from,
@@ -38,6 +40,14 @@ impl<T: StateContract + Animatable<T>> Animation<T> {
     }
 }
 
+impl Animation<f64> {
+    /// Animate the state from `from` with the velocity of the decay, until it comes to rest
+    /// at the target of the decay.
+    pub fn fling<S: IntoState<f64>>(state: S, from: f64, decay: Decay) -> Self {
+        Animation::new(state, from, decay.target(from)).decay(decay)
+    }
+}
+
 impl<T: StateContract> Animation<T> {
     pub fn new_custom<S: IntoState<T>>(
         state: S,
@@ -51,6 +61,7 @@ impl<T: StateContract> Animation<T> {
             repeat_mode: RepeatMode::None,
             //repeat_count: None,
             animation_curve: linear,
+            simulation: None,
             custom_interpolation: interpolation,
             state: state.into_state().as_dyn(),
             from,
@@ -70,6 +81,25 @@ impl<T: StateContract> Animation<T> {
 
     pub fn curve(mut self, curve: fn(f64) -> f64) -> Self {
         self.animation_curve = curve;
+        self.simulation = None;
+        self
+    }
+
+    /// Animate with the spring instead of a curve. The duration is set to the time it takes
+    /// for the spring to settle.
+    pub fn spring(mut self, spring: Spring) -> Self {
+        let simulation = Simulation::Spring(spring);
+        self.duration = simulation.settling_duration();
+        self.simulation = Some(simulation);
+        self
+    }
+
+    /// Animate with the decay instead of a curve. The value slows down as it gets closer to
+    /// the target. The duration is set to the time it takes for the decay to come to rest.
+    pub fn decay(mut self, decay: Decay) -> Self {
+        let simulation = Simulation::Decay(decay);
+        self.duration = simulation.settling_duration();
+        self.simulation = Some(simulation);
         self
     }
 
@@ -94,7 +124,7 @@ impl<T: StateContract> Animation<T> {
                     duration.as_secs_f64() / self.duration.as_secs_f64()
                 };
 
-                let percentage = (self.animation_curve)(un_curved_percentage);
+                let percentage = self.progress(un_curved_percentage);
 
                 let interpolated = (self.custom_interpolation)(&self.from, &self.to, percentage);
                 self.state.set_value(interpolated);
@@ -104,7 +134,7 @@ impl<T: StateContract> Animation<T> {
             RepeatMode::FromBeginning => {
                 let percentage = duration.as_secs_f64() / self.duration.as_secs_f64() % 1.0;
 
-                let percentage = (self.animation_curve)(percentage);
+                let percentage = self.progress(percentage);
 
                 let interpolated = (self.custom_interpolation)(&self.from, &self.to, percentage);
                 self.state.set_value(interpolated);
@@ -115,7 +145,7 @@ impl<T: StateContract> Animation<T> {
                 let temp = duration.as_secs_f64() / self.duration.as_secs_f64() % 2.0;
                 let percentage = if temp >= 1.0 { 2.0 - temp } else { temp };
 
-                let percentage = (self.animation_curve)(percentage);
+                let percentage = self.progress(percentage);
 
                 let interpolated = (self.custom_interpolation)(&self.from, &self.to, percentage);
                 self.state.set_value(interpolated);
@@ -124,6 +154,15 @@ impl<T: StateContract> Animation<T> {
             }
         }
     }
+
+    fn progress(&self, percentage: f64) -> f64 {
+        match &self.simulation {
+            // The simulation might not be exactly at its target when the duration ends.
+            Some(_) if percentage >= 1.0 => 1.0,
+            Some(simulation) => simulation.value(percentage * self.duration.as_secs_f64()),
+            None => (self.animation_curve)(percentage),
+        }
+    }
 }
 
 impl<T: StateContract> Debug for Animation<T> {
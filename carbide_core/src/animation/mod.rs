@@ -3,11 +3,17 @@ pub use animation::Animation;
 
 pub use crate::animation::animation_curve::*;
 pub use animation_manager::AnimationManager;
+pub use simulation::{Decay, Simulation, Spring};
+pub use transition::{Transition, TransitionEffect};
+pub use velocity_tracker::VelocityTracker;
 
 mod animatable;
 mod animation;
 pub mod animation_curve;
 mod animation_manager;
+mod simulation;
+mod transition;
+mod velocity_tracker;
 
 #[macro_export]
 macro_rules! animate {
@@ -0,0 +1,317 @@
+use crate::time::*;
+
+/// The distance from the target, as a fraction of the animated distance, below which a
+/// simulation is considered at rest.
+const REST_THRESHOLD: f64 = 0.001;
+
+/// The velocity, in animated distances per second, below which a spring is considered at rest.
+const VELOCITY_THRESHOLD: f64 = 0.01;
+
+/// The longest time a simulation is allowed to run before it is considered settled.
+const MAX_SETTLING_TIME: f64 = 60.0;
+
+/// The lowest friction of a decay. Without friction a decay would never come to rest.
+const MIN_FRICTION: f64 = 0.01;
+
+/// # Spring
+/// A damped spring moving a value from its start to its target. Unlike animation curves a spring
+/// does not have a fixed duration, but runs until it has settled at the target. Springs with a
+/// damping ratio below one overshoot the target before settling.
+///
+/// The spring is simulated on the progress from the start (0.0) to the target (1.0), so the
+/// initial velocity is given in animated distances per second. An initial velocity of 2.0 means
+/// the value starts moving at twice the distance to the target each second.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Spring {
+    stiffness: f64,
+    damping: f64,
+    mass: f64,
+    initial_velocity: f64,
+}
+
+impl Spring {
+    pub fn new(stiffness: f64, damping: f64) -> Spring {
+        Spring {
+            stiffness,
+            damping,
+            mass: 1.0,
+            initial_velocity: 0.0,
+        }
+    }
+
+    /// A spring that settles quickly without overshooting the target.
+    pub fn smooth() -> Spring {
+        Spring::new(170.0, 26.0)
+    }
+
+    /// A spring that overshoots the target a few times before settling.
+    pub fn bouncy() -> Spring {
+        Spring::new(180.0, 12.0)
+    }
+
+    pub fn mass(mut self, mass: f64) -> Spring {
+        self.mass = mass;
+        self
+    }
+
+    pub fn initial_velocity(mut self, velocity: f64) -> Spring {
+        self.initial_velocity = velocity;
+        self
+    }
+
+    /// The ratio between the damping of the spring and the damping needed to settle without
+    /// overshooting. Springs with a ratio below one oscillate.
+    pub fn damping_ratio(&self) -> f64 {
+        self.damping / (2.0 * (self.stiffness * self.mass).sqrt())
+    }
+
+    /// The progress at the time in seconds after the spring was started.
+    pub fn value(&self, time: f64) -> f64 {
+        1.0 + self.displacement(time).0
+    }
+
+    /// The velocity, in animated distances per second, at the time in seconds after the spring
+    /// was started.
+    pub fn velocity(&self, time: f64) -> f64 {
+        self.displacement(time).1
+    }
+
+    pub fn is_settled(&self, time: f64) -> bool {
+        let (displacement, velocity) = self.displacement(time);
+        displacement.abs() < REST_THRESHOLD && velocity.abs() < VELOCITY_THRESHOLD
+    }
+
+    /// Returns the displacement from the target and the velocity at the time. This is the
+    /// closed form solution of the damped harmonic oscillator starting at -1.0.
+    fn displacement(&self, time: f64) -> (f64, f64) {
+        if self.stiffness <= 0.0 || self.mass <= 0.0 {
+            return (0.0, 0.0);
+        }
+
+        let x0 = -1.0;
+        let v0 = self.initial_velocity;
+        let omega = (self.stiffness / self.mass).sqrt();
+        let zeta = self.damping_ratio();
+
+        if zeta < 1.0 {
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            let a = x0;
+            let b = (v0 + zeta * omega * x0) / omega_d;
+
+            let envelope = (-zeta * omega * time).exp();
+            let (sin, cos) = (omega_d * time).sin_cos();
+
+            let displacement = envelope * (a * cos + b * sin);
+            let velocity = envelope * ((b * omega_d - zeta * omega * a) * cos - (a * omega_d + zeta * omega * b) * sin);
+
+            (displacement, velocity)
+        } else if zeta == 1.0 {
+            let a = x0;
+            let b = v0 + omega * x0;
+
+            let envelope = (-omega * time).exp();
+
+            let displacement = envelope * (a + b * time);
+            let velocity = envelope * (b - omega * (a + b * time));
+
+            (displacement, velocity)
+        } else {
+            let root = (zeta * zeta - 1.0).sqrt();
+            let r1 = -omega * (zeta - root);
+            let r2 = -omega * (zeta + root);
+            let c2 = (v0 - r1 * x0) / (r2 - r1);
+            let c1 = x0 - c2;
+
+            let displacement = c1 * (r1 * time).exp() + c2 * (r2 * time).exp();
+            let velocity = r1 * c1 * (r1 * time).exp() + r2 * c2 * (r2 * time).exp();
+
+            (displacement, velocity)
+        }
+    }
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Spring::smooth()
+    }
+}
+
+/// # Decay
+/// A value moving with an initial velocity that is slowed down by friction until it comes to
+/// rest, like a list that keeps scrolling after being flung. The velocity is given in units of
+/// the animated value per second, and the friction is the rate at which the velocity decays.
+///
+/// Because a decay decides where it comes to rest, animations of a value use [Decay::target] as
+/// their target.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Decay {
+    velocity: f64,
+    friction: f64,
+}
+
+impl Decay {
+    pub fn new(velocity: f64) -> Decay {
+        Decay {
+            velocity,
+            friction: 4.0,
+        }
+    }
+
+    /// Set the friction. Higher values bring the value to rest sooner. Frictions below 0.01,
+    /// including zero and negative values, are clamped to 0.01.
+    pub fn friction(mut self, friction: f64) -> Decay {
+        self.friction = friction.max(MIN_FRICTION);
+        self
+    }
+
+    /// The distance travelled before the value comes to rest.
+    pub fn distance(&self) -> f64 {
+        self.velocity / self.friction
+    }
+
+    /// The value where a value starting at `from` comes to rest.
+    pub fn target(&self, from: f64) -> f64 {
+        from + self.distance()
+    }
+
+    /// The distance travelled at the time in seconds after the decay was started.
+    pub fn offset(&self, time: f64) -> f64 {
+        self.distance() * (1.0 - (-self.friction * time).exp())
+    }
+
+    /// The velocity at the time in seconds after the decay was started.
+    pub fn velocity(&self, time: f64) -> f64 {
+        self.velocity * (-self.friction * time).exp()
+    }
+
+    pub fn is_settled(&self, time: f64) -> bool {
+        self.velocity == 0.0 || (-self.friction * time).exp() < REST_THRESHOLD
+    }
+}
+
+/// A physics based alternative to animating with a curve over a fixed duration. The simulation
+/// is given the time since it was started, and returns the progress from the start (0.0) to the
+/// target (1.0) of the animation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Simulation {
+    Spring(Spring),
+    Decay(Decay),
+}
+
+impl Simulation {
+    /// The progress at the time in seconds after the simulation was started. Springs can go
+    /// beyond the target, and thus have a progress above 1.0.
+    pub fn value(&self, time: f64) -> f64 {
+        match self {
+            Simulation::Spring(spring) => spring.value(time),
+            Simulation::Decay(decay) => 1.0 - (-decay.friction * time).exp(),
+        }
+    }
+
+    /// The velocity, in animated distances per second, at the time in seconds after the
+    /// simulation was started.
+    pub fn velocity(&self, time: f64) -> f64 {
+        match self {
+            Simulation::Spring(spring) => spring.velocity(time),
+            Simulation::Decay(decay) => decay.friction * (-decay.friction * time).exp(),
+        }
+    }
+
+    /// Returns true if the simulation has come to rest at the time in seconds after it was
+    /// started, or has run for longer than a simulation is allowed to.
+    pub fn is_settled(&self, time: f64) -> bool {
+        if time >= MAX_SETTLING_TIME {
+            return true;
+        }
+
+        match self {
+            Simulation::Spring(spring) => spring.is_settled(time),
+            Simulation::Decay(decay) => decay.is_settled(time),
+        }
+    }
+
+    /// The time it takes for the simulation to settle.
+    pub fn settling_duration(&self) -> Duration {
+        let time = match self {
+            Simulation::Spring(spring) => {
+                let step = 1.0 / 240.0;
+                let mut time = 0.0;
+
+                while !spring.is_settled(time) && time < MAX_SETTLING_TIME {
+                    time += step;
+                }
+
+                time
+            }
+            Simulation::Decay(decay) if decay.velocity == 0.0 => 0.0,
+            Simulation::Decay(decay) => ((1.0 / REST_THRESHOLD).ln() / decay.friction).min(MAX_SETTLING_TIME),
+        };
+
+        Duration::from_secs_f64(time)
+    }
+
+    /// Returns the simulation started with the velocity, given in animated distances per second.
+    /// This is used to carry the velocity over when an animation gets a new target. Decays
+    /// always start with the velocity needed to come to rest at the target, so they are
+    /// returned unchanged.
+    pub fn with_initial_velocity(self, velocity: f64) -> Simulation {
+        match self {
+            Simulation::Spring(spring) => Simulation::Spring(spring.initial_velocity(velocity)),
+            Simulation::Decay(decay) => Simulation::Decay(decay),
+        }
+    }
+}
+
+impl From<Spring> for Simulation {
+    fn from(value: Spring) -> Self {
+        Simulation::Spring(value)
+    }
+}
+
+impl From<Decay> for Simulation {
+    fn from(value: Decay) -> Self {
+        Simulation::Decay(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::animation::{Decay, Simulation, Spring};
+
+    #[test]
+    fn springs_settle_at_the_target() {
+        for spring in [Spring::smooth(), Spring::bouncy(), Spring::new(100.0, 40.0), Spring::smooth().initial_velocity(-5.0)] {
+            let simulation = Simulation::from(spring);
+            let duration = simulation.settling_duration().as_secs_f64();
+
+            assert!(simulation.value(0.0).abs() < 1e-9);
+            assert!(duration > 0.0 && duration < 5.0);
+            assert!((simulation.value(duration) - 1.0).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn spring_starts_with_initial_velocity() {
+        assert!((Spring::smooth().initial_velocity(3.0).velocity(0.0) - 3.0).abs() < 1e-9);
+        assert!((Spring::bouncy().initial_velocity(3.0).velocity(0.0) - 3.0).abs() < 1e-9);
+        assert!((Spring::new(100.0, 40.0).initial_velocity(3.0).velocity(0.0) - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn decay_without_friction_comes_to_rest() {
+        let decay = Decay::new(100.0).friction(0.0);
+
+        assert!(decay.target(0.0).is_finite());
+        assert!(Simulation::from(decay).is_settled(60.0));
+    }
+
+    #[test]
+    fn decay_comes_to_rest_at_target() {
+        let decay = Decay::new(2000.0).friction(4.0);
+        let duration = Simulation::from(decay).settling_duration().as_secs_f64();
+
+        assert_eq!(decay.target(100.0), 600.0);
+        assert!((decay.offset(duration) - 500.0).abs() < 1.0);
+        assert!(decay.velocity(duration) < 2.1);
+    }
+}
@@ -0,0 +1,191 @@
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+use cgmath::{Matrix4, Vector3};
+
+use crate::animation::{ease_in_out, Animatable};
+use crate::draw::{Position, Rect};
+use crate::render::RenderContext;
+use crate::time::*;
+
+/// # Transition
+/// A transition describes how a widget appears when it is inserted and disappears when it is
+/// removed by a container like [IfElse](crate::widget::IfElse), [ForEach](crate::widget::ForEach)
+/// or [NavigationStack](crate::widget::NavigationStack). Transitions are made from one or more
+/// effects that are applied while rendering, so they do not change the layout of the content.
+///
+/// The transition is driven by the frame time of the [AnimationManager](crate::animation::AnimationManager).
+///
+/// ```ignore
+/// IfElse::new(show_details)
+///     .when_true(details)
+///     .transition(Transition::opacity().combined(Transition::offset(0.0, 20.0)))
+/// ```
+#[derive(Clone, Debug)]
+pub struct Transition {
+    insertion: Vec<TransitionEffect>,
+    removal: Vec<TransitionEffect>,
+    duration: Duration,
+    curve: fn(f64) -> f64,
+}
+
+/// A single effect of a [Transition]. Each effect is given how far the content is from being
+/// fully shown, where 0.0 is fully shown and 1.0 is fully removed.
+#[derive(Clone)]
+pub enum TransitionEffect {
+    /// Fade the content in and out.
+    Opacity,
+    /// Move the content from the given offset when inserted, and to it when removed.
+    Offset(Position),
+    /// Scale the content around its center from the given scale when inserted, and to it when removed.
+    Scale(f64),
+    /// Apply a custom effect. The function is given the amount, the bounding box of the content,
+    /// the render context and a function rendering the content.
+    Custom(Rc<dyn Fn(f64, Rect, &mut RenderContext, &mut dyn FnMut(&mut RenderContext))>),
+}
+
+impl Transition {
+    fn new(effect: TransitionEffect) -> Transition {
+        Transition {
+            insertion: vec![effect.clone()],
+            removal: vec![effect],
+            duration: Duration::from_millis(300),
+            curve: ease_in_out,
+        }
+    }
+
+    /// A transition without any effects. The content is inserted and removed without animating.
+    pub fn identity() -> Transition {
+        Transition {
+            insertion: vec![],
+            removal: vec![],
+            duration: Duration::ZERO,
+            curve: ease_in_out,
+        }
+    }
+
+    /// Fade the content in when inserted and out when removed.
+    pub fn opacity() -> Transition {
+        Transition::new(TransitionEffect::Opacity)
+    }
+
+    /// Move the content in from the offset when inserted and out to it when removed.
+    pub fn offset(x: f64, y: f64) -> Transition {
+        Transition::new(TransitionEffect::Offset(Position::new(x, y)))
+    }
+
+    /// Scale the content from the scale when inserted and to it when removed.
+    pub fn scale(scale: f64) -> Transition {
+        Transition::new(TransitionEffect::Scale(scale))
+    }
+
+    /// Create a transition from any [Animatable] value. The value is interpolated between
+    /// `identity`, when the content is fully shown, and `removed`, when the content is fully removed,
+    /// and passed to `apply` together with the bounding box of the content, the render context
+    /// and a function rendering the content.
+    ///
+    /// ```ignore
+    /// Transition::custom(0.0, 90.0, |degrees: &f64, bounding_box, ctx, content| {
+    ///     ctx.hue_rotation(*degrees as f32 / 360.0, content)
+    /// })
+    /// ```
+    pub fn custom<T, F>(identity: T, removed: T, apply: F) -> Transition
+        where
+            T: Animatable<T> + 'static,
+            F: Fn(&T, Rect, &mut RenderContext, &mut dyn FnMut(&mut RenderContext)) + 'static,
+    {
+        Transition::new(TransitionEffect::Custom(Rc::new(move |amount, bounding_box, ctx, content| {
+            let value = identity.interpolate(&removed, amount);
+            apply(&value, bounding_box, ctx, content)
+        })))
+    }
+
+    /// Use one transition when the content is inserted and another when it is removed. The duration
+    /// and curve of the insertion transition is used.
+    pub fn asymmetric(insertion: Transition, removal: Transition) -> Transition {
+        Transition {
+            insertion: insertion.insertion,
+            removal: removal.removal,
+            duration: insertion.duration,
+            curve: insertion.curve,
+        }
+    }
+
+    /// Apply the effects of both transitions. The duration and curve of this transition is kept.
+    pub fn combined(mut self, other: Transition) -> Transition {
+        self.insertion.extend(other.insertion);
+        self.removal.extend(other.removal);
+        self
+    }
+
+    pub fn duration(mut self, duration: Duration) -> Transition {
+        self.duration = duration;
+        self
+    }
+
+    pub fn curve(mut self, curve: fn(f64) -> f64) -> Transition {
+        self.curve = curve;
+        self
+    }
+
+    pub(crate) fn get_duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Render the content at the given linear progress of either the insertion or removal.
+    pub(crate) fn render(&self, progress: f64, removal: bool, bounding_box: Rect, ctx: &mut RenderContext, content: &mut dyn FnMut(&mut RenderContext)) {
+        let curved = (self.curve)(progress);
+
+        let (effects, amount) = if removal {
+            (&self.removal, curved)
+        } else {
+            (&self.insertion, 1.0 - curved)
+        };
+
+        render_effects(effects, amount, bounding_box, ctx, content);
+    }
+}
+
+fn render_effects(effects: &[TransitionEffect], amount: f64, bounding_box: Rect, ctx: &mut RenderContext, content: &mut dyn FnMut(&mut RenderContext)) {
+    let Some((effect, rest)) = effects.split_first() else {
+        content(ctx);
+        return;
+    };
+
+    let inner: &mut dyn FnMut(&mut RenderContext) = &mut |ctx| render_effects(rest, amount, bounding_box, ctx, content);
+
+    match effect {
+        TransitionEffect::Opacity => {
+            if amount < 1.0 {
+                ctx.opacity(1.0 - amount as f32, inner)
+            }
+        }
+        TransitionEffect::Offset(offset) => {
+            let offset = Position::new(0.0, 0.0).interpolate(offset, amount);
+            let matrix = Matrix4::from_translation(Vector3::new(offset.x as f32, offset.y as f32, 0.0));
+            ctx.transform(matrix, inner)
+        }
+        TransitionEffect::Scale(scale) => {
+            let scale = 1.0f64.interpolate(scale, amount);
+            let center = bounding_box.center();
+            let matrix = Matrix4::from_translation(Vector3::new(center.x as f32, center.y as f32, 0.0))
+                * Matrix4::from_scale(scale as f32)
+                * Matrix4::from_translation(Vector3::new(-center.x as f32, -center.y as f32, 0.0));
+            ctx.transform(matrix, inner)
+        }
+        TransitionEffect::Custom(apply) => {
+            apply(amount, bounding_box, ctx, inner)
+        }
+    }
+}
+
+impl Debug for TransitionEffect {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TransitionEffect::Opacity => f.write_str("Opacity"),
+            TransitionEffect::Offset(offset) => f.debug_tuple("Offset").field(offset).finish(),
+            TransitionEffect::Scale(scale) => f.debug_tuple("Scale").field(scale).finish(),
+            TransitionEffect::Custom(_) => f.write_str("Custom"),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::VecDeque;
+
+use crate::draw::Position;
+use crate::time::*;
+
+/// The samples within this window, before the latest, are used to estimate the velocity.
+const VELOCITY_WINDOW: Duration = Duration::from_millis(100);
+
+/// # VelocityTracker
+/// Estimates the velocity of the mouse from its recent positions, for example while dragging.
+/// When the drag is released, the velocity can start a [Decay](crate::animation::Decay) or be
+/// given as the initial velocity of a [Spring](crate::animation::Spring), such that the
+/// movement continues smoothly.
+#[derive(Clone, Debug, Default)]
+pub struct VelocityTracker {
+    samples: VecDeque<(Instant, Position)>,
+}
+
+impl VelocityTracker {
+    pub fn new() -> VelocityTracker {
+        VelocityTracker {
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Add a position at the given time. Samples are expected to be added in the order they happen.
+    pub fn add(&mut self, time: Instant, position: Position) {
+        self.samples.push_back((time, position));
+
+        while let Some((oldest, _)) = self.samples.front() {
+            if time.duration_since(*oldest) > VELOCITY_WINDOW {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.samples.clear();
+    }
+
+    /// The velocity in points per second at the given time. The velocity is zero if the latest
+    /// position is older than the window, meaning the mouse has been held still before release.
+    pub fn velocity(&self, now: Instant) -> Position {
+        let (Some((first_time, first)), Some((last_time, last))) = (self.samples.front(), self.samples.back()) else {
+            return Position::new(0.0, 0.0);
+        };
+
+        let elapsed = last_time.duration_since(*first_time).as_secs_f64();
+
+        if elapsed <= 0.0 || now.duration_since(*last_time) > VELOCITY_WINDOW {
+            return Position::new(0.0, 0.0);
+        }
+
+        (*last - *first) / elapsed
+    }
+}
@@ -1,5 +1,6 @@
 use std::any::Any;
-use std::fmt::Debug;
+use std::fmt::{Debug, Write};
+use std::hash::{DefaultHasher, Hasher};
 
 pub trait AnyDebug: Any + Debug {
     fn as_any(&self) -> &dyn Any;
@@ -36,4 +37,22 @@ impl dyn AnyDebug {
     pub fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
         self.as_any_mut().downcast_mut::<T>()
     }
-}
\ No newline at end of file
+}
+
+/// Returns a hash of the debug representation of the value. This is used to notice changes of
+/// values that can not be compared, like the values of the environment. Values with the same
+/// debug representation are considered unchanged.
+pub(crate) fn debug_fingerprint<T: Debug + ?Sized>(value: &T) -> u64 {
+    let mut writer = HashWriter(DefaultHasher::new());
+    let _ = write!(writer, "{:?}", value);
+    writer.0.finish()
+}
+
+struct HashWriter(DefaultHasher);
+
+impl Write for HashWriter {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
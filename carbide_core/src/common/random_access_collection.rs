@@ -1,7 +1,7 @@
 use carbide::identifiable::Identifiable;
 use carbide::state::{IndexState, LocalState, ReadState, State, StateContract};
 use std::ops::{Deref, Index, IndexMut, Range, RangeFrom, RangeInclusive};
-use crate::state::{AnyReadState, AnyState, FieldState, Functor, Map1, Map2, ReadStateExtNew, ValueState};
+use crate::state::{AnyReadState, AnyState, FieldState, Functor, Map1, Map2, ReadStateExtNew, StateExtNew, ValueState};
 
 /// A collection that can be accessed by an index, provides a start index, end index, and a way of
 /// getting the next index.
@@ -31,6 +31,12 @@ pub trait RandomAccessCollection<T>: StateContract + 'static where T: StateContr
     /// Provided an index, get the next index.
     fn next_index(&self, idx: Self::Idx) -> Self::Idx;
     fn prev_index(&self, idx: Self::Idx) -> Self::Idx;
+
+    /// Get a copy of the collection that is not affected by later changes to the collection.
+    /// Returns None if the collection can not be copied.
+    fn snapshot(&self) -> Option<Self> {
+        None
+    }
 }
 
 impl<T: StateContract> RandomAccessCollection<T> for Vec<T> {
@@ -84,6 +90,10 @@ impl<T: StateContract> RandomAccessCollection<T> for Vec<T> {
     fn prev_index(&self, idx: Self::Idx) -> Self::Idx {
         idx.saturating_sub(1)
     }
+
+    fn snapshot(&self) -> Option<Self> {
+        Some(self.clone())
+    }
 }
 
 impl RandomAccessCollection<u32> for Range<u32> {
@@ -135,6 +145,10 @@ impl RandomAccessCollection<u32> for Range<u32> {
     fn prev_index(&self, idx: Self::Idx) -> Self::Idx {
         idx.saturating_sub(1)
     }
+
+    fn snapshot(&self) -> Option<Self> {
+        Some(self.clone())
+    }
 }
 
 impl RandomAccessCollection<u32> for RangeInclusive<u32> {
@@ -186,6 +200,10 @@ impl RandomAccessCollection<u32> for RangeInclusive<u32> {
     fn prev_index(&self, idx: Self::Idx) -> Self::Idx {
         idx.saturating_sub(1)
     }
+
+    fn snapshot(&self) -> Option<Self> {
+        Some(self.clone())
+    }
 }
 
 impl<T: StateContract + 'static, A: RandomAccessCollection<T>> RandomAccessCollection<T> for LocalState<A>
@@ -234,6 +252,10 @@ where
     fn prev_index(&self, idx: Self::Idx) -> Self::Idx {
         self.value().prev_index(idx)
     }
+
+    fn snapshot(&self) -> Option<Self> {
+        Some(LocalState::new(self.value().clone()))
+    }
 }
 
 impl<S: State<T=FROM>, FROM: StateContract, T: StateContract + 'static, A: RandomAccessCollection<T>> RandomAccessCollection<T> for FieldState<S, FROM, A>
@@ -331,6 +353,10 @@ where
     fn prev_index(&self, idx: Self::Idx) -> Self::Idx {
         self.value().prev_index(idx)
     }
+
+    fn snapshot(&self) -> Option<Self> {
+        Some(LocalState::new(self.value().clone()).as_dyn())
+    }
 }
 
 impl<T: StateContract + 'static, A: RandomAccessCollection<T>> RandomAccessCollection<T> for Box<dyn AnyReadState<T=A>>
@@ -379,4 +405,8 @@ where
     fn prev_index(&self, idx: Self::Idx) -> Self::Idx {
         self.value().prev_index(idx)
     }
+
+    fn snapshot(&self) -> Option<Self> {
+        Some(LocalState::new(self.value().clone()).as_dyn_read())
+    }
 }
\ No newline at end of file
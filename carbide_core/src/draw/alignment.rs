@@ -1,4 +1,5 @@
 use crate::draw::{Dimension, Position};
+use crate::widget::LayoutDirection;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Alignment {
@@ -32,6 +33,25 @@ impl Alignment {
         }
     }
 
+    /// Returns the alignment with leading and trailing swapped when the layout direction is
+    /// right to left, such that leading is always the side the content starts from.
+    pub fn resolve(&self, direction: LayoutDirection) -> Alignment {
+        if !direction.is_right_to_left() {
+            return *self;
+        }
+
+        match self {
+            Alignment::TopLeading => Alignment::TopTrailing,
+            Alignment::TopTrailing => Alignment::TopLeading,
+            Alignment::Leading => Alignment::Trailing,
+            Alignment::Trailing => Alignment::Leading,
+            Alignment::BottomLeading => Alignment::BottomTrailing,
+            Alignment::BottomTrailing => Alignment::BottomLeading,
+            Alignment::Custom(x, y) => Alignment::Custom(1.0 - *x, *y),
+            alignment => *alignment,
+        }
+    }
+
     fn top_leading(position: Position, _: Dimension, _: Dimension) -> Position {
         position
     }
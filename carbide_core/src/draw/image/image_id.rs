@@ -79,7 +79,7 @@ pub trait IntoImageId {
     fn into(self) -> ImageId;
 }
 
-impl IntoImageId for &'static str {
+impl IntoImageId for &str {
     fn into(self) -> ImageId {
         match Url::parse(self) {
             Ok(url) if url.scheme() == "file" => {
@@ -112,6 +112,12 @@ impl IntoImageId for &'static str {
     }
 }
 
+impl IntoImageId for String {
+    fn into(self) -> ImageId {
+        IntoImageId::into(self.as_str())
+    }
+}
+
 impl IntoImageId for PathBuf {
     fn into(self) -> ImageId {
         let format = match self.extension() {
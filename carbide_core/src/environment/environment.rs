@@ -1,10 +1,21 @@
 use std::any::{Any, TypeId};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::hash::{BuildHasherDefault, Hasher};
 use std::marker::PhantomData;
 use std::mem::transmute;
-use crate::common::any_debug::AnyDebug;
+use smallvec::SmallVec;
+use crate::common::any_debug::{debug_fingerprint, AnyDebug};
+
+/// The keys read from the environment while recording, together with a fingerprint of the value
+/// read, or None if the key was not in the environment.
+pub(crate) type EnvironmentReads = SmallVec<[(TypeId, Option<u64>); 4]>;
+
+thread_local! {
+    /// The reads of the recordings in progress, with the innermost recording last.
+    static READS: RefCell<Vec<EnvironmentReads>> = const { RefCell::new(Vec::new()) };
+}
 
 /// # EnvironmentKey
 /// A key into the environment that can be used to retrieve a value.
@@ -115,6 +126,12 @@ impl<'a> Environment<'a> {
     pub fn get<K: EnvironmentKey + ?Sized>(&self) -> Option<&K::Value> {
         let id = TypeId::of::<K>();
 
+        READS.with_borrow_mut(|reads| {
+            if let Some(current) = reads.last_mut() {
+                current.push((id, self.fingerprint(id)));
+            }
+        });
+
         Option::map(self.data.get(&id), |value| {
             match value {
                 EnvironmentValue::Value(value1) => value1.downcast_ref(),
@@ -148,6 +165,73 @@ impl<'a> Environment<'a> {
     }
 }
 
+impl<'a> Environment<'a> {
+    /// Run the function, and return the keys read from the environment by it using
+    /// [get](Self::get), together with fingerprints of the values read. Reads of keys that are
+    /// shadowed within the function are left out, as they do not depend on the environment the
+    /// function is run in. The reads are also recorded by any recording this is nested in.
+    ///
+    /// Values retrieved mutably, like managers, are not recorded.
+    pub(crate) fn record_reads<R>(f: impl FnOnce() -> R) -> (R, EnvironmentReads) {
+        READS.with_borrow_mut(|reads| reads.push(EnvironmentReads::new()));
+
+        let result = f();
+
+        let mut recorded = READS.with_borrow_mut(|reads| reads.pop()).unwrap_or_default();
+        recorded.sort_unstable_by_key(|(key, _)| *key);
+        recorded.dedup_by_key(|(key, _)| *key);
+
+        Environment::add_reads(&recorded);
+
+        (result, recorded)
+    }
+
+    /// Add reads to the innermost recording in progress, for example the reads recorded
+    /// earlier for a result that is reused instead of being calculated again.
+    pub(crate) fn add_reads(recorded: &EnvironmentReads) {
+        READS.with_borrow_mut(|reads| {
+            if let Some(current) = reads.last_mut() {
+                current.extend_from_slice(recorded);
+            }
+        });
+    }
+
+    /// Returns the fingerprint of the value of the key, if the key is in the environment.
+    /// Compare with the reads returned by [record_reads](Self::record_reads) to check if the
+    /// environment has changed since.
+    pub(crate) fn fingerprint(&self, id: TypeId) -> Option<u64> {
+        self.data.get(&id).map(|value| {
+            match value {
+                EnvironmentValue::Value(value1) => debug_fingerprint(*value1),
+                EnvironmentValue::ValueMut(value2) => debug_fingerprint(&**value2),
+            }
+        })
+    }
+
+    /// Returns the number of reads recorded so far by the innermost recording in progress.
+    fn reads_mark() -> Option<usize> {
+        READS.with_borrow(|reads| reads.last().map(|current| current.len()))
+    }
+
+    /// Forget the reads of the key recorded after the mark, because the key was shadowed.
+    fn forget_reads(mark: Option<usize>, id: TypeId) {
+        let Some(mark) = mark else {
+            return;
+        };
+
+        READS.with_borrow_mut(|reads| {
+            if let Some(current) = reads.last_mut() {
+                let mut position = 0;
+                current.retain(|(key, _)| {
+                    let keep = position < mark || *key != id;
+                    position += 1;
+                    keep
+                });
+            }
+        });
+    }
+}
+
 impl<'a> Environment<'a> {
     #[allow(unsafe_code)]
     pub fn with<'b, K: EnvironmentKey + ?Sized>(&mut self, v: &'b K::Value, f: impl FnOnce(&mut Environment)) where 'a: 'b {
@@ -166,8 +250,12 @@ impl<'a> Environment<'a> {
 
         transmuted.data.insert(id, EnvironmentValue::Value(v));
 
+        let mark = Environment::reads_mark();
+
         f(transmuted);
 
+        Environment::forget_reads(mark, id);
+
         if let Some(old) = old {
             transmuted.data.insert(id, old);
         } else {
@@ -192,8 +280,12 @@ impl<'a> Environment<'a> {
 
         transmuted.data.insert(id, EnvironmentValue::ValueMut(v));
 
+        let mark = Environment::reads_mark();
+
         f(transmuted);
 
+        Environment::forget_reads(mark, id);
+
         if let Some(old) = old {
             transmuted.data.insert(id, old);
         } else {
@@ -255,6 +347,7 @@ impl Hasher for TypeIdHasher {
 
 #[cfg(test)]
 mod tests {
+    use std::any::TypeId;
     use crate::environment::environment::{EnvironmentKey, Environment};
 
     #[derive(Copy, Clone, Debug)]
@@ -262,6 +355,11 @@ mod tests {
 
     impl EnvironmentKey for TestKey { type Value = u64; }
 
+    #[derive(Copy, Clone, Debug)]
+    struct ShadowedKey;
+
+    impl EnvironmentKey for ShadowedKey { type Value = u64; }
+
     #[test]
     fn simple() {
         let mut map = Environment::new();
@@ -274,4 +372,27 @@ mod tests {
         let get = map.get::<TestKey>();
         println!("{:?}", get);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn reads_are_recorded_unless_shadowed() {
+        let mut map = Environment::new();
+
+        map.with::<TestKey>(&1, |env| {
+            let (_, reads) = Environment::record_reads(|| {
+                env.get::<TestKey>();
+
+                env.with::<ShadowedKey>(&2, |inner| {
+                    inner.get::<ShadowedKey>();
+                });
+            });
+
+            let key = TypeId::of::<TestKey>();
+
+            assert_eq!(reads.as_slice(), &[(key, env.fingerprint(key))]);
+
+            env.with::<TestKey>(&3, |inner| {
+                assert_ne!(inner.fingerprint(key), reads[0].1);
+            });
+        });
+    }
+}
@@ -1,4 +1,5 @@
 pub use environment::{Environment, EnvironmentKey, EnvironmentKeyable, EnvironmentKeyDefault};
+pub(crate) use environment::EnvironmentReads;
 pub use environment_color::*;
 pub use environment_font_size::*;
 
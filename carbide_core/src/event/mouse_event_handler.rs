@@ -6,6 +6,12 @@ use crate::focus::Focusable;
 use crate::text::TextContext;
 use crate::widget::{CommonWidget, WidgetSync};
 
+/// The maximum time between two clicks, for the second to count as a double or n-click.
+pub const N_CLICK_THRESHOLD: Duration = Duration::from_millis(500);
+/// The maximum distance the mouse can move between press and release for a click to be emitted,
+/// and between two clicks for the second to count as a double or n-click.
+pub const MOUSE_CLICK_MAX_DISTANCE: f64 = 3.0;
+
 pub trait MouseEventHandler: CommonWidget + WidgetSync + Focusable {
     /// A function that will be called when a mouse event occurs.
     /// It will only get called on the events where the cursor is inside.
@@ -143,6 +149,30 @@ pub enum MouseEvent {
 }
 
 impl MouseEvent {
+    /// Returns the number of the click with the button at the position, given the latest click
+    /// and the time it was emitted. A click will become a double click, if and only if it is
+    /// within the threshold time and distance of the latest click of the same button.
+    pub fn click_number(last_click: Option<&(Instant, MouseEvent)>, button: MouseButton, position: Position) -> u32 {
+        match last_click {
+            // Too long since last click, so we emit a click and not a double click
+            Some((time, _)) if Instant::now().duration_since(*time) > N_CLICK_THRESHOLD => 1,
+
+            // Our previous click was a normal click of the same button within the
+            // same location as the previous click. The time is checked in a previous case.
+            Some((_, MouseEvent::Click(b, location, _))) if button == *b && position.dist(location) < MOUSE_CLICK_MAX_DISTANCE => 2,
+
+            // Our previous click was a double click within time and with the same button
+            // and within range of the previous click.
+            Some((_, MouseEvent::NClick(b, location, _, n))) if button == *b && position.dist(location) < MOUSE_CLICK_MAX_DISTANCE => n + 1,
+
+            // Either the previous click was not in range or not with the same button
+            Some((_, _)) => 1,
+
+            // No previous click, so we emit a click and not a double click
+            None => 1,
+        }
+    }
+
     pub fn get_current_mouse_position(&self) -> Position {
         match self {
             MouseEvent::Press { position: n, .. } => *n,
@@ -0,0 +1,36 @@
+use crate::draw::{Rect, Scalar};
+use crate::environment::{Environment, EnvironmentKey};
+use crate::layout::{Layout, LayoutContext};
+
+/// The absolute y coordinate of the first baseline found while positioning a widget, used by
+/// [position_children_with_baseline].
+#[derive(Debug)]
+pub(crate) struct FirstBaseline;
+
+impl EnvironmentKey for FirstBaseline {
+    type Value = Option<Scalar>;
+}
+
+/// Report the absolute y coordinate of a baseline. This is called by widgets showing text when
+/// they are positioned, and only the first baseline reported is kept.
+pub(crate) fn report_baseline(env: &mut Environment, y: impl FnOnce() -> Option<Scalar>) {
+    if let Some(first @ None) = env.get_mut::<FirstBaseline>() {
+        *first = y();
+    }
+}
+
+/// Position the children of the widget, and return the distance from the top of the widget to
+/// the first baseline of the text within it, if it contains any text.
+pub(crate) fn position_children_with_baseline<W: Layout + ?Sized>(widget: &mut W, bounding_box: Rect, ctx: &mut LayoutContext) -> Option<Scalar> {
+    let mut first = None;
+
+    ctx.env.with_mut::<FirstBaseline>(&mut first, |env| {
+        widget.position_children(bounding_box, &mut LayoutContext {
+            text: ctx.text,
+            image: ctx.image,
+            env,
+        })
+    });
+
+    first.map(|y| y - widget.y())
+}
@@ -1,8 +1,9 @@
 use carbide::draw::Rect;
 use crate::draw::{Dimension, ImageContext};
 use crate::environment::{Environment};
+use crate::layout::LayoutCache;
 use crate::text::TextContext;
-use crate::widget::CommonWidget;
+use crate::widget::{CommonWidget, LayoutDirection};
 
 pub trait Layout: CommonWidget {
     /// This method is used to calculate and set the size of a widget. The parent widget provides
@@ -14,7 +15,7 @@ pub trait Layout: CommonWidget {
     /// chosen size. If no child are present, the widget will choose the requested size.
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
         if self.child_count() != 0 {
-            let chosen = self.child(0).calculate_size(requested_size, ctx);
+            let chosen = self.child(0).process_calculate_size(requested_size, ctx);
 
             self.set_dimension(chosen);
             chosen
@@ -24,12 +25,21 @@ pub trait Layout: CommonWidget {
         }
     }
 
+    /// This method is used by parents to get the size of the widget. It calculates the size
+    /// using [calculate_size](Self::calculate_size), unless the widget was proposed the same size
+    /// the last time it was measured, and neither it nor its descendants have changed since. In
+    /// that case the size chosen last time is used, and the widget is not measured again. See
+    /// [LayoutCache] for details.
+    fn process_calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        LayoutCache::calculate_size(self, requested_size, ctx)
+    }
+
     /// This method positions the children of the widget. When positioning, we use the alignment of
     /// the widget to position. The default alignment is Center.
     /// The default behavior is to position the first child using the alignment of the widget. If
     /// no child are present the default is a no-op.
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
-        let positioning = self.alignment();
+        let positioning = self.alignment().resolve(LayoutDirection::get(ctx.env));
         let position = self.position();
         let dimension = self.dimension();
 
@@ -0,0 +1,267 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hasher};
+
+use crate::application::ApplicationManager;
+use crate::draw::{Dimension, Scalar};
+use crate::environment::{Environment, EnvironmentReads};
+use crate::layout::{Layout, LayoutContext};
+use crate::scene::SceneManager;
+use crate::widget::WidgetId;
+
+/// The number of frames an entry is kept after its widget was last updated.
+const ENTRY_LIFETIME: u32 = 300;
+
+thread_local! {
+    /// The number of changes reported while updating widgets.
+    static CHANGES: Cell<u64> = const { Cell::new(0) };
+
+    static ENTRIES: RefCell<HashMap<WidgetId, Entry>> = RefCell::new(HashMap::new());
+
+    /// The frame the entries were last cleaned up.
+    static SWEPT: Cell<u32> = const { Cell::new(0) };
+
+    /// Whether the results of the measurements in progress can be cached, with the innermost
+    /// measurement last.
+    static MEASURING: RefCell<Vec<bool>> = const { RefCell::new(Vec::new()) };
+}
+
+/// # Layout cache
+/// Remembers the size each widget chose for the size last proposed to it, such that measuring an
+/// unchanged widget again with the same proposal can be skipped, together with its descendants.
+/// Parents measure their children through [Layout::process_calculate_size], which uses the cache.
+///
+/// The cached size of a widget is forgotten when the widget or one of its descendants reports a
+/// changed state while being updated, when the children of any of them change, or when the
+/// values read from the environment during the measurement change. States are reported as
+/// changed by the derived [WidgetSync](crate::widget::WidgetSync). Widgets implementing it
+/// manually can call [LayoutCache::state_changed] when a state affecting their size changes.
+///
+/// Only widgets that are updated through [LayoutCache::track] in the same frame are cached,
+/// which the default [Update::process_update](crate::lifecycle::Update::process_update) does.
+/// A widget measuring any widget that is not tracked is not cached either.
+#[derive(Debug, Copy, Clone)]
+pub struct LayoutCache;
+
+#[derive(Debug, Clone)]
+struct Entry {
+    /// The frame the widget was last updated.
+    updated: u32,
+    /// A hash of the ids of the children of the widget when it was last updated.
+    children: u64,
+    measurement: Option<Measurement>,
+}
+
+#[derive(Debug, Clone)]
+struct Measurement {
+    requested: Dimension,
+    chosen: Dimension,
+    scale_factor: Scalar,
+    environment: EnvironmentReads,
+}
+
+impl LayoutCache {
+    /// Record that a state synced by a widget has changed. This invalidates the cached sizes
+    /// of the widget and its ancestors.
+    pub fn state_changed() {
+        CHANGES.with(|changes| changes.set(changes.get() + 1));
+    }
+
+    /// Forget the cached size of the widget, and report a change such that the cached sizes of
+    /// its ancestors are forgotten too. This is used by widgets that share the id of their child,
+    /// when a value they provide to the child changes.
+    pub fn invalidate(id: WidgetId) {
+        ENTRIES.with_borrow_mut(|entries| {
+            if let Some(entry) = entries.get_mut(&id) {
+                entry.measurement = None;
+            }
+        });
+
+        LayoutCache::state_changed();
+    }
+
+    /// Update a widget through the function, which is given a hasher to write the ids of the
+    /// children of the widget to. The cached size of the widget is forgotten if a change is
+    /// reported while updating it or its descendants, or if its children are different from
+    /// the last update.
+    pub fn track(id: WidgetId, f: impl FnOnce(&mut DefaultHasher)) {
+        let before = CHANGES.with(|changes| changes.get());
+
+        let mut hasher = DefaultHasher::new();
+        f(&mut hasher);
+        let children = hasher.finish();
+
+        let frame = ApplicationManager::application_frame();
+
+        ENTRIES.with_borrow_mut(|entries| {
+            if frame.wrapping_sub(SWEPT.get()) > ENTRY_LIFETIME {
+                entries.retain(|_, entry| frame.wrapping_sub(entry.updated) <= ENTRY_LIFETIME);
+                SWEPT.set(frame);
+            }
+
+            let entry = entries.entry(id).or_insert(Entry {
+                updated: frame,
+                children,
+                measurement: None,
+            });
+
+            if entry.children != children {
+                entry.children = children;
+                LayoutCache::state_changed();
+            }
+
+            if CHANGES.with(|changes| changes.get()) != before {
+                entry.measurement = None;
+            }
+
+            entry.updated = frame;
+        });
+    }
+
+    /// Calculate the size of the widget, or reuse the size it chose the last time it was
+    /// measured, if it was proposed the same size and nothing it depends on has changed.
+    pub(crate) fn calculate_size<W: Layout + ?Sized>(widget: &mut W, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        let id = widget.id();
+        let frame = ApplicationManager::application_frame();
+
+        let scale_factor = ctx.env.get_mut::<SceneManager>()
+            .map(|a| a.scale_factor())
+            .unwrap_or(1.0);
+
+        let (tracked, cached) = ENTRIES.with_borrow(|entries| {
+            let Some(entry) = entries.get(&id).filter(|entry| entry.updated == frame) else {
+                return (false, None);
+            };
+
+            let cached = entry.measurement.as_ref().filter(|measurement| {
+                measurement.requested == requested_size
+                    && measurement.scale_factor == scale_factor
+                    && measurement.environment.iter().all(|(key, value)| ctx.env.fingerprint(*key) == *value)
+            }).map(|measurement| {
+                // The enclosing measurements depend on the environment read by this one
+                Environment::add_reads(&measurement.environment);
+                measurement.chosen
+            });
+
+            (true, cached)
+        });
+
+        if let Some(chosen) = cached {
+            widget.set_dimension(chosen);
+            return chosen;
+        }
+
+        MEASURING.with_borrow_mut(|measuring| measuring.push(tracked));
+
+        let (chosen, environment) = Environment::record_reads(|| {
+            widget.calculate_size(requested_size, ctx)
+        });
+
+        let cacheable = MEASURING.with_borrow_mut(|measuring| {
+            let cacheable = measuring.pop().unwrap_or(false);
+
+            // A widget measuring a widget that can not be cached can not be cached either
+            if let Some(parent) = measuring.last_mut() {
+                *parent &= cacheable;
+            }
+
+            cacheable
+        });
+
+        if cacheable {
+            ENTRIES.with_borrow_mut(|entries| {
+                if let Some(entry) = entries.get_mut(&id) {
+                    entry.measurement = Some(Measurement {
+                        requested: requested_size,
+                        chosen,
+                        scale_factor,
+                        environment,
+                    });
+                }
+            });
+        }
+
+        chosen
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    use crate::draw::{Dimension, Rect};
+    use crate::identifiable::Identifiable;
+    use crate::state::{LocalState, State};
+    use crate::testing::TestHarness;
+    use crate::widget::{CommonWidget, CustomLayout, LayoutAlgorithm, LayoutDirection, Rectangle, Subviews, WidgetExt};
+
+    /// Sizes to the first child, and counts the number of times it is measured.
+    #[derive(Debug, Clone)]
+    struct Counting(Rc<Cell<u32>>);
+
+    impl LayoutAlgorithm for Counting {
+        fn size_that_fits(&mut self, proposal: Dimension, subviews: &mut Subviews) -> Dimension {
+            self.0.set(self.0.get() + 1);
+            subviews.size_that_fits(0, proposal)
+        }
+
+        fn place_subviews(&mut self, bounds: Rect, subviews: &mut Subviews) {
+            let dimension = subviews.dimension(0);
+            subviews.place(0, bounds.position, dimension);
+        }
+    }
+
+    #[test]
+    fn unchanged_widgets_are_not_measured_again() {
+        let measured = Rc::new(Cell::new(0));
+        let mut width = LocalState::new(40.0);
+
+        let rectangle = Rectangle::new().frame(width.clone(), 20.0);
+        let id = rectangle.id();
+
+        let mut harness = TestHarness::new(
+            CustomLayout::new(Counting(measured.clone()), vec![rectangle]),
+            Dimension::new(200.0, 200.0),
+        );
+
+        harness.update();
+        harness.update();
+
+        let count = measured.get();
+
+        harness.update();
+        assert_eq!(measured.get(), count);
+
+        width.set_value(60.0);
+        harness.update();
+
+        assert!(measured.get() > count);
+        assert_eq!(harness.bounding_box(id).unwrap().dimension, Dimension::new(60.0, 20.0));
+    }
+
+    #[test]
+    fn changed_environment_values_are_measured_again() {
+        let measured = Rc::new(Cell::new(0));
+        let mut direction = LocalState::new(LayoutDirection::LeftToRight);
+
+        let mut harness = TestHarness::new(
+            CustomLayout::new(Counting(measured.clone()), vec![Rectangle::new().frame(40.0, 20.0)])
+                .layout_direction(direction.clone()),
+            Dimension::new(200.0, 200.0),
+        );
+
+        harness.update();
+        harness.update();
+
+        let count = measured.get();
+
+        harness.update();
+        assert_eq!(measured.get(), count);
+
+        direction.set_value(LayoutDirection::RightToLeft);
+        harness.update();
+
+        assert!(measured.get() > count);
+    }
+}
@@ -1,9 +1,17 @@
+pub(crate) use baseline::{position_children_with_baseline, report_baseline};
 pub(crate) use stack_layouts::calculate_size_hstack;
+pub(crate) use stack_layouts::calculate_size_hwrap;
 pub(crate) use stack_layouts::calculate_size_vstack;
+pub(crate) use stack_layouts::calculate_size_vwrap;
 pub(crate) use stack_layouts::position_children_hstack;
+pub(crate) use stack_layouts::position_children_hwrap;
 pub(crate) use stack_layouts::position_children_vstack;
+pub(crate) use stack_layouts::position_children_vwrap;
 
 pub use self::layout::*;
+pub use self::layout_cache::*;
 
+mod baseline;
 mod layout;
+mod layout_cache;
 mod stack_layouts;
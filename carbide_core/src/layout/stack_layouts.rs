@@ -2,7 +2,7 @@ use smallvec::{SmallVec, smallvec};
 use crate::draw::{Dimension, Position, Rect};
 use crate::common::flags::WidgetFlag;
 use crate::layout::{Layout, LayoutContext};
-use crate::widget::{CrossAxisAlignment, AnyWidget};
+use crate::widget::{CrossAxisAlignment, AnyWidget, LayoutDirection, LineJustification};
 
 pub(crate) fn calculate_size_vstack(
     widget: &mut dyn Layout,
@@ -37,6 +37,7 @@ pub(crate) fn position_children_vstack(
         y_x,
         cross_axis_alignment,
         spacing,
+        false,
         bounding_box,
         ctx,
     );
@@ -66,6 +67,9 @@ pub(crate) fn position_children_hstack(
     bounding_box: Rect,
     ctx: &mut LayoutContext
 ) {
+    // In right to left layouts the first child is placed at the right edge.
+    let reversed = LayoutDirection::get(ctx.env).is_right_to_left();
+
     position_children_stack(
         widget,
         x,
@@ -75,6 +79,104 @@ pub(crate) fn position_children_hstack(
         x_y,
         cross_axis_alignment,
         spacing,
+        reversed,
+        bounding_box,
+        ctx,
+    );
+}
+
+pub(crate) fn calculate_size_hwrap(
+    widget: &mut dyn Layout,
+    spacing: f64,
+    line_spacing: f64,
+    justification: LineJustification,
+    requested_size: Dimension,
+    ctx: &mut LayoutContext,
+) {
+    calculate_size_wrap(
+        widget,
+        width,
+        height,
+        width_height,
+        spacing,
+        line_spacing,
+        justification,
+        requested_size,
+        ctx,
+    );
+}
+
+pub(crate) fn position_children_hwrap(
+    widget: &mut dyn Layout,
+    spacing: f64,
+    line_spacing: f64,
+    cross_axis_alignment: CrossAxisAlignment,
+    justification: LineJustification,
+    bounding_box: Rect,
+    ctx: &mut LayoutContext
+) {
+    // In right to left layouts each line starts at the right edge.
+    let reversed = LayoutDirection::get(ctx.env).is_right_to_left();
+
+    position_children_wrap(
+        widget,
+        x,
+        width,
+        y,
+        height,
+        x_y,
+        spacing,
+        line_spacing,
+        cross_axis_alignment,
+        justification,
+        reversed,
+        bounding_box,
+        ctx,
+    );
+}
+
+pub(crate) fn calculate_size_vwrap(
+    widget: &mut dyn Layout,
+    spacing: f64,
+    line_spacing: f64,
+    justification: LineJustification,
+    requested_size: Dimension,
+    ctx: &mut LayoutContext,
+) {
+    calculate_size_wrap(
+        widget,
+        height,
+        width,
+        height_width,
+        spacing,
+        line_spacing,
+        justification,
+        requested_size,
+        ctx,
+    );
+}
+
+pub(crate) fn position_children_vwrap(
+    widget: &mut dyn Layout,
+    spacing: f64,
+    line_spacing: f64,
+    cross_axis_alignment: CrossAxisAlignment,
+    justification: LineJustification,
+    bounding_box: Rect,
+    ctx: &mut LayoutContext
+) {
+    position_children_wrap(
+        widget,
+        y,
+        height,
+        x,
+        width,
+        y_x,
+        spacing,
+        line_spacing,
+        cross_axis_alignment,
+        justification,
+        false,
         bounding_box,
         ctx,
     );
@@ -174,7 +276,7 @@ fn calculate_size_stack(
             cross_axis(size_for_children),
         );
 
-        let chosen_size = child.calculate_size(size_for_child, ctx);
+        let chosen_size = child.process_calculate_size(size_for_child, ctx);
 
         if cross_axis(chosen_size) > max_cross_axis {
             max_cross_axis = cross_axis(chosen_size);
@@ -198,7 +300,7 @@ fn calculate_size_stack(
             max_cross_axis,
         );
 
-        let chosen_size = child.calculate_size(size_for_child, ctx);
+        let chosen_size = child.process_calculate_size(size_for_child, ctx);
 
         size_for_children = dimension(
             (main_axis(size_for_children) - main_axis(chosen_size)).max(0.0),
@@ -224,7 +326,7 @@ fn calculate_size_stack(
 
     widget.foreach_child(&mut |child| {
         if child.is_spacer() {
-            let chosen_size = child.calculate_size(request_dimension, ctx);
+            let chosen_size = child.process_calculate_size(request_dimension, ctx);
             total_main_axis += main_axis(chosen_size);
         }
     });
@@ -241,6 +343,7 @@ fn position_children_stack(
     position_from_main_and_cross: fn(f64, f64) -> Position,
     cross_axis_alignment: CrossAxisAlignment,
     spacing: f64,
+    reversed: bool,
     bounding_box: Rect,
     ctx: &mut LayoutContext,
 ) {
@@ -263,10 +366,15 @@ fn position_children_stack(
             }
         };
 
-        child.set_position(position_from_main_and_cross(
-            main_axis_position(position) + main_axis_offset,
-            cross,
-        ));
+        let main = if reversed {
+            main_axis_position(position) + main_axis_dimension(dimension)
+                - main_axis_offset
+                - main_axis_dimension(child.dimension())
+        } else {
+            main_axis_position(position) + main_axis_offset
+        };
+
+        child.set_position(position_from_main_and_cross(main, cross));
 
         if !child.is_spacer() {
             main_axis_offset += spacing;
@@ -276,3 +384,192 @@ fn position_children_stack(
         child.position_children(bounding_box, ctx);
     });
 }
+
+/// A line of children in a wrapping layout. The children from `start` up to `end` are in the line.
+#[derive(Debug, Clone, PartialEq)]
+struct Line {
+    start: usize,
+    end: usize,
+    main_axis: f64,
+    cross_axis: f64,
+}
+
+/// Break the children into lines, such that each line is at most `available` long on the main
+/// axis. A child longer than `available` gets a line of its own.
+fn wrap_lines(
+    sizes: &[Dimension],
+    main_axis: fn(Dimension) -> f64,
+    cross_axis: fn(Dimension) -> f64,
+    spacing: f64,
+    available: f64,
+) -> SmallVec<[Line; 4]> {
+    let mut lines: SmallVec<[Line; 4]> = smallvec![];
+    let mut current = Line { start: 0, end: 0, main_axis: 0.0, cross_axis: 0.0 };
+
+    for (index, size) in sizes.iter().enumerate() {
+        if current.end > current.start {
+            if current.main_axis + spacing + main_axis(*size) > available {
+                let next = Line { start: index, end: index, main_axis: 0.0, cross_axis: 0.0 };
+                lines.push(std::mem::replace(&mut current, next));
+            } else {
+                current.main_axis += spacing;
+            }
+        }
+
+        current.main_axis += main_axis(*size);
+        current.cross_axis = current.cross_axis.max(cross_axis(*size));
+        current.end = index + 1;
+    }
+
+    if current.end > current.start {
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Returns the offset of the first child, and the spacing between children, of a line.
+fn line_offsets(line: &Line, available: f64, spacing: f64, justification: LineJustification) -> (f64, f64) {
+    let rest_space = (available - line.main_axis).max(0.0);
+    let count = line.end - line.start;
+
+    match justification {
+        LineJustification::Start => (0.0, spacing),
+        LineJustification::Center => (rest_space / 2.0, spacing),
+        LineJustification::SpaceBetween if count > 1 => (0.0, spacing + rest_space / (count - 1) as f64),
+        LineJustification::SpaceBetween => (0.0, spacing),
+    }
+}
+
+/// The children are measured using the same requested size as the wrap, and then broken into
+/// lines on the main axis of the requested size. The wrap takes up the full main axis if the
+/// lines are justified, and the length of the longest line otherwise.
+fn calculate_size_wrap(
+    widget: &mut dyn Layout,
+    main_axis: fn(Dimension) -> f64,
+    cross_axis: fn(Dimension) -> f64,
+    dimension: fn(f64, f64) -> Dimension,
+    spacing: f64,
+    line_spacing: f64,
+    justification: LineJustification,
+    requested_size: Dimension,
+    ctx: &mut LayoutContext
+) {
+    let mut sizes: SmallVec<[Dimension; 10]> = smallvec![];
+
+    widget.foreach_child(&mut |child| {
+        sizes.push(child.process_calculate_size(requested_size, ctx));
+    });
+
+    let lines = wrap_lines(&sizes, main_axis, cross_axis, spacing, main_axis(requested_size));
+
+    let longest_line = lines.iter().fold(0.0, |longest: f64, line| longest.max(line.main_axis));
+
+    let total_main_axis = if justification != LineJustification::Start && main_axis(requested_size).is_finite() {
+        main_axis(requested_size).max(longest_line)
+    } else {
+        longest_line
+    };
+
+    let total_cross_axis = lines.iter().map(|line| line.cross_axis).sum::<f64>()
+        + lines.len().saturating_sub(1) as f64 * line_spacing;
+
+    widget.set_dimension(dimension(total_main_axis, total_cross_axis));
+}
+
+fn position_children_wrap(
+    widget: &mut dyn Layout,
+    main_axis_position: fn(Position) -> f64,
+    main_axis_dimension: fn(Dimension) -> f64,
+    cross_axis_position: fn(Position) -> f64,
+    cross_axis_dimension: fn(Dimension) -> f64,
+    position_from_main_and_cross: fn(f64, f64) -> Position,
+    spacing: f64,
+    line_spacing: f64,
+    cross_axis_alignment: CrossAxisAlignment,
+    justification: LineJustification,
+    reversed: bool,
+    bounding_box: Rect,
+    ctx: &mut LayoutContext,
+) {
+    let position = widget.position();
+    let dimension = widget.dimension();
+
+    let mut sizes: SmallVec<[Dimension; 10]> = smallvec![];
+
+    widget.foreach_child(&mut |child| {
+        sizes.push(child.dimension());
+    });
+
+    // The lines are broken in the same way as when calculating the size, because the wrap
+    // is at least as long as its longest line.
+    let lines = wrap_lines(&sizes, main_axis_dimension, cross_axis_dimension, spacing, main_axis_dimension(dimension));
+
+    let mut index = 0;
+    let mut line_index = 0;
+    let mut cross_axis_offset = 0.0;
+    let (mut main_axis_offset, mut child_spacing) = match lines.first() {
+        Some(line) => line_offsets(line, main_axis_dimension(dimension), spacing, justification),
+        None => (0.0, spacing),
+    };
+
+    widget.foreach_child(&mut |child| {
+        if index >= lines[line_index].end {
+            cross_axis_offset += lines[line_index].cross_axis + line_spacing;
+            line_index += 1;
+            (main_axis_offset, child_spacing) = line_offsets(&lines[line_index], main_axis_dimension(dimension), spacing, justification);
+        }
+
+        let line = &lines[line_index];
+        let line_cross_axis = cross_axis_position(position) + cross_axis_offset;
+
+        let cross = match cross_axis_alignment {
+            CrossAxisAlignment::Start => line_cross_axis,
+            CrossAxisAlignment::Center => {
+                line_cross_axis + line.cross_axis / 2.0 - cross_axis_dimension(child.dimension()) / 2.0
+            }
+            CrossAxisAlignment::End => {
+                line_cross_axis + line.cross_axis - cross_axis_dimension(child.dimension())
+            }
+        };
+
+        let main = if reversed {
+            main_axis_position(position) + main_axis_dimension(dimension)
+                - main_axis_offset
+                - main_axis_dimension(child.dimension())
+        } else {
+            main_axis_position(position) + main_axis_offset
+        };
+
+        child.set_position(position_from_main_and_cross(main, cross));
+
+        main_axis_offset += main_axis_dimension(child.dimension()) + child_spacing;
+        index += 1;
+
+        child.position_children(bounding_box, ctx);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::draw::Dimension;
+    use crate::layout::stack_layouts::{height, width, wrap_lines, Line};
+
+    #[test]
+    fn wrap_lines_breaks_when_exceeding_available() {
+        let sizes = [
+            Dimension::new(40.0, 20.0),
+            Dimension::new(40.0, 30.0),
+            Dimension::new(40.0, 20.0),
+            Dimension::new(120.0, 10.0),
+        ];
+
+        let lines = wrap_lines(&sizes, width, height, 10.0, 100.0);
+
+        assert_eq!(lines.as_slice(), &[
+            Line { start: 0, end: 2, main_axis: 90.0, cross_axis: 30.0 },
+            Line { start: 2, end: 3, main_axis: 40.0, cross_axis: 20.0 },
+            Line { start: 3, end: 4, main_axis: 120.0, cross_axis: 10.0 },
+        ]);
+    }
+}
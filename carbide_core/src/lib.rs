@@ -53,6 +53,7 @@ pub mod lifecycle;
 pub mod render;
 pub mod scene;
 pub mod state;
+pub mod testing;
 pub mod text;
 pub mod widget;
 
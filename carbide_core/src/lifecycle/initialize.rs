@@ -1,17 +1,20 @@
 use crate::widget::{CommonWidget, WidgetSync};
 use crate::environment::Environment;
+use crate::state::StateInspector;
 
 pub trait Initialize: CommonWidget + WidgetSync {
     #[allow(unused_variables)]
     fn initialize(&mut self, ctx: &mut InitializationContext) {}
 
     fn process_initialization(&mut self, ctx: &mut InitializationContext) {
-        self.sync(ctx.env);
-        self.initialize(ctx);
+        StateInspector::with_creator(self.id(), std::any::type_name::<Self>(), || {
+            self.sync(ctx.env);
+            self.initialize(ctx);
 
-        self.foreach_child(&mut |child| {
-            child.process_initialization(ctx);
-        });
+            self.foreach_child(&mut |child| {
+                child.process_initialization(ctx);
+            });
+        })
     }
 }
 
@@ -1,5 +1,9 @@
+use std::hash::Hash;
+
 use crate::draw::ImageContext;
 use crate::environment::{Environment};
+use crate::layout::LayoutCache;
+use crate::state::StateInspector;
 use crate::text::TextContext;
 use crate::widget::{CommonWidget, WidgetSync};
 
@@ -8,12 +12,19 @@ pub trait Update: CommonWidget + WidgetSync {
     fn update(&mut self, ctx: &mut UpdateContext) {}
 
     fn process_update(&mut self, ctx: &mut UpdateContext) {
-        self.sync(ctx.env);
-        self.update(ctx);
+        let id = self.id();
+
+        LayoutCache::track(id, |children| {
+            StateInspector::with_creator(id, std::any::type_name::<Self>(), || {
+                self.sync(ctx.env);
+                self.update(ctx);
 
-        self.foreach_child(&mut |child| {
-            child.process_update(ctx);
-        });
+                self.foreach_child(&mut |child| {
+                    child.id().hash(children);
+                    child.process_update(ctx);
+                });
+            })
+        })
     }
 }
 
@@ -1,7 +1,7 @@
 use cgmath::Vector3;
 use carbide::draw::{ImageMetrics, Position};
 use carbide::render::{RenderInstruction, RenderInstructionValue, Style};
-use carbide::text::TextStyle;
+use carbide::text::{AttributedText, TextStyle};
 use crate::color::{Color, WHITE};
 use crate::draw::stroke::StrokeDashPattern;
 use crate::draw::{CompositeDrawShape, Dimension, DrawOptions, DrawShape, DrawStyle, ImageContext, ImageFormat, ImageId, ImageOptions, Rect, Scalar};
@@ -162,6 +162,14 @@ impl<'a, 'b: 'a> RenderContext<'a, 'b> {
         self.text.calculate_size_new(text, style, requested_size, self.env)
     }
 
+    pub fn attributed_text(&mut self, text: &AttributedText, style: &TextStyle, position: Position, requested_size: Option<Dimension>) {
+        self.render.attributed_text(text, style, position, requested_size, self.env, self.text)
+    }
+
+    pub fn measure_attributed_text(&mut self, text: &AttributedText, style: &TextStyle, requested_size: Option<Dimension>) -> Dimension {
+        self.text.calculate_size_attributed(text, style, requested_size, self.env)
+    }
+
     pub fn text_old(&mut self, text: TextId) {
         self.render.text_old(text, self.text);
     }
@@ -202,6 +210,19 @@ impl<'a, 'b: 'a> RenderContext<'a, 'b> {
         res
     }
 
+    /// Render the nested context with the given opacity, where 0.0 is fully transparent and 1.0
+    /// is fully opaque.
+    pub fn opacity<R, F: FnOnce(&mut RenderContext) -> R>(&mut self, opacity: f32, f: F) -> R {
+        if opacity >= 1.0 {
+            return f(self);
+        }
+
+        self.render.filter_new();
+        let res = f(self);
+        self.render.filter_new_pop(&ImageFilter::opacity(opacity.max(0.0)), WHITE, false);
+        res
+    }
+
     pub fn shadow<R, F: FnOnce(&mut RenderContext) -> R>(&mut self, id: &ImageFilter, id2: &ImageFilter, color: Color, f: F) -> R {
         self.render.filter_new();
         let res = f(self);
@@ -262,6 +283,10 @@ pub trait InnerRenderContext {
     fn raster_image(&mut self, id: &ImageId, bounding_box: Rect, options: ImageOptions);
 
     fn text(&mut self, text: &str, style: &TextStyle, position: Position, requested_size: Option<Dimension>, env: &mut Environment, ctx: &mut dyn TextContext);
+    /// Render attributed text. Contexts without support for attributes render the plain text.
+    fn attributed_text(&mut self, text: &AttributedText, style: &TextStyle, position: Position, requested_size: Option<Dimension>, env: &mut Environment, ctx: &mut dyn TextContext) {
+        self.text(&text.text, style, position, requested_size, env, ctx)
+    }
     fn text_old(&mut self, text: TextId, ctx: &mut dyn TextContext);
 
     fn filter_new(&mut self);
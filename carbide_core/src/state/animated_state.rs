@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 
 use crate::animation::animation_curve::linear;
-use crate::animation::{Animatable, AnimationManager};
+use crate::animation::{Animatable, AnimationManager, Decay, Simulation, Spring};
 use crate::environment::Environment;
 use crate::state::util::value_cell::ValueRef;
 use crate::state::{AnyReadState, RMap1, StateSync};
@@ -24,6 +24,7 @@ pub struct AnimatedState {
     repeat_count: Option<u32>,
     frame_time: Instant,
     animation_curve: fn(f64) -> f64,
+    simulation: Option<Simulation>,
 }
 
 impl AnimatedState {
@@ -41,9 +42,28 @@ impl AnimatedState {
             repeat_count: None,
             frame_time: now,
             animation_curve: curve,
+            simulation: None,
         }
     }
 
+    /// Animate with the spring instead of a curve. The duration is the time it takes for the
+    /// spring to settle.
+    pub fn spring(spring: Spring) -> AnimatedState {
+        Self::simulation(Simulation::Spring(spring))
+    }
+
+    /// Animate with the decay instead of a curve. The duration is the time it takes for the
+    /// decay to come to rest.
+    pub fn decay(decay: Decay) -> AnimatedState {
+        Self::simulation(Simulation::Decay(decay))
+    }
+
+    fn simulation(simulation: Simulation) -> AnimatedState {
+        let mut state = Self::linear().duration(simulation.settling_duration());
+        state.simulation = Some(simulation);
+        state
+    }
+
     pub fn duration(mut self, duration: Duration) -> AnimatedState {
         self.duration = duration;
         self
@@ -96,19 +116,26 @@ impl AnimatedState {
             }
         };
 
-        self.percent = (self.animation_curve)(percentage);
+        self.percent = match &self.simulation {
+            // The simulation might not be exactly at its target when the duration ends.
+            Some(_) if percentage >= 1.0 => 1.0,
+            Some(simulation) => simulation.value(percentage * self.duration.as_secs_f64()),
+            None => (self.animation_curve)(percentage),
+        };
     }
 }
 
 impl StateSync for AnimatedState {
     fn sync(&mut self, env: &mut Environment) -> bool {
+        let previous = self.percent;
+
         if let Some(manager) = env.get_mut::<AnimationManager>() {
             manager.request_animation_frame();
             self.frame_time = manager.frame_time();
             self.calc_percentage();
         }
 
-        false
+        self.percent != previous
     }
 }
 
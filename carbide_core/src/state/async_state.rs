@@ -1,113 +1,182 @@
+use std::cell::{Cell, RefCell};
 use std::fmt::{Debug, Formatter};
 use std::future::Future;
 use std::rc::Rc;
 
-use carbide_core::prelude::{Id, Listenable, Listener, NewStateSync};
+use futures::future::{AbortHandle, Abortable, BoxFuture};
 use futures::FutureExt;
-use oneshot::{Receiver, TryRecvError};
 
+use crate::application::ApplicationManager;
+use crate::asynchronous::spawn_task;
 use crate::environment::Environment;
-use crate::state::{ReadState, State, StateContract, TState};
-use crate::state::util::value_cell::{ValueRef, ValueRefMut};
-use crate::state::widget_state::WidgetState;
+use crate::state::{AnyReadState, Fn2, Functor, IntoReadState, Map1, ReadState, RMap1, StateContract, StateSync, ValueCell, ValueRef};
+
+/// The value of an [AsyncState]. The state is loading until its task completes, after which it
+/// is either ready with the value produced or failed with the error.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AsyncValue<T, E> {
+    Loading,
+    Ready(T),
+    Failed(E),
+}
+
+impl<T, E> AsyncValue<T, E> {
+    pub fn is_loading(&self) -> bool {
+        matches!(self, AsyncValue::Loading)
+    }
 
+    pub fn ready(&self) -> Option<&T> {
+        match self {
+            AsyncValue::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn failed(&self) -> Option<&E> {
+        match self {
+            AsyncValue::Failed(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl<T, E> From<Result<T, E>> for AsyncValue<T, E> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => AsyncValue::Ready(value),
+            Err(error) => AsyncValue::Failed(error),
+        }
+    }
+}
+
+crate::impl_state_value_generic!(AsyncValue<T, E>);
+
+/// # Async state
+/// The async state contains the result of a future. The future is spawned using
+/// [spawn_task](crate::asynchronous::spawn_task) when the state is created, and the value is set
+/// when the task completes and is picked up by [check_tasks](crate::asynchronous::check_tasks).
+///
+/// Clones of the state share the value and the running task. When the last clone is dropped, the
+/// running task is cancelled. The task can be started again using [AsyncState::refresh()], for
+/// example to reload data.
+///
+/// ```ignore
+/// let articles = AsyncState::new(|| async {
+///     reqwest::get("https://example.com/articles.json").await?.json::<Vec<Article>>().await
+/// });
+/// ```
 #[derive(Clone)]
-pub struct AsyncState<T>
+pub struct AsyncState<T, E>
     where
         T: StateContract + Send,
+        E: StateContract + Send,
 {
-    value: T,
-    receiver: Option<Rc<Receiver<T>>>,
+    inner: Rc<AsyncInner<T, E>>,
+    /// The frame this clone was last synced in.
+    synced_frame: Option<u32>,
 }
 
-impl<T: StateContract + Send + 'static + Default> AsyncState<T> {
-    pub fn new(a: impl Future<Output=T> + Send + 'static) -> TState<T> {
-        let (sender, receiver) = oneshot::channel();
+struct AsyncInner<T: StateContract, E: StateContract> {
+    value: ValueCell<AsyncValue<T, E>>,
+    /// The frame the value was last changed in.
+    changed_frame: Cell<u32>,
+    task: Box<dyn Fn() -> BoxFuture<'static, Result<T, E>>>,
+    /// The handle used to cancel the running task, if any.
+    running: RefCell<Option<AbortHandle>>,
+    /// Incremented every time the task is started, such that results of earlier runs are ignored.
+    generation: Cell<u64>,
+}
 
-        let task = a.then(|message| async move {
-            let _ = sender.send(message);
-            ()
-        });
+impl<T: StateContract + Send, E: StateContract + Send> AsyncState<T, E> {
+    /// Returns a new async state and starts the task. The function is called again to create a
+    /// new future each time the state is refreshed.
+    pub fn new<F: Future<Output=Result<T, E>> + Send + 'static>(task: impl Fn() -> F + 'static) -> AsyncState<T, E> {
+        let state = AsyncState {
+            inner: Rc::new(AsyncInner {
+                value: ValueCell::new(AsyncValue::Loading),
+                changed_frame: Cell::new(ApplicationManager::application_frame()),
+                task: Box::new(move || task().boxed()),
+                running: RefCell::new(None),
+                generation: Cell::new(0),
+            }),
+            synced_frame: None,
+        };
+
+        state.refresh();
+
+        state
+    }
 
-        #[cfg(feature = "async-std")]
-        let _ = async_std::task::spawn(task);
+    /// Cancels the running task, if any, and starts it again. The state is loading until the new
+    /// task completes.
+    pub fn refresh(&self) {
+        if let Some(handle) = self.inner.running.borrow_mut().take() {
+            handle.abort();
+        }
 
-        Box::new(AsyncState {
-            value: T::default(),
-            receiver: Some(Rc::new(receiver)),
-        }).into()
-    }
-}
+        let generation = self.inner.generation.get() + 1;
+        self.inner.generation.set(generation);
 
-impl<T: StateContract + Send + 'static> AsyncState<T> {
-    pub fn new_with_default(a: impl Future<Output=T> + Send + 'static, default: T) -> TState<T> {
-        let (sender, receiver) = oneshot::channel();
+        self.inner.set(AsyncValue::Loading);
 
-        let task = a.then(|message| async move {
-            let _ = sender.send(message);
-            ()
-        });
+        let (handle, registration) = AbortHandle::new_pair();
+        *self.inner.running.borrow_mut() = Some(handle);
 
-        #[cfg(feature = "async-std")]
-            let _ = async_std::task::spawn(task);
+        // The continuation only holds a weak reference, such that the task does not keep the
+        // state alive after the last clone is dropped.
+        let inner = Rc::downgrade(&self.inner);
 
-        Box::new(AsyncState {
-            value: default,
-            receiver: Some(Rc::new(receiver)),
-        }).into()
-    }
-}
+        spawn_task(Abortable::new((self.inner.task)(), registration), move |result, _ctx| {
+            let Some(inner) = inner.upgrade() else {
+                return;
+            };
 
-impl<T: StateContract + Send> NewStateSync for AsyncState<T> {
-    fn sync(&mut self, env: &mut Environment) {
-        if let Some(receiver) = &self.receiver {
-            match receiver.try_recv() {
-                Ok(message) => {
-                    println!("Received asynchronous state");
-                    self.value = message;
-                    self.receiver = None;
-                }
-                Err(TryRecvError::Empty) => {}
-                Err(e) => {
-                    eprintln!("{:?}", e)
-                }
+            if inner.generation.get() != generation {
+                return;
             }
-        }
-    }
-}
-
-impl<T: StateContract + Send> Listenable<T> for AsyncState<T> {
-    fn subscribe(&self, subscriber: Box<dyn Listener<T>>) -> Id {
-        todo!()
-    }
 
-    fn unsubscribe(&self, id: &Id) {
-        todo!()
+            // An aborted task has been replaced or cancelled, so the result is ignored
+            if let Ok(result) = result {
+                inner.running.borrow_mut().take();
+                inner.set(AsyncValue::from(result));
+            }
+        });
     }
 }
 
-impl<T: StateContract + Send> ReadState<T> for AsyncState<T> {
-    fn value(&self) -> ValueRef<T> {
-        ValueRef::Borrow(&self.value)
+impl<T: StateContract, E: StateContract> AsyncInner<T, E> {
+    fn set(&self, value: AsyncValue<T, E>) {
+        *self.value.borrow_mut() = value;
+        self.changed_frame.set(ApplicationManager::application_frame());
     }
-
 }
 
-impl<T: StateContract + Send> State<T> for AsyncState<T> {
-    fn value_mut(&mut self) -> ValueRefMut<T> {
-        ValueRefMut::Borrow(&mut self.value)
+impl<T: StateContract, E: StateContract> Drop for AsyncInner<T, E> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.running.get_mut().take() {
+            handle.abort();
+        }
     }
+}
 
-    fn set_value(&mut self, value: T) {
-        self.value = value
+impl<T: StateContract + Send, E: StateContract + Send> StateSync for AsyncState<T, E> {
+    fn sync(&mut self, _env: &mut Environment) -> bool {
+        // A change in the same frame as the last sync might have happened after it, so it is
+        // reported again.
+        let updated = self.synced_frame.is_none_or(|synced| self.inner.changed_frame.get() >= synced);
+        self.synced_frame = Some(ApplicationManager::application_frame());
+        updated
     }
+}
 
-    fn notify(&self) {
-        todo!()
+impl<T: StateContract + Send, E: StateContract + Send> AnyReadState for AsyncState<T, E> {
+    type T = AsyncValue<T, E>;
+    fn value_dyn(&self) -> ValueRef<'_, AsyncValue<T, E>> {
+        self.inner.value.borrow()
     }
 }
 
-impl<T: StateContract + Send + 'static> Debug for AsyncState<T> {
+impl<T: StateContract + Send, E: StateContract + Send> Debug for AsyncState<T, E> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AsyncState")
             .field("value", &*self.value())
@@ -115,8 +184,62 @@ impl<T: StateContract + Send + 'static> Debug for AsyncState<T> {
     }
 }
 
-impl<T: StateContract + Send + 'static> Into<TState<T>> for Box<AsyncState<T>> {
-    fn into(self) -> TState<T> {
-        WidgetState::new(self)
+impl<T: StateContract, V: StateContract + Send, E: StateContract + Send> Functor<T> for AsyncState<V, E> where AsyncState<V, E>: IntoReadState<T> {
+    // Can be simplified once this is stabilized: https://github.com/rust-lang/rust/issues/63063
+    type Output<G: StateContract, F: Fn2<T, G>> = RMap1<F, T, G, <AsyncState<V, E> as IntoReadState<T>>::Output>;
+
+    fn map<U: StateContract, F: Fn2<T, U>>(self, f: F) -> Self::Output<U, F> {
+        Map1::read_map(self.into_read_state(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::asynchronous::{check_tasks, sleep, AsyncContext};
+    use crate::draw::NOOPImageContext;
+    use crate::environment::Environment;
+    use crate::state::{AsyncState, AsyncValue, ReadState};
+    use crate::text::NOOPTextContext;
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let mut env = Environment::new();
+        let start = Instant::now();
+
+        while !condition() && start.elapsed() < Duration::from_secs(5) {
+            check_tasks(&mut AsyncContext {
+                text: &mut NOOPTextContext,
+                image: &mut NOOPImageContext,
+                env: &mut env,
+            });
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn ready_after_task_completes() {
+        let state = AsyncState::new(|| async { Ok::<_, String>(42) });
+
+        assert_eq!(*state.value(), AsyncValue::Loading);
+
+        wait_until(|| !state.value().is_loading());
+
+        assert_eq!(*state.value(), AsyncValue::Ready(42));
+    }
+
+    #[test]
+    fn refresh_ignores_earlier_run() {
+        let state = AsyncState::new(|| async {
+            sleep(Duration::from_millis(10)).await;
+            Err::<u32, _>("failed".to_string())
+        });
+
+        state.refresh();
+
+        wait_until(|| !state.value().is_loading());
+
+        assert_eq!(state.value().failed().map(|e| e.as_str()), Some("failed"));
+        assert_eq!(state.inner.generation.get(), 2);
     }
 }
@@ -0,0 +1,188 @@
+use std::cell::Cell;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::application::ApplicationManager;
+use crate::asynchronous::{get_event_sink, start_stream};
+use crate::environment::Environment;
+use crate::event::{CoreEvent, EventSink};
+use crate::state::{AnyReadState, Fn2, Functor, IntoReadState, Map1, ReadState, RMap1, StateContract, StateSync, ValueCell, ValueRef};
+
+/// # Channel state
+/// A read-only state that is fed by values sent from other threads or tasks through a
+/// [StateSender]. Sending a value wakes the event loop, and the value is applied on the main
+/// thread when the async tasks are next checked. If several values are sent before that, only the
+/// newest is applied.
+///
+/// The state must be created on the main thread after the application has been created, because
+/// the senders use the event sink of the application to wake the event loop.
+///
+/// ```ignore
+/// let temperature = ChannelState::new(0.0);
+/// let sender = temperature.sender();
+///
+/// std::thread::spawn(move || loop {
+///     sender.send(read_sensor());
+///     std::thread::sleep(Duration::from_millis(100));
+/// });
+/// ```
+#[derive(Clone)]
+pub struct ChannelState<T>
+    where
+        T: StateContract + Send,
+{
+    inner: Rc<ChannelInner<T>>,
+    /// The frame this clone was last synced in.
+    synced_frame: Option<u32>,
+    sender: StateSender<T>,
+}
+
+struct ChannelInner<T: StateContract> {
+    value: ValueCell<T>,
+    /// The frame the value was last changed in.
+    changed_frame: Cell<u32>,
+}
+
+/// The sending half of a [ChannelState]. The sender can be cloned and moved to other threads.
+/// Values sent after the state has been dropped are discarded.
+#[derive(Clone)]
+pub struct StateSender<T: Send> {
+    /// The newest value not yet applied to the state.
+    latest: Arc<Mutex<Option<T>>>,
+    notify: Sender<()>,
+    sink: Box<dyn EventSink>,
+}
+
+impl<T: StateContract + Send> ChannelState<T> {
+    pub fn new(value: T) -> ChannelState<T> {
+        let inner = Rc::new(ChannelInner {
+            value: ValueCell::new(value),
+            changed_frame: Cell::new(ApplicationManager::application_frame()),
+        });
+
+        let latest = Arc::new(Mutex::new(None::<T>));
+        let (notify, receiver) = channel::<()>();
+
+        {
+            let latest = latest.clone();
+            // The stream only holds a weak reference, such that it stops when the last clone of
+            // the state is dropped.
+            let inner = Rc::downgrade(&inner);
+
+            start_stream(receiver, move |_, _ctx| {
+                let Some(inner) = inner.upgrade() else {
+                    return true;
+                };
+
+                if let Some(value) = latest.lock().take() {
+                    *inner.value.borrow_mut() = value;
+                    inner.changed_frame.set(ApplicationManager::application_frame());
+                }
+
+                false
+            });
+        }
+
+        ChannelState {
+            inner,
+            synced_frame: None,
+            sender: StateSender {
+                latest,
+                notify,
+                sink: get_event_sink(),
+            },
+        }
+    }
+
+    /// Returns a sender that can be used to send values to the state from other threads.
+    pub fn sender(&self) -> StateSender<T> {
+        self.sender.clone()
+    }
+}
+
+impl<T: Send> StateSender<T> {
+    /// Send a value to the state, replacing any value sent that has not been applied yet.
+    pub fn send(&self, value: T) {
+        let previous = self.latest.lock().replace(value);
+
+        // Only wake the event loop for the first value since the state was last updated
+        if previous.is_none() && self.notify.send(()).is_ok() {
+            self.sink.send(CoreEvent::AsyncStream);
+        }
+    }
+}
+
+impl<T: Send> Debug for StateSender<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StateSender").finish()
+    }
+}
+
+impl<T: StateContract + Send> StateSync for ChannelState<T> {
+    fn sync(&mut self, _env: &mut Environment) -> bool {
+        // A change in the same frame as the last sync might have happened after it, so it is
+        // reported again.
+        let updated = self.synced_frame.is_none_or(|synced| self.inner.changed_frame.get() >= synced);
+        self.synced_frame = Some(ApplicationManager::application_frame());
+        updated
+    }
+}
+
+impl<T: StateContract + Send> AnyReadState for ChannelState<T> {
+    type T = T;
+    fn value_dyn(&self) -> ValueRef<'_, T> {
+        self.inner.value.borrow()
+    }
+}
+
+impl<T: StateContract + Send> Debug for ChannelState<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChannelState")
+            .field("value", &*self.value())
+            .finish()
+    }
+}
+
+impl<T: StateContract, V: StateContract + Send> Functor<T> for ChannelState<V> where ChannelState<V>: IntoReadState<T> {
+    // Can be simplified once this is stabilized: https://github.com/rust-lang/rust/issues/63063
+    type Output<G: StateContract, F: Fn2<T, G>> = RMap1<F, T, G, <ChannelState<V> as IntoReadState<T>>::Output>;
+
+    fn map<U: StateContract, F: Fn2<T, U>>(self, f: F) -> Self::Output<U, F> {
+        Map1::read_map(self.into_read_state(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::asynchronous::{check_tasks, AsyncContext};
+    use crate::draw::NOOPImageContext;
+    use crate::environment::Environment;
+    use crate::state::{ChannelState, ReadState};
+    use crate::text::NOOPTextContext;
+
+    #[test]
+    fn newest_value_is_applied() {
+        let state = ChannelState::new(0);
+        let sender = state.sender();
+
+        std::thread::spawn(move || {
+            for i in 1..=10 {
+                sender.send(i);
+            }
+        }).join().unwrap();
+
+        assert_eq!(*state.value(), 0);
+
+        check_tasks(&mut AsyncContext {
+            text: &mut NOOPTextContext,
+            image: &mut NOOPImageContext,
+            env: &mut Environment::new(),
+        });
+
+        assert_eq!(*state.value(), 10);
+    }
+}
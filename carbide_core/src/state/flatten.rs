@@ -23,12 +23,13 @@ where
 
 impl<T: StateContract, S: ReadState<T=T>, SS: ReadState<T=S>> StateSync for FlattenedReadState<T, S, SS> {
     fn sync(&mut self, env: &mut Environment) -> bool {
-        if self.state.sync(env) {
+        let updated = self.state.sync(env);
+
+        if updated || self.current.is_none() {
             self.current = Some(self.state.value().clone());
-            self.current.as_mut().unwrap().sync(env)
-        } else {
-            false
         }
+
+        self.current.as_mut().unwrap().sync(env) || updated
     }
 }
 
@@ -1,11 +1,15 @@
 use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
 use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use crate::state::AnyReadState;
 
+use crate::application::ApplicationManager;
 use crate::environment::{Environment};
 use crate::state::{AnyState, Fn2, Functor, IntoReadState, Map1, StateSync, ReadState, RMap1, StateContract, ValueRef, ValueRefMut};
+#[cfg(feature = "inspector")]
+use crate::state::{StateId, StateInspector};
 
 #[derive(Clone)]
 pub struct GlobalState<T>
@@ -14,25 +18,56 @@ pub struct GlobalState<T>
 {
     /// The shared state
     inner_value: Arc<RwLock<T>>,
+    /// The frame the value was last changed in, shared between clones.
+    changed_frame: Arc<AtomicU32>,
+    /// The frame this clone was last synced in.
+    synced_frame: Option<u32>,
+    /// The id of the state in the state inspector, if it was recording when the state was created.
+    #[cfg(feature = "inspector")]
+    inspector_id: Option<StateId>,
 }
 
 impl<T: StateContract> GlobalState<T> {
     /// Returns a new local state containing the value provided.
     /// Returns the local state wrapped within a WidgetState.
     pub fn new(value: T) -> GlobalState<T> {
+        let inner_value = Arc::new(RwLock::new(value));
+
+        #[cfg(feature = "inspector")]
+        let inspector_id = {
+            let weak = Arc::downgrade(&inner_value);
+            StateInspector::created("GlobalState", &*inner_value.read(), move || weak.strong_count() > 0)
+        };
+
         GlobalState {
-            inner_value: Arc::new(RwLock::new(value)),
+            inner_value,
+            changed_frame: Arc::new(AtomicU32::new(ApplicationManager::application_frame())),
+            synced_frame: None,
+            #[cfg(feature = "inspector")]
+            inspector_id,
         }
     }
+
+    fn changed(&self) {
+        self.changed_frame.store(ApplicationManager::application_frame(), Ordering::Relaxed);
+
+        // Writes from other threads are not recorded, because the recording is kept per thread
+        #[cfg(feature = "inspector")]
+        StateInspector::written(self.inspector_id);
+    }
 }
 
 impl<T: StateContract> StateSync for GlobalState<T> {
     fn sync(&mut self, _env: &mut Environment) -> bool {
-        // TODO: find a smarter way to determine if local state has been updated.
-        // I guess we can figuring it out by storing a frame number in the local state
-        // and in the env, and then comparing and updating whenever this is called and set_value
-        // is called.
-        true
+        // A change in the same frame as the last sync might have happened after it, so it is
+        // reported again.
+        let updated = self.synced_frame.is_none_or(|synced| self.changed_frame.load(Ordering::Relaxed) >= synced);
+        self.synced_frame = Some(ApplicationManager::application_frame());
+
+        #[cfg(feature = "inspector")]
+        StateInspector::read(self.inspector_id, updated, || format!("{:?}", &*self.value()));
+
+        updated
     }
 }
 
@@ -47,12 +82,14 @@ impl<T: StateContract> AnyReadState for GlobalState<T> {
 
 impl<T: StateContract> AnyState for GlobalState<T> {
     fn value_dyn_mut(&mut self) -> ValueRefMut<'_, T> {
+        self.changed();
         ValueRefMut::Locked(
             Some(RwLockWriteGuard::map(self.inner_value.write(), |a| a))
         )
     }
 
     fn set_value_dyn(&mut self, value: T) {
+        self.changed();
         *self.inner_value.write() = value;
     }
 }
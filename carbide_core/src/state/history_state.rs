@@ -0,0 +1,304 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::rc::{Rc, Weak};
+
+use crate::environment::Environment;
+use crate::state::{AnyReadState, AnyState, Fn2, Functor, IntoReadState, LocalState, Map1, ReadState, RMap1, State, StateContract, StateSync, ValueRef, ValueRefMut};
+use crate::widget::managers::{Undoable, UndoManager};
+
+/// The maximum number of snapshots kept for undoing.
+const HISTORY_LIMIT: usize = 100;
+
+/// # History state
+/// Wraps a state and records a snapshot of the value each time it is changed, such that the
+/// changes can be undone and redone. The wrapped state should be shared between clones, like
+/// [LocalState], because undoing sets the value of the state.
+///
+/// Changes made within a transaction are undone as a single change. When the state is synced
+/// within an [UndoManager], changes are also registered with the manager, such that they can be
+/// undone using the keyboard shortcuts.
+///
+/// ```ignore
+/// let text = HistoryState::new(LocalState::new("".to_string()));
+/// let can_undo = text.can_undo();
+/// ```
+#[derive(Clone)]
+pub struct HistoryState<S>
+    where
+        S: State,
+{
+    state: S,
+    history: Rc<RefCell<History<S>>>,
+}
+
+struct History<S: State> {
+    state: S,
+    undo: Vec<S::T>,
+    redo: Vec<S::T>,
+    /// The number of transactions currently open.
+    transactions: usize,
+    /// True if a snapshot has been recorded in the current transaction.
+    recorded: bool,
+    can_undo: LocalState<bool>,
+    can_redo: LocalState<bool>,
+    manager: Option<UndoManager>,
+}
+
+impl<S: State> HistoryState<S> {
+    pub fn new(state: S) -> HistoryState<S> {
+        HistoryState {
+            state: state.clone(),
+            history: Rc::new(RefCell::new(History {
+                state,
+                undo: vec![],
+                redo: vec![],
+                transactions: 0,
+                recorded: false,
+                can_undo: LocalState::new(false),
+                can_redo: LocalState::new(false),
+                manager: None,
+            })),
+        }
+    }
+
+    /// Undo the last change. Returns false if there is nothing to undo. If the state is
+    /// registered with an [UndoManager], the manager is told, such that it does not undo the
+    /// change again.
+    pub fn undo(&self) -> bool {
+        if !self.history.undo() {
+            return false;
+        }
+
+        if let Some(manager) = self.manager() {
+            manager.undone(&self.undoable());
+        }
+
+        true
+    }
+
+    /// Redo the last undone change. Returns false if there is nothing to redo.
+    pub fn redo(&self) -> bool {
+        if !self.history.redo() {
+            return false;
+        }
+
+        if let Some(manager) = self.manager() {
+            manager.redone(&self.undoable());
+        }
+
+        true
+    }
+
+    fn manager(&self) -> Option<UndoManager> {
+        self.history.borrow().manager.clone()
+    }
+
+    fn undoable(&self) -> Weak<dyn Undoable> {
+        let undoable: Rc<dyn Undoable> = self.history.clone();
+        Rc::downgrade(&undoable)
+    }
+
+    pub fn can_undo(&self) -> impl ReadState<T=bool> {
+        self.history.borrow().can_undo.clone()
+    }
+
+    pub fn can_redo(&self) -> impl ReadState<T=bool> {
+        self.history.borrow().can_redo.clone()
+    }
+
+    /// Start a transaction. Changes made until the transaction is ended are undone as one.
+    /// Transactions can be nested, in which case the outermost transaction groups the changes.
+    pub fn begin_transaction(&self) {
+        let mut history = self.history.borrow_mut();
+
+        if history.transactions == 0 {
+            history.recorded = false;
+        }
+
+        history.transactions += 1;
+    }
+
+    pub fn end_transaction(&self) {
+        let mut history = self.history.borrow_mut();
+        history.transactions = history.transactions.saturating_sub(1);
+    }
+
+    /// Make the changes within the closure as a single transaction.
+    pub fn transaction(&mut self, f: impl FnOnce(&mut HistoryState<S>)) {
+        self.begin_transaction();
+        f(self);
+        self.end_transaction();
+    }
+
+    /// Record the current value, before it is changed.
+    fn record(&self) {
+        let mut history = self.history.borrow_mut();
+
+        if history.transactions > 0 {
+            if history.recorded {
+                return;
+            }
+
+            history.recorded = true;
+        }
+
+        let snapshot = self.state.value().clone();
+        history.undo.push(snapshot);
+
+        if history.undo.len() > HISTORY_LIMIT {
+            history.undo.remove(0);
+        }
+
+        history.redo.clear();
+        history.update_availability();
+
+        if let Some(manager) = &history.manager {
+            manager.push(self.undoable());
+        }
+    }
+}
+
+impl<S: State> History<S> {
+    fn update_availability(&mut self) {
+        let can_undo = !self.undo.is_empty();
+        let can_redo = !self.redo.is_empty();
+
+        if *self.can_undo.value() != can_undo {
+            self.can_undo.set_value(can_undo);
+        }
+
+        if *self.can_redo.value() != can_redo {
+            self.can_redo.set_value(can_redo);
+        }
+    }
+}
+
+impl<S: State> Undoable for RefCell<History<S>> {
+    fn undo(&self) -> bool {
+        let mut history = self.borrow_mut();
+
+        let Some(previous) = history.undo.pop() else {
+            return false;
+        };
+
+        let current = history.state.value().clone();
+        history.redo.push(current);
+        history.state.set_value(previous);
+        history.update_availability();
+
+        true
+    }
+
+    fn redo(&self) -> bool {
+        let mut history = self.borrow_mut();
+
+        let Some(next) = history.redo.pop() else {
+            return false;
+        };
+
+        let current = history.state.value().clone();
+        history.undo.push(current);
+        history.state.set_value(next);
+        history.update_availability();
+
+        true
+    }
+}
+
+impl<S: State> StateSync for HistoryState<S> {
+    fn sync(&mut self, env: &mut Environment) -> bool {
+        // Register with the closest undo manager the first time the state is synced within one
+        if self.history.borrow().manager.is_none() {
+            if let Some(manager) = env.get::<UndoManager>() {
+                self.history.borrow_mut().manager = Some(manager.clone());
+            }
+        }
+
+        self.state.sync(env)
+    }
+}
+
+impl<S: State> AnyReadState for HistoryState<S> {
+    type T = S::T;
+    fn value_dyn(&self) -> ValueRef<'_, S::T> {
+        self.state.value()
+    }
+}
+
+impl<S: State> AnyState for HistoryState<S> {
+    fn value_dyn_mut(&mut self) -> ValueRefMut<'_, S::T> {
+        self.record();
+        self.state.value_mut()
+    }
+
+    fn set_value_dyn(&mut self, value: S::T) {
+        self.record();
+        self.state.set_value(value);
+    }
+}
+
+impl<S: State> Debug for HistoryState<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let history = self.history.borrow();
+
+        f.debug_struct("HistoryState")
+            .field("value", &*self.value())
+            .field("undo", &history.undo.len())
+            .field("redo", &history.redo.len())
+            .finish()
+    }
+}
+
+impl<T: StateContract, S: State> Functor<T> for HistoryState<S> where HistoryState<S>: IntoReadState<T> {
+    // Can be simplified once this is stabilized: https://github.com/rust-lang/rust/issues/63063
+    type Output<G: StateContract, F: Fn2<T, G>> = RMap1<F, T, G, <HistoryState<S> as IntoReadState<T>>::Output>;
+
+    fn map<U: StateContract, F: Fn2<T, U>>(self, f: F) -> Self::Output<U, F> {
+        Map1::read_map(self.into_read_state(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::state::{HistoryState, LocalState, ReadState, State};
+
+    #[test]
+    fn undo_and_redo() {
+        let mut state = HistoryState::new(LocalState::new(0));
+        let can_undo = state.can_undo();
+
+        state.set_value(1);
+        state.set_value(2);
+
+        assert!(*can_undo.value());
+
+        assert!(state.undo());
+        assert_eq!(*state.value(), 1);
+
+        assert!(state.redo());
+        assert_eq!(*state.value(), 2);
+
+        assert!(state.undo());
+        assert!(state.undo());
+        assert_eq!(*state.value(), 0);
+        assert!(!state.undo());
+        assert!(!*can_undo.value());
+
+        // A new change clears the redo history
+        state.set_value(3);
+        assert!(!state.redo());
+    }
+
+    #[test]
+    fn transaction_is_undone_as_one() {
+        let mut state = HistoryState::new(LocalState::new(0));
+
+        state.transaction(|state| {
+            state.set_value(1);
+            state.set_value(2);
+        });
+
+        assert!(state.undo());
+        assert_eq!(*state.value(), 0);
+    }
+}
@@ -0,0 +1,513 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Debug, Write};
+
+#[cfg(feature = "inspector")]
+use serde::Serialize;
+
+use crate::application::ApplicationManager;
+use crate::environment::{Environment, EnvironmentColor, EnvironmentKey};
+use crate::state::{AnyReadState, StateSync, ValueRef};
+use crate::time::{Duration, Instant};
+use crate::widget::{HStack, OverlayManager, Rectangle, Scroll, Spacer, Text, Widget, WidgetExt, WidgetId};
+
+/// The maximum number of writes kept for each state.
+const WRITE_LIMIT: usize = 100;
+
+thread_local! {
+    static INSPECTOR: RefCell<Inspector> = RefCell::new(Inspector::default());
+}
+
+#[derive(Default)]
+struct Inspector {
+    /// The time recording started, or none if the inspector is not recording.
+    started: Option<Instant>,
+    next_id: u64,
+    /// The widget currently syncing its states, if any.
+    current: Option<WidgetId>,
+    /// The widget currently being initialized or updated, if any.
+    creator: Option<WidgetId>,
+    widgets: BTreeMap<WidgetId, &'static str>,
+    states: BTreeMap<StateId, Entry>,
+}
+
+struct Entry {
+    record: StateRecord,
+    /// Returns false when the last clone of the state has been dropped.
+    alive: Box<dyn Fn() -> bool>,
+}
+
+/// The id of a state recorded by the [StateInspector].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct StateId(u64);
+
+/// Everything recorded about a state by the [StateInspector].
+#[derive(Clone, Debug)]
+pub struct StateRecord {
+    pub id: StateId,
+    /// The kind of state, for example `LocalState`.
+    pub kind: &'static str,
+    /// The type of the value.
+    pub value_type: &'static str,
+    /// The debug representation of the value, as of the last time it was read.
+    pub value: String,
+    pub alive: bool,
+    /// The widget being initialized or updated when the state was created, if any. This is for
+    /// example the widget whose [ForEach](crate::widget::ForEach) created the content owning the
+    /// state. States created while building the widget tree, before it is initialized, have none.
+    pub created_by: Option<WidgetId>,
+    /// The widgets that have synced the state.
+    pub readers: BTreeSet<WidgetId>,
+    pub writes: Vec<StateWrite>,
+}
+
+/// A write to a state recorded by the [StateInspector].
+#[derive(Clone, Debug)]
+pub struct StateWrite {
+    /// The time since the inspector started recording.
+    pub time: Duration,
+    pub frame: u32,
+    /// The debug representation of the value written, recorded the next time the state is read.
+    pub value: Option<String>,
+}
+
+/// # State inspector
+/// An opt-in recorder of the states created while it is recording, the widgets reading them and
+/// every write to them. It is used to answer why a widget was updated, by dumping the recording as
+/// a [DOT](StateInspector::to_dot()) graph or as JSON using `StateInspector::to_json()`, or by
+/// showing it on top of the application using [StateInspector::overlay()].
+///
+/// Only states created after [StateInspector::start()] are recorded. The states are only
+/// instrumented when the `inspector` feature is enabled, such that the inspector has no cost
+/// otherwise. The recording is kept per thread, and the states are recorded on the main thread.
+pub struct StateInspector;
+
+impl StateInspector {
+    /// Start recording. States created from now on are recorded.
+    pub fn start() {
+        INSPECTOR.with_borrow_mut(|inspector| {
+            inspector.started.get_or_insert_with(Instant::now);
+        })
+    }
+
+    /// Stop recording. The recording is kept until it is cleared.
+    pub fn stop() {
+        INSPECTOR.with_borrow_mut(|inspector| inspector.started = None)
+    }
+
+    pub fn is_recording() -> bool {
+        INSPECTOR.with_borrow(|inspector| inspector.started.is_some())
+    }
+
+    /// Remove everything recorded so far.
+    pub fn clear() {
+        INSPECTOR.with_borrow_mut(|inspector| {
+            inspector.widgets.clear();
+            inspector.states.clear();
+        })
+    }
+
+    /// Returns the recorded states, ordered by the time they were created.
+    pub fn states() -> Vec<StateRecord> {
+        INSPECTOR.with_borrow(|inspector| {
+            inspector.states.values()
+                .map(|entry| StateRecord {
+                    alive: (entry.alive)(),
+                    ..entry.record.clone()
+                })
+                .collect()
+        })
+    }
+
+    /// Sync states as the widget with the id, such that it is recorded as reading them. This is
+    /// called by the derived [WidgetSync](crate::widget::WidgetSync) implementations.
+    pub fn with_widget<R>(id: WidgetId, widget_type: &'static str, f: impl FnOnce() -> R) -> R {
+        if !cfg!(feature = "inspector") || !StateInspector::is_recording() {
+            return f();
+        }
+
+        let previous = INSPECTOR.with_borrow_mut(|inspector| {
+            inspector.widgets.insert(id, widget_type);
+            inspector.current.replace(id)
+        });
+
+        let result = f();
+
+        INSPECTOR.with_borrow_mut(|inspector| inspector.current = previous);
+
+        result
+    }
+
+    /// Attribute the states created by `f` to the widget with the id. This is called by the default
+    /// [Initialize](crate::lifecycle::Initialize) and [Update](crate::lifecycle::Update)
+    /// implementations, which is when containers create their content.
+    pub fn with_creator<R>(id: WidgetId, widget_type: &'static str, f: impl FnOnce() -> R) -> R {
+        if !cfg!(feature = "inspector") || !StateInspector::is_recording() {
+            return f();
+        }
+
+        let previous = INSPECTOR.with_borrow_mut(|inspector| {
+            inspector.widgets.insert(id, widget_type);
+            inspector.creator.replace(id)
+        });
+
+        let result = f();
+
+        INSPECTOR.with_borrow_mut(|inspector| inspector.creator = previous);
+
+        result
+    }
+
+    /// Record the creation of a state. Returns none if the inspector is not recording.
+    #[allow(dead_code)]
+    pub(crate) fn created<T: Debug>(kind: &'static str, value: &T, alive: impl Fn() -> bool + 'static) -> Option<StateId> {
+        if !StateInspector::is_recording() {
+            return None;
+        }
+
+        let value = format!("{:?}", value);
+
+        INSPECTOR.with_borrow_mut(|inspector| {
+            inspector.next_id += 1;
+            let id = StateId(inspector.next_id);
+
+            let record = StateRecord {
+                id,
+                kind,
+                value_type: std::any::type_name::<T>(),
+                value,
+                alive: true,
+                created_by: inspector.creator,
+                readers: BTreeSet::new(),
+                writes: vec![],
+            };
+
+            inspector.states.insert(id, Entry { record, alive: Box::new(alive) });
+
+            Some(id)
+        })
+    }
+
+    /// Record that the state has been synced by the current widget. If the state reports a
+    /// change, the value is formatted and recorded.
+    #[allow(dead_code)]
+    pub(crate) fn read(id: Option<StateId>, updated: bool, value: impl FnOnce() -> String) {
+        let Some(id) = id else {
+            return;
+        };
+
+        if !StateInspector::is_recording() {
+            return;
+        }
+
+        // The value is formatted before borrowing the recording, in case formatting reads states
+        let value = updated.then(value);
+
+        INSPECTOR.with_borrow_mut(|inspector| {
+            let current = inspector.current;
+
+            let Some(entry) = inspector.states.get_mut(&id) else {
+                return;
+            };
+
+            if let Some(widget) = current {
+                entry.record.readers.insert(widget);
+            }
+
+            if let Some(value) = value {
+                if let Some(write) = entry.record.writes.last_mut() {
+                    write.value.get_or_insert_with(|| value.clone());
+                }
+
+                entry.record.value = value;
+            }
+        })
+    }
+
+    /// Record a write to the state.
+    #[allow(dead_code)]
+    pub(crate) fn written(id: Option<StateId>) {
+        let Some(id) = id else {
+            return;
+        };
+
+        INSPECTOR.with_borrow_mut(|inspector| {
+            let Some(started) = inspector.started else {
+                return;
+            };
+
+            let Some(entry) = inspector.states.get_mut(&id) else {
+                return;
+            };
+
+            let writes = &mut entry.record.writes;
+
+            if writes.len() >= WRITE_LIMIT {
+                writes.remove(0);
+            }
+
+            writes.push(StateWrite {
+                time: started.elapsed(),
+                frame: ApplicationManager::application_frame(),
+                value: None,
+            });
+        })
+    }
+
+    /// Returns the recording as a graph in the DOT format, with an edge from each widget to the
+    /// states it reads, and a dashed edge to the states it created.
+    pub fn to_dot() -> String {
+        let states = StateInspector::states();
+        let widgets = INSPECTOR.with_borrow(|inspector| inspector.widgets.clone());
+
+        let mut dot = String::from("digraph states {\n    rankdir=LR;\n");
+
+        for (id, widget_type) in &widgets {
+            let _ = writeln!(dot, "    \"w{}\" [shape=ellipse, label=\"{} {}\"];", id.as_u32(), escape(&short_type(widget_type)), id);
+        }
+
+        for state in &states {
+            let label = format!("{}<{}>\n{}\n{} writes", state.kind, short_type(state.value_type), state.value, state.writes.len());
+            let style = if state.alive { "solid" } else { "dotted" };
+            let _ = writeln!(dot, "    \"s{}\" [shape=box, style={}, label=\"{}\"];", state.id.0, style, escape(&label));
+
+            if let Some(creator) = state.created_by {
+                let _ = writeln!(dot, "    \"w{}\" -> \"s{}\" [style=dashed, label=\"created\"];", creator.as_u32(), state.id.0);
+            }
+
+            for reader in &state.readers {
+                let _ = writeln!(dot, "    \"w{}\" -> \"s{}\";", reader.as_u32(), state.id.0);
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Returns the recording as JSON, with a list of widgets and a list of states.
+    #[cfg(feature = "inspector")]
+    pub fn to_json() -> String {
+        #[derive(Serialize)]
+        struct JsonWidget {
+            id: u32,
+            #[serde(rename = "type")]
+            widget_type: &'static str,
+        }
+
+        #[derive(Serialize)]
+        struct JsonState {
+            id: u64,
+            kind: &'static str,
+            #[serde(rename = "type")]
+            value_type: &'static str,
+            value: String,
+            alive: bool,
+            created_by: Option<u32>,
+            readers: Vec<u32>,
+            writes: Vec<JsonWrite>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonWrite {
+            /// The time since the inspector started recording, in seconds.
+            time: f64,
+            frame: u32,
+            value: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        struct JsonRecording {
+            widgets: Vec<JsonWidget>,
+            states: Vec<JsonState>,
+        }
+
+        let widgets = INSPECTOR.with_borrow(|inspector| inspector.widgets.clone());
+
+        let recording = JsonRecording {
+            widgets: widgets.into_iter()
+                .map(|(id, widget_type)| JsonWidget { id: id.as_u32(), widget_type })
+                .collect(),
+            states: StateInspector::states().into_iter()
+                .map(|state| JsonState {
+                    id: state.id.0,
+                    kind: state.kind,
+                    value_type: state.value_type,
+                    value: state.value,
+                    alive: state.alive,
+                    created_by: state.created_by.map(|id| id.as_u32()),
+                    readers: state.readers.iter().map(|reader| reader.as_u32()).collect(),
+                    writes: state.writes.into_iter()
+                        .map(|write| JsonWrite {
+                            time: write.time.as_secs_f64(),
+                            frame: write.frame,
+                            value: write.value,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        };
+
+        serde_json::to_string(&recording).expect("The recording to contain only values that can be serialized")
+    }
+
+    /// Wrap the content such that the inspector can be shown on top of it, using
+    /// [StateInspector::show()]. The content remains interactive while the inspector is shown.
+    ///
+    /// ```ignore
+    /// Window::new("My app", Dimension::new(600.0, 400.0), StateInspector::overlay(content))
+    /// ```
+    pub fn overlay(content: impl Widget) -> impl Widget {
+        content.overlay::<InspectorOverlayKey>()
+    }
+
+    /// Show the recorded states that are still alive, updated every frame, in a panel at the
+    /// trailing edge of the closest [StateInspector::overlay()].
+    pub fn show(env: &mut Environment) {
+        let panel = Scroll::new(
+            Text::new(InspectorReport)
+                .padding(10.0)
+        ).background(Rectangle::new().fill(EnvironmentColor::SecondarySystemBackground))
+            .frame_fixed_width(360.0);
+
+        OverlayManager::get::<InspectorOverlayKey>(env, |manager| {
+            manager.insert(HStack::new((Spacer::new(), panel)));
+        })
+    }
+
+    /// Hide the inspector panel shown by [StateInspector::show()].
+    pub fn hide(env: &mut Environment) {
+        OverlayManager::get::<InspectorOverlayKey>(env, |manager| {
+            manager.clear();
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct InspectorOverlayKey;
+
+impl EnvironmentKey for InspectorOverlayKey {
+    type Value = OverlayManager;
+}
+
+/// A summary of the live states recorded, read by the panel shown by [StateInspector::show()].
+#[derive(Clone, Debug)]
+struct InspectorReport;
+
+impl StateSync for InspectorReport {
+    fn sync(&mut self, _env: &mut Environment) -> bool {
+        true
+    }
+}
+
+impl AnyReadState for InspectorReport {
+    type T = String;
+    fn value_dyn(&self) -> ValueRef<'_, String> {
+        let mut report = String::new();
+
+        for state in StateInspector::states().iter().filter(|state| state.alive) {
+            let _ = writeln!(report, "{}<{}> = {}", state.kind, short_type(state.value_type), state.value);
+
+            if let Some(creator) = state.created_by {
+                let _ = writeln!(report, "    created by {}", creator);
+            }
+
+            if !state.readers.is_empty() {
+                let readers = state.readers.iter().map(|reader| reader.to_string()).collect::<Vec<_>>();
+                let _ = writeln!(report, "    read by {}", readers.join(", "));
+            }
+
+            if let Some(write) = state.writes.last() {
+                let _ = writeln!(report, "    {} writes, last in frame {} at {:.3}s", state.writes.len(), write.frame, write.time.as_secs_f64());
+            }
+        }
+
+        ValueRef::Owned(report)
+    }
+}
+
+/// Removes the module paths from a type name, such that `alloc::string::String` becomes `String`.
+fn short_type(type_name: &str) -> String {
+    let mut short = String::new();
+    let mut segment = String::new();
+
+    for c in type_name.chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            short.push_str(segment.rsplit("::").next().unwrap_or_default());
+            segment.clear();
+            short.push(c);
+        }
+    }
+
+    short.push_str(segment.rsplit("::").next().unwrap_or_default());
+    short
+}
+
+fn escape(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+
+    for c in string.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(all(test, feature = "inspector"))]
+mod tests {
+    use crate::environment::Environment;
+    use crate::state::{LocalState, State, StateInspector, StateSync};
+    use crate::widget::WidgetId;
+
+    #[test]
+    fn records_readers_and_writes() {
+        StateInspector::clear();
+        StateInspector::start();
+
+        let widget = WidgetId::new();
+        let mut state = LocalState::new(1);
+        let mut env = Environment::new();
+
+        state.set_value(2);
+
+        StateInspector::with_widget(widget, "Reader", || {
+            state.sync(&mut env);
+        });
+
+        StateInspector::stop();
+
+        let states = StateInspector::states();
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].value, "2");
+        assert!(states[0].readers.contains(&widget));
+        assert_eq!(states[0].writes.len(), 1);
+        assert_eq!(states[0].writes[0].value.as_deref(), Some("2"));
+
+        assert!(StateInspector::to_dot().contains(&format!("\"w{}\" -> \"s", widget.as_u32())));
+        assert!(StateInspector::to_json().contains("\"kind\":\"LocalState\""));
+    }
+
+    #[test]
+    fn records_the_creating_widget() {
+        StateInspector::clear();
+        StateInspector::start();
+
+        let widget = WidgetId::new();
+        let _state = StateInspector::with_creator(widget, "Creator", || LocalState::new(1));
+
+        StateInspector::stop();
+
+        let states = StateInspector::states();
+        assert_eq!(states.len(), 1);
+        assert_eq!(states[0].created_by, Some(widget));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::common::any_debug::debug_fingerprint;
 use crate::environment::{Environment, EnvironmentKey};
 use crate::state::{AnyReadState, StateSync, ValueRef};
 
@@ -19,8 +20,14 @@ impl<K: EnvironmentKey> KeyState<K> where K::Value: Clone {
 
 impl<K: EnvironmentKey> StateSync for KeyState<K> where K::Value: Clone {
     fn sync(&mut self, env: &mut Environment) -> bool {
-        self.current = env.get::<K>().cloned().unwrap_or(self.default.clone());
-        true
+        let current = env.get::<K>().cloned().unwrap_or(self.default.clone());
+
+        // The environment is not able to report changes, so the value is compared with the
+        // previous value instead.
+        let updated = debug_fingerprint(&current) != debug_fingerprint(&self.current);
+        self.current = current;
+
+        updated
     }
 }
 
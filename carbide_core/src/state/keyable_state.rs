@@ -1,3 +1,4 @@
+use crate::common::any_debug::debug_fingerprint;
 use crate::environment::{Environment, EnvironmentKeyable};
 use crate::state::ReadState;
 use crate::state::{AnyReadState, StateSync, ValueRef};
@@ -23,9 +24,15 @@ impl<K: EnvironmentKeyable, S: ReadState<T=K>> KeyableState<K, S> where K::Outpu
 impl<K: EnvironmentKeyable, S: ReadState<T=K>> StateSync for KeyableState<K, S>
 where K::Output: Clone {
     fn sync(&mut self, env: &mut Environment) -> bool {
-        self.state.sync(env);
-        self.current = env.value(&*self.state.value()).unwrap_or(self.default.clone());
-        true
+        let updated = self.state.sync(env);
+        let current = env.value(&*self.state.value()).unwrap_or(self.default.clone());
+
+        // The environment is not able to report changes, so the value is compared with the
+        // previous value instead.
+        let updated = updated || debug_fingerprint(&current) != debug_fingerprint(&self.current);
+        self.current = current;
+
+        updated
     }
 }
 
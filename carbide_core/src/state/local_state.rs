@@ -1,12 +1,16 @@
+use std::cell::Cell;
 use std::fmt::{Debug, Formatter};
 use std::rc::Rc;
 
 use crate::state::AnyState;
 use crate::state::state_sync::StateSync;
 
+use crate::application::ApplicationManager;
 use crate::environment::{Environment};
 use crate::state::{AnyReadState, Fn2, Functor, InnerState, IntoReadState, Map1, ReadState, RMap1, StateContract};
 use crate::state::util::value_cell::{ValueCell, ValueRef, ValueRefMut};
+#[cfg(feature = "inspector")]
+use crate::state::{StateId, StateInspector};
 
 /// # Local state
 /// The local state is used as a shared state between multiple widgets within the same widget tree.
@@ -17,8 +21,9 @@ use crate::state::util::value_cell::{ValueCell, ValueRef, ValueRefMut};
 /// whenever this state changes.
 ///
 /// Local state does not need to do any updating when [StateSync::sync()] is called because
-/// all state is stored directly within.
-/// Also it does not depend on any other states and therefore the event can be ignored.
+/// all state is stored directly within. The state remembers the frame it was last changed in,
+/// such that [StateSync::sync()] only reports a change when the value has been written since the
+/// clone was last synced.
 #[derive(Clone)]
 pub struct LocalState<T>
     where
@@ -26,25 +31,55 @@ pub struct LocalState<T>
 {
     /// The shared state
     inner_value: InnerState<T>,
+    /// The frame the value was last changed in, shared between clones.
+    changed_frame: Rc<Cell<u32>>,
+    /// The frame this clone was last synced in.
+    synced_frame: Option<u32>,
+    /// The id of the state in the state inspector, if it was recording when the state was created.
+    #[cfg(feature = "inspector")]
+    inspector_id: Option<StateId>,
 }
 
 impl<T: StateContract> LocalState<T> {
     /// Returns a new local state containing the value provided.
     /// Returns the local state wrapped within a WidgetState.
     pub fn new(value: T) -> LocalState<T> {
+        let inner_value = Rc::new(ValueCell::new(value));
+
+        #[cfg(feature = "inspector")]
+        let inspector_id = {
+            let weak = Rc::downgrade(&inner_value);
+            StateInspector::created("LocalState", &*inner_value.borrow(), move || weak.strong_count() > 0)
+        };
+
         LocalState {
-            inner_value: Rc::new(ValueCell::new(value)),
+            inner_value,
+            changed_frame: Rc::new(Cell::new(ApplicationManager::application_frame())),
+            synced_frame: None,
+            #[cfg(feature = "inspector")]
+            inspector_id,
         }
     }
+
+    fn changed(&self) {
+        self.changed_frame.set(ApplicationManager::application_frame());
+
+        #[cfg(feature = "inspector")]
+        StateInspector::written(self.inspector_id);
+    }
 }
 
 impl<T: StateContract> StateSync for LocalState<T> {
     fn sync(&mut self, _env: &mut Environment) -> bool {
-        // TODO: find a smarter way to determine if local state has been updated.
-        // I guess we can figuring it out by storing a frame number in the local state
-        // and in the env, and then comparing and updating whenever this is called and set_value
-        // is called.
-        true
+        // A change in the same frame as the last sync might have happened after it, so it is
+        // reported again.
+        let updated = self.synced_frame.is_none_or(|synced| self.changed_frame.get() >= synced);
+        self.synced_frame = Some(ApplicationManager::application_frame());
+
+        #[cfg(feature = "inspector")]
+        StateInspector::read(self.inspector_id, updated, || format!("{:?}", &*self.value()));
+
+        updated
     }
 }
 
@@ -57,10 +92,12 @@ impl<T: StateContract> AnyReadState for LocalState<T> {
 
 impl<T: StateContract> AnyState for LocalState<T> {
     fn value_dyn_mut(&mut self) -> ValueRefMut<'_, T> {
+        self.changed();
         self.inner_value.borrow_mut()
     }
 
     fn set_value_dyn(&mut self, value: T) {
+        self.changed();
         *self.inner_value.borrow_mut() = value;
     }
 }
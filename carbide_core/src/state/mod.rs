@@ -7,11 +7,14 @@ pub use util::value_cell::{ValueCell, ValueRef, ValueRefMut};
 pub use self::animated_state::*;
 pub use self::cache_state::CachedReadState;
 pub use self::cache_state::CachedState;
-//pub use self::async_state::*;
+pub use self::async_state::*;
+pub use self::channel_state::*;
 pub use self::field_state::*;
 pub use self::global_state::GlobalState;
+pub use self::history_state::HistoryState;
 pub use self::ignore_writes_state::IgnoreWritesState;
 pub use self::index_state::IndexState;
+pub use self::inspector::*;
 pub use self::local_state::LocalState;
 pub use self::read_state::*;
 pub use self::into_read_state::*;
@@ -22,10 +25,13 @@ pub use self::keyable_state::*;
 pub use self::state_ext::*;
 pub use self::state_sync::StateSync;
 pub use self::value_state::ValueState;
+pub use self::validated_state::*;
 pub use self::logging_state::*;
 pub use self::transition_state::*;
 pub use self::static_state::*;
 pub use self::functor::*;
+#[cfg(feature = "persistence")]
+pub use self::persistent_state::*;
 
 mod animated_state;
 mod local_state;
@@ -33,8 +39,11 @@ mod state;
 mod state_ext;
 mod state_sync;
 mod value_state;
+mod validated_state;
 mod index_state;
-//mod async_state;
+mod inspector;
+mod async_state;
+mod channel_state;
 mod field_state;
 mod ignore_writes_state;
 mod read_state;
@@ -42,6 +51,7 @@ mod tuple_state;
 mod util;
 mod cache_state;
 mod global_state;
+mod history_state;
 mod into_state;
 mod into_read_state;
 mod transition_state;
@@ -53,6 +63,8 @@ mod empty_state;
 mod extensions;
 mod key_state;
 mod keyable_state;
+#[cfg(feature = "persistence")]
+mod persistent_state;
 
 pub use carbide_derive::StateValue;
 
@@ -64,8 +76,10 @@ impl<T> StateContract for T where T: Clone + Debug + 'static {}
 
 #[cfg(test)]
 mod tests {
+    use crate::application::ApplicationManager;
+    use crate::environment::Environment;
     use crate::state::Map1;
-    use crate::state::{GlobalState, LocalState, ReadState, State};
+    use crate::state::{GlobalState, LocalState, ReadState, State, StateSync};
 
     #[test]
     fn mutate_mapped_local_state() {
@@ -127,4 +141,28 @@ mod tests {
 
         println!("State: {}, Mapped: {}, Mapped2: {}", state.value(), mapped.value(), mapped2.value());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn sync_local_state_reports_changes() {
+        let mut manager = ApplicationManager::new();
+        let mut env = Environment::new();
+
+        let mut state = LocalState::new(0);
+        let mut clone = state.clone();
+
+        manager.begin_frame();
+
+        assert!(state.sync(&mut env));
+        assert!(!state.sync(&mut env));
+
+        state.set_value(1);
+        manager.begin_frame();
+
+        assert!(state.sync(&mut env));
+        assert!(!state.sync(&mut env));
+
+        // Clones keep track of their own syncs
+        assert!(clone.sync(&mut env));
+        assert!(!clone.sync(&mut env));
+    }
+}
@@ -0,0 +1,329 @@
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::environment::Environment;
+use crate::state::{AnyReadState, AnyState, Fn2, Functor, IntoReadState, LocalState, Map1, ReadState, RMap1, State, StateContract, StateSync, ValueRef, ValueRefMut};
+use crate::time::Duration;
+
+/// The time to wait after a change before the store is written, such that changes made in quick
+/// succession, like dragging a slider, only results in a single write.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// # Persistent store
+/// A keyed store of values that is saved as a json file. The file is read when the store is
+/// created, and written in the background shortly after values have changed.
+///
+/// Changes made right before the application exits might not have been written yet, so
+/// [PersistentStore::flush()] should be called before exiting, if the last changes are important.
+#[derive(Clone)]
+pub struct PersistentStore {
+    path: Arc<PathBuf>,
+    values: Arc<Mutex<Map<String, Value>>>,
+    /// Sends requests to the writer thread, which is the only one writing the file. The thread
+    /// exits when the last store is dropped.
+    writer: Sender<WriteRequest>,
+}
+
+enum WriteRequest {
+    /// The values have changed, and should be written after the debounce duration.
+    Changed,
+    /// Write the values now, and acknowledge when written.
+    Flush(Sender<()>),
+}
+
+impl PersistentStore {
+    /// Returns the store for the application, placed in the data directory of the platform:
+    /// `%APPDATA%` on windows, `~/Library/Application Support` on macOS and `$XDG_DATA_HOME`
+    /// or `~/.local/share` elsewhere.
+    pub fn new(application: &str) -> PersistentStore {
+        PersistentStore::with_path(data_directory().join(application).join("state.json"))
+    }
+
+    /// Returns a store saved in the file at the given path.
+    pub fn with_path(path: impl Into<PathBuf>) -> PersistentStore {
+        let path = Arc::new(path.into());
+        let values = Arc::new(Mutex::new(read(&path)));
+
+        let (sender, receiver) = channel::<WriteRequest>();
+
+        {
+            let path = path.clone();
+            let values = values.clone();
+
+            std::thread::spawn(move || writer(&path, &values, receiver));
+        }
+
+        PersistentStore {
+            path,
+            values,
+            writer: sender,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the value stored for the key, if any and if it can be deserialized.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.values.lock().get(key)?.clone();
+
+        match serde_json::from_value(value) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                eprintln!("Could not read persistent value for '{}': {}", key, e);
+                None
+            }
+        }
+    }
+
+    /// Stores the value for the key. The store is written shortly after, unless the value is
+    /// unchanged.
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) {
+        let value = match serde_json::to_value(value) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("Could not persist value for '{}': {}", key, e);
+                return;
+            }
+        };
+
+        let mut values = self.values.lock();
+
+        if values.get(key) != Some(&value) {
+            values.insert(key.to_string(), value);
+            let _ = self.writer.send(WriteRequest::Changed);
+        }
+    }
+
+    /// Writes the store to disk immediately, and waits until it has been written.
+    pub fn flush(&self) {
+        let (sender, receiver) = channel();
+
+        if self.writer.send(WriteRequest::Flush(sender)).is_ok() {
+            let _ = receiver.recv();
+        }
+    }
+}
+
+impl Debug for PersistentStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistentStore")
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+fn data_directory() -> PathBuf {
+    let home = std::env::var_os("HOME").map(PathBuf::from).unwrap_or_default();
+
+    if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from).unwrap_or(home)
+    } else if cfg!(target_os = "macos") {
+        home.join("Library").join("Application Support")
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(".local").join("share"))
+    }
+}
+
+/// Writes the values each time they have not changed for the debounce duration, or when
+/// flushed. All writes go through this, such that two writes never race on the temporary file.
+fn writer(path: &Path, values: &Mutex<Map<String, Value>>, receiver: Receiver<WriteRequest>) {
+    while let Ok(request) = receiver.recv() {
+        let mut flushed = vec![];
+
+        match request {
+            WriteRequest::Changed => {
+                // Wait until no changes have been made for the debounce duration
+                loop {
+                    match receiver.recv_timeout(DEBOUNCE) {
+                        Ok(WriteRequest::Changed) => continue,
+                        Ok(WriteRequest::Flush(sender)) => {
+                            flushed.push(sender);
+                            break;
+                        }
+                        Err(RecvTimeoutError::Timeout) | Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            }
+            WriteRequest::Flush(sender) => flushed.push(sender),
+        }
+
+        write(path, &values.lock());
+
+        for sender in flushed {
+            let _ = sender.send(());
+        }
+    }
+}
+
+fn read(path: &Path) -> Map<String, Value> {
+    let Ok(contents) = std::fs::read_to_string(path) else {
+        return Map::new();
+    };
+
+    match serde_json::from_str(&contents) {
+        Ok(values) => values,
+        Err(e) => {
+            eprintln!("Could not read persistent store at {:?}: {}", path, e);
+            Map::new()
+        }
+    }
+}
+
+fn write(path: &Path, values: &Map<String, Value>) {
+    if let Err(e) = try_write(path, values) {
+        eprintln!("Could not write persistent store to {:?}: {}", path, e);
+    }
+}
+
+fn try_write(path: &Path, values: &Map<String, Value>) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    // Write to a temporary file first, such that the store is not corrupted if the
+    // application exits while writing.
+    let temporary = path.with_extension("json.tmp");
+    std::fs::write(&temporary, serde_json::to_string_pretty(values)?)?;
+    std::fs::rename(&temporary, path)
+}
+
+/// # Persistent state
+/// A state that is loaded from a [PersistentStore] when created, and saved back to the store when
+/// changed. If the store does not contain a value for the key, the default is used.
+///
+/// Values set using [State::set_value()] are stored immediately, while changes made through
+/// [State::value_mut()] are stored when the state is next synced.
+///
+/// ```ignore
+/// let store = PersistentStore::new("my_application");
+/// let split = PersistentState::new(&store, "sidebar_width", 200.0);
+/// ```
+#[derive(Clone)]
+pub struct PersistentState<T>
+    where
+        T: StateContract + Serialize + DeserializeOwned,
+{
+    key: Arc<str>,
+    store: PersistentStore,
+    state: LocalState<T>,
+}
+
+impl<T: StateContract + Serialize + DeserializeOwned> PersistentState<T> {
+    pub fn new(store: &PersistentStore, key: &str, default: T) -> PersistentState<T> {
+        let value = store.get(key).unwrap_or(default);
+
+        PersistentState {
+            key: Arc::from(key),
+            store: store.clone(),
+            state: LocalState::new(value),
+        }
+    }
+}
+
+impl<T: StateContract + Serialize + DeserializeOwned> StateSync for PersistentState<T> {
+    fn sync(&mut self, env: &mut Environment) -> bool {
+        let updated = self.state.sync(env);
+
+        if updated {
+            self.store.set(&self.key, &*self.state.value());
+        }
+
+        updated
+    }
+}
+
+impl<T: StateContract + Serialize + DeserializeOwned> AnyReadState for PersistentState<T> {
+    type T = T;
+    fn value_dyn(&self) -> ValueRef<'_, T> {
+        self.state.value()
+    }
+}
+
+impl<T: StateContract + Serialize + DeserializeOwned> AnyState for PersistentState<T> {
+    fn value_dyn_mut(&mut self) -> ValueRefMut<'_, T> {
+        self.state.value_mut()
+    }
+
+    fn set_value_dyn(&mut self, value: T) {
+        self.store.set(&self.key, &value);
+        self.state.set_value(value);
+    }
+}
+
+impl<T: StateContract + Serialize + DeserializeOwned> Debug for PersistentState<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PersistentState")
+            .field("key", &self.key)
+            .field("value", &*self.value())
+            .finish()
+    }
+}
+
+impl<T: StateContract, V: StateContract + Serialize + DeserializeOwned> Functor<T> for PersistentState<V> where PersistentState<V>: IntoReadState<T> {
+    // Can be simplified once this is stabilized: https://github.com/rust-lang/rust/issues/63063
+    type Output<G: StateContract, F: Fn2<T, G>> = RMap1<F, T, G, <PersistentState<V> as IntoReadState<T>>::Output>;
+
+    fn map<U: StateContract, F: Fn2<T, U>>(self, f: F) -> Self::Output<U, F> {
+        Map1::read_map(self.into_read_state(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::state::{PersistentState, PersistentStore, ReadState, State};
+
+    fn path(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("carbide_persistent_state_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+
+        directory.join("state.json")
+    }
+
+    #[test]
+    fn value_survives_reopening_store() {
+        let path = path("reopening");
+
+        let store = PersistentStore::with_path(&path);
+        let mut state = PersistentState::new(&store, "width", 200.0);
+
+        assert_eq!(*state.value(), 200.0);
+
+        state.set_value(320.0);
+        store.flush();
+
+        let store = PersistentStore::with_path(&path);
+        let state = PersistentState::new(&store, "width", 200.0);
+
+        assert_eq!(*state.value(), 320.0);
+    }
+
+    #[test]
+    fn flush_writes_the_latest_values() {
+        let path = path("flush");
+        let store = PersistentStore::with_path(&path);
+
+        for width in 0..100 {
+            store.set("width", &width);
+        }
+
+        store.flush();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let values: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&contents).unwrap();
+
+        assert_eq!(values.get("width"), Some(&serde_json::Value::from(99)));
+    }
+}
@@ -143,11 +143,7 @@ impl<T: Debug + Clone + 'static, E: Debug + Clone + 'static> AnyState for Result
 macro_rules! impl_state_value {
     ($($typ: ty),*) => {
         $(
-        impl $crate::state::StateSync for $typ {
-            fn sync(&mut self, _env: &mut $crate::environment::Environment) -> bool {
-                true
-            }
-        }
+        impl $crate::state::StateSync for $typ {}
         impl $crate::state::AnyReadState for $typ {
             type T = $typ;
             fn value_dyn(&self) -> $crate::state::ValueRef<'_, $typ> {
@@ -1,7 +1,9 @@
+use std::cell::Cell;
 use std::fmt::{Debug, Formatter};
 
 use crate::state::state_sync::StateSync;
 
+use crate::application::ApplicationManager;
 use crate::environment::{Environment};
 use crate::state::{AnyReadState, ReadState, StateContract, AnyState, Functor, IntoReadState, RMap1, Fn2, Map1};
 use crate::state::util::value_cell::{ValueCell, ValueRef, ValueRefMut};
@@ -13,6 +15,10 @@ where
 {
     /// The shared state
     inner: &'static ValueCell<T>,
+    /// The frame the value was last changed in, shared between copies.
+    changed_frame: &'static Cell<u32>,
+    /// The frame this copy was last synced in.
+    synced_frame: Option<u32>,
 }
 
 impl<T: StateContract> Copy for StaticState<T> {}
@@ -21,17 +27,23 @@ impl<T: StateContract> StaticState<T> {
     pub fn new(value: T) -> StaticState<T> {
         StaticState {
             inner: Box::leak(Box::new(ValueCell::new(value))),
+            changed_frame: Box::leak(Box::new(Cell::new(ApplicationManager::application_frame()))),
+            synced_frame: None,
         }
     }
+
+    fn changed(&self) {
+        self.changed_frame.set(ApplicationManager::application_frame());
+    }
 }
 
 impl<T: StateContract> StateSync for StaticState<T> {
     fn sync(&mut self, _env: &mut Environment) -> bool {
-        // TODO: find a smarter way to determine if static state has been updated.
-        // I guess we can figuring it out by storing a frame number in the local state
-        // and in the env, and then comparing and updating whenever this is called and set_value
-        // is called.
-        true
+        // A change in the same frame as the last sync might have happened after it, so it is
+        // reported again.
+        let updated = self.synced_frame.is_none_or(|synced| self.changed_frame.get() >= synced);
+        self.synced_frame = Some(ApplicationManager::application_frame());
+        updated
     }
 }
 
@@ -44,10 +56,12 @@ impl<T: StateContract> AnyReadState for StaticState<T> {
 
 impl<T: StateContract> AnyState for StaticState<T> {
     fn value_dyn_mut(&mut self) -> ValueRefMut<'_, T> {
+        self.changed();
         self.inner.borrow_mut()
     }
 
     fn set_value_dyn(&mut self, value: T) {
+        self.changed();
         *self.inner.borrow_mut() = value;
     }
 }
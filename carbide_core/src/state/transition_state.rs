@@ -4,8 +4,9 @@ use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use crate::time::*;
 use crate::animation::AnimationManager;
-use crate::animation::{Animatable, ease_in_out};
+use crate::animation::{Animatable, ease_in_out, Decay, Simulation, Spring};
 use crate::environment::{Environment};
+use crate::math::InnerSpace;
 use crate::state::{AnyReadState, Fn2, Functor, InnerState, IntoReadState, Map1, StateSync, ReadState, RMap1, StateContract, ValueCell, ValueRef};
 
 #[derive(Clone)]
@@ -20,6 +21,7 @@ pub struct TransitionState<T, S>
 
     duration: Duration,
     curve: fn(f64) -> f64,
+    simulation: Option<Simulation>,
     interpolation: fn(&T, &T, f64) -> T,
 
     transition: Rc<RefCell<Option<Transition<T>>>>,
@@ -31,6 +33,8 @@ struct Transition<T> where T: StateContract + Animatable<T> + PartialEq {
     start: Instant,
     from: T,
     to: T,
+    /// The simulation, started with the velocity of the transition it replaced.
+    simulation: Option<Simulation>,
 }
 
 impl TransitionState<f64, f64> {
@@ -40,6 +44,7 @@ impl TransitionState<f64, f64> {
             value: Rc::new(ValueCell::new(T::default())),
             duration: Duration::new(1, 0),
             curve: ease_in_out,
+            simulation: None,
             interpolation: T::interpolate,
             transition: Rc::new(RefCell::new(None)),
             initialized: Default::default(),
@@ -55,6 +60,22 @@ impl<T: StateContract + Animatable<T> + PartialEq, S: ReadState<T=T>> Transition
 
     pub fn curve(mut self, curve: fn(f64) -> f64) -> Self {
         self.curve = curve;
+        self.simulation = None;
+        self
+    }
+
+    /// Transition with the spring instead of a curve. When the value changes while transitioning,
+    /// the new transition starts with the velocity of the current, if the values have a
+    /// [displacement](Animatable::displacement). The duration is not used.
+    pub fn spring(mut self, spring: Spring) -> Self {
+        self.simulation = Some(Simulation::Spring(spring));
+        self
+    }
+
+    /// Transition with the decay instead of a curve, slowing down as the value gets closer to
+    /// its target. The duration is not used.
+    pub fn decay(mut self, decay: Decay) -> Self {
+        self.simulation = Some(Simulation::Decay(decay));
         self
     }
 
@@ -63,6 +84,35 @@ impl<T: StateContract + Animatable<T> + PartialEq, S: ReadState<T=T>> Transition
         self
     }
 
+    /// The velocity of the transition, in animated distances per second, at the elapsed time in
+    /// seconds. For curves this is the derivative of the curve.
+    fn velocity(&self, transition: &Transition<T>, elapsed: f64) -> f64 {
+        if let Some(simulation) = &transition.simulation {
+            return simulation.velocity(elapsed);
+        }
+
+        let duration = self.duration.as_secs_f64();
+
+        if duration <= 0.0 || elapsed >= duration {
+            return 0.0;
+        }
+
+        let step = 0.001;
+        let before = (elapsed / duration - step).max(0.0);
+        let after = (elapsed / duration + step).min(1.0);
+
+        ((self.curve)(after) - (self.curve)(before)) / ((after - before) * duration)
+    }
+
+    /// Transitions with a simulation are finished when the simulation has settled, and
+    /// transitions with a curve when the duration has elapsed.
+    fn is_finished(&self, transition: &Transition<T>, elapsed: f64) -> bool {
+        match &transition.simulation {
+            Some(simulation) => simulation.is_settled(elapsed),
+            None => elapsed >= self.duration.as_secs_f64(),
+        }
+    }
+
     /*fn progression(&mut self) -> Option<T> {
         let res = match &*self.range.borrow() {
             None => None,
@@ -126,28 +176,54 @@ impl<T: StateContract + Animatable<T> + PartialEq, S: ReadState<T=T>> AnyReadSta
         };
 
         if update {
+            let now = Instant::now();
+            let from = self.value.borrow().clone();
+            let to = self.inner.value().clone();
+
+            // Carry the velocity of the running transition over to the new one, by projecting
+            // it onto the direction of the new transition. The velocity is in animated distances
+            // per second, so it is scaled to the distance of the new transition.
+            let velocity = match &*self.transition.borrow() {
+                Some(running) => {
+                    let velocity = self.velocity(running, (now - running.start).as_secs_f64());
+
+                    match (running.from.displacement(&running.to), from.displacement(&to)) {
+                        (Some(previous), Some(next)) if next.magnitude2() > f64::EPSILON => {
+                            velocity * previous.dot(next) / next.magnitude2()
+                        }
+                        _ => 0.0,
+                    }
+                }
+                None => 0.0,
+            };
+
             let transition = Transition {
-                start: Instant::now(),
-                from: self.value.borrow().clone(),
-                to: self.inner.value().clone(),
+                start: now,
+                simulation: self.simulation.map(|simulation| simulation.with_initial_velocity(velocity)),
+                from,
+                to,
             };
 
             *self.transition.borrow_mut() = Some(transition);
         }
 
         let remove = if let Some(transition) = &*self.transition.borrow() {
-            let percentage = transition.start.elapsed().as_secs_f64() / self.duration.as_secs_f64();
-            if percentage > 1.0 {
+            let elapsed = transition.start.elapsed().as_secs_f64();
+
+            if self.is_finished(transition, elapsed) {
                 *self.value.borrow_mut() = self.inner.value().clone();
+                true
             } else {
-                let animated = (self.curve)(percentage);
+                let animated = match &transition.simulation {
+                    Some(simulation) => simulation.value(elapsed),
+                    None => (self.curve)(elapsed / self.duration.as_secs_f64()),
+                };
 
                 let res = (self.interpolation)(&transition.from, &transition.to, animated);
 
                 *self.value.borrow_mut() = res;
+                false
             }
-
-            transition.start.elapsed() > self.duration
         } else {
             false
         };
@@ -182,3 +258,44 @@ pub trait ReadStateExtTransition<T>: ReadState<T=T> + Sized + Clone + 'static wh
 }
 
 impl<T: StateContract + Default + PartialEq + Animatable<T>, S> ReadStateExtTransition<T> for S where S: ReadState<T=T> + Sized + Clone + 'static {}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+
+    use crate::animation::Spring;
+    use crate::state::{LocalState, ReadState, State, TransitionState};
+    use crate::time::Duration;
+
+    #[test]
+    fn transition_finishes_at_the_target() {
+        let mut target = LocalState::new(0.0);
+        let transition = TransitionState::new(target.clone()).duration(Duration::from_millis(20));
+
+        assert_eq!(*transition.value(), 0.0);
+
+        target.set_value(10.0);
+        assert!(*transition.value() < 10.0);
+
+        sleep(Duration::from_millis(40));
+
+        assert_eq!(*transition.value(), 10.0);
+        assert!(transition.transition.borrow().is_none());
+    }
+
+    #[test]
+    fn spring_transition_finishes_when_settled() {
+        let mut target = LocalState::new(0.0);
+        let transition = TransitionState::new(target.clone()).spring(Spring::new(40000.0, 400.0));
+
+        assert_eq!(*transition.value(), 0.0);
+
+        target.set_value(10.0);
+        assert!(*transition.value() < 10.0);
+
+        sleep(Duration::from_millis(200));
+
+        assert_eq!(*transition.value(), 10.0);
+        assert!(transition.transition.borrow().is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use std::rc::Rc;
 
+use crate::common::any_debug::debug_fingerprint;
 use crate::environment::Environment;
 use crate::state::{
     StateSync, ReadState, State, Functor, IntoReadState, Fn2,
@@ -253,13 +254,19 @@ macro_rules! tuple_state {
             MAP: Fn(&mut Environment, $(&$type),*) -> TO + Clone + 'static
         > StateSync for $env_map_name<MAP, $($type),*, TO, $($type2),*> {
             fn sync(&mut self, env: &mut Environment) -> bool {
+                let mut updated = false;
+
                 $(
-                    self.$name.sync(env);
+                    updated |= self.$name.sync(env);
                 )*
 
-                self.value = (self.map)(env, $(&*self.$name.value()),*);
+                // The environment is not able to report changes, so the value is compared with
+                // the previous value instead.
+                let value = (self.map)(env, $(&*self.$name.value()),*);
+                updated |= debug_fingerprint(&value) != debug_fingerprint(&self.value);
+                self.value = value;
 
-                true // We could check if the value changed with PartialEq
+                updated
             }
         }
 
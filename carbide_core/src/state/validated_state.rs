@@ -0,0 +1,611 @@
+use std::cell::{Cell, RefCell};
+use std::fmt::{Debug, Display, Formatter};
+use std::future::Future;
+use std::ops::RangeInclusive;
+use std::rc::{Rc, Weak};
+
+use futures::future::BoxFuture;
+use futures::FutureExt;
+
+use crate::asynchronous::spawn_task;
+use crate::environment::{Environment, EnvironmentKey};
+use crate::state::{AnyReadState, AnyState, Fn2, Functor, IntoReadState, LocalState, Map1, ReadState, RMap1, State, StateContract, StateSync, ValueRef, ValueRefMut};
+
+/// The result of validating the value of a [ValidatedState].
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum ValidationResult {
+    #[default]
+    Valid,
+    /// One or more async validators are still running.
+    Pending,
+    /// The value is invalid, with a message describing why.
+    Invalid(String),
+}
+
+impl ValidationResult {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, ValidationResult::Valid)
+    }
+
+    pub fn is_pending(&self) -> bool {
+        matches!(self, ValidationResult::Pending)
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            ValidationResult::Invalid(message) => Some(message),
+            _ => None,
+        }
+    }
+}
+
+crate::impl_state_value!(ValidationResult);
+
+/// A check of a value, returning an error message if the value is invalid. Closures taking a
+/// reference to the value and returning `Result<(), String>` are validators.
+pub trait Validator<T>: 'static {
+    fn validate(&self, value: &T) -> Result<(), String>;
+}
+
+impl<T, F: Fn(&T) -> Result<(), String> + 'static> Validator<T> for F {
+    fn validate(&self, value: &T) -> Result<(), String> {
+        self(value)
+    }
+}
+
+/// A check of a value that completes in the background, for example checking if a user name is
+/// taken. The returned future must own the data it needs from the value.
+pub trait AsyncValidator<T>: 'static {
+    fn validate(&self, value: &T) -> BoxFuture<'static, Result<(), String>>;
+}
+
+impl<T, F: Fn(&T) -> R + 'static, R: Future<Output=Result<(), String>> + Send + 'static> AsyncValidator<T> for F {
+    fn validate(&self, value: &T) -> BoxFuture<'static, Result<(), String>> {
+        self(value).boxed()
+    }
+}
+
+/// Requires the value to be present: a string that is not blank, an option that is some, or a
+/// parsed value whose text is not blank.
+#[derive(Clone, Debug)]
+pub struct Required {
+    message: String,
+}
+
+impl Required {
+    pub fn new(message: impl Into<String>) -> Required {
+        Required { message: message.into() }
+    }
+}
+
+impl Validator<String> for Required {
+    fn validate(&self, value: &String) -> Result<(), String> {
+        if value.trim().is_empty() {
+            return Err(self.message.clone());
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Validator<Option<T>> for Required {
+    fn validate(&self, value: &Option<T>) -> Result<(), String> {
+        value.as_ref().map(|_| ()).ok_or_else(|| self.message.clone())
+    }
+}
+
+impl<T> Validator<Result<T, String>> for Required {
+    fn validate(&self, value: &Result<T, String>) -> Result<(), String> {
+        match value {
+            Err(text) if text.trim().is_empty() => Err(self.message.clone()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Requires a parsed value to have been parsed successfully. The text that could not be parsed
+/// is kept as the error of the value, see [ConvertInto](crate::state::ConvertInto).
+#[derive(Clone, Debug)]
+pub struct Parsed {
+    message: String,
+}
+
+impl Parsed {
+    pub fn new(message: impl Into<String>) -> Parsed {
+        Parsed { message: message.into() }
+    }
+}
+
+impl<T> Validator<Result<T, String>> for Parsed {
+    fn validate(&self, value: &Result<T, String>) -> Result<(), String> {
+        value.as_ref().map(|_| ()).map_err(|_| self.message.clone())
+    }
+}
+
+/// Requires the value to be within the range. Values that could not be parsed are not checked.
+#[derive(Clone, Debug)]
+pub struct InRange<T> {
+    range: RangeInclusive<T>,
+    message: String,
+}
+
+impl<T: PartialOrd + Display> InRange<T> {
+    /// Returns a validator with a message stating the range.
+    pub fn new(range: RangeInclusive<T>) -> InRange<T> {
+        let message = format!("Must be between {} and {}", range.start(), range.end());
+        InRange { range, message }
+    }
+
+    pub fn message(self, message: impl Into<String>) -> InRange<T> {
+        InRange { message: message.into(), ..self }
+    }
+}
+
+impl<T: PartialOrd + 'static> Validator<T> for InRange<T> {
+    fn validate(&self, value: &T) -> Result<(), String> {
+        if !self.range.contains(value) {
+            return Err(self.message.clone());
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: PartialOrd + 'static> Validator<Result<T, String>> for InRange<T> {
+    fn validate(&self, value: &Result<T, String>) -> Result<(), String> {
+        match value {
+            Ok(value) => Validator::<T>::validate(self, value),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+/// Requires the whole string to match the regular expression.
+#[cfg(feature = "regex")]
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    regex: regex::Regex,
+    message: String,
+}
+
+#[cfg(feature = "regex")]
+impl Pattern {
+    /// Returns an error if the pattern is not a valid regular expression.
+    pub fn new(pattern: &str, message: impl Into<String>) -> Result<Pattern, regex::Error> {
+        let regex = regex::Regex::new(&format!("^(?:{})$", pattern))?;
+
+        Ok(Pattern { regex, message: message.into() })
+    }
+}
+
+#[cfg(feature = "regex")]
+impl Validator<String> for Pattern {
+    fn validate(&self, value: &String) -> Result<(), String> {
+        if !self.regex.is_match(value) {
+            return Err(self.message.clone());
+        }
+
+        Ok(())
+    }
+}
+
+/// # Validated state
+/// Wraps a state and validates its value each time it changes. The validators are checked in the
+/// order they are added, and the message of the first failing validator is the result. Async
+/// validators are only started if all other validators pass, and the result is pending until
+/// they complete.
+///
+/// A text input given a validated state shows the result automatically, by way of the
+/// [FieldValidation] it places in the environment. When the state is synced within a form, the
+/// result is included in the validity of the [FormState].
+///
+/// ```ignore
+/// let name = ValidatedState::new(LocalState::new("".to_string()))
+///     .required("A name is required")
+///     .validator(|name: &String| if name.len() > 20 { Err("Too long".to_string()) } else { Ok(()) });
+///
+/// let age = ValidatedState::new(LocalState::new(Ok::<u32, String>(30)))
+///     .parsed("Must be a number")
+///     .in_range(18..=120);
+/// ```
+#[derive(Clone)]
+pub struct ValidatedState<S>
+    where
+        S: State,
+{
+    state: S,
+    inner: Rc<Validation<S::T>>,
+}
+
+struct Validation<T: StateContract> {
+    validators: RefCell<Vec<Box<dyn Validator<T>>>>,
+    async_validators: RefCell<Vec<Box<dyn AsyncValidator<T>>>>,
+    result: LocalState<ValidationResult>,
+    field: RefCell<Option<FieldValidation>>,
+    /// Incremented every time the value is validated, such that results of earlier async
+    /// validations are ignored.
+    generation: Cell<u64>,
+    form: RefCell<Option<FormState>>,
+}
+
+impl<S: State> ValidatedState<S> {
+    pub fn new(state: S) -> ValidatedState<S> {
+        let state = ValidatedState {
+            state,
+            inner: Rc::new(Validation {
+                validators: RefCell::new(vec![]),
+                async_validators: RefCell::new(vec![]),
+                result: LocalState::new(ValidationResult::Valid),
+                field: RefCell::new(None),
+                generation: Cell::new(0),
+                form: RefCell::new(None),
+            }),
+        };
+
+        state.validate();
+        state
+    }
+
+    pub fn validator(self, validator: impl Validator<S::T>) -> ValidatedState<S> {
+        self.inner.validators.borrow_mut().push(Box::new(validator));
+        self.validate();
+        self
+    }
+
+    pub fn async_validator(self, validator: impl AsyncValidator<S::T>) -> ValidatedState<S> {
+        self.inner.async_validators.borrow_mut().push(Box::new(validator));
+        self.validate();
+        self
+    }
+
+    pub fn required(self, message: impl Into<String>) -> ValidatedState<S> where Required: Validator<S::T> {
+        self.validator(Required::new(message))
+    }
+
+    pub fn parsed(self, message: impl Into<String>) -> ValidatedState<S> where Parsed: Validator<S::T> {
+        self.validator(Parsed::new(message))
+    }
+
+    pub fn in_range<T: PartialOrd + Display>(self, range: RangeInclusive<T>) -> ValidatedState<S> where InRange<T>: Validator<S::T> {
+        self.validator(InRange::new(range))
+    }
+
+    /// Returns an error if the pattern is not a valid regular expression.
+    #[cfg(feature = "regex")]
+    pub fn pattern(self, pattern: &str, message: impl Into<String>) -> Result<ValidatedState<S>, regex::Error> where Pattern: Validator<S::T> {
+        Ok(self.validator(Pattern::new(pattern, message)?))
+    }
+
+    /// The result of validating the current value.
+    pub fn result(&self) -> impl ReadState<T=ValidationResult> {
+        self.inner.result.clone()
+    }
+
+    pub fn is_valid(&self) -> impl ReadState<T=bool> {
+        Map1::read_map(self.inner.result.clone(), |result| result.is_valid())
+    }
+
+    fn validate(&self) {
+        let generation = self.inner.generation.get() + 1;
+        self.inner.generation.set(generation);
+
+        let value = self.state.value();
+
+        for validator in self.inner.validators.borrow().iter() {
+            if let Err(message) = validator.validate(&*value) {
+                self.inner.set_result(ValidationResult::Invalid(message));
+                return;
+            }
+        }
+
+        let validations = self.inner.async_validators.borrow()
+            .iter()
+            .map(|validator| validator.validate(&*value))
+            .collect::<Vec<_>>();
+
+        if validations.is_empty() {
+            self.inner.set_result(ValidationResult::Valid);
+            return;
+        }
+
+        self.inner.set_result(ValidationResult::Pending);
+
+        let inner = Rc::downgrade(&self.inner);
+
+        spawn_task(async move {
+            for validation in validations {
+                validation.await?;
+            }
+
+            Ok(())
+        }, move |result: Result<(), String>, _ctx| {
+            let Some(inner) = inner.upgrade() else {
+                return;
+            };
+
+            // The value has changed since the validation was started
+            if inner.generation.get() != generation {
+                return;
+            }
+
+            inner.set_result(match result {
+                Ok(()) => ValidationResult::Valid,
+                Err(message) => ValidationResult::Invalid(message),
+            });
+        });
+    }
+}
+
+impl<T: StateContract> Validation<T> {
+    fn set_result(&self, result: ValidationResult) {
+        if *self.result.value() != result {
+            self.result.clone().set_value(result);
+        }
+
+        if let Some(form) = &*self.form.borrow() {
+            form.update();
+        }
+    }
+}
+
+impl<S: State> StateSync for ValidatedState<S> {
+    fn sync(&mut self, env: &mut Environment) -> bool {
+        // Register with the closest form the first time the state is synced within one
+        if self.inner.form.borrow().is_none() {
+            if let Some(form) = env.get::<FormState>() {
+                let field: Rc<dyn FormField> = self.inner.clone();
+                form.register(Rc::downgrade(&field));
+                *self.inner.form.borrow_mut() = Some(form.clone());
+                form.update();
+            }
+        }
+
+        // Attach the result to the closest field the first time the state is synced within one
+        if self.inner.field.borrow().is_none() {
+            if let Some(field) = env.get::<FieldValidation>() {
+                field.attach(self.inner.result.clone());
+                *self.inner.field.borrow_mut() = Some(field.clone());
+            }
+        }
+
+        let updated = self.state.sync(env);
+
+        if updated {
+            self.validate();
+        }
+
+        updated
+    }
+}
+
+impl<S: State> AnyReadState for ValidatedState<S> {
+    type T = S::T;
+    fn value_dyn(&self) -> ValueRef<'_, S::T> {
+        self.state.value()
+    }
+}
+
+impl<S: State> AnyState for ValidatedState<S> {
+    fn value_dyn_mut(&mut self) -> ValueRefMut<'_, S::T> {
+        // The value is validated when the state is next synced
+        self.state.value_mut()
+    }
+
+    fn set_value_dyn(&mut self, value: S::T) {
+        self.state.set_value(value);
+        self.validate();
+    }
+}
+
+impl<S: State> Debug for ValidatedState<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidatedState")
+            .field("value", &*self.value())
+            .field("result", &*self.inner.result.value())
+            .finish()
+    }
+}
+
+impl<T: StateContract, S: State> Functor<T> for ValidatedState<S> where ValidatedState<S>: IntoReadState<T> {
+    // Can be simplified once this is stabilized: https://github.com/rust-lang/rust/issues/63063
+    type Output<G: StateContract, F: Fn2<T, G>> = RMap1<F, T, G, <ValidatedState<S> as IntoReadState<T>>::Output>;
+
+    fn map<U: StateContract, F: Fn2<T, U>>(self, f: F) -> Self::Output<U, F> {
+        Map1::read_map(self.into_read_state(), f)
+    }
+}
+
+trait FormField {
+    fn result(&self) -> ValidationResult;
+}
+
+impl<T: StateContract> FormField for Validation<T> {
+    fn result(&self) -> ValidationResult {
+        self.result.value().clone()
+    }
+}
+
+/// # Form state
+/// Aggregates the results of the validated states synced within it. The form is valid when all
+/// its fields that are still alive are valid, and is typically used to enable a submit button.
+/// The form state is placed in the environment by the `Form` container in carbide_controls.
+#[derive(Clone)]
+pub struct FormState {
+    fields: Rc<RefCell<Vec<Weak<dyn FormField>>>>,
+    valid: LocalState<bool>,
+}
+
+impl FormState {
+    pub fn new() -> FormState {
+        FormState {
+            fields: Rc::new(RefCell::new(vec![])),
+            valid: LocalState::new(true),
+        }
+    }
+
+    pub fn is_valid(&self) -> impl ReadState<T=bool> {
+        self.valid.clone()
+    }
+
+    /// Returns the messages of the invalid fields of the form.
+    pub fn messages(&self) -> Vec<String> {
+        self.fields.borrow()
+            .iter()
+            .filter_map(|field| field.upgrade())
+            .filter_map(|field| field.result().message().map(|message| message.to_string()))
+            .collect()
+    }
+
+    fn register(&self, field: Weak<dyn FormField>) {
+        self.fields.borrow_mut().push(field);
+    }
+
+    fn update(&self) {
+        let mut fields = self.fields.borrow_mut();
+        fields.retain(|field| field.strong_count() > 0);
+
+        let valid = fields.iter()
+            .filter_map(|field| field.upgrade())
+            .all(|field| field.result().is_valid());
+
+        if *self.valid.value() != valid {
+            self.valid.clone().set_value(valid);
+        }
+    }
+}
+
+impl Default for FormState {
+    fn default() -> Self {
+        FormState::new()
+    }
+}
+
+impl Debug for FormState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FormState")
+            .field("fields", &self.fields.borrow().len())
+            .field("valid", &*self.valid.value())
+            .finish()
+    }
+}
+
+impl EnvironmentKey for FormState {
+    type Value = FormState;
+}
+
+/// # Field validation
+/// The result of the [ValidatedState] synced within a field, for example the text of a text
+/// input. The field places it in the environment of its content, and the validated state attaches
+/// its result the first time it is synced there. Until a result is attached, the field is valid.
+#[derive(Clone, Debug, Default)]
+pub struct FieldValidation {
+    result: Rc<RefCell<Option<LocalState<ValidationResult>>>>,
+}
+
+impl FieldValidation {
+    pub fn new() -> FieldValidation {
+        FieldValidation::default()
+    }
+
+    fn attach(&self, result: LocalState<ValidationResult>) {
+        *self.result.borrow_mut() = Some(result);
+    }
+}
+
+impl StateSync for FieldValidation {
+    fn sync(&mut self, env: &mut Environment) -> bool {
+        match &mut *self.result.borrow_mut() {
+            Some(result) => result.sync(env),
+            None => false,
+        }
+    }
+}
+
+impl AnyReadState for FieldValidation {
+    type T = ValidationResult;
+    fn value_dyn(&self) -> ValueRef<'_, ValidationResult> {
+        match &*self.result.borrow() {
+            Some(result) => ValueRef::Owned(result.value().clone()),
+            None => ValueRef::Owned(ValidationResult::Valid),
+        }
+    }
+}
+
+impl EnvironmentKey for FieldValidation {
+    type Value = FieldValidation;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::Environment;
+    use crate::state::{FieldValidation, FormState, LocalState, ReadState, State, StateSync, ValidatedState, ValidationResult};
+
+    #[test]
+    fn first_failing_validator_is_reported() {
+        let mut age = ValidatedState::new(LocalState::new(Ok::<u32, String>(30)))
+            .required("Age is required")
+            .parsed("Must be a number")
+            .in_range(18..=120);
+
+        let result = age.result();
+        assert_eq!(*result.value(), ValidationResult::Valid);
+
+        age.set_value(Err("".to_string()));
+        assert_eq!(result.value().message(), Some("Age is required"));
+
+        age.set_value(Err("abc".to_string()));
+        assert_eq!(result.value().message(), Some("Must be a number"));
+
+        age.set_value(Ok(10));
+        assert_eq!(result.value().message(), Some("Must be between 18 and 120"));
+    }
+
+    #[test]
+    fn form_is_valid_when_all_fields_are() {
+        let form = FormState::new();
+        let valid = form.is_valid();
+        let mut env = Environment::new();
+
+        let mut name = ValidatedState::new(LocalState::new("".to_string()))
+            .required("A name is required");
+
+        let mut email = ValidatedState::new(LocalState::new("a@b.c".to_string()))
+            .validator(|email: &String| if email.contains('@') { Ok(()) } else { Err("Invalid email".to_string()) });
+
+        env.with::<FormState>(&form, |env| {
+            name.sync(env);
+            email.sync(env);
+        });
+
+        assert!(!*valid.value());
+        assert_eq!(form.messages(), vec!["A name is required".to_string()]);
+
+        name.set_value("Holger".to_string());
+        assert!(*valid.value());
+
+        drop(name);
+        email.set_value("invalid".to_string());
+        assert!(!*valid.value());
+        assert_eq!(form.messages(), vec!["Invalid email".to_string()]);
+    }
+
+    #[test]
+    fn field_shows_the_result_of_the_state_synced_within_it() {
+        let field = FieldValidation::new();
+        let mut env = Environment::new();
+
+        let mut name = ValidatedState::new(LocalState::new("".to_string()))
+            .required("A name is required");
+
+        assert_eq!(*field.value(), ValidationResult::Valid);
+
+        env.with::<FieldValidation>(&field, |env| {
+            name.sync(env);
+        });
+
+        assert_eq!(field.value().message(), Some("A name is required"));
+
+        name.set_value("Holger".to_string());
+        assert_eq!(*field.value(), ValidationResult::Valid);
+    }
+}
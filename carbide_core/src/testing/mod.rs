@@ -0,0 +1,3 @@
+pub use test_harness::*;
+
+mod test_harness;
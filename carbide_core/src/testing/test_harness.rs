@@ -0,0 +1,625 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::animation::AnimationManager;
+use crate::application::ApplicationManager;
+use crate::draw::{Alignment, Dimension, ImageContext, NOOPImageContext, Position, Rect, Scalar};
+use crate::environment::Environment;
+use crate::event::{EventId, EventSink, Key, KeyboardEvent, KeyboardEventContext, ModifierKey, MOUSE_CLICK_MAX_DISTANCE, MouseButton, MouseEvent, MouseEventContext, NoopEventSink, OtherEvent, OtherEventContext, WindowEvent, WindowEventContext};
+use crate::focus::{Focus, FocusContext, FocusManager, Refocus};
+use crate::layout::LayoutContext;
+use crate::lifecycle::{InitializationContext, UpdateContext};
+use crate::mouse_position::MousePositionKey;
+use crate::scene::SceneManager;
+use crate::text::{NOOPTextContext, TextContext};
+use crate::time::*;
+use crate::widget::managers::{ShortcutManager, ShortcutPressed, ShortcutReleased};
+use crate::widget::{AnyWidget, WidgetId};
+
+/// # Test harness
+/// A harness for driving a widget tree without a window. The harness owns the environment with
+/// the managers that a running application would install, and dispatches events to the root
+/// widget in the same way as a window does. After each event, focus requests are handled and
+/// the widget tree is updated and laid out again, such that tests can assert on positions,
+/// dimensions and state values.
+///
+/// The text context defaults to a [NOOPTextContext], that panics if used. If the tree contains
+/// text, a real text context should be provided using [TestHarness::with_text_context].
+///
+/// ```ignore
+/// let counter = LocalState::new(0);
+///
+/// let mut harness = TestHarness::new(
+///     Rectangle::new()
+///         .frame(100.0, 50.0)
+///         .on_click(closure!(|_| { *$counter += 1; })),
+///     Dimension::new(200.0, 200.0),
+/// );
+///
+/// harness.click(Position::new(100.0, 100.0));
+///
+/// assert_eq!(*counter.value(), 1);
+/// ```
+pub struct TestHarness<W: AnyWidget> {
+    widget: W,
+    dimension: Dimension,
+    scale_factor: Scalar,
+    environment: Environment<'static>,
+    focus_manager: FocusManager,
+    animation_manager: AnimationManager,
+    application_manager: ApplicationManager,
+    text: Box<dyn TextContext>,
+    image: Box<dyn ImageContext>,
+    mouse_position: Position,
+    modifiers: ModifierKey,
+    pressed_buttons: HashMap<MouseButton, (MouseEvent, Instant)>,
+    last_click: Option<(Instant, MouseEvent)>,
+    event_id: u32,
+    initialized: bool,
+}
+
+impl<W: AnyWidget> TestHarness<W> {
+    /// Create a new harness with the widget as the root, given the dimension of the window
+    /// it should be laid out within.
+    pub fn new(widget: W, dimension: Dimension) -> TestHarness<W> {
+        TestHarness {
+            widget,
+            dimension,
+            scale_factor: 1.0,
+            environment: Environment::new(),
+            focus_manager: FocusManager::new(),
+            animation_manager: AnimationManager::new(),
+            application_manager: ApplicationManager::new(),
+            text: Box::new(NOOPTextContext),
+            image: Box::new(NOOPImageContext),
+            mouse_position: Position::origin(),
+            modifiers: ModifierKey::EMPTY,
+            pressed_buttons: HashMap::new(),
+            last_click: None,
+            event_id: 0,
+            initialized: false,
+        }
+    }
+
+    pub fn with_text_context(mut self, text: impl TextContext + 'static) -> Self {
+        self.text = Box::new(text);
+        self
+    }
+
+    pub fn with_image_context(mut self, image: impl ImageContext + 'static) -> Self {
+        self.image = Box::new(image);
+        self
+    }
+
+    pub fn with_scale_factor(mut self, scale_factor: Scalar) -> Self {
+        self.scale_factor = scale_factor;
+        self
+    }
+
+    pub fn widget(&self) -> &W {
+        &self.widget
+    }
+
+    pub fn widget_mut(&mut self) -> &mut W {
+        &mut self.widget
+    }
+
+    /// The environment that is used for all the events. Values inserted will be available
+    /// for the widgets in the tree.
+    pub fn environment(&mut self) -> &mut Environment<'static> {
+        &mut self.environment
+    }
+
+    pub fn text_context(&mut self) -> &mut dyn TextContext {
+        &mut *self.text
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.dimension
+    }
+
+    pub fn mouse_position(&self) -> Position {
+        self.mouse_position
+    }
+
+    /// Set the modifier keys that will be held for the following events.
+    pub fn set_modifiers(&mut self, modifiers: ModifierKey) {
+        self.modifiers = modifiers;
+    }
+
+    /// Run update and layout on the widget tree, similar to how a window does it each frame.
+    pub fn update(&mut self) {
+        self.ensure_initialized();
+        self.begin_frame();
+        self.layout();
+    }
+
+    /// Resize the window, and send the resize event to the widget tree.
+    pub fn resize(&mut self, dimension: Dimension) {
+        self.dimension = dimension;
+        self.window_event(&WindowEvent::Resize(dimension));
+    }
+
+    /// Find the bounding box of the widget with the given id, if it exists in the tree. The tree
+    /// is laid out first, if it has not been already.
+    pub fn bounding_box(&mut self, id: WidgetId) -> Option<Rect> {
+        self.ensure_initialized();
+
+        fn find(widget: &mut dyn AnyWidget, id: WidgetId, result: &mut Option<Rect>) {
+            if widget.id() == id {
+                *result = Some(widget.bounding_box());
+                return;
+            }
+
+            widget.foreach_child(&mut |child| {
+                if result.is_none() {
+                    find(child, id, result);
+                }
+            });
+        }
+
+        let mut result = None;
+        find(&mut self.widget, id, &mut result);
+        result
+    }
+
+    /// Get the id of the focused widget in the tree, if any.
+    pub fn focused(&mut self) -> Option<WidgetId> {
+        self.ensure_initialized();
+
+        fn find(widget: &mut dyn AnyWidget, result: &mut Option<WidgetId>) {
+            if widget.is_focusable() && widget.get_focus() == Focus::Focused {
+                *result = Some(widget.id());
+                return;
+            }
+
+            widget.foreach_child(&mut |child| {
+                if result.is_none() {
+                    find(child, result);
+                }
+            });
+        }
+
+        let mut result = None;
+        find(&mut self.widget, &mut result);
+        result
+    }
+
+    /// Move the mouse to the position. Drag events are sent for all the pressed buttons.
+    pub fn mouse_move(&mut self, to: Position) {
+        let from = self.mouse_position;
+        self.mouse_position = to;
+
+        if from == to {
+            return;
+        }
+
+        let delta_xy = to - from;
+
+        self.mouse_event(&MouseEvent::Move {
+            from,
+            to,
+            delta_xy,
+            modifiers: self.modifiers,
+        });
+
+        let drags = self.pressed_buttons.iter()
+            .filter_map(|(button, (event, _))| match event {
+                MouseEvent::Press { position: origin, .. } => Some(MouseEvent::Drag {
+                    button: *button,
+                    origin: *origin,
+                    from,
+                    to,
+                    delta_xy,
+                    total_delta_xy: to - *origin,
+                    modifiers: self.modifiers,
+                }),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for drag in &drags {
+            self.mouse_event(drag);
+        }
+    }
+
+    /// Press the mouse button at the current mouse position.
+    pub fn mouse_press(&mut self, button: MouseButton) {
+        let event = MouseEvent::Press {
+            id: self.next_id(),
+            button,
+            position: self.mouse_position,
+            modifiers: self.modifiers,
+        };
+
+        self.mouse_event(&event);
+
+        self.pressed_buttons.insert(button, (event, Instant::now()));
+    }
+
+    /// Release the mouse button at the current mouse position. If the mouse has not moved
+    /// too far since the press, a click event is sent as well.
+    pub fn mouse_release(&mut self, button: MouseButton) {
+        let Some((pressed_event, pressed_time)) = self.pressed_buttons.remove(&button) else {
+            return;
+        };
+
+        let event = MouseEvent::Release {
+            id: self.next_id(),
+            button,
+            position: self.mouse_position,
+            modifiers: self.modifiers,
+            press_id: pressed_event.id(),
+            duration: Instant::now().duration_since(pressed_time),
+        };
+
+        self.mouse_event(&event);
+
+        let is_click = self.mouse_position.dist(&pressed_event.get_current_mouse_position()) < MOUSE_CLICK_MAX_DISTANCE;
+
+        if !is_click {
+            self.last_click = None;
+            return;
+        }
+
+        let click_number = MouseEvent::click_number(self.last_click.as_ref(), button, self.mouse_position);
+
+        let event = if click_number == 1 {
+            MouseEvent::Click(button, self.mouse_position, self.modifiers)
+        } else {
+            MouseEvent::NClick(button, self.mouse_position, self.modifiers, click_number)
+        };
+
+        self.mouse_event(&event);
+
+        self.last_click = Some((Instant::now(), event));
+    }
+
+    /// Move the mouse to the position and click with the left mouse button.
+    pub fn click(&mut self, position: Position) {
+        self.mouse_move(position);
+        self.mouse_press(MouseButton::Left);
+        self.mouse_release(MouseButton::Left);
+    }
+
+    /// Scroll at the current mouse position.
+    pub fn scroll(&mut self, x: Scalar, y: Scalar) {
+        self.mouse_event(&MouseEvent::Scroll {
+            x,
+            y,
+            mouse_position: self.mouse_position,
+            modifiers: self.modifiers,
+        });
+    }
+
+    /// Press the key with the current modifiers. Shortcuts and tab focus changes are
+    /// handled in the same way as in a window.
+    pub fn key_press(&mut self, key: Key) {
+        let event = KeyboardEvent::Press {
+            key: key.clone(),
+            modifiers: self.modifiers,
+            no_modifier_key: key.clone(),
+        };
+
+        let (prevent_default, shortcut) = self.dispatch_keyboard_event(&event);
+
+        if let Some(shortcut) = shortcut {
+            self.other_event(&OtherEvent::new(ShortcutPressed(shortcut)));
+            return;
+        }
+
+        if !prevent_default && key == Key::Tab {
+            if self.modifiers.shift_key() {
+                self.focus_manager.request_focus(Refocus::FocusPrevious);
+            } else if self.modifiers.is_empty() {
+                self.focus_manager.request_focus(Refocus::FocusNext);
+            }
+        }
+
+        self.after_event();
+    }
+
+    /// Release the key with the current modifiers.
+    pub fn key_release(&mut self, key: Key) {
+        let event = KeyboardEvent::Release {
+            key: key.clone(),
+            modifiers: self.modifiers,
+            no_modifier_key: key,
+        };
+
+        let (_, shortcut) = self.dispatch_keyboard_event(&event);
+
+        if let Some(shortcut) = shortcut {
+            self.other_event(&OtherEvent::new(ShortcutReleased(shortcut)));
+            return;
+        }
+
+        self.after_event();
+    }
+
+    /// Press and release the key.
+    pub fn key(&mut self, key: Key) {
+        self.key_press(key.clone());
+        self.key_release(key);
+    }
+
+    /// Type the text character by character, by pressing and releasing the keys.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.key(Key::Character(c.to_string()));
+        }
+    }
+
+    /// Send a mouse event to the widget tree.
+    pub fn mouse_event(&mut self, event: &MouseEvent) {
+        self.ensure_initialized();
+        self.begin_frame();
+
+        self.with_env(|env, widget, text, image| {
+            widget.process_mouse_event(event, &mut MouseEventContext {
+                text,
+                image,
+                is_current: &true,
+                window_id: &0,
+                consumed: &mut false,
+                env,
+            });
+        });
+
+        self.after_event();
+    }
+
+    /// Send a keyboard event to the widget tree. Unlike [TestHarness::key_press], this does
+    /// not handle shortcuts and tab focus changes.
+    pub fn keyboard_event(&mut self, event: &KeyboardEvent) {
+        self.dispatch_keyboard_event(event);
+        self.after_event();
+    }
+
+    /// Send a window event to the widget tree.
+    pub fn window_event(&mut self, event: &WindowEvent) {
+        self.ensure_initialized();
+        self.begin_frame();
+
+        self.with_env(|env, widget, text, image| {
+            widget.process_window_event(event, &mut WindowEventContext {
+                text,
+                image,
+                env,
+                is_current: &true,
+                window_id: &0,
+            });
+        });
+
+        self.after_event();
+    }
+
+    /// Send an other event to the widget tree.
+    pub fn other_event(&mut self, event: &OtherEvent) {
+        self.ensure_initialized();
+        self.begin_frame();
+
+        self.with_env(|env, widget, text, image| {
+            widget.process_other_event(event, &mut OtherEventContext {
+                text,
+                image,
+                env,
+                is_current: &true,
+                is_consumed: &mut false,
+            });
+        });
+
+        self.after_event();
+    }
+
+    fn dispatch_keyboard_event(&mut self, event: &KeyboardEvent) -> (bool, Option<WidgetId>) {
+        self.ensure_initialized();
+        self.begin_frame();
+
+        let mut prevent_default = false;
+        let mut shortcut_manager = ShortcutManager::new();
+
+        self.with_env(|env, widget, text, image| {
+            env.with_mut::<ShortcutManager>(&mut shortcut_manager, |env| {
+                widget.process_keyboard_event(event, &mut KeyboardEventContext {
+                    text,
+                    image,
+                    env,
+                    is_current: &true,
+                    window_id: &0,
+                    prevent_default: &mut prevent_default,
+                });
+            });
+        });
+
+        (prevent_default, shortcut_manager.has_shortcut())
+    }
+
+    fn next_id(&mut self) -> EventId {
+        self.event_id += 1;
+        EventId::new(self.event_id)
+    }
+
+    fn begin_frame(&mut self) {
+        self.application_manager.begin_frame();
+        self.animation_manager.update_frame_time();
+    }
+
+    fn ensure_initialized(&mut self) {
+        if self.initialized {
+            return;
+        }
+
+        self.initialized = true;
+
+        self.with_env(|env, widget, _, _| {
+            widget.process_initialization(&mut InitializationContext {
+                env,
+            });
+        });
+
+        self.begin_frame();
+        self.layout();
+    }
+
+    /// Handle focus requests made during the event, and update the tree, similar to what
+    /// happens when a redraw is requested.
+    fn after_event(&mut self) {
+        if let Some(focus) = self.focus_manager.requested_focus() {
+            self.with_env(|env, widget, _, _| {
+                match focus {
+                    Refocus::FocusRequest => {
+                        widget.process_focus_request(&mut FocusContext {
+                            env,
+                            focus_count: &mut 0,
+                            available: &mut false,
+                        });
+                    }
+                    Refocus::FocusNext => {
+                        let mut count = 0;
+
+                        widget.process_focus_next(&mut FocusContext {
+                            env,
+                            focus_count: &mut count,
+                            available: &mut false,
+                        });
+
+                        if count == 0 {
+                            widget.process_focus_next(&mut FocusContext {
+                                env,
+                                focus_count: &mut 0,
+                                available: &mut true,
+                            });
+                        }
+                    }
+                    Refocus::FocusPrevious => {
+                        let mut count = 0;
+
+                        widget.process_focus_previous(&mut FocusContext {
+                            env,
+                            focus_count: &mut count,
+                            available: &mut false,
+                        });
+
+                        if count == 0 {
+                            widget.process_focus_previous(&mut FocusContext {
+                                env,
+                                focus_count: &mut 0,
+                                available: &mut true,
+                            });
+                        }
+                    }
+                }
+            });
+        }
+
+        self.layout();
+    }
+
+    fn layout(&mut self) {
+        let dimension = self.dimension;
+
+        self.with_env(|env, widget, text, image| {
+            widget.process_update(&mut UpdateContext {
+                text,
+                image,
+                env,
+            });
+
+            widget.process_calculate_size(dimension, &mut LayoutContext {
+                text,
+                image,
+                env,
+            });
+
+            widget.set_position(Alignment::Center.position(Position::origin(), dimension, widget.dimension()));
+
+            widget.position_children(Rect::new(Position::origin(), dimension), &mut LayoutContext {
+                text,
+                image,
+                env,
+            });
+        });
+    }
+
+    /// Run the function with the managers installed in the environment.
+    fn with_env(&mut self, f: impl FnOnce(&mut Environment, &mut W, &mut dyn TextContext, &mut dyn ImageContext)) {
+        let TestHarness {
+            widget,
+            dimension,
+            scale_factor,
+            environment,
+            focus_manager,
+            animation_manager,
+            application_manager,
+            text,
+            image,
+            mouse_position,
+            ..
+        } = self;
+
+        let mut scene_manager = SceneManager::new(
+            *scale_factor,
+            Dimension::new(dimension.width * *scale_factor, dimension.height * *scale_factor),
+        );
+
+        environment.with_mut::<AnimationManager>(animation_manager, |env| {
+            env.with_mut::<ApplicationManager>(application_manager, |env| {
+                env.with_mut::<FocusManager>(focus_manager, |env| {
+                    env.with_mut::<SceneManager>(&mut scene_manager, |env| {
+                        env.with::<MousePositionKey>(mouse_position, |env| {
+                            env.with::<dyn EventSink>(&(Arc::new(NoopEventSink) as Arc<dyn EventSink>), |env| {
+                                f(env, widget, &mut **text, &mut **image)
+                            })
+                        })
+                    })
+                })
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::draw::{Dimension, Position, Rect};
+    use crate::identifiable::Identifiable;
+    use crate::state::{LocalState, ReadState, State};
+    use crate::testing::TestHarness;
+    use crate::widget::{CommonWidget, MouseAreaActionContext, Rectangle, WidgetExt};
+
+    #[test]
+    fn layout_centers_root() {
+        let mut harness = TestHarness::new(
+            Rectangle::new().frame(100.0, 50.0),
+            Dimension::new(200.0, 200.0),
+        );
+
+        harness.update();
+
+        assert_eq!(harness.widget().position(), Position::new(50.0, 75.0));
+        assert_eq!(harness.widget().dimension(), Dimension::new(100.0, 50.0));
+    }
+
+    #[test]
+    fn click_inside_and_outside() {
+        let counter = LocalState::new(0);
+
+        let counter2 = counter.clone();
+        let widget = Rectangle::new()
+            .frame(100.0, 50.0)
+            .on_click(move |_: MouseAreaActionContext| {
+                *counter2.clone().value_mut() += 1;
+            });
+
+        let mut harness = TestHarness::new(widget, Dimension::new(200.0, 200.0));
+
+        let id = harness.widget().id();
+        assert_eq!(harness.bounding_box(id), Some(Rect::new(Position::new(50.0, 75.0), Dimension::new(100.0, 50.0))));
+
+        harness.click(Position::new(100.0, 100.0));
+        assert_eq!(*counter.value(), 1);
+
+        harness.click(Position::new(10.0, 10.0));
+        assert_eq!(*counter.value(), 1);
+    }
+}
@@ -0,0 +1,210 @@
+use std::ops::Range;
+
+use carbide_derive::StateValue;
+
+use crate::draw::{Color, Scalar};
+use crate::environment::{Environment, EnvironmentKey};
+use crate::text::{FontSize, FontStyle, FontWeight, TextDecoration, TextStyle};
+
+/// A string where ranges of the text have their own attributes. Text outside the spans uses the
+/// style of the widget showing the text.
+///
+/// ```ignore
+/// let text = AttributedText::new()
+///     .span("Bold", TextAttributes::new().bold())
+///     .push(" and ")
+///     .span("italic", TextAttributes::new().italic())
+///     .push(" with a ")
+///     .span("link", TextAttributes::new().link("https://example.com"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Default, StateValue)]
+pub struct AttributedText {
+    pub text: String,
+    /// The spans of the text. The ranges are byte ranges into the text, and are expected to be
+    /// ordered and not overlapping.
+    pub spans: Vec<TextSpan>,
+}
+
+impl AttributedText {
+    pub fn new() -> AttributedText {
+        AttributedText::default()
+    }
+
+    /// Append text using the style of the widget.
+    pub fn push(mut self, text: impl AsRef<str>) -> Self {
+        self.text.push_str(text.as_ref());
+        self
+    }
+
+    /// Append text with the given attributes.
+    pub fn span(mut self, text: impl AsRef<str>, attributes: TextAttributes) -> Self {
+        let start = self.text.len();
+        self.text.push_str(text.as_ref());
+
+        self.spans.push(TextSpan {
+            range: start..self.text.len(),
+            attributes,
+        });
+
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Get the span containing the byte index, if any.
+    pub fn span_at(&self, index: usize) -> Option<&TextSpan> {
+        self.spans.iter().find(|span| span.range.contains(&index))
+    }
+
+    /// Get the link of the span containing the byte index, if any.
+    pub fn link_at(&self, index: usize) -> Option<&str> {
+        self.span_at(index).and_then(|span| span.attributes.link.as_deref())
+    }
+}
+
+impl From<String> for AttributedText {
+    fn from(text: String) -> Self {
+        AttributedText {
+            text,
+            spans: vec![],
+        }
+    }
+}
+
+impl From<&str> for AttributedText {
+    fn from(text: &str) -> Self {
+        AttributedText::from(text.to_string())
+    }
+}
+
+/// A range of text with its own attributes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextSpan {
+    pub range: Range<usize>,
+    pub attributes: TextAttributes,
+}
+
+/// The attributes of a span of text. Attributes that are `None` use the value from the style
+/// of the widget showing the text.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TextAttributes {
+    pub family: Option<String>,
+    pub font_size: Option<FontSize>,
+    pub font_style: Option<FontStyle>,
+    pub font_weight: Option<FontWeight>,
+    pub text_decoration: Option<TextDecoration>,
+    pub color: Option<Color>,
+    /// The offset of the baseline of the span in points, where positive values raise the text.
+    /// This can be used for superscript and subscript.
+    pub baseline_offset: Scalar,
+    /// The link the span refers to. When the span is clicked, the link is passed to the
+    /// [LinkHandler] in the environment.
+    pub link: Option<String>,
+}
+
+impl TextAttributes {
+    pub fn new() -> TextAttributes {
+        TextAttributes::default()
+    }
+
+    pub fn family(mut self, family: impl Into<String>) -> Self {
+        self.family = Some(family.into());
+        self
+    }
+
+    pub fn font_size(mut self, font_size: FontSize) -> Self {
+        self.font_size = Some(font_size);
+        self
+    }
+
+    pub fn font_style(mut self, font_style: FontStyle) -> Self {
+        self.font_style = Some(font_style);
+        self
+    }
+
+    pub fn font_weight(mut self, font_weight: FontWeight) -> Self {
+        self.font_weight = Some(font_weight);
+        self
+    }
+
+    pub fn bold(self) -> Self {
+        self.font_weight(FontWeight::Bold)
+    }
+
+    pub fn italic(self) -> Self {
+        self.font_style(FontStyle::Italic)
+    }
+
+    pub fn text_decoration(mut self, text_decoration: TextDecoration) -> Self {
+        self.text_decoration = Some(text_decoration);
+        self
+    }
+
+    pub fn underline(self) -> Self {
+        self.text_decoration(TextDecoration::Underline)
+    }
+
+    pub fn strike_through(self) -> Self {
+        self.text_decoration(TextDecoration::StrikeThrough)
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn baseline_offset(mut self, offset: Scalar) -> Self {
+        self.baseline_offset = offset;
+        self
+    }
+
+    pub fn link(mut self, link: impl Into<String>) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    /// The style of the span, given the style of the widget showing the text.
+    pub fn resolve(&self, style: &TextStyle) -> TextStyle {
+        TextStyle {
+            family: self.family.clone().unwrap_or_else(|| style.family.clone()),
+            font_size: self.font_size.unwrap_or(style.font_size),
+            line_height: style.line_height,
+            font_style: self.font_style.unwrap_or(style.font_style),
+            font_weight: self.font_weight.unwrap_or(style.font_weight),
+            text_decoration: self.text_decoration.clone().unwrap_or_else(|| style.text_decoration.clone()),
+            color: self.color.or(style.color),
+            wrap: style.wrap,
+            direction: style.direction,
+        }
+    }
+}
+
+/// The function called with the link of a span, when the span is clicked.
+#[derive(Debug)]
+pub struct LinkHandler;
+
+impl EnvironmentKey for LinkHandler {
+    type Value = fn(&str, &mut Environment);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::text::{AttributedText, FontWeight, TextAttributes};
+
+    #[test]
+    fn spans_cover_the_appended_text() {
+        let text = AttributedText::new()
+            .span("Bold", TextAttributes::new().bold())
+            .push(" and ")
+            .span("link", TextAttributes::new().link("https://example.com"));
+
+        assert_eq!(text.text, "Bold and link");
+        assert_eq!(text.spans[0].range, 0..4);
+        assert_eq!(text.spans[1].range, 9..13);
+        assert_eq!(text.spans[0].attributes.font_weight, Some(FontWeight::Bold));
+        assert_eq!(text.link_at(10), Some("https://example.com"));
+        assert_eq!(text.link_at(5), None);
+    }
+}
@@ -1,9 +1,12 @@
-use crate::draw::Rect;
+use crate::draw::{Color, Rect};
 use crate::text::glyph::GlyphRenderMode;
 
 #[derive(Debug, Clone)]
 pub struct Glyph {
     pub bounding_box: Rect,
     pub texture_coords: Rect,
-    pub mode: GlyphRenderMode
+    pub mode: GlyphRenderMode,
+    /// The color of the span the glyph is part of. If `None`, the glyph is drawn using the
+    /// current style.
+    pub color: Option<Color>,
 }
\ No newline at end of file
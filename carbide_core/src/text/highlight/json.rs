@@ -0,0 +1,35 @@
+use crate::text::highlight::scanner::Scanner;
+use crate::text::highlight::{Token, TokenKind};
+
+pub(super) fn tokenize(text: &str) -> Vec<Token> {
+    let mut scanner = Scanner::new(text);
+
+    while let Some(c) = scanner.peek() {
+        let start = scanner.position();
+
+        if scanner.eat("\"") {
+            scanner.eat_until("\"", true);
+
+            // Strings followed by a colon are the keys of an object
+            if scanner.followed_by(":") {
+                scanner.token(TokenKind::Key, start);
+            } else {
+                scanner.token(TokenKind::String, start);
+            }
+        } else if c == '-' || c.is_ascii_digit() {
+            scanner.bump();
+            scanner.eat_while(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+            scanner.token(TokenKind::Number, start);
+        } else if c.is_ascii_alphabetic() {
+            scanner.eat_while(|c| c.is_ascii_alphabetic());
+
+            if matches!(&text[start..scanner.position()], "true" | "false" | "null") {
+                scanner.token(TokenKind::Constant, start);
+            }
+        } else {
+            scanner.bump();
+        }
+    }
+
+    scanner.finish()
+}
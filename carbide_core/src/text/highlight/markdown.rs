@@ -0,0 +1,144 @@
+use crate::text::highlight::scanner::Scanner;
+use crate::text::highlight::{Token, TokenKind};
+
+pub(super) fn tokenize(text: &str) -> Vec<Token> {
+    let mut scanner = Scanner::new(text);
+    let mut fence: Option<&str> = None;
+
+    while !scanner.is_done() {
+        let start = scanner.position();
+        let line = scanner.rest().split('\n').next().unwrap_or("");
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+
+        if let Some(marker) = fence {
+            // Lines within a fenced code block, including the closing fence
+            scanner.eat_line();
+            scanner.token(TokenKind::Code, start);
+
+            if trimmed.starts_with(marker) {
+                fence = None;
+            }
+        } else if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            scanner.eat_line();
+            scanner.token(TokenKind::Code, start);
+        } else if is_heading(trimmed) {
+            scanner.eat_line();
+            scanner.token(TokenKind::Heading, start);
+        } else if trimmed.starts_with('>') {
+            scanner.eat_line();
+            scanner.token(TokenKind::Quote, start);
+        } else {
+            if let Some(length) = list_marker(trimmed) {
+                scanner.eat(&line[..indent]);
+                scanner.eat(&trimmed[..length]);
+                scanner.token(TokenKind::ListMarker, start + indent);
+            }
+
+            inline(&mut scanner);
+        }
+
+        scanner.eat("\n");
+    }
+
+    scanner.finish()
+}
+
+fn is_heading(line: &str) -> bool {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    (1..=6).contains(&level) && line[level..].chars().next().is_none_or(|c| c == ' ')
+}
+
+/// The length of the marker of a list item, like "-", "*", "+" or "1.", if the line is a list item.
+fn list_marker(line: &str) -> Option<usize> {
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+
+    let length = if digits > 0 {
+        match line[digits..].chars().next() {
+            Some('.') | Some(')') => digits + 1,
+            _ => return None,
+        }
+    } else {
+        match line.chars().next() {
+            Some('-') | Some('*') | Some('+') => 1,
+            _ => return None,
+        }
+    };
+
+    match line[length..].chars().next() {
+        Some(' ') | Some('\t') => Some(length),
+        _ => None,
+    }
+}
+
+/// Tokenize the inline content until the end of the line. Inline elements do not span lines.
+fn inline(scanner: &mut Scanner) {
+    while let Some(c) = scanner.peek() {
+        if c == '\n' {
+            return;
+        }
+
+        let start = scanner.position();
+        let line = scanner.rest().split('\n').next().unwrap_or("");
+
+        if c == '`' {
+            let ticks = line.chars().take_while(|c| *c == '`').count();
+            let delimiter = &line[..ticks];
+
+            match line[ticks..].find(delimiter) {
+                Some(end) => {
+                    scanner.eat(&line[..ticks + end + ticks]);
+                    scanner.token(TokenKind::Code, start);
+                }
+                None => {
+                    scanner.eat(delimiter);
+                }
+            }
+        } else if let Some(length) = delimited(line, "**").or_else(|| delimited(line, "__")) {
+            scanner.eat(&line[..length]);
+            scanner.token(TokenKind::Strong, start);
+        } else if let Some(length) = delimited(line, "*").or_else(|| delimited(line, "_")) {
+            scanner.eat(&line[..length]);
+            scanner.token(TokenKind::Emphasis, start);
+        } else if let Some(length) = link(line) {
+            scanner.eat(&line[..length]);
+            scanner.token(TokenKind::Link, start);
+        } else {
+            // Skip words, such that underscores within words do not start emphasis
+            scanner.bump();
+
+            if c.is_alphanumeric() {
+                scanner.eat_while(|c| c.is_alphanumeric() || c == '_');
+            }
+        }
+    }
+}
+
+/// The length of the text enclosed by the delimiter at the start of the line, including the
+/// delimiters, if any. The enclosed text can not start with a space.
+fn delimited(line: &str, delimiter: &str) -> Option<usize> {
+    let content = line.strip_prefix(delimiter)?;
+
+    if content.starts_with([' ', '*', '_']) || content.is_empty() {
+        return None;
+    }
+
+    let end = content.find(delimiter)?;
+
+    Some(delimiter.len() * 2 + end)
+}
+
+/// The length of the link or image like [text](url) or ![description](url) at the start of the
+/// line, if any. Autolinks like <https://example.com> are also links.
+fn link(line: &str) -> Option<usize> {
+    if line.starts_with("<http") {
+        return line.find('>').map(|end| end + 1);
+    }
+
+    let start = if line.starts_with("![") { 2 } else if line.starts_with('[') { 1 } else { return None };
+    let text_end = start + line[start..].find("](")?;
+    let url_end = text_end + line[text_end..].find(')')?;
+
+    Some(url_end + 1)
+}
@@ -0,0 +1,226 @@
+//! Syntax highlighting of text. A [Highlighter] maps byte ranges of a text to attributes, and can
+//! be set on a [Text](crate::widget::Text) to style its content. The [Language] highlighter
+//! contains tokenizers for a few common languages.
+//!
+//! ```ignore
+//! Text::new("fn main() {}").highlight(Language::Rust)
+//! ```
+
+use std::fmt::Debug;
+use std::ops::Range;
+
+use dyn_clone::DynClone;
+
+use crate::environment::{Environment, EnvironmentColor, EnvironmentKeyable};
+use crate::text::{TextAttributes, TextSpan};
+
+mod scanner;
+mod rust;
+mod json;
+mod toml;
+mod markdown;
+
+/// Styles the byte ranges of a text, for example to color source code.
+pub trait Highlighter: Debug + DynClone {
+    /// Returns the spans of the text. The spans are expected to be ordered and not overlapping.
+    fn highlight(&self, text: &str, env: &Environment) -> Vec<TextSpan>;
+}
+
+dyn_clone::clone_trait_object!(Highlighter);
+
+impl Highlighter for Box<dyn Highlighter> {
+    fn highlight(&self, text: &str, env: &Environment) -> Vec<TextSpan> {
+        (**self).highlight(text, env)
+    }
+}
+
+/// The languages with a built-in tokenizer. Each language highlights its tokens using the colors
+/// of the environment, see [TokenKind::attributes].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    Rust,
+    Json,
+    Toml,
+    Markdown,
+}
+
+impl Language {
+    /// Get the language from a name or file extension, like the language of a fenced code block.
+    pub fn from_name(name: &str) -> Option<Language> {
+        match name.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Some(Language::Rust),
+            "json" => Some(Language::Json),
+            "toml" => Some(Language::Toml),
+            "markdown" | "md" => Some(Language::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Split the text into tokens. Text that is not part of a token, like whitespace and
+    /// punctuation, is left out.
+    pub fn tokenize(&self, text: &str) -> Vec<Token> {
+        match self {
+            Language::Rust => rust::tokenize(text),
+            Language::Json => json::tokenize(text),
+            Language::Toml => toml::tokenize(text),
+            Language::Markdown => markdown::tokenize(text),
+        }
+    }
+}
+
+impl Highlighter for Language {
+    fn highlight(&self, text: &str, env: &Environment) -> Vec<TextSpan> {
+        self.tokenize(text)
+            .into_iter()
+            .map(|token| TextSpan {
+                range: token.range,
+                attributes: token.kind.attributes(env),
+            })
+            .collect()
+    }
+}
+
+/// A range of the text with a kind.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// The byte range of the token in the text.
+    pub range: Range<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Keyword,
+    Type,
+    Function,
+    /// Macros and attributes.
+    Macro,
+    String,
+    Number,
+    /// Literals like true, false and null.
+    Constant,
+    Comment,
+    /// Keys of objects and tables.
+    Key,
+    /// Table headers.
+    Section,
+    Heading,
+    Emphasis,
+    Strong,
+    Code,
+    Link,
+    Quote,
+    ListMarker,
+}
+
+impl TokenKind {
+    /// The attributes used to highlight the kind of token, using the colors of the environment.
+    pub fn attributes(&self, env: &Environment) -> TextAttributes {
+        let color = |color: EnvironmentColor| {
+            let attributes = TextAttributes::new();
+
+            match color.get(env) {
+                Some(color) => attributes.color(color),
+                None => attributes,
+            }
+        };
+
+        match self {
+            TokenKind::Keyword => color(EnvironmentColor::Pink).bold(),
+            TokenKind::Type => color(EnvironmentColor::Teal),
+            TokenKind::Function => color(EnvironmentColor::Blue),
+            TokenKind::Macro => color(EnvironmentColor::Orange),
+            TokenKind::String => color(EnvironmentColor::Red),
+            TokenKind::Number | TokenKind::Constant => color(EnvironmentColor::Purple),
+            TokenKind::Comment => color(EnvironmentColor::SecondaryLabel).italic(),
+            TokenKind::Key => color(EnvironmentColor::Blue),
+            TokenKind::Section => color(EnvironmentColor::Orange).bold(),
+            TokenKind::Heading => color(EnvironmentColor::Blue).bold(),
+            TokenKind::Emphasis => TextAttributes::new().italic(),
+            TokenKind::Strong => TextAttributes::new().bold(),
+            TokenKind::Code => color(EnvironmentColor::Red).family("monospace"),
+            TokenKind::Link => color(EnvironmentColor::Link).underline(),
+            TokenKind::Quote => color(EnvironmentColor::SecondaryLabel),
+            TokenKind::ListMarker => color(EnvironmentColor::Orange),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::text::highlight::{Language, TokenKind};
+
+    fn tokens(language: Language, text: &str) -> Vec<(TokenKind, &str)> {
+        language.tokenize(text)
+            .into_iter()
+            .map(|token| (token.kind, &text[token.range]))
+            .collect()
+    }
+
+    #[test]
+    fn rust() {
+        let text = "#[derive(Debug)]\nfn main() {\n    // Print\n    let x: u32 = 0x10;\n    println!(\"{}\", 'a');\n}";
+
+        assert_eq!(tokens(Language::Rust, text), vec![
+            (TokenKind::Macro, "#[derive(Debug)]"),
+            (TokenKind::Keyword, "fn"),
+            (TokenKind::Function, "main"),
+            (TokenKind::Comment, "// Print"),
+            (TokenKind::Keyword, "let"),
+            (TokenKind::Type, "u32"),
+            (TokenKind::Number, "0x10"),
+            (TokenKind::Macro, "println!"),
+            (TokenKind::String, "\"{}\""),
+            (TokenKind::String, "'a'"),
+        ]);
+    }
+
+    #[test]
+    fn json() {
+        let text = "{\"name\": \"carbide\", \"stars\": -1.5e3, \"tags\": [true, null]}";
+
+        assert_eq!(tokens(Language::Json, text), vec![
+            (TokenKind::Key, "\"name\""),
+            (TokenKind::String, "\"carbide\""),
+            (TokenKind::Key, "\"stars\""),
+            (TokenKind::Number, "-1.5e3"),
+            (TokenKind::Key, "\"tags\""),
+            (TokenKind::Constant, "true"),
+            (TokenKind::Constant, "null"),
+        ]);
+    }
+
+    #[test]
+    fn toml() {
+        let text = "# Window\n[window]\ntitle = \"Carbide\" # The title\nsize.width = 800\nresizable = true\n";
+
+        assert_eq!(tokens(Language::Toml, text), vec![
+            (TokenKind::Comment, "# Window"),
+            (TokenKind::Section, "[window]"),
+            (TokenKind::Key, "title"),
+            (TokenKind::String, "\"Carbide\""),
+            (TokenKind::Comment, "# The title"),
+            (TokenKind::Key, "size.width"),
+            (TokenKind::Number, "800"),
+            (TokenKind::Key, "resizable"),
+            (TokenKind::Constant, "true"),
+        ]);
+    }
+
+    #[test]
+    fn markdown() {
+        let text = "# Title\n\n- Some **bold** and `code` with a [link](https://example.com)\n> Quote\n```\nlet a = *b*;\n```";
+
+        assert_eq!(tokens(Language::Markdown, text), vec![
+            (TokenKind::Heading, "# Title"),
+            (TokenKind::ListMarker, "-"),
+            (TokenKind::Strong, "**bold**"),
+            (TokenKind::Code, "`code`"),
+            (TokenKind::Link, "[link](https://example.com)"),
+            (TokenKind::Quote, "> Quote"),
+            (TokenKind::Code, "```"),
+            (TokenKind::Code, "let a = *b*;"),
+            (TokenKind::Code, "```"),
+        ]);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::text::highlight::scanner::Scanner;
+use crate::text::highlight::{Token, TokenKind};
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "type", "unsafe", "use",
+    "where", "while",
+];
+
+const PRIMITIVES: &[&str] = &[
+    "bool", "char", "str", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64",
+    "i128", "isize", "f32", "f64",
+];
+
+pub(super) fn tokenize(text: &str) -> Vec<Token> {
+    let mut scanner = Scanner::new(text);
+
+    while let Some(c) = scanner.peek() {
+        let start = scanner.position();
+
+        if scanner.eat("//") {
+            scanner.eat_line();
+            scanner.token(TokenKind::Comment, start);
+        } else if scanner.eat("/*") {
+            block_comment(&mut scanner);
+            scanner.token(TokenKind::Comment, start);
+        } else if scanner.eat("#[") || scanner.eat("#![") {
+            attribute(&mut scanner);
+            scanner.token(TokenKind::Macro, start);
+        } else if raw_string(&mut scanner) {
+            scanner.token(TokenKind::String, start);
+        } else if scanner.eat("\"") || scanner.eat("b\"") {
+            scanner.eat_until("\"", true);
+            scanner.token(TokenKind::String, start);
+        } else if c == '\'' {
+            // Either a character literal or a lifetime. Lifetimes are not highlighted.
+            scanner.bump();
+
+            if scanner.peek() == Some('\\') || scanner.peek_nth(1) == Some('\'') {
+                scanner.eat_until("'", true);
+                scanner.token(TokenKind::String, start);
+            }
+        } else if c.is_ascii_digit() {
+            number(&mut scanner);
+            scanner.token(TokenKind::Number, start);
+        } else if c.is_alphabetic() || c == '_' {
+            scanner.eat_while(|c| c.is_alphanumeric() || c == '_');
+            let word = &text[start..scanner.position()];
+
+            if scanner.eat("!") && !scanner.rest().starts_with('=') {
+                scanner.token(TokenKind::Macro, start);
+            } else if KEYWORDS.contains(&word) {
+                scanner.token(TokenKind::Keyword, start);
+            } else if word == "true" || word == "false" {
+                scanner.token(TokenKind::Constant, start);
+            } else if PRIMITIVES.contains(&word) || word.starts_with(char::is_uppercase) {
+                scanner.token(TokenKind::Type, start);
+            } else if scanner.rest().starts_with('(') || scanner.rest().starts_with("::<") {
+                scanner.token(TokenKind::Function, start);
+            }
+        } else {
+            scanner.bump();
+        }
+    }
+
+    scanner.finish()
+}
+
+/// Block comments can be nested.
+fn block_comment(scanner: &mut Scanner) {
+    let mut depth = 1;
+
+    while depth > 0 && !scanner.is_done() {
+        if scanner.eat("/*") {
+            depth += 1;
+        } else if scanner.eat("*/") {
+            depth -= 1;
+        } else {
+            scanner.bump();
+        }
+    }
+}
+
+fn attribute(scanner: &mut Scanner) {
+    let mut depth = 1;
+
+    while let Some(c) = scanner.bump() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            '"' => scanner.eat_until("\"", true),
+            _ => {}
+        }
+
+        if depth == 0 {
+            break;
+        }
+    }
+}
+
+/// Raw strings like r"text" and r#"text"#. Returns false if there is no raw string at the position.
+fn raw_string(scanner: &mut Scanner) -> bool {
+    let rest = scanner.rest();
+    let prefix = if rest.starts_with("br") { 2 } else if rest.starts_with('r') { 1 } else { return false };
+
+    let hashes = rest[prefix..].chars().take_while(|c| *c == '#').count();
+
+    if !rest[prefix + hashes..].starts_with('"') {
+        return false;
+    }
+
+    let terminator = format!("\"{}", "#".repeat(hashes));
+
+    scanner.eat(&rest[..prefix + hashes + 1]);
+    scanner.eat_until(&terminator, false);
+
+    true
+}
+
+fn number(scanner: &mut Scanner) {
+    loop {
+        scanner.eat_while(|c| c.is_alphanumeric() || c == '_');
+
+        // A fraction, but not a range like 0..10 or a method call like 1.max(2)
+        if scanner.rest().starts_with('.') && scanner.peek_nth(1).is_some_and(|c| c.is_ascii_digit()) {
+            scanner.bump();
+        } else {
+            break;
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use crate::text::highlight::{Token, TokenKind};
+
+/// A cursor over the characters of a text, used by the tokenizers to collect tokens.
+pub(super) struct Scanner<'a> {
+    text: &'a str,
+    position: usize,
+    tokens: Vec<Token>,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(text: &'a str) -> Scanner<'a> {
+        Scanner {
+            text,
+            position: 0,
+            tokens: vec![],
+        }
+    }
+
+    /// The byte offset of the next character.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The text from the next character to the end.
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.position >= self.text.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Look at the character n characters ahead of the next character.
+    pub fn peek_nth(&self, n: usize) -> Option<char> {
+        self.rest().chars().nth(n)
+    }
+
+    pub fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    /// Consume the string if the rest of the text starts with it.
+    pub fn eat(&mut self, s: &str) -> bool {
+        if self.rest().starts_with(s) {
+            self.position += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn eat_while(&mut self, f: impl Fn(char) -> bool) {
+        while let Some(c) = self.peek() {
+            if !f(c) {
+                break;
+            }
+
+            self.position += c.len_utf8();
+        }
+    }
+
+    /// Consume the rest of the current line, excluding the line feed.
+    pub fn eat_line(&mut self) {
+        self.eat_while(|c| c != '\n');
+    }
+
+    /// Consume text until and including the terminator, or to the end of the text. Characters
+    /// following a backslash are skipped if escapes is true.
+    pub fn eat_until(&mut self, terminator: &str, escapes: bool) {
+        while !self.is_done() {
+            if self.eat(terminator) {
+                return;
+            }
+
+            if self.bump() == Some('\\') && escapes {
+                self.bump();
+            }
+        }
+    }
+
+    /// Add a token of the kind from the start to the current position.
+    pub fn token(&mut self, kind: TokenKind, start: usize) {
+        if start < self.position {
+            self.tokens.push(Token {
+                kind,
+                range: start..self.position,
+            });
+        }
+    }
+
+    /// True if the text after the current position, ignoring spaces and tabs, starts with the
+    /// string.
+    pub fn followed_by(&self, s: &str) -> bool {
+        self.rest().trim_start_matches([' ', '\t']).starts_with(s)
+    }
+
+    pub fn finish(self) -> Vec<Token> {
+        self.tokens
+    }
+}
@@ -0,0 +1,66 @@
+use crate::text::highlight::scanner::Scanner;
+use crate::text::highlight::{Token, TokenKind};
+
+pub(super) fn tokenize(text: &str) -> Vec<Token> {
+    let mut scanner = Scanner::new(text);
+    let mut line_start = true;
+
+    while let Some(c) = scanner.peek() {
+        let start = scanner.position();
+
+        if c == '\n' {
+            scanner.bump();
+            line_start = true;
+            continue;
+        }
+
+        if c == ' ' || c == '\t' {
+            scanner.bump();
+            continue;
+        }
+
+        if c == '#' {
+            scanner.eat_line();
+            scanner.token(TokenKind::Comment, start);
+        } else if c == '[' && line_start {
+            // Table headers like [table] and [[array]]
+            scanner.eat_while(|c| c != ']' && c != '\n' && c != '#');
+            scanner.eat_while(|c| c == ']');
+            scanner.token(TokenKind::Section, start);
+        } else if scanner.eat("\"\"\"") {
+            scanner.eat_until("\"\"\"", true);
+            scanner.token(TokenKind::String, start);
+        } else if scanner.eat("'''") {
+            scanner.eat_until("'''", false);
+            scanner.token(TokenKind::String, start);
+        } else if c == '"' || c == '\'' {
+            scanner.bump();
+            scanner.eat_until(if c == '"' { "\"" } else { "'" }, c == '"');
+
+            // Quoted strings followed by an equals sign or a dot are keys
+            if scanner.followed_by("=") || scanner.followed_by(".") {
+                scanner.token(TokenKind::Key, start);
+            } else {
+                scanner.token(TokenKind::String, start);
+            }
+        } else if c.is_alphanumeric() || matches!(c, '_' | '-' | '+') {
+            scanner.eat_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '+' | '.' | ':'));
+            let word = &text[start..scanner.position()];
+
+            if scanner.followed_by("=") {
+                scanner.token(TokenKind::Key, start);
+            } else if word == "true" || word == "false" {
+                scanner.token(TokenKind::Constant, start);
+            } else if word.starts_with(|c: char| c.is_ascii_digit() || c == '+' || c == '-') || word == "inf" || word == "nan" {
+                scanner.token(TokenKind::Number, start);
+            }
+        } else {
+            scanner.bump();
+        }
+
+        line_start = false;
+    }
+
+    scanner.finish()
+}
+
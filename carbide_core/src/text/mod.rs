@@ -3,14 +3,17 @@ pub use types::font_weight::FontWeight;
 pub use text_decoration::TextDecoration;
 pub use text_context::*;
 pub use text_style::*;
+pub use attributed_text::*;
 
 mod types;
 mod text_context;
 mod text_style;
+mod attributed_text;
 pub mod text_wrap;
 pub mod text_justify;
 pub mod text_decoration;
 pub mod glyph;
+pub mod highlight;
 
 pub type FontId = usize;
 pub type FontSize = u32;
\ No newline at end of file
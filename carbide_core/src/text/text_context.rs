@@ -1,8 +1,8 @@
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicU32, Ordering};
 use image::DynamicImage;
-use crate::text::TextStyle;
-use crate::draw::{Dimension, Position};
+use crate::text::{AttributedText, TextSpan, TextStyle};
+use crate::draw::{Dimension, Position, Rect, Scalar};
 use crate::environment::Environment;
 use crate::text::glyph::Glyph;
 
@@ -29,6 +29,33 @@ pub trait TextContext {
         f: &mut dyn FnMut(&Glyph)
     );
 
+    /// Calculate the size of a piece of attributed text based on a requested size. Contexts
+    /// without support for attributes measure the plain text.
+    fn calculate_size_attributed(
+        &mut self,
+        text: &AttributedText,
+        style: &TextStyle,
+        requested_size: Option<Dimension>,
+        env: &mut Environment
+    ) -> Dimension {
+        self.calculate_size_new(&text.text, style, requested_size, env)
+    }
+
+    /// Render a piece of attributed text at a given position. The attributes of each span
+    /// are applied on top of the style. Contexts without support for attributes render the
+    /// plain text.
+    fn render_attributed(
+        &mut self,
+        text: &AttributedText,
+        style: &TextStyle,
+        position: Position,
+        requested_size: Option<Dimension>,
+        env: &mut Environment,
+        f: &mut dyn FnMut(&Glyph)
+    ) {
+        self.render_new(&text.text, style, position, requested_size, env, f)
+    }
+
     fn calculate_size(&mut self, id: TextId, requested_size: Dimension, env: &mut Environment) -> Dimension;
 
     fn calculate_position(&mut self, id: TextId, requested_offset: Position, env: &mut Environment);
@@ -37,6 +64,13 @@ pub trait TextContext {
 
     fn update(&mut self, id: TextId, text: &str, style: &TextStyle);
 
+    /// Update the text with spans of attributes applied on top of the style. The ranges of the
+    /// spans are byte ranges into the text.
+    #[allow(unused_variables)]
+    fn update_attributed(&mut self, id: TextId, text: &str, spans: &[TextSpan], style: &TextStyle) {
+        self.update(id, text, style)
+    }
+
     fn render(&mut self, id: TextId, f: &mut dyn FnMut(&Glyph));
 
     fn prepare_render(&mut self);
@@ -51,6 +85,34 @@ pub trait TextContext {
 
     fn position_of(&self, id: TextId, line: usize, index: usize) -> Position;
 
+    /// Returns the byte offset into the text closest to the position relative to the text.
+    /// Unlike [TextContext::hit] this takes all lines of the text into account, including
+    /// lines that are wrapped. Contexts without support for this return the start of the text.
+    #[allow(unused_variables)]
+    fn hit_offset(&self, id: TextId, position: Position) -> usize {
+        0
+    }
+
+    /// Returns the rectangle of a caret placed before the byte offset, relative to the text.
+    /// The rectangle has zero width and the height of the line containing the offset. Contexts
+    /// without support for this return an empty rectangle at the start of the text.
+    #[allow(unused_variables)]
+    fn caret_rect(&self, id: TextId, offset: usize) -> Rect {
+        Rect::new(Position::origin(), Dimension::new(0.0, 0.0))
+    }
+
+    /// Returns the index of the span at the position relative to the text, if any.
+    #[allow(unused_variables)]
+    fn span_at(&self, id: TextId, position: Position) -> Option<usize> {
+        None
+    }
+
+    /// Returns the distance from the top of the text to the baseline of its first line, if any.
+    #[allow(unused_variables)]
+    fn first_baseline(&self, id: TextId) -> Option<Scalar> {
+        None
+    }
+
     fn remove(&mut self, id: TextId);
 }
 
@@ -109,6 +171,14 @@ impl TextContext for NOOPTextContext {
         unimplemented!()
     }
 
+    fn hit_offset(&self, _id: TextId, _position: Position) -> usize {
+        unimplemented!()
+    }
+
+    fn caret_rect(&self, _id: TextId, _offset: usize) -> Rect {
+        unimplemented!()
+    }
+
     fn remove(&mut self, _id: TextId) {
         unimplemented!()
     }
@@ -5,6 +5,7 @@ use crate::text::FontStyle;
 use crate::text::FontWeight;
 use crate::text::TextDecoration;
 use crate::text::text_wrap::Wrap;
+use crate::widget::LayoutDirection;
 
 /// The text style for a piece of text
 #[derive(Clone, Debug, PartialEq)]
@@ -23,6 +24,9 @@ pub struct TextStyle {
     /// The primary color for the text
     pub color: Option<Color>,
     pub wrap: Wrap,
+    /// The direction of the layout the text is shown in. Lines are aligned to the right in
+    /// right to left layouts.
+    pub direction: LayoutDirection,
 }
 
 impl Default for TextStyle {
@@ -36,6 +40,7 @@ impl Default for TextStyle {
             text_decoration: TextDecoration::None,
             color: None,
             wrap: Wrap::None,
+            direction: LayoutDirection::LeftToRight,
         }
     }
 }
\ No newline at end of file
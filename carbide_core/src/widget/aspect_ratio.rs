@@ -86,7 +86,7 @@ impl<D: ReadState<T=Dimension>, M: ReadState<T=ContentMode>, C: Widget> Layout f
             }
         };
 
-        self.child.calculate_size(adjusted_requested, ctx);
+        self.child.process_calculate_size(adjusted_requested, ctx);
 
         self.dimension = adjusted_requested;
 
@@ -3,7 +3,7 @@ use crate::CommonWidgetImpl;
 use crate::draw::{Alignment, Dimension, Position};
 use crate::layout::{Layout, LayoutContext};
 use crate::render::{Render, RenderContext};
-use crate::widget::{CommonWidget, Empty, Widget, WidgetId};
+use crate::widget::{CommonWidget, Empty, LayoutDirection, Widget, WidgetId};
 
 /// Takes a child and a background widget, and sizes the background the same as the child.
 /// The background will be shown behind the child widget.
@@ -53,14 +53,14 @@ impl<F: Widget, B: Widget> Background<F, B> {
 
 impl<F: Widget, B: Widget> Layout for Background<F, B> {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
-        let child_size = self.child.calculate_size(requested_size, ctx);
-        self.background.calculate_size(child_size, ctx);
+        let child_size = self.child.process_calculate_size(requested_size, ctx);
+        self.background.process_calculate_size(child_size, ctx);
         self.dimension = child_size;
         self.dimension
     }
 
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
-        let alignment = self.alignment();
+        let alignment = self.alignment().resolve(LayoutDirection::get(ctx.env));
         let position = self.position;
         let dimension = self.dimension;
 
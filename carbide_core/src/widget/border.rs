@@ -5,7 +5,7 @@ use crate::draw::{Color, Dimension, DrawStyle, Position, Rect};
 use crate::layout::{Layout, LayoutContext};
 use crate::render::{Render, RenderContext};
 use crate::state::{IntoReadState, ReadState};
-use crate::widget::{CommonWidget, Empty, Widget, WidgetId};
+use crate::widget::{CommonWidget, Empty, LayoutDirection, Widget, WidgetId};
 use crate::CommonWidgetImpl;
 
 /// A basic, non-interactive rectangle shape widget.
@@ -62,7 +62,7 @@ impl<W: Widget, C: ReadState<T=Color>> Layout for Border<W, C> {
             requested_size.height - border_width - border_width,
         );
 
-        let child_dimensions = self.child.calculate_size(dimensions, ctx);
+        let child_dimensions = self.child.process_calculate_size(dimensions, ctx);
 
         self.dimension = Dimension::new(
             child_dimensions.width + border_width + border_width,
@@ -74,7 +74,7 @@ impl<W: Widget, C: ReadState<T=Color>> Layout for Border<W, C> {
 
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
         let border_width = self.border_width as f64;
-        let alignment = self.alignment();
+        let alignment = self.alignment().resolve(LayoutDirection::get(ctx.env));
         let position = Position::new(self.x() + border_width, self.y() + border_width);
         let dimension = Dimension::new(
             self.width() - border_width - border_width,
@@ -9,7 +9,7 @@ use crate::render::{RenderContext, Style};
 use crate::state::{IntoReadState, ReadState, StateSync};
 use crate::text::text_wrap::Wrap;
 use crate::text::{FontStyle, FontWeight, TextDecoration, TextId, TextStyle};
-use crate::widget::ShapeStyle;
+use crate::widget::{LayoutDirection, ShapeStyle};
 use std::fmt::{Debug, Formatter};
 
 pub struct CanvasContext<'a, 'b, 'c: 'b> {
@@ -329,6 +329,7 @@ impl<'a, 'b, 'c: 'b> CanvasContext<'a, 'b, 'c> {
             text_decoration: TextDecoration::None,
             color: None,
             wrap: Wrap::Character,
+            direction: LayoutDirection::LeftToRight,
         };
 
         let size = self.render_context.measure_text(text, &text_style, None);
@@ -42,13 +42,13 @@ impl<W: Widget> Layout for Clip<W> {
     // Calculate the size of the child, but force clip to requested_size. This makes sure that if
     // the child is larger than the requested, that is is clipped.
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
-        self.child.calculate_size(requested_size, ctx);
+        self.child.process_calculate_size(requested_size, ctx);
         self.dimension = requested_size;
         requested_size
     }
 
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
-        let positioning = self.alignment;
+        let positioning = self.alignment.resolve(LayoutDirection::get(ctx.env));
         let position = self.position();
         let dimension = self.dimension();
         let inner_bounding_box = self.bounding_box();
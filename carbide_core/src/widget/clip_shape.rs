@@ -52,8 +52,8 @@ impl<C: Widget, S: AnyShape + AnyWidget + Clone> WidgetSync for ClipShape<C, S>
 
 impl<C: Widget, S: AnyShape + AnyWidget + Clone> Layout for ClipShape<C, S> {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
-        self.child.calculate_size(requested_size, ctx);
-        self.shape.calculate_size(requested_size, ctx);
+        self.child.process_calculate_size(requested_size, ctx);
+        self.shape.process_calculate_size(requested_size, ctx);
         self.dimension = requested_size;
         requested_size
     }
@@ -1,7 +1,7 @@
 use crate::environment::{EnvironmentColor, EnvironmentFontSize};
 use crate::render::Style;
 use crate::state::IntoReadState;
-use crate::text::{FontStyle, FontWeight};
+use crate::text::{FontStyle, FontWeight, TextSpan};
 use crate::text::text_wrap::WrapState;
 use crate::widget::{Text, Widget};
 
@@ -28,7 +28,7 @@ impl<T> IntoWidget for T where T: Widget {
 }
 
 impl IntoWidget for String {
-    type Output = Text<String, <EnvironmentFontSize as IntoReadState<u32>>::Output, <EnvironmentColor as IntoReadState<Style>>::Output, FontStyle, FontWeight, WrapState>;
+    type Output = Text<String, <EnvironmentFontSize as IntoReadState<u32>>::Output, <EnvironmentColor as IntoReadState<Style>>::Output, FontStyle, FontWeight, WrapState, Vec<TextSpan>>;
 
     fn into_widget(self) -> Self::Output {
         Text::new(self)
@@ -38,7 +38,7 @@ impl IntoWidget for String {
 }
 
 impl IntoWidget for &'static str {
-    type Output = Text<String, <EnvironmentFontSize as IntoReadState<u32>>::Output, <EnvironmentColor as IntoReadState<Style>>::Output, FontStyle, FontWeight, WrapState>;
+    type Output = Text<String, <EnvironmentFontSize as IntoReadState<u32>>::Output, <EnvironmentColor as IntoReadState<Style>>::Output, FontStyle, FontWeight, WrapState, Vec<TextSpan>>;
 
     fn into_widget(self) -> Self::Output {
         Text::new(self.to_string())
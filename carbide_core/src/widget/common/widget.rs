@@ -236,6 +236,10 @@ impl<T: AnyWidget + ?Sized> Layout for Box<T> {
         self.deref_mut().calculate_size(requested_size, ctx)
     }
 
+    fn process_calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        self.deref_mut().process_calculate_size(requested_size, ctx)
+    }
+
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
         self.deref_mut().position_children(bounding_box, ctx)
     }
@@ -1,6 +1,6 @@
 use cgmath::Matrix4;
 use carbide::widget::{EnvUpdatingNew, WidgetProperties};
-use crate::widget::managers::ThemeManager;
+use crate::widget::managers::{ThemeManager, UndoManager, UndoShortcuts};
 use crate::color::RED;
 use crate::draw::{Angle, Color, Rect, Scalar};
 use crate::draw::Dimension;
@@ -15,8 +15,9 @@ use crate::state::{IntoState, ReadState, StateContract};
 use crate::draw::theme::{Theme};
 use crate::event;
 use crate::text::text_wrap::{TextWrapKey, Wrap};
-use crate::widget::{Absolute, AnyWidget, AspectRatio, Background, Border, Changed, Clip, ClipShape, ContentMode, CornerRadii, EdgeInsets, Flagged, Flexibility, Frame, GeometryReader, Hidden, HueRotation, Mask, MouseArea, Offset, OnKey, OnKeyAction, Padding, Rotation3DEffect, RoundedRectangle, Saturation, Shadow, AnyShape, Transform, MouseAreaActionContext, Action, EnvUpdatingNew3, Overlay, OverlayManager};
+use crate::widget::{Absolute, AnyWidget, AspectRatio, Background, Border, Changed, Clip, ClipShape, ContentMode, CornerRadii, EdgeInsets, Flagged, Flexibility, Frame, GeometryReader, GridCell, GridSpan, Hidden, LayoutDirection, HueRotation, Mask, MouseArea, Offset, OnKey, OnKeyAction, Padding, Rotation3DEffect, RoundedRectangle, Saturation, Shadow, AnyShape, Transform, MouseAreaActionContext, Action, EnvUpdatingNew3, Overlay, OverlayManager};
 use crate::widget::environment_updating_new2::EnvUpdatingNew2;
+use crate::widget::types::LayoutDirectionKey;
 use crate::widget::keyboard_shortcut::KeyboardShortcut;
 use crate::widget::luminance::Luminance;
 use crate::widget::OnChange;
@@ -29,6 +30,7 @@ use crate::widget::Widget;
 pub(crate) type AccentColor<C, K, V> = EnvUpdatingNew2<C, K, V>;
 pub(crate) type ForegroundColor<C, K, V> = EnvUpdatingNew2<C, K, V>;
 pub(crate) type Wrapped<C, K, V> = EnvUpdatingNew2<C, K, V>;
+pub(crate) type Directed<C, K, V> = EnvUpdatingNew2<C, K, V>;
 
 pub trait WidgetExt: AnyWidget + WidgetProperties + Clone + Sized {
 
@@ -51,6 +53,11 @@ pub trait WidgetExt: AnyWidget + WidgetProperties + Clone + Sized {
         Flexibility::new(self, flexibility)
     }
 
+    /// Make the widget span a number of columns and rows when placed in a [Grid](crate::widget::Grid).
+    fn grid_cell(self, columns: usize, rows: usize) -> GridCell<Self> {
+        GridCell::new(self, GridSpan::new(columns, rows))
+    }
+
     /// Change the flags of a given widget. This can for example be used to make any widget take
     /// Flags::USEMAXCROSSAXIS to make it use the max cross axis instead of expanding infinitely
     /// within a VStack or HStack.
@@ -145,6 +152,11 @@ pub trait WidgetExt: AnyWidget + WidgetProperties + Clone + Sized {
         EnvUpdatingNew2::<Self, TextWrapKey, E::Output>::new(wrap.into_read_state(), self)
     }
 
+    /// Set the direction the widget and its children are laid out in. See [LayoutDirection].
+    fn layout_direction<D: IntoReadState<LayoutDirection>>(self, direction: D) -> Directed<Self, impl EnvironmentKey<Value=LayoutDirection>, impl ReadState<T=LayoutDirection>> {
+        EnvUpdatingNew2::<Self, LayoutDirectionKey, D::Output>::new(direction.into_read_state(), self)
+    }
+
     fn corner_radius(self, radius: impl Into<CornerRadii>) -> ClipShape<Self, RoundedRectangle<Style, Style>> {
         ClipShape::new(self, RoundedRectangle::new(radius).fill(Style::Color(RED)).stroke(Style::Color(RED)))
     }
@@ -243,6 +255,12 @@ pub trait WidgetExt: AnyWidget + WidgetProperties + Clone + Sized {
         KeyboardShortcut::new(self, key, modifier_key)
     }
 
+    /// Place the undo manager in the environment of the widget, such that history states synced
+    /// within it are registered with the manager, and undo and redo on ctrl+z and ctrl+shift+z.
+    fn undo_manager(self, manager: UndoManager) -> EnvUpdatingNew<UndoShortcuts<Self>, UndoManager> {
+        EnvUpdatingNew::<UndoShortcuts<Self>, UndoManager>::new(manager.clone(), UndoShortcuts::new(manager, self))
+    }
+
     fn vertical_scroll_style(self, value: impl ScrollBarStyle) -> impl Widget {
         EnvUpdatingNew::<Self, VerticalScrollBarStyleKey>::new(Box::new(value) as Box<dyn ScrollBarStyle>, self)
     }
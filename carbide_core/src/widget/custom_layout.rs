@@ -0,0 +1,225 @@
+use std::fmt::Debug;
+
+use carbide::draw::Rect;
+
+use crate::CommonWidgetImpl;
+use crate::draw::{Dimension, Position, Scalar};
+use crate::layout::{Layout, LayoutContext, position_children_with_baseline};
+use crate::widget::{AnySequence, CommonWidget, Sequence, Widget, WidgetId};
+
+/// A layout algorithm used by [CustomLayout] to size and place its children.
+///
+/// The algorithm is asked for the size that fits a proposed size, and afterwards to place the
+/// children within the bounds of the layout. The children are reached through [Subviews], and
+/// can be measured as many times as needed with different proposals. Because the methods take
+/// `&mut self`, the algorithm can keep values calculated while sizing, for use when placing.
+pub trait LayoutAlgorithm: Debug + Clone + 'static {
+    /// Returns the size of the layout given the proposed size. The size is usually calculated
+    /// by measuring the subviews.
+    fn size_that_fits(&mut self, proposal: Dimension, subviews: &mut Subviews) -> Dimension;
+
+    /// Place each of the subviews within the bounds. The bounds have the size returned from
+    /// [LayoutAlgorithm::size_that_fits], and the position of the layout.
+    fn place_subviews(&mut self, bounds: Rect, subviews: &mut Subviews);
+}
+
+/// The children of a [CustomLayout], as seen from its [LayoutAlgorithm].
+pub struct Subviews<'a, 'b, 'c: 'b> {
+    children: &'a mut dyn AnySequence,
+    bounds: Rect,
+    ctx: &'a mut LayoutContext<'b, 'c>,
+}
+
+impl<'a, 'b, 'c: 'b> Subviews<'a, 'b, 'c> {
+    pub fn len(&mut self) -> usize {
+        self.children.count()
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.len() == 0
+    }
+
+    /// Measure the subview with the proposed size, and return the size it chose. This can be
+    /// done multiple times, for example to find the smallest and largest size of the subview.
+    pub fn size_that_fits(&mut self, index: usize, proposal: Dimension) -> Dimension {
+        self.children.index(index).process_calculate_size(proposal, self.ctx)
+    }
+
+    /// Place the top left corner of the subview at the position, and size it with the proposed
+    /// size. The position is in the same coordinates as the bounds given to
+    /// [LayoutAlgorithm::place_subviews].
+    pub fn place(&mut self, index: usize, position: Position, proposal: Dimension) {
+        let child = self.children.index(index);
+        child.process_calculate_size(proposal, self.ctx);
+        child.set_position(position);
+        child.position_children(self.bounds, self.ctx);
+    }
+
+    /// The size of the subview from the last time it was measured.
+    pub fn dimension(&mut self, index: usize) -> Dimension {
+        self.children.index(index).dimension()
+    }
+
+    pub fn flexibility(&mut self, index: usize) -> u32 {
+        self.children.index(index).flexibility()
+    }
+
+    /// The distance from the top of the subview to its first baseline. The subview needs to be
+    /// placed for the baseline to be known, and is positioned again where it was placed.
+    pub fn first_baseline(&mut self, index: usize) -> Option<Scalar> {
+        position_children_with_baseline(self.children.index(index), self.bounds, self.ctx)
+    }
+}
+
+/// # CustomLayout
+/// A container sizing and placing its children using a [LayoutAlgorithm]. This makes it possible
+/// to write layouts, like a radial menu or a masonry gallery, without implementing a whole widget.
+///
+/// ```
+/// use carbide_core::draw::{Dimension, Position, Rect};
+/// use carbide_core::widget::{CustomLayout, LayoutAlgorithm, Rectangle, Subviews, WidgetExt};
+///
+/// /// Places the children diagonally, each below and to the right of the previous.
+/// #[derive(Debug, Clone)]
+/// struct Diagonal;
+///
+/// impl LayoutAlgorithm for Diagonal {
+///     fn size_that_fits(&mut self, proposal: Dimension, subviews: &mut Subviews) -> Dimension {
+///         let mut size = Dimension::new(0.0, 0.0);
+///
+///         for index in 0..subviews.len() {
+///             size = size + subviews.size_that_fits(index, proposal);
+///         }
+///
+///         size
+///     }
+///
+///     fn place_subviews(&mut self, bounds: Rect, subviews: &mut Subviews) {
+///         let mut position = bounds.position;
+///
+///         for index in 0..subviews.len() {
+///             let dimension = subviews.dimension(index);
+///             subviews.place(index, position, dimension);
+///             position = position + Position::new(dimension.width, dimension.height);
+///         }
+///     }
+/// }
+///
+/// fn main() {
+///     CustomLayout::new(Diagonal, (
+///         Rectangle::new().frame(40.0, 20.0),
+///         Rectangle::new().frame(60.0, 20.0),
+///     ));
+/// }
+/// ```
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Layout)]
+pub struct CustomLayout<L, W> where L: LayoutAlgorithm, W: Sequence
+{
+    #[id] id: WidgetId,
+    children: W,
+    position: Position,
+    dimension: Dimension,
+    layout: L,
+}
+
+impl<L: LayoutAlgorithm, W: Sequence> CustomLayout<L, W> {
+    pub fn new(layout: L, children: W) -> Self {
+        CustomLayout {
+            id: WidgetId::new(),
+            children,
+            position: Position::new(0.0, 0.0),
+            dimension: Dimension::new(100.0, 100.0),
+            layout,
+        }
+    }
+}
+
+impl<L: LayoutAlgorithm, W: Sequence> Layout for CustomLayout<L, W> {
+    fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        let mut subviews = Subviews {
+            children: &mut self.children,
+            bounds: Rect::new(self.position, self.dimension),
+            ctx,
+        };
+
+        self.dimension = self.layout.size_that_fits(requested_size, &mut subviews);
+        self.dimension
+    }
+
+    fn position_children(&mut self, _bounding_box: Rect, ctx: &mut LayoutContext) {
+        let bounds = Rect::new(self.position, self.dimension);
+
+        let mut subviews = Subviews {
+            children: &mut self.children,
+            bounds,
+            ctx,
+        };
+
+        self.layout.place_subviews(bounds, &mut subviews);
+    }
+}
+
+impl<L: LayoutAlgorithm, W: Sequence> CommonWidget for CustomLayout<L, W> {
+    CommonWidgetImpl!(self, child: self.children, position: self.position, dimension: self.dimension);
+}
+
+#[cfg(test)]
+mod tests {
+    use carbide::draw::Rect;
+
+    use crate::draw::{Dimension, Position};
+    use crate::identifiable::Identifiable;
+    use crate::testing::TestHarness;
+    use crate::widget::{CustomLayout, LayoutAlgorithm, Rectangle, Subviews, WidgetExt};
+
+    /// Places the children in a row, with every child as wide as the widest at its smallest size.
+    #[derive(Debug, Clone)]
+    struct EqualWidths;
+
+    impl LayoutAlgorithm for EqualWidths {
+        fn size_that_fits(&mut self, proposal: Dimension, subviews: &mut Subviews) -> Dimension {
+            let mut widest = 0.0f64;
+            let mut tallest = 0.0f64;
+
+            for index in 0..subviews.len() {
+                let size = subviews.size_that_fits(index, Dimension::new(0.0, proposal.height));
+                widest = widest.max(size.width);
+                tallest = tallest.max(size.height);
+            }
+
+            Dimension::new(widest * subviews.len() as f64, tallest)
+        }
+
+        fn place_subviews(&mut self, bounds: Rect, subviews: &mut Subviews) {
+            let width = bounds.dimension.width / subviews.len() as f64;
+
+            for index in 0..subviews.len() {
+                let position = bounds.position + Position::new(width * index as f64, 0.0);
+                subviews.place(index, position, Dimension::new(width, bounds.dimension.height));
+            }
+        }
+    }
+
+    #[test]
+    fn children_are_placed_by_the_algorithm() {
+        let first = Rectangle::new().frame_fixed_height(20.0);
+        let second = Rectangle::new().frame_fixed_height(20.0);
+        let wide = Rectangle::new().frame(60.0, 20.0);
+
+        let ids = [first.id(), second.id()];
+
+        let mut harness = TestHarness::new(
+            CustomLayout::new(EqualWidths, (first, second, wide)),
+            Dimension::new(400.0, 400.0),
+        );
+
+        harness.update();
+
+        let first = harness.bounding_box(ids[0]).unwrap();
+        let second = harness.bounding_box(ids[1]).unwrap();
+
+        assert_eq!(second.position - first.position, Position::new(60.0, 0.0));
+        assert_eq!(first.dimension, Dimension::new(60.0, 20.0));
+    }
+}
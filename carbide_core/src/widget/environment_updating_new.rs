@@ -58,7 +58,7 @@ impl<C: Widget, K: EnvironmentKey> Layout for EnvUpdatingNew<C, K> where K::Valu
         let mut response = requested_size;
 
         ctx.env.with::<K>(&self.value, |inner| {
-            response = self.child.calculate_size(requested_size, &mut LayoutContext {
+            response = self.child.process_calculate_size(requested_size, &mut LayoutContext {
                 text: ctx.text,
                 image: ctx.image,
                 env: inner,
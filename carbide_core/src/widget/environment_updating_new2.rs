@@ -8,6 +8,7 @@ use crate::focus::FocusContext;
 use crate::focus::Focusable;
 use crate::layout::Layout;
 use crate::layout::LayoutContext;
+use crate::layout::LayoutCache;
 use crate::lifecycle::{InitializationContext, UpdateContext};
 use crate::lifecycle::{Initialize, Update};
 use crate::render::Render;
@@ -60,7 +61,7 @@ impl<C: Widget, K: EnvironmentKey, V: ReadState<T=K::Value>> Layout for EnvUpdat
         let mut response = requested_size;
 
         ctx.env.with::<K>(&*self.value.value(), |inner| {
-            response = self.child.calculate_size(requested_size, &mut LayoutContext {
+            response = self.child.process_calculate_size(requested_size, &mut LayoutContext {
                 text: ctx.text,
                 image: ctx.image,
                 env: inner,
@@ -91,7 +92,10 @@ impl<C: Widget, K: EnvironmentKey, V: ReadState<T=K::Value>> Layout for EnvUpdat
 
 impl<C: Widget, K: EnvironmentKey, V: ReadState<T=K::Value>> Update for EnvUpdatingNew2<C, K, V> where K::Value: Clone {
     fn process_update(&mut self, ctx: &mut UpdateContext) {
-        self.value.sync(ctx.env);
+        // The child is measured in the value, so the cached size is forgotten when it changes
+        if self.value.sync(ctx.env) {
+            LayoutCache::invalidate(self.id());
+        }
 
         ctx.env.with::<K>(&*self.value.value(), |inner| {
             self.child.process_update(&mut UpdateContext {
@@ -8,6 +8,7 @@ use crate::focus::FocusContext;
 use crate::focus::Focusable;
 use crate::layout::Layout;
 use crate::layout::LayoutContext;
+use crate::layout::LayoutCache;
 use crate::lifecycle::{InitializationContext, UpdateContext};
 use crate::lifecycle::{Initialize, Update};
 use crate::render::Render;
@@ -50,7 +51,7 @@ impl<C: Widget, K: EnvironmentKeyable + Clone, V: ReadState<T=K::Output>> Layout
         let mut response = requested_size;
 
         self.key.with(&*self.value.value(), ctx.env, |inner| {
-            response = self.child.calculate_size(requested_size, &mut LayoutContext {
+            response = self.child.process_calculate_size(requested_size, &mut LayoutContext {
                 text: ctx.text,
                 image: ctx.image,
                 env: inner,
@@ -81,7 +82,10 @@ impl<C: Widget, K: EnvironmentKeyable + Clone, V: ReadState<T=K::Output>> Layout
 
 impl<C: Widget, K: EnvironmentKeyable + Clone, V: ReadState<T=K::Output>> Update for EnvUpdatingNew3<C, K, V> where K::Output: Clone {
     fn process_update(&mut self, ctx: &mut UpdateContext) {
-        self.value.sync(ctx.env);
+        // The child is measured in the value, so the cached size is forgotten when it changes
+        if self.value.sync(ctx.env) {
+            LayoutCache::invalidate(self.id());
+        }
 
         self.key.with(&*self.value.value(), ctx.env, |inner| {
             self.child.process_update(&mut UpdateContext {
@@ -1,18 +1,21 @@
+use crate::animation::Transition;
+use crate::application::ApplicationManager;
 use crate::common::flags::WidgetFlag;
 use crate::draw::{Dimension, Position};
 use crate::identifiable::Identifiable;
 use crate::random_access_collection::RandomAccessCollection;
-use crate::state::{AnyReadState, LocalState, State, StateContract};
+use crate::state::{AnyReadState, LocalState, ReadState, State, StateContract};
 use crate::widget::foreach_widget::Delegate as ForEachChildDelegate;
 use crate::widget::foreach_widget::ForEachWidget;
 use crate::widget::properties::{WidgetKind, WidgetKindProxy};
-use crate::widget::{AnySequence, AnyWidget, CommonWidget, Empty, Sequence as ForEachSequence, Widget, WidgetExt, WidgetId, WidgetProperties, WidgetSync};
+use crate::widget::{AnySequence, AnyWidget, CommonWidget, Empty, Sequence as ForEachSequence, Transitioned, Widget, WidgetExt, WidgetId, WidgetProperties, WidgetSync};
 use carbide::widget::properties::Kind;
 use dyn_clone::DynClone;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Formatter};
 use std::hash::Hash;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
 pub trait Delegate<M: RandomAccessCollection<T>, T: StateContract, O: Widget>: Clone + 'static {
     fn call<'a>(&'a self, item: M::Item<'a>, index: Box<dyn AnyReadState<T=M::Idx>>) -> O;
@@ -24,6 +27,15 @@ impl<M: RandomAccessCollection<T>, T: StateContract, K, O: Widget> Delegate<M, T
     }
 }
 
+/// A change to the items of a [ForEach], found by comparing the ids of the items with the ids from
+/// the previous time the children were accessed. The indices are offsets within the collection.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ForEachChange<Id> {
+    Inserted { id: Id, index: usize },
+    Removed { id: Id, index: usize },
+    Moved { id: Id, from: usize, to: usize },
+}
+
 #[derive(Clone)]
 pub struct EmptyDelegate;
 
@@ -49,8 +61,17 @@ where
     model: M,
     delegate: U,
 
-    widgets: HashMap<Id, W>,
+    widgets: HashMap<Id, Transitioned<W>>,
     indices: HashMap<Id, LocalState<M::Idx>>,
+    /// The ids of the items the last time the children were reconciled.
+    order: Option<Vec<Id>>,
+    /// The ids of the children in the order they are shown, including children being removed.
+    displayed: Vec<Id>,
+    on_change: Option<Rc<dyn Fn(&ForEachChange<Id>)>>,
+    transition: Option<Transition>,
+    /// A copy of the collection, taken the first time the children were accessed in a frame.
+    snapshot: Option<M>,
+    snapshot_frame: Option<u32>,
 
     phantom: PhantomData<T>,
     ident: fn(&T) -> Id,
@@ -64,6 +85,12 @@ impl ForEach<(), Vec<()>, EmptyDelegate, Empty, ()> {
             delegate,
             widgets: HashMap::new(),
             indices: HashMap::new(),
+            order: None,
+            displayed: vec![],
+            on_change: None,
+            transition: None,
+            snapshot: None,
+            snapshot_frame: None,
             phantom: PhantomData::default(),
             ident: T::id,
         }
@@ -76,6 +103,12 @@ impl ForEach<(), Vec<()>, EmptyDelegate, Empty, ()> {
             delegate,
             widgets: HashMap::new(),
             indices: HashMap::new(),
+            order: None,
+            displayed: vec![],
+            on_change: None,
+            transition: None,
+            snapshot: None,
+            snapshot_frame: None,
             phantom: PhantomData::default(),
             ident: id,
         }
@@ -105,25 +138,292 @@ where
     U: Delegate<M, T, W>,
     Id: Hash + Eq + Clone + Debug + 'static
 {
+    /// Call the function for each item inserted, removed or moved within the collection. The
+    /// changes are found when the children are accessed, by comparing the ids of the items.
+    pub fn on_change(mut self, f: impl Fn(&ForEachChange<Id>) + 'static) -> Self {
+        self.on_change = Some(Rc::new(f));
+        self
+    }
+
+    /// Transition the children of items when they are inserted into or removed from the
+    /// collection. The children of removed items are recreated from a copy of the collection,
+    /// which is taken once per frame, and kept until their transition has finished. Collections
+    /// that can not be copied remove their children without a transition.
+    pub fn transition(mut self, transition: Transition) -> Self {
+        self.transition = Some(transition);
+        self
+    }
+
+    /// Update the index of each child from the ids of the items in the collection, such that the
+    /// children, and the states within them, follow their item when it is moved. The children of
+    /// items that have been removed are dropped.
+    ///
+    /// Parents traverse into the children of a proxy without syncing or updating it, so this is
+    /// called when a traversal of all the children starts, and not when indexing a single child.
+    /// Nothing is done unless the ids of the items have changed or a transition has finished.
+    fn reconcile(&mut self) {
+        // Take a copy of the collection the first time the children are accessed in each frame.
+        // The previous copy is used to recreate the children of removed items, since the items
+        // can no longer be read from the collection.
+        let frame = ApplicationManager::application_frame();
+        let previous = if self.transition.is_some() && self.snapshot_frame != Some(frame) {
+            self.snapshot_frame = Some(frame);
+            std::mem::replace(&mut self.snapshot, self.model.snapshot())
+        } else {
+            None
+        };
+
+        let finished = self.transition.is_some() && self.displayed.iter()
+            .any(|id| self.widgets.get(id).is_some_and(|widget| widget.is_removed()));
+
+        if !finished && !self.order_changed() {
+            return;
+        }
+
+        let mut ids = Vec::with_capacity(self.model.len());
+
+        let mut current_index = self.model.start_index();
+        let end_index = self.model.end_index();
+
+        while current_index < end_index {
+            let id = self.model.map(current_index.clone(), self.ident);
+
+            match self.indices.get_mut(&id) {
+                Some(index) => {
+                    if *index.value() != current_index {
+                        index.set_value(current_index.clone());
+                    }
+                }
+                None => {
+                    self.indices.insert(id.clone(), LocalState::new(current_index.clone()));
+                }
+            }
+
+            ids.push(id);
+            current_index = self.model.next_index(current_index);
+        }
+
+        // The items present the first time are not reported as inserted
+        let changes = match &self.order {
+            Some(previous) if self.on_change.is_some() || self.transition.is_some() => diff(previous, &ids),
+            _ => vec![],
+        };
+
+        if let Some(transition) = self.transition.clone() {
+            for change in &changes {
+                match change {
+                    ForEachChange::Inserted { id, index } => {
+                        // A child still transitioning out shows the old item, so it is replaced.
+                        if self.widgets.get(id).is_some_and(|widget| widget.is_removing() || widget.is_removed()) {
+                            self.widgets.remove(id);
+                        }
+
+                        self.ensure_exist(self.model.index_from_offset(*index));
+                        self.widgets.get_mut(id).unwrap().insert(&transition);
+                    }
+                    ForEachChange::Removed { id, index } => {
+                        let snapshot = previous.as_ref().or(self.snapshot.as_ref());
+
+                        match (self.widgets.contains_key(id), snapshot) {
+                            (true, Some(snapshot)) => {
+                                let index = LocalState::new(snapshot.index_from_offset(*index));
+                                let item = snapshot.index(index.clone());
+
+                                let mut widget = Transitioned::new(self.delegate.call(item, Box::new(index)));
+                                widget.remove(&transition);
+
+                                self.widgets.insert(id.clone(), widget);
+                            }
+                            _ => {
+                                self.widgets.remove(id);
+                            }
+                        }
+                    }
+                    ForEachChange::Moved { .. } => {}
+                }
+            }
+        }
+
+        self.displayed = displayed_order(&self.displayed, &ids, |id| {
+            self.widgets.get(id).is_some_and(|widget| widget.is_removing())
+        });
+
+        let present = ids.iter().collect::<HashSet<_>>();
+        self.indices.retain(|id, _| present.contains(id));
+
+        let displayed = self.displayed.iter().collect::<HashSet<_>>();
+        self.widgets.retain(|id, _| displayed.contains(id));
+
+        if let Some(on_change) = &self.on_change {
+            for change in &changes {
+                on_change(change);
+            }
+        }
+
+        self.order = Some(ids);
+    }
+
+    /// Returns true if the ids of the items differ from the ids the last time the children were
+    /// reconciled.
+    fn order_changed(&self) -> bool {
+        let Some(order) = &self.order else {
+            return true;
+        };
+
+        if order.len() != self.model.len() {
+            return true;
+        }
+
+        let mut current_index = self.model.start_index();
+
+        for id in order {
+            if self.model.map(current_index.clone(), self.ident) != *id {
+                return true;
+            }
+
+            current_index = self.model.next_index(current_index);
+        }
+
+        false
+    }
+
+    /// Get the child of a displayed id, creating it if it does not exist.
+    fn displayed_child(&mut self, id: &Id) -> &mut Transitioned<W> {
+        if !self.widgets.contains_key(id) {
+            let index = self.indices[id].value().clone();
+            self.ensure_exist(index);
+        }
+
+        self.widgets.get_mut(id).unwrap()
+    }
+
+    /// Create the child of the item at the index, if it does not exist. If it exists, its index
+    /// is updated, since the item might have moved since the children were last reconciled.
     fn ensure_exist(&mut self, index: M::Idx) {
         let id = self.model.map(index.clone(), self.ident);
 
-        if let Some(i) = self.indices.get_mut(&id) {
-            *i.value_mut() = index.clone();
-        } else {
-            self.indices.insert(id.clone(), LocalState::new(index.clone()));
+        if self.widgets.contains_key(&id) {
+            if let Some(state) = self.indices.get_mut(&id) {
+                if *state.value() != index {
+                    state.set_value(index);
+                }
+            }
+
+            return;
         }
 
-        if self.widgets.get(&id).is_none() {
-            println!("Creating: {:?}", id);
-            let item = self.model.index(self.indices.get(&id).unwrap().clone());
-            let i = self.indices.get(&id).unwrap();
+        let index = self.indices.entry(id.clone())
+            .or_insert_with(|| LocalState::new(index))
+            .clone();
 
-            let new = self.delegate.call(item, Box::new(i.clone()));
+        let item = self.model.index(index.clone());
+        let widget = self.delegate.call(item, Box::new(index));
 
-            self.widgets.insert(id, new);
+        self.widgets.insert(id, Transitioned::new(widget));
+    }
+}
+
+/// Returns the changes needed to go from the old to the new order of ids. The items that keep
+/// their relative order are not reported as moved, such that inserting or removing a single item
+/// does not report every item after it as moved.
+fn diff<Id: Hash + Eq + Clone>(old: &[Id], new: &[Id]) -> Vec<ForEachChange<Id>> {
+    let old_indices = old.iter()
+        .enumerate()
+        .map(|(index, id)| (id, index))
+        .collect::<HashMap<_, _>>();
+
+    let new_ids = new.iter().collect::<HashSet<_>>();
+
+    let mut changes = vec![];
+
+    for (index, id) in old.iter().enumerate() {
+        if !new_ids.contains(id) {
+            changes.push(ForEachChange::Removed { id: id.clone(), index });
+        }
+    }
+
+    // The items present both before and after, as (from, to) in their new order
+    let kept = new.iter()
+        .enumerate()
+        .filter_map(|(to, id)| old_indices.get(id).map(|from| (*from, to)))
+        .collect::<Vec<_>>();
+
+    let stationary = longest_increasing(&kept.iter().map(|(from, _)| *from).collect::<Vec<_>>());
+
+    for ((from, to), stationary) in kept.into_iter().zip(stationary) {
+        if !stationary {
+            changes.push(ForEachChange::Moved { id: new[to].clone(), from, to });
         }
     }
+
+    for (index, id) in new.iter().enumerate() {
+        if !old_indices.contains_key(id) {
+            changes.push(ForEachChange::Inserted { id: id.clone(), index });
+        }
+    }
+
+    changes
+}
+
+/// Returns the order to show the children in. This is the new order of ids, with each id being
+/// removed kept after the id it was shown after.
+fn displayed_order<Id: Hash + Eq + Clone>(previous: &[Id], new: &[Id], removing: impl Fn(&Id) -> bool) -> Vec<Id> {
+    let new_ids = new.iter().collect::<HashSet<_>>();
+
+    // The ids being removed, grouped by the closest id before them that is still present
+    let mut after: HashMap<Option<&Id>, Vec<Id>> = HashMap::new();
+    let mut anchor = None;
+
+    for id in previous {
+        if new_ids.contains(id) {
+            anchor = Some(id);
+        } else if removing(id) {
+            after.entry(anchor).or_default().push(id.clone());
+        }
+    }
+
+    let mut result = after.remove(&None).unwrap_or_default();
+
+    for id in new {
+        result.push(id.clone());
+
+        if let Some(removed) = after.remove(&Some(id)) {
+            result.extend(removed);
+        }
+    }
+
+    result
+}
+
+/// Returns for each value whether it is part of the longest strictly increasing subsequence.
+fn longest_increasing(values: &[usize]) -> Vec<bool> {
+    // The index of the last value of the best subsequence of each length
+    let mut tails: Vec<usize> = vec![];
+    let mut previous = vec![None; values.len()];
+
+    for (i, value) in values.iter().enumerate() {
+        let length = tails.partition_point(|tail| values[*tail] < *value);
+
+        if length > 0 {
+            previous[i] = Some(tails[length - 1]);
+        }
+
+        if length == tails.len() {
+            tails.push(i);
+        } else {
+            tails[length] = i;
+        }
+    }
+
+    let mut result = vec![false; values.len()];
+    let mut current = tails.last().copied();
+
+    while let Some(i) = current {
+        result[i] = true;
+        current = previous[i];
+    }
+
+    result
 }
 
 impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T, W>, Id: Hash + Eq + Clone + Debug + 'static> ForEach<T, M, U, W, Id> {
@@ -133,7 +433,7 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
             self.ensure_exist(idx.clone());
             let id = self.model.map(idx, self.ident);
 
-            <W as AnySequence<A>>::index(self.widgets.get_mut(&id).unwrap(), 0)
+            <W as AnySequence<A>>::index(self.widgets.get_mut(&id).unwrap().inner_mut(), 0)
         } else {
             let mut current_index = self.model.start_index();
             let end_index = self.model.end_index();
@@ -145,7 +445,7 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
 
                 self.ensure_exist(current_index.clone());
 
-                let child = self.widgets.get_mut(&id).unwrap();
+                let child = self.widgets.get_mut(&id).unwrap().inner_mut();
 
                 if child.is_ignore() {
 
@@ -171,7 +471,7 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
             if current_index < end_index {
                 let id = self.model.map(current_index.clone(), self.ident);
 
-                let child = self.widgets.get_mut(&id).unwrap();
+                let child = self.widgets.get_mut(&id).unwrap().inner_mut();
 
                 if child.is_ignore() {
 
@@ -187,6 +487,8 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
     }
 
     pub fn foreach_child<A: ?Sized>(&mut self, f: &mut dyn FnMut(&mut A)) where W: AnySequence<A> {
+        self.reconcile();
+
         let mut current_index = self.model.start_index();
         let end_index = self.model.end_index();
 
@@ -195,7 +497,7 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
 
             self.ensure_exist(current_index.clone());
 
-            let widget = self.widgets.get_mut(&id).unwrap();
+            let widget = self.widgets.get_mut(&id).unwrap().inner_mut();
 
             <W as AnySequence<A>>::foreach(widget, f);
 
@@ -204,6 +506,8 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
     }
 
     pub fn foreach_child_rev<A: ?Sized>(&mut self, f: &mut dyn FnMut(&mut A)) where W: AnySequence<A> {
+        self.reconcile();
+
         // If the end and start indices are equal, there are no elements in the collection
         if self.model.len() == 0 {
             return;
@@ -219,7 +523,7 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
 
             self.ensure_exist(current_index.clone());
 
-            let widget = self.widgets.get_mut(&id).unwrap();
+            let widget = self.widgets.get_mut(&id).unwrap().inner_mut();
 
             <W as AnySequence<A>>::foreach_rev(widget, f);
 
@@ -227,7 +531,7 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
                 break;
             }
 
-            current_index = self.model.next_index(current_index);
+            current_index = self.model.prev_index(current_index);
         }
     }
 }
@@ -238,10 +542,45 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
     }
 
     fn child(&mut self, index: usize) -> &mut dyn AnyWidget {
-        self.child::<dyn AnyWidget>(index)
+        if self.transition.is_none() {
+            return self.child::<dyn AnyWidget>(index);
+        }
+
+        // The displayed children are reconciled when a traversal starts, unless never accessed.
+        if self.order.is_none() {
+            self.reconcile();
+        }
+
+        let mut passed = 0;
+
+        for i in 0..self.displayed.len() {
+            let id = self.displayed[i].clone();
+            let child_count = AnySequence::<dyn AnyWidget>::count(self.displayed_child(&id));
+
+            if index < passed + child_count {
+                return self.displayed_child(&id).index(index - passed);
+            }
+
+            passed += child_count;
+        }
+
+        panic!("Index out of bounds. Index: {}, Passed: {}", index, passed);
     }
 
     fn child_count(&mut self) -> usize {
+        self.reconcile();
+
+        if self.transition.is_some() {
+            let mut count = 0;
+
+            for i in 0..self.displayed.len() {
+                let id = self.displayed[i].clone();
+                count += AnySequence::<dyn AnyWidget>::count(self.displayed_child(&id));
+            }
+
+            return count;
+        }
+
         // We can special case when the widget is of kind simple, since we will know the count
         // of children produced, will be equal to the model.
         if W::Kind::kind() == Kind::Simple {
@@ -257,7 +596,7 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
 
                 self.ensure_exist(current_index.clone());
 
-                let child = self.widgets.get_mut(&id).unwrap();
+                let child = self.widgets.get_mut(&id).unwrap().inner_mut();
 
                 if child.is_ignore() {
 
@@ -275,11 +614,29 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
     }
 
     fn foreach_child(&mut self, f: &mut dyn FnMut(&mut dyn AnyWidget)) {
-        self.foreach_child::<dyn AnyWidget>(f)
+        if self.transition.is_none() {
+            return self.foreach_child::<dyn AnyWidget>(f);
+        }
+
+        self.reconcile();
+
+        for i in 0..self.displayed.len() {
+            let id = self.displayed[i].clone();
+            self.displayed_child(&id).foreach(f);
+        }
     }
 
     fn foreach_child_rev(&mut self, f: &mut dyn FnMut(&mut dyn AnyWidget)) {
-        self.foreach_child_rev::<dyn AnyWidget>(f)
+        if self.transition.is_none() {
+            return self.foreach_child_rev::<dyn AnyWidget>(f);
+        }
+
+        self.reconcile();
+
+        for i in (0..self.displayed.len()).rev() {
+            let id = self.displayed[i].clone();
+            self.displayed_child(&id).foreach_rev(f);
+        }
     }
 
     fn position(&self) -> Position {
@@ -327,8 +684,106 @@ impl<T: StateContract, M: RandomAccessCollection<T>, W: Widget, U: Delegate<M, T
             delegate: self.delegate.clone(),
             widgets: HashMap::new(),
             indices: HashMap::new(),
+            order: None,
+            displayed: vec![],
+            on_change: self.on_change.clone(),
+            transition: self.transition.clone(),
+            snapshot: None,
+            snapshot_frame: None,
             phantom: Default::default(),
             ident: self.ident,
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::draw::Dimension;
+    use crate::state::{AnyReadState, AnyState, LocalState, ReadState, State};
+    use crate::testing::TestHarness;
+    use crate::widget::foreach::{diff, displayed_order, ForEachChange};
+    use crate::widget::{AnyWidget, CommonWidget, ForEach, Rectangle, VStack, WidgetExt};
+
+    type Rows = Rc<RefCell<Vec<(u32, Box<dyn AnyReadState<T=usize>>)>>>;
+
+    /// A delegate that records the item and the index state of each row it creates.
+    fn recording_delegate(rows: Rows) -> impl Clone + Fn(Box<dyn AnyState<T=u32>>, Box<dyn AnyReadState<T=usize>>) -> Box<dyn AnyWidget> {
+        move |item: Box<dyn AnyState<T=u32>>, index: Box<dyn AnyReadState<T=usize>>| {
+            rows.borrow_mut().push((*item.value(), index));
+            Rectangle::new().frame(10.0, 10.0).boxed()
+        }
+    }
+
+    fn indices(rows: &Rows) -> Vec<(u32, usize)> {
+        rows.borrow().iter().map(|(item, index)| (*item, *index.value())).collect()
+    }
+
+    #[test]
+    fn rows_survive_insert_at_top() {
+        let items = LocalState::new(vec![1u32, 2, 3]);
+        let rows: Rows = Rc::new(RefCell::new(vec![]));
+
+        let mut harness = TestHarness::new(
+            VStack::new(ForEach::new(items.clone(), recording_delegate(rows.clone()))),
+            Dimension::new(100.0, 100.0),
+        );
+
+        harness.update();
+        assert_eq!(indices(&rows), vec![(1, 0), (2, 1), (3, 2)]);
+
+        items.clone().value_mut().insert(0, 0);
+        harness.update();
+
+        // Only the row of the inserted item is created, and the existing rows, with the
+        // states within them, follow their item to its new index.
+        assert_eq!(indices(&rows), vec![(1, 1), (2, 2), (3, 3), (0, 0)]);
+    }
+
+    #[test]
+    fn indexing_a_child_updates_its_index() {
+        let items = LocalState::new(vec![1u32, 2, 3]);
+        let rows: Rows = Rc::new(RefCell::new(vec![]));
+
+        let mut foreach = ForEach::new(items.clone(), recording_delegate(rows.clone()));
+
+        CommonWidget::foreach_child(&mut foreach, &mut |_| {});
+        assert_eq!(indices(&rows), vec![(1, 0), (2, 1), (3, 2)]);
+
+        items.clone().value_mut().insert(0, 0);
+
+        // Indexing does not reconcile the children, but the index of the child is updated.
+        CommonWidget::child(&mut foreach, 1);
+        assert!(indices(&rows).contains(&(1, 1)));
+    }
+
+    #[test]
+    fn insert_at_top_only_reports_insert() {
+        let changes = diff(&[1, 2, 3], &[0, 1, 2, 3]);
+
+        assert_eq!(changes, vec![ForEachChange::Inserted { id: 0, index: 0 }]);
+    }
+
+    #[test]
+    fn reports_removes_and_moves() {
+        let changes = diff(&[1, 2, 3, 4], &[4, 1, 3]);
+
+        assert_eq!(changes, vec![
+            ForEachChange::Removed { id: 2, index: 1 },
+            ForEachChange::Moved { id: 4, from: 3, to: 0 },
+        ]);
+    }
+
+    #[test]
+    fn removing_items_keep_their_place() {
+        let order = displayed_order(&[1, 2, 3, 4], &[0, 1, 4], |id| *id == 2 || *id == 3);
+
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+
+        let order = displayed_order(&[1, 2, 3], &[2, 3], |id| *id == 1);
+
+        assert_eq!(order, vec![1, 2, 3]);
+    }
+}
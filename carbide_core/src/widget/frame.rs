@@ -6,7 +6,7 @@ use crate::draw::{Alignment, Dimension, Position};
 use crate::environment::{Environment};
 use crate::layout::{Layout, LayoutContext};
 use crate::state::{AnyReadState, AnyState, IntoState, StateSync, ReadState, State, ValueRef, ValueRefMut};
-use crate::widget::{AnyWidget, CommonWidget, Empty, Widget, WidgetId};
+use crate::widget::{AnyWidget, CommonWidget, Empty, LayoutDirection, Widget, WidgetId};
 use crate::widget::properties::WidgetKindSimple;
 
 #[derive(Debug, Clone, Widget)]
@@ -154,9 +154,9 @@ impl<W: State<T=f64>, H: State<T=f64>, C: Widget> Layout for Frame<W, H, C> {
             self.width.set_value(requested_size.width);
         } else if let Fixity::Fit(_) = &mut self.width {
             let child_dimensions = if fixed_height {
-                self.child.calculate_size(Dimension::new(requested_size.width, height), ctx)
+                self.child.process_calculate_size(Dimension::new(requested_size.width, height), ctx)
             } else {
-                self.child.calculate_size(requested_size, ctx)
+                self.child.process_calculate_size(requested_size, ctx)
             };
             self.width.set_value(child_dimensions.width);
         }
@@ -166,13 +166,13 @@ impl<W: State<T=f64>, H: State<T=f64>, C: Widget> Layout for Frame<W, H, C> {
         if let Fixity::Expand(_) = &mut self.height {
             self.height.set_value(requested_size.height);
         } else if let Fixity::Fit(_) = &mut self.height {
-            let child_dimensions = self.child.calculate_size(Dimension::new(width, requested_size.height), ctx);
+            let child_dimensions = self.child.process_calculate_size(Dimension::new(width, requested_size.height), ctx);
             self.height.set_value(child_dimensions.height);
         }
 
         let dimensions = self.dimension();
 
-        self.child.calculate_size(dimensions, ctx);
+        self.child.process_calculate_size(dimensions, ctx);
 
         self.dimension()
     }
@@ -180,7 +180,7 @@ impl<W: State<T=f64>, H: State<T=f64>, C: Widget> Layout for Frame<W, H, C> {
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
         let position = self.position;
         let dimension = Dimension::new(self.width(), self.height());
-        let alignment = self.alignment;
+        let alignment = self.alignment.resolve(LayoutDirection::get(ctx.env));
 
         self.child.set_position(alignment.position(position, dimension, self.child.dimension()));
         self.child.position_children(bounding_box, ctx);
@@ -0,0 +1,550 @@
+use std::hash::Hash;
+
+use smallvec::{SmallVec, smallvec, ToSmallVec};
+
+use carbide::draw::Rect;
+
+use crate::CommonWidgetImpl;
+use crate::draw::{Dimension, Position};
+use crate::layout::{Layout, LayoutCache, LayoutContext, position_children_with_baseline};
+use crate::lifecycle::{Update, UpdateContext};
+use crate::state::StateInspector;
+use crate::widget::{AnyWidget, CommonWidget, CrossAxisAlignment, GridCellSpan, GridRowAlignment, GridSpan, GridTrack, Sequence, Widget, WidgetId, WidgetSync};
+
+/// # Grid
+/// An eager grid that places its children in cells, row by row. Unlike [LazyVGrid](crate::widget::LazyVGrid)
+/// all children are laid out, and the columns and rows can be sized independently.
+///
+/// Each column and row is a [GridTrack], which can be a fixed size, a fraction of the remaining
+/// space or sized to fit the largest child within it. Rows that are not specified are sized to
+/// fit their content. Children can span multiple columns and rows using
+/// [grid_cell](crate::widget::WidgetExt::grid_cell), and are otherwise placed in the next free cell.
+///
+/// Within their cells, the children are aligned horizontally by the alignment of their column, and
+/// vertically by the alignment of their row. Rows aligned on the first baseline line up the text
+/// of the children, which is useful for forms with a label column and a field column.
+///
+/// ```
+/// use carbide_core::widget::{CrossAxisAlignment, Grid, GridRowAlignment, GridTrack, Rectangle, Text, WidgetExt};
+///
+/// fn main() {
+///     Grid::new(vec![GridTrack::Auto, GridTrack::Flexible(1.0)], (
+///         Text::new("Name"),
+///         Rectangle::new().frame(100.0, 22.0),
+///         Text::new("Description"),
+///         Rectangle::new().frame(100.0, 66.0),
+///         Rectangle::new().frame(100.0, 22.0).grid_cell(2, 1),
+///     ))
+///         .column_alignment(0, CrossAxisAlignment::End)
+///         .row_alignment(1, GridRowAlignment::FirstBaseline);
+/// }
+/// ```
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Layout, Update)]
+pub struct Grid<W> where W: Sequence
+{
+    #[id] id: WidgetId,
+    children: W,
+    position: Position,
+    dimension: Dimension,
+    spacing: Dimension,
+    columns: SmallVec<[GridTrack; 8]>,
+    rows: SmallVec<[GridTrack; 8]>,
+    column_alignments: SmallVec<[CrossAxisAlignment; 8]>,
+    row_alignments: SmallVec<[GridRowAlignment; 8]>,
+
+    /// The span of each child, collected when the children are updated.
+    spans: SmallVec<[GridSpan; 16]>,
+    cells: SmallVec<[Cell; 16]>,
+    column_sizes: SmallVec<[f64; 8]>,
+    row_sizes: SmallVec<[f64; 8]>,
+    row_baselines: SmallVec<[f64; 8]>,
+}
+
+/// The placement of a child in the grid, calculated during layout.
+#[derive(Debug, Clone)]
+struct Cell {
+    column: usize,
+    row: usize,
+    span: GridSpan,
+    /// The distance from the top of the child to its first baseline, if the child is aligned on
+    /// its baseline.
+    baseline: Option<f64>,
+}
+
+impl<W: Sequence> Grid<W> {
+    pub fn new(columns: Vec<GridTrack>, children: W) -> Grid<W> {
+        Grid {
+            id: WidgetId::new(),
+            children,
+            position: Position::new(0.0, 0.0),
+            dimension: Dimension::new(100.0, 100.0),
+            spacing: Dimension::new(10.0, 10.0),
+            columns: columns.to_smallvec(),
+            rows: smallvec![],
+            column_alignments: smallvec![],
+            row_alignments: smallvec![],
+            spans: smallvec![],
+            cells: smallvec![],
+            column_sizes: smallvec![],
+            row_sizes: smallvec![],
+            row_baselines: smallvec![],
+        }
+    }
+
+    /// Set the sizes of the rows. Rows beyond these are sized to fit their content.
+    pub fn rows(mut self, rows: Vec<GridTrack>) -> Self {
+        self.rows = rows.to_smallvec();
+        self
+    }
+
+    pub fn spacing(mut self, spacing: Dimension) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    /// Set the horizontal alignment of the children within a column. The default is center.
+    pub fn column_alignment(mut self, column: usize, alignment: CrossAxisAlignment) -> Self {
+        if self.column_alignments.len() <= column {
+            self.column_alignments.resize(column + 1, CrossAxisAlignment::Center);
+        }
+
+        self.column_alignments[column] = alignment;
+        self
+    }
+
+    /// Set the vertical alignment of the children within a row. The default is center.
+    pub fn row_alignment(mut self, row: usize, alignment: GridRowAlignment) -> Self {
+        if self.row_alignments.len() <= row {
+            self.row_alignments.resize(row + 1, GridRowAlignment::Center);
+        }
+
+        self.row_alignments[row] = alignment;
+        self
+    }
+
+    fn row_alignment_of(&self, row: usize) -> GridRowAlignment {
+        self.row_alignments.get(row).copied().unwrap_or(GridRowAlignment::Center)
+    }
+
+    fn column_alignment_of(&self, column: usize) -> CrossAxisAlignment {
+        self.column_alignments.get(column).copied().unwrap_or(CrossAxisAlignment::Center)
+    }
+}
+
+impl<W: Sequence> Update for Grid<W> {
+    fn process_update(&mut self, ctx: &mut UpdateContext) {
+        let id = self.id;
+
+        LayoutCache::track(id, |children| {
+            StateInspector::with_creator(id, std::any::type_name::<Self>(), || {
+                self.sync(ctx.env);
+                self.update(ctx);
+
+                let spans = &mut self.spans;
+                spans.clear();
+
+                // The span of each child is provided by the outermost grid cell within it.
+                self.children.foreach(&mut |child: &mut dyn AnyWidget| {
+                    child.id().hash(children);
+
+                    let mut span = None;
+
+                    ctx.env.with_mut::<GridCellSpan>(&mut span, |env| {
+                        child.process_update(&mut UpdateContext {
+                            text: ctx.text,
+                            image: ctx.image,
+                            env,
+                        })
+                    });
+
+                    spans.push(span.unwrap_or(GridSpan::new(1, 1)));
+                });
+            })
+        })
+    }
+}
+
+impl<W: Sequence> Layout for Grid<W> {
+    fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        let column_count = self.columns.len().max(1);
+
+        // Place the children in the cells of the grid. Children that have not been updated yet
+        // span a single cell.
+        let mut spans: SmallVec<[GridSpan; 16]> = smallvec![];
+        let mut index = 0;
+        let updated = &self.spans;
+
+        self.children.foreach(&mut |_: &mut dyn AnyWidget| {
+            let span = updated.get(index).copied().unwrap_or(GridSpan::new(1, 1));
+            spans.push(GridSpan::new(span.columns.min(column_count), span.rows));
+            index += 1;
+        });
+
+        self.cells = place_cells(&spans, column_count).into_iter()
+            .zip(spans)
+            .map(|((row, column), span)| Cell { column, row, span, baseline: None })
+            .collect();
+
+        let row_count = self.cells.iter()
+            .map(|cell| cell.row + cell.span.rows)
+            .max()
+            .unwrap_or(0)
+            .max(self.rows.len());
+
+        let columns = tracks(&self.columns, column_count);
+        let rows = tracks(&self.rows, row_count);
+
+        // Measure the widths of the children. Children only spanning fixed columns are given
+        // the width of those, while the rest are given the requested width.
+        let mut widths: SmallVec<[TrackItem; 16]> = smallvec![];
+        let mut index = 0;
+        let cells = &self.cells;
+        let spacing = self.spacing;
+
+        self.children.foreach(&mut |child: &mut dyn AnyWidget| {
+            let cell = &cells[index];
+            let width = fixed_size(&columns[cell.column..cell.column + cell.span.columns], spacing.width)
+                .unwrap_or(requested_size.width);
+
+            let chosen_size = child.process_calculate_size(Dimension::new(width, requested_size.height), ctx);
+
+            widths.push(TrackItem { start: cell.column, span: cell.span.columns, size: chosen_size.width });
+            index += 1;
+        });
+
+        self.column_sizes = track_sizes(&columns, &widths, spacing.width, requested_size.width);
+
+        // Measure the heights of the children, now that the widths of the columns are known.
+        let mut heights: SmallVec<[TrackItem; 16]> = smallvec![];
+        let mut baselines: SmallVec<[(f64, f64); 8]> = smallvec![(0.0, 0.0); row_count];
+        let mut index = 0;
+        let column_sizes = &self.column_sizes;
+        let row_alignments: SmallVec<[GridRowAlignment; 8]> = (0..row_count).map(|row| self.row_alignment_of(row)).collect();
+        let cells = &mut self.cells;
+
+        self.children.foreach(&mut |child: &mut dyn AnyWidget| {
+            let cell = &mut cells[index];
+            let width = spanned_size(&column_sizes[cell.column..cell.column + cell.span.columns], spacing.width);
+            let height = fixed_size(&rows[cell.row..cell.row + cell.span.rows], spacing.height)
+                .unwrap_or(requested_size.height);
+
+            let chosen_size = child.process_calculate_size(Dimension::new(width, height), ctx);
+
+            // To find the baseline of the child, it needs to be positioned. The baseline is relative
+            // to the top of the child, so we position the child at the origin for now.
+            if row_alignments[cell.row] == GridRowAlignment::FirstBaseline && cell.span.rows == 1 {
+                child.set_position(Position::new(0.0, 0.0));
+                cell.baseline = position_children_with_baseline(child, Rect::new(Position::new(0.0, 0.0), chosen_size), ctx);
+            }
+
+            match cell.baseline {
+                Some(baseline) => {
+                    let (ascent, descent) = &mut baselines[cell.row];
+                    *ascent = ascent.max(baseline);
+                    *descent = descent.max(chosen_size.height - baseline);
+                }
+                None => {
+                    heights.push(TrackItem { start: cell.row, span: cell.span.rows, size: chosen_size.height });
+                }
+            }
+
+            index += 1;
+        });
+
+        // Rows aligned on the baseline must fit the highest ascent and the lowest descent.
+        for (row, (ascent, descent)) in baselines.iter().enumerate() {
+            if *ascent > 0.0 || *descent > 0.0 {
+                heights.push(TrackItem { start: row, span: 1, size: ascent + descent });
+            }
+        }
+
+        self.row_sizes = track_sizes(&rows, &heights, spacing.height, requested_size.height);
+        self.row_baselines = baselines.iter().map(|(ascent, _)| *ascent).collect();
+
+        // Children in flexible rows are given the final height of their rows.
+        let mut index = 0;
+        let column_sizes = &self.column_sizes;
+        let row_sizes = &self.row_sizes;
+        let cells = &self.cells;
+
+        self.children.foreach(&mut |child: &mut dyn AnyWidget| {
+            let cell = &cells[index];
+
+            if rows[cell.row..cell.row + cell.span.rows].iter().any(|track| matches!(track, GridTrack::Flexible(_))) {
+                let width = spanned_size(&column_sizes[cell.column..cell.column + cell.span.columns], spacing.width);
+                let height = spanned_size(&row_sizes[cell.row..cell.row + cell.span.rows], spacing.height);
+
+                child.process_calculate_size(Dimension::new(width, height), ctx);
+            }
+
+            index += 1;
+        });
+
+        self.dimension = Dimension::new(
+            spanned_size(&self.column_sizes, spacing.width),
+            spanned_size(&self.row_sizes, spacing.height),
+        );
+
+        self.dimension
+    }
+
+    fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
+        let position = self.position;
+        let spacing = self.spacing;
+
+        let column_offsets = offsets(&self.column_sizes, spacing.width);
+        let row_offsets = offsets(&self.row_sizes, spacing.height);
+
+        let column_alignments: SmallVec<[CrossAxisAlignment; 8]> = (0..self.column_sizes.len()).map(|column| self.column_alignment_of(column)).collect();
+        let row_alignments: SmallVec<[GridRowAlignment; 8]> = (0..self.row_sizes.len()).map(|row| self.row_alignment_of(row)).collect();
+
+        let cells = &self.cells;
+        let column_sizes = &self.column_sizes;
+        let row_sizes = &self.row_sizes;
+        let row_baselines = &self.row_baselines;
+        let mut index = 0;
+
+        self.children.foreach(&mut |child: &mut dyn AnyWidget| {
+            let Some(cell) = cells.get(index) else {
+                return;
+            };
+
+            index += 1;
+
+            let x = position.x + column_offsets[cell.column];
+            let y = position.y + row_offsets[cell.row];
+            let width = spanned_size(&column_sizes[cell.column..cell.column + cell.span.columns], spacing.width);
+            let height = spanned_size(&row_sizes[cell.row..cell.row + cell.span.rows], spacing.height);
+
+            let x = match column_alignments[cell.column] {
+                CrossAxisAlignment::Start => x,
+                CrossAxisAlignment::Center => x + width / 2.0 - child.width() / 2.0,
+                CrossAxisAlignment::End => x + width - child.width(),
+            };
+
+            let y = match (row_alignments[cell.row], cell.baseline) {
+                (GridRowAlignment::Top, _) => y,
+                (GridRowAlignment::Center, _) => y + height / 2.0 - child.height() / 2.0,
+                (GridRowAlignment::Bottom, _) => y + height - child.height(),
+                (GridRowAlignment::FirstBaseline, Some(baseline)) => y + row_baselines[cell.row] - baseline,
+                (GridRowAlignment::FirstBaseline, None) => y,
+            };
+
+            child.set_position(Position::new(x, y));
+            child.position_children(bounding_box, ctx);
+        });
+    }
+}
+
+impl<W: Sequence> CommonWidget for Grid<W> {
+    CommonWidgetImpl!(self, child: self.children, position: self.position, dimension: self.dimension, flexibility: 1);
+}
+
+/// A child measured along the columns or rows it spans.
+#[derive(Debug, Clone)]
+struct TrackItem {
+    start: usize,
+    span: usize,
+    size: f64,
+}
+
+/// Returns the tracks, extended with auto tracks up to the count.
+fn tracks(tracks: &[GridTrack], count: usize) -> SmallVec<[GridTrack; 8]> {
+    (0..count).map(|index| tracks.get(index).copied().unwrap_or(GridTrack::Auto)).collect()
+}
+
+/// Returns the total size of the tracks, if they are all fixed.
+fn fixed_size(tracks: &[GridTrack], spacing: f64) -> Option<f64> {
+    let mut total = spacing * tracks.len().saturating_sub(1) as f64;
+
+    for track in tracks {
+        match track {
+            GridTrack::Fixed(size) => total += size,
+            _ => return None,
+        }
+    }
+
+    Some(total)
+}
+
+/// Returns the total size of the tracks including the spacing between them.
+fn spanned_size(sizes: &[f64], spacing: f64) -> f64 {
+    sizes.iter().sum::<f64>() + spacing * sizes.len().saturating_sub(1) as f64
+}
+
+/// Returns the offset of each track from the start of the grid.
+fn offsets(sizes: &[f64], spacing: f64) -> SmallVec<[f64; 8]> {
+    let mut offset = 0.0;
+
+    sizes.iter().map(|size| {
+        let current = offset;
+        offset += size + spacing;
+        current
+    }).collect()
+}
+
+/// Place the children row by row in the first free cells that fit their spans. Returns the
+/// row and column of each child.
+fn place_cells(spans: &[GridSpan], column_count: usize) -> SmallVec<[(usize, usize); 16]> {
+    let mut occupied: Vec<bool> = vec![];
+    let mut placements = smallvec![];
+
+    let is_free = |occupied: &Vec<bool>, row: usize, column: usize, span: &GridSpan| {
+        (row..row + span.rows).all(|row| {
+            (column..column + span.columns).all(|column| !occupied.get(row * column_count + column).copied().unwrap_or(false))
+        })
+    };
+
+    let mut row = 0;
+    let mut column = 0;
+
+    for span in spans {
+        loop {
+            if column + span.columns > column_count {
+                row += 1;
+                column = 0;
+            } else if is_free(&occupied, row, column, span) {
+                break;
+            } else {
+                column += 1;
+            }
+        }
+
+        let end = (row + span.rows) * column_count;
+
+        if occupied.len() < end {
+            occupied.resize(end, false);
+        }
+
+        for occupied_row in row..row + span.rows {
+            for occupied_column in column..column + span.columns {
+                occupied[occupied_row * column_count + occupied_column] = true;
+            }
+        }
+
+        placements.push((row, column));
+        column += span.columns;
+    }
+
+    placements
+}
+
+/// Calculate the sizes of the tracks. Fixed tracks have their size, and auto tracks fit the
+/// children within them. Children spanning multiple tracks grow the auto tracks they span if
+/// needed. Flexible tracks share the space that is left. If no space is available, flexible
+/// tracks fit their children like auto tracks.
+fn track_sizes(tracks: &[GridTrack], items: &[TrackItem], spacing: f64, available: f64) -> SmallVec<[f64; 8]> {
+    let mut sizes: SmallVec<[f64; 8]> = tracks.iter().map(|track| match track {
+        GridTrack::Fixed(size) => *size,
+        GridTrack::Flexible(_) | GridTrack::Auto => 0.0,
+    }).collect();
+
+    let mut content_sizes: SmallVec<[f64; 8]> = smallvec![0.0; tracks.len()];
+
+    for item in items.iter().filter(|item| item.span == 1) {
+        content_sizes[item.start] = content_sizes[item.start].max(item.size);
+
+        if tracks[item.start] == GridTrack::Auto {
+            sizes[item.start] = sizes[item.start].max(item.size);
+        }
+    }
+
+    for item in items.iter().filter(|item| item.span > 1) {
+        let range = item.start..item.start + item.span;
+        let current = spanned_size(&sizes[range.clone()], spacing);
+        let auto_count = tracks[range.clone()].iter().filter(|track| **track == GridTrack::Auto).count();
+
+        if item.size > current && auto_count > 0 {
+            let extra = (item.size - current) / auto_count as f64;
+
+            for index in range {
+                if tracks[index] == GridTrack::Auto {
+                    sizes[index] += extra;
+                }
+            }
+        }
+    }
+
+    let total_fraction = tracks.iter().map(|track| match track {
+        GridTrack::Flexible(fraction) => *fraction,
+        _ => 0.0,
+    }).sum::<f64>();
+
+    if total_fraction > 0.0 {
+        let used = spanned_size(&sizes, spacing);
+
+        for (index, track) in tracks.iter().enumerate() {
+            if let GridTrack::Flexible(fraction) = track {
+                sizes[index] = if available.is_finite() {
+                    (available - used).max(0.0) * fraction / total_fraction
+                } else {
+                    content_sizes[index]
+                };
+            }
+        }
+    }
+
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::draw::{Dimension, Position};
+    use crate::identifiable::Identifiable;
+    use crate::testing::TestHarness;
+    use crate::widget::{Grid, GridSpan, GridTrack, Rectangle, WidgetExt};
+    use crate::widget::grid::{place_cells, track_sizes, TrackItem};
+
+    #[test]
+    fn cells_are_placed_around_spans() {
+        let spans = [
+            GridSpan::new(1, 2),
+            GridSpan::new(1, 1),
+            GridSpan::new(1, 1),
+            GridSpan::new(2, 1),
+            GridSpan::new(3, 1),
+        ];
+
+        let placements = place_cells(&spans, 3);
+
+        assert_eq!(placements.as_slice(), &[(0, 0), (0, 1), (0, 2), (1, 1), (2, 0)]);
+    }
+
+    #[test]
+    fn tracks_are_sized_by_kind() {
+        let tracks = [GridTrack::Fixed(50.0), GridTrack::Auto, GridTrack::Flexible(1.0), GridTrack::Flexible(3.0)];
+
+        let items = [
+            TrackItem { start: 1, span: 1, size: 30.0 },
+            TrackItem { start: 1, span: 1, size: 40.0 },
+            TrackItem { start: 0, span: 2, size: 120.0 },
+        ];
+
+        let sizes = track_sizes(&tracks, &items, 10.0, 300.0);
+
+        assert_eq!(sizes.as_slice(), &[50.0, 60.0, 40.0, 120.0]);
+    }
+
+    #[test]
+    fn spans_are_found_within_modifiers() {
+        let wide = Rectangle::new().frame(110.0, 20.0);
+        let below = Rectangle::new().frame(50.0, 20.0);
+        let ids = [wide.id(), below.id()];
+
+        let mut harness = TestHarness::new(
+            Grid::new(vec![GridTrack::Fixed(50.0), GridTrack::Fixed(50.0)], (
+                wide.grid_cell(2, 1).padding(0.0),
+                below,
+            )),
+            Dimension::new(200.0, 200.0),
+        );
+
+        harness.update();
+
+        let wide = harness.bounding_box(ids[0]).unwrap();
+        let below = harness.bounding_box(ids[1]).unwrap();
+
+        assert_eq!(below.position - wide.position, Position::new(0.0, 30.0));
+    }
+}
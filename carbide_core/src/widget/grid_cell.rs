@@ -0,0 +1,55 @@
+use crate::widget::{CommonWidget, Empty, GridSpan};
+use carbide_macro::carbide_default_builder2;
+
+use crate::CommonWidgetImpl;
+use crate::draw::{Dimension, Position};
+use crate::environment::EnvironmentKey;
+use crate::lifecycle::{Update, UpdateContext};
+use crate::widget::{Widget, WidgetId};
+
+/// Makes the child span a number of columns and rows when placed in a [Grid](crate::widget::Grid).
+/// The cell can be wrapped by other modifiers, in which case the grid uses the span of the
+/// outermost cell within its child.
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Update)]
+pub struct GridCell<C> where C: Widget {
+    #[id] id: WidgetId,
+    child: C,
+    position: Position,
+    dimension: Dimension,
+    span: GridSpan,
+}
+
+/// The span of the outermost [GridCell] within a child of a [Grid](crate::widget::Grid). The grid
+/// provides the value while updating each child, and the cells fill it in.
+#[derive(Debug)]
+pub(crate) struct GridCellSpan;
+
+impl EnvironmentKey for GridCellSpan {
+    type Value = Option<GridSpan>;
+}
+
+impl GridCell<Empty> {
+    #[carbide_default_builder2]
+    pub fn new<C: Widget>(child: C, span: GridSpan) -> GridCell<C> {
+        GridCell {
+            id: WidgetId::new(),
+            child,
+            position: Position::new(0.0, 0.0),
+            dimension: Dimension::new(100.0, 100.0),
+            span,
+        }
+    }
+}
+
+impl<C: Widget> Update for GridCell<C> {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        if let Some(span @ None) = ctx.env.get_mut::<GridCellSpan>() {
+            *span = Some(self.span);
+        }
+    }
+}
+
+impl<C: Widget> CommonWidget for GridCell<C> {
+    CommonWidgetImpl!(self, child: self.child, position: self.position, dimension: self.dimension);
+}
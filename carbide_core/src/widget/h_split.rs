@@ -6,7 +6,7 @@ use crate::draw::{Dimension, Position};
 use crate::event::{MouseEvent, MouseEventContext, MouseEventHandler};
 use crate::layout::{Layout, LayoutContext};
 use crate::state::{IntoState, State};
-use crate::widget::{AnyWidget, CommonWidget, CrossAxisAlignment, Empty, SplitType, Widget, WidgetId, AnySequence};
+use crate::widget::{AnyWidget, CommonWidget, CrossAxisAlignment, Empty, LayoutDirection, SplitType, Widget, WidgetId, AnySequence};
 
 #[derive(Clone, Debug, Widget)]
 #[carbide_exclude(Layout, MouseEvent)]
@@ -21,6 +21,9 @@ pub struct HSplit<S, L, T> where S: State<T=f64>, L: Widget, T: Widget {
     dragging: bool,
     hovering: bool,
     draggable: bool,
+    /// The direction the split was last laid out in. In right to left layouts the leading
+    /// widget is placed on the right side.
+    direction: LayoutDirection,
 }
 
 impl HSplit<f64, Empty, Empty> {
@@ -49,6 +52,7 @@ impl<S: State<T=f64>, L: Widget, T: Widget> HSplit<S, L, T> {
             dragging: false,
             hovering: false,
             draggable,
+            direction: LayoutDirection::LeftToRight,
         }
     }
 
@@ -92,6 +96,17 @@ impl<S: State<T=f64>, L: Widget, T: Widget> HSplit<S, L, T> {
         self.cross_axis_alignment = alignment;
         self
     }
+
+    /// Returns the position relative to the top leading corner of the split, with x increasing
+    /// towards the trailing edge.
+    fn offset_from_leading(&self, position: Position) -> Position {
+        let relative = position - self.position;
+
+        match self.direction {
+            LayoutDirection::LeftToRight => relative,
+            LayoutDirection::RightToLeft => Position::new(self.dimension.width - relative.x, relative.y),
+        }
+    }
 }
 
 impl<S: State<T=f64>, L: Widget, T: Widget> MouseEventHandler for HSplit<S, L, T> {
@@ -104,7 +119,7 @@ impl<S: State<T=f64>, L: Widget, T: Widget> MouseEventHandler for HSplit<S, L, T
 
         match event {
             MouseEvent::Press { position, .. } => {
-                let relative_to_position = *position - self.position;
+                let relative_to_position = self.offset_from_leading(*position);
 
                 let split = self.leading.dimension();
 
@@ -120,7 +135,7 @@ impl<S: State<T=f64>, L: Widget, T: Widget> MouseEventHandler for HSplit<S, L, T
                 self.dragging = false;
             }
             MouseEvent::Move { to, .. } => {
-                let relative_to_position = *to - self.position;
+                let relative_to_position = self.offset_from_leading(*to);
                 let split = self.leading.dimension();
 
                 if relative_to_position.x > split.width - press_margin
@@ -179,19 +194,19 @@ impl<S: State<T=f64>, L: Widget, T: Widget> Layout for HSplit<S, L, T> {
         };
 
         let leading_size = Dimension::new(requested_leading_width, requested_size.height);
-        let mut leading = self.leading.calculate_size(leading_size, ctx);
+        let mut leading = self.leading.process_calculate_size(leading_size, ctx);
 
         let trailing_size = Dimension::new(requested_trailing_width, requested_size.height);
-        let mut trailing = self.trailing.calculate_size(trailing_size, ctx);
+        let mut trailing = self.trailing.process_calculate_size(trailing_size, ctx);
 
         if leading.width > requested_leading_width {
             let trailing_size =
                 Dimension::new(requested_size.width - leading.width, requested_size.height);
-            trailing = self.trailing.calculate_size(trailing_size, ctx);
+            trailing = self.trailing.process_calculate_size(trailing_size, ctx);
         } else if trailing.width > requested_trailing_width {
             let leading_size =
                 Dimension::new(requested_size.width - trailing.width, requested_size.height);
-            leading = self.leading.calculate_size(leading_size, ctx);
+            leading = self.leading.process_calculate_size(leading_size, ctx);
         }
 
         self.set_dimension(Dimension::new(
@@ -205,6 +220,9 @@ impl<S: State<T=f64>, L: Widget, T: Widget> Layout for HSplit<S, L, T> {
         let position = self.position();
         let dimension = self.dimension();
         let alignment = self.cross_axis_alignment;
+        let direction = LayoutDirection::get(ctx.env);
+
+        self.direction = direction;
 
         let mut main_axis_offset = 0.0;
 
@@ -217,7 +235,12 @@ impl<S: State<T=f64>, L: Widget, T: Widget> Layout for HSplit<S, L, T> {
                 CrossAxisAlignment::End => position.y + dimension.height - child.dimension().height,
             };
 
-            child.set_position(Position::new(position.x + main_axis_offset, cross));
+            let x = match direction {
+                LayoutDirection::LeftToRight => position.x + main_axis_offset,
+                LayoutDirection::RightToLeft => position.x + dimension.width - main_axis_offset - child.dimension().width,
+            };
+
+            child.set_position(Position::new(x, cross));
             main_axis_offset += child.dimension().width;
             child.position_children(bounding_box, ctx);
         });
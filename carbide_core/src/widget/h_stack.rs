@@ -226,4 +226,35 @@ impl<W: Sequence> Layout for HStack<W> {
 
 impl<W: Sequence> CommonWidget for HStack<W> {
     CommonWidgetImpl!(self, child: self.children, position: self.position, dimension: self.dimension, flexibility: 1);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::draw::{Dimension, Position};
+    use crate::identifiable::Identifiable;
+    use crate::testing::TestHarness;
+    use crate::widget::{CommonWidget, HStack, LayoutDirection, Rectangle, WidgetExt};
+
+    #[test]
+    fn right_to_left_places_first_child_on_the_right() {
+        let first = Rectangle::new().frame(40.0, 20.0);
+        let second = Rectangle::new().frame(20.0, 20.0);
+
+        let first_id = first.id();
+        let second_id = second.id();
+
+        let mut harness = TestHarness::new(
+            HStack::new((first, second))
+                .spacing(10.0)
+                .layout_direction(LayoutDirection::RightToLeft),
+            Dimension::new(200.0, 200.0),
+        );
+
+        harness.update();
+
+        let first = harness.bounding_box(first_id).unwrap();
+        let second = harness.bounding_box(second_id).unwrap();
+
+        assert_eq!(first.position - second.position, Position::new(30.0, 0.0));
+    }
+}
@@ -64,7 +64,7 @@ impl<W: Sequence> Layout for LazyHStack<W> {
         if self.child_width_estimate.is_none() {
             // Calculate width estimate based on the first N children
             let child = self.children.index(0);
-            let chosen_size = child.calculate_size(requested_size, ctx);
+            let chosen_size = child.process_calculate_size(requested_size, ctx);
 
             self.child_widths.insert(child.id(), chosen_size.width);
 
@@ -113,7 +113,7 @@ impl<W: Sequence> Layout for LazyHStack<W> {
 
             let child = self.children.index(index);
 
-            let chosen_size = child.calculate_size(Dimension::new(
+            let chosen_size = child.process_calculate_size(Dimension::new(
                 self.requested_width,
                 height
             ), ctx);
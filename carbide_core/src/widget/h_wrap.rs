@@ -0,0 +1,163 @@
+use carbide::draw::Rect;
+
+use crate::CommonWidgetImpl;
+use crate::draw::{Dimension, Position, Scalar};
+use crate::layout::{calculate_size_hwrap, Layout, LayoutContext, position_children_hwrap};
+use crate::widget::{CommonWidget, CrossAxisAlignment, LineJustification, Sequence, Widget, WidgetId};
+
+/// # HWrap
+/// A horizontal flow layout. The children are placed left to right like in a [HStack](crate::widget::HStack),
+/// but when the next child would exceed the proposed width, it is placed on a new line below.
+/// This is useful for tags, chips and toolbars that should wrap on narrow windows instead of
+/// overflowing.
+///
+/// The children are measured with the size proposed to the wrap. `spacing` is the space between
+/// children in a line, and `line_spacing` the space between lines. Each line is as tall as its
+/// tallest child, and the children are aligned within the line using the cross axis alignment.
+///
+/// ```
+/// use carbide_core::widget::{HWrap, LineJustification, Rectangle, WidgetExt};
+///
+/// fn main() {
+///     HWrap::new(vec![
+///         Rectangle::new().frame(40.0, 20.0),
+///         Rectangle::new().frame(60.0, 20.0),
+///         Rectangle::new().frame(50.0, 20.0),
+///     ])
+///         .justification(LineJustification::SpaceBetween);
+/// }
+/// ```
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Layout)]
+pub struct HWrap<W> where W: Sequence
+{
+    #[id] id: WidgetId,
+    children: W,
+    position: Position,
+    dimension: Dimension,
+    spacing: Scalar,
+    line_spacing: Scalar,
+    cross_axis_alignment: CrossAxisAlignment,
+    justification: LineJustification,
+}
+
+impl<W: Sequence> HWrap<W> {
+    pub fn new(children: W) -> Self {
+        HWrap {
+            id: WidgetId::new(),
+            children,
+            position: Position::new(0.0, 0.0),
+            dimension: Dimension::new(100.0, 100.0),
+            spacing: 10.0,
+            line_spacing: 10.0,
+            cross_axis_alignment: CrossAxisAlignment::Center,
+            justification: LineJustification::Start,
+        }
+    }
+
+    pub fn cross_axis_alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.cross_axis_alignment = alignment;
+        self
+    }
+
+    /// Set how the children of each line are placed horizontally. When the lines are justified
+    /// the wrap takes up the full proposed width.
+    pub fn justification(mut self, justification: LineJustification) -> Self {
+        self.justification = justification;
+        self
+    }
+
+    pub fn spacing(mut self, spacing: f64) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn line_spacing(mut self, line_spacing: f64) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+}
+
+impl<W: Sequence> Layout for HWrap<W> {
+    fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        let spacing = self.spacing;
+        let line_spacing = self.line_spacing;
+        let justification = self.justification;
+        calculate_size_hwrap(self, spacing, line_spacing, justification, requested_size, ctx);
+        self.dimension
+    }
+
+    fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
+        let spacing = self.spacing;
+        let line_spacing = self.line_spacing;
+        let cross_axis_alignment = self.cross_axis_alignment;
+        let justification = self.justification;
+        position_children_hwrap(self, spacing, line_spacing, cross_axis_alignment, justification, bounding_box, ctx)
+    }
+}
+
+impl<W: Sequence> CommonWidget for HWrap<W> {
+    CommonWidgetImpl!(self, child: self.children, position: self.position, dimension: self.dimension, flexibility: 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::draw::{Dimension, Position};
+    use crate::identifiable::Identifiable;
+    use crate::testing::TestHarness;
+    use crate::widget::{CommonWidget, HWrap, LayoutDirection, Rectangle, WidgetExt};
+
+    #[test]
+    fn children_wrap_onto_new_lines() {
+        let children = vec![
+            Rectangle::new().frame(40.0, 20.0),
+            Rectangle::new().frame(40.0, 20.0),
+            Rectangle::new().frame(40.0, 20.0),
+        ];
+
+        let ids = children.iter().map(|child| child.id()).collect::<Vec<_>>();
+
+        let mut harness = TestHarness::new(
+            HWrap::new(children).spacing(10.0).line_spacing(5.0).frame(100.0, 100.0),
+            Dimension::new(200.0, 200.0),
+        );
+
+        harness.update();
+
+        let first = harness.bounding_box(ids[0]).unwrap();
+        let second = harness.bounding_box(ids[1]).unwrap();
+        let third = harness.bounding_box(ids[2]).unwrap();
+
+        assert_eq!(second.position - first.position, Position::new(50.0, 0.0));
+        assert_eq!(third.position - first.position, Position::new(0.0, 25.0));
+    }
+
+    #[test]
+    fn right_to_left_starts_lines_on_the_right() {
+        let children = vec![
+            Rectangle::new().frame(40.0, 20.0),
+            Rectangle::new().frame(40.0, 20.0),
+            Rectangle::new().frame(40.0, 20.0),
+        ];
+
+        let ids = children.iter().map(|child| child.id()).collect::<Vec<_>>();
+
+        let mut harness = TestHarness::new(
+            HWrap::new(children)
+                .spacing(10.0)
+                .line_spacing(5.0)
+                .layout_direction(LayoutDirection::RightToLeft)
+                .frame(100.0, 100.0),
+            Dimension::new(200.0, 200.0),
+        );
+
+        harness.update();
+
+        let first = harness.bounding_box(ids[0]).unwrap();
+        let second = harness.bounding_box(ids[1]).unwrap();
+        let third = harness.bounding_box(ids[2]).unwrap();
+
+        assert_eq!(first.position - second.position, Position::new(50.0, 0.0));
+        assert_eq!(third.position - first.position, Position::new(0.0, 25.0));
+    }
+}
@@ -3,10 +3,11 @@ use std::marker::PhantomData;
 use carbide::widget::properties::WidgetKind;
 use carbide_macro::carbide_default_builder2;
 
+use crate::animation::Transition;
 use crate::draw::{Dimension, Position};
 use crate::common::flags::WidgetFlag;
 use crate::state::ReadState;
-use crate::widget::{AnyWidget, CommonWidget, Empty, Widget, WidgetId, WidgetProperties};
+use crate::widget::{AnySequence, AnyWidget, CommonWidget, Empty, Transitioned, Widget, WidgetId, WidgetProperties};
 use crate::widget::properties::{WidgetKindDynamic, WidgetKindIgnore, WidgetKindProxy, WidgetKindSimple};
 
 /// # If-Else Widget
@@ -37,8 +38,11 @@ pub struct IfElse<T, F, S, K, KTrue, KFalse> where
     position: Position,
     dimension: Dimension,
     #[state] predicate: S,
-    when_true: T,
-    when_false: F,
+    when_true: Transitioned<T>,
+    when_false: Transitioned<F>,
+    transition: Option<Transition>,
+    /// The value of the predicate when the branches were last transitioned.
+    shown: Option<bool>,
 
     phantom_data: PhantomData<K>,
     phantom_data_true: PhantomData<KTrue>,
@@ -50,8 +54,10 @@ impl IfElse<Empty, Empty, bool, WidgetKindSimple, WidgetKindSimple, WidgetKindSi
         IfElse {
             id: WidgetId::new(),
             predicate,
-            when_true: Empty::new(),
-            when_false: Empty::new(),
+            when_true: Transitioned::new(Empty::new()),
+            when_false: Transitioned::new(Empty::new()),
+            transition: None,
+            shown: None,
             position: Position::new(0.0, 0.0),
             dimension: Dimension::new(0.0, 0.0),
             phantom_data: Default::default(),
@@ -68,8 +74,10 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
         IfElse {
             id: self.id,
             predicate: self.predicate,
-            when_true,
+            when_true: Transitioned::new(when_true),
             when_false: self.when_false,
+            transition: self.transition,
+            shown: self.shown,
             position: self.position,
             dimension: self.dimension,
             phantom_data: Default::default(),
@@ -85,7 +93,9 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
             id: self.id,
             predicate: self.predicate,
             when_true: self.when_true,
-            when_false,
+            when_false: Transitioned::new(when_false),
+            transition: self.transition,
+            shown: self.shown,
             position: self.position,
             dimension: self.dimension,
             phantom_data: Default::default(),
@@ -100,8 +110,10 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
         IfElse {
             id: self.id,
             predicate: self.predicate,
-            when_true,
+            when_true: Transitioned::new(when_true),
             when_false: self.when_false,
+            transition: self.transition,
+            shown: self.shown,
             position: self.position,
             dimension: self.dimension,
             phantom_data: Default::default(),
@@ -117,7 +129,9 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
             id: self.id,
             predicate: self.predicate,
             when_true: self.when_true,
-            when_false,
+            when_false: Transitioned::new(when_false),
+            transition: self.transition,
+            shown: self.shown,
             position: self.position,
             dimension: self.dimension,
             phantom_data: Default::default(),
@@ -132,8 +146,10 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
         IfElse {
             id: self.id,
             predicate: self.predicate,
-            when_true,
+            when_true: Transitioned::new(when_true),
             when_false: self.when_false,
+            transition: self.transition,
+            shown: self.shown,
             position: self.position,
             dimension: self.dimension,
             phantom_data: Default::default(),
@@ -149,7 +165,9 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
             id: self.id,
             predicate: self.predicate,
             when_true: self.when_true,
-            when_false,
+            when_false: Transitioned::new(when_false),
+            transition: self.transition,
+            shown: self.shown,
             position: self.position,
             dimension: self.dimension,
             phantom_data: Default::default(),
@@ -164,8 +182,10 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
         IfElse {
             id: self.id,
             predicate: self.predicate,
-            when_true,
+            when_true: Transitioned::new(when_true),
             when_false: self.when_false,
+            transition: self.transition,
+            shown: self.shown,
             position: self.position,
             dimension: self.dimension,
             phantom_data: Default::default(),
@@ -181,7 +201,9 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
             id: self.id,
             predicate: self.predicate,
             when_true: self.when_true,
-            when_false,
+            when_false: Transitioned::new(when_false),
+            transition: self.transition,
+            shown: self.shown,
             position: self.position,
             dimension: self.dimension,
             phantom_data: Default::default(),
@@ -192,12 +214,54 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
 }
 
 impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind, KTrue: WidgetKind, KFalse: WidgetKind> IfElse<T, F, S, K, KTrue, KFalse> {
+    /// Transition between the branches when the predicate changes. The branch being removed is
+    /// kept until its transition has finished.
+    pub fn transition(mut self, transition: Transition) -> Self {
+        self.transition = Some(transition);
+        self
+    }
+
+    /// Start the transitions of the branches if the predicate has changed since last time.
+    fn update_transition(&mut self) {
+        let Some(transition) = &self.transition else {
+            return;
+        };
+
+        let predicate = *self.predicate.value();
+
+        if self.shown == Some(predicate) {
+            return;
+        }
+
+        // The branch shown initially should appear without a transition.
+        if self.shown.is_some() {
+            if predicate {
+                self.when_true.insert(transition);
+                self.when_false.remove(transition);
+            } else {
+                self.when_false.insert(transition);
+                self.when_true.remove(transition);
+            }
+        }
+
+        self.shown = Some(predicate);
+    }
+
+    /// Returns whether the true and false branches should be shown.
+    fn shown_branches(&self) -> (bool, bool) {
+        let predicate = *self.predicate.value();
+
+        (predicate || self.when_true.is_removing(), !predicate || self.when_false.is_removing())
+    }
+
     pub fn inverse(self) -> IfElse<F, T, S, K, KFalse, KTrue> {
         IfElse {
             id: self.id,
             predicate: self.predicate,
             when_true: self.when_false,
             when_false: self.when_true,
+            transition: self.transition,
+            shown: self.shown,
             position: self.position,
             dimension: self.dimension,
             phantom_data: Default::default(),
@@ -213,118 +277,66 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
     }
 
     fn child(&mut self, index: usize) -> &mut dyn AnyWidget {
-        if *self.predicate.value() {
-            if self.when_true.is_ignore() {
-                panic!("The child is ignore, and thus can not be indexed.");
-            }
-
-            if self.when_true.is_proxy() {
-                // Pass the index directly to the proxy child.
-                return self.when_true.child(index);
-            }
-
-            // If the child is neither an ignore nor a proxy, we have only a single child.
-            // We thus expect the index to be 0, otherwise we panic.
-            if index != 0 {
-                panic!("The index was not within the correct bounds.")
-            }
+        self.update_transition();
+        let (show_true, show_false) = self.shown_branches();
 
-            &mut self.when_true
+        let true_count = if show_true {
+            AnySequence::<dyn AnyWidget>::count(&mut self.when_true)
         } else {
-            if self.when_false.is_ignore() {
-                panic!("The child is ignore, and thus can not be indexed.");
-            }
-
-            if self.when_false.is_proxy() {
-                // Pass the index directly to the proxy child.
-                return self.when_false.child(index);
-            }
+            0
+        };
 
-            // If the child is neither an ignore nor a proxy, we have only a single child.
-            // We thus expect the index to be 0, otherwise we panic.
-            if index != 0 {
-                panic!("The index was not within the correct bounds.")
-            }
+        if index < true_count {
+            return self.when_true.index(index);
+        }
 
-            &mut self.when_false
+        if show_false {
+            return self.when_false.index(index - true_count);
         }
+
+        panic!("The index was not within the correct bounds.")
     }
 
     fn child_count(&mut self) -> usize {
-        if *self.predicate.value() {
-            if self.when_true.is_ignore() {
-                return 0;
-            }
-
-            if self.when_true.is_proxy() {
-                return self.when_true.child_count();
-            }
+        self.update_transition();
+        let (show_true, show_false) = self.shown_branches();
 
-            // If the child is neither an ignore nor a proxy, we have only a single child.
-            1
-        } else {
-            if self.when_false.is_ignore() {
-                return 0;
-            }
+        let mut count = 0;
 
-            if self.when_false.is_proxy() {
-                return self.when_false.child_count();
-            }
+        if show_true {
+            count += AnySequence::<dyn AnyWidget>::count(&mut self.when_true);
+        }
 
-            // If the child is neither an ignore nor a proxy, we have only a single child.
-            1
+        if show_false {
+            count += AnySequence::<dyn AnyWidget>::count(&mut self.when_false);
         }
+
+        count
     }
 
     fn foreach_child(&mut self, f: &mut dyn FnMut(&mut dyn AnyWidget)) {
-        if *self.predicate.value() {
-            if self.when_true.is_ignore() {
-                return;
-            }
-
-            if self.when_true.is_proxy() {
-                self.when_true.foreach_child(f);
-                return;
-            }
-
-            f(&mut self.when_true)
-        } else {
-            if self.when_false.is_ignore() {
-                return;
-            }
+        self.update_transition();
+        let (show_true, show_false) = self.shown_branches();
 
-            if self.when_false.is_proxy() {
-                self.when_false.foreach_child(f);
-                return;
-            }
+        if show_true {
+            self.when_true.foreach(f);
+        }
 
-            f(&mut self.when_false)
+        if show_false {
+            self.when_false.foreach(f);
         }
     }
 
     fn foreach_child_rev(&mut self, f: &mut dyn FnMut(&mut dyn AnyWidget)) {
-        if *self.predicate.value() {
-            if self.when_true.is_ignore() {
-                return;
-            }
-
-            if self.when_true.is_proxy() {
-                self.when_true.foreach_child_rev(f);
-                return;
-            }
-
-            f(&mut self.when_true)
-        } else {
-            if self.when_false.is_ignore() {
-                return;
-            }
+        self.update_transition();
+        let (show_true, show_false) = self.shown_branches();
 
-            if self.when_false.is_proxy() {
-                self.when_false.foreach_child_rev(f);
-                return;
-            }
+        if show_false {
+            self.when_false.foreach_rev(f);
+        }
 
-            f(&mut self.when_false)
+        if show_true {
+            self.when_true.foreach_rev(f);
         }
     }
 
@@ -354,8 +366,8 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
         f.debug_struct("IfElse")
             .field("id", &self.id)
             .field("predicate", &self.predicate)
-            .field("when_true", &self.when_true)
-            .field("when_false", &self.when_false)
+            .field("when_true", self.when_true.inner())
+            .field("when_false", self.when_false.inner())
             .finish_non_exhaustive()
     }
 }
@@ -369,6 +381,8 @@ impl<T: Widget, F: Widget, S: ReadState<T=bool> + Clone + 'static, K: WidgetKind
             predicate: self.predicate.clone(),
             when_true: self.when_true.clone(),
             when_false: self.when_false.clone(),
+            transition: self.transition.clone(),
+            shown: self.shown,
             phantom_data: Default::default(),
             phantom_data_true: Default::default(),
             phantom_data_false: Default::default(),
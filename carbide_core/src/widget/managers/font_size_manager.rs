@@ -51,7 +51,7 @@ impl<C: Widget> Layout for FontSizeManager<C> {
         let mut response = requested_size;
 
         EnvironmentFontSize::with_all(&self.sizes, ctx.env, |inner| {
-            response = self.child.calculate_size(requested_size, &mut LayoutContext {
+            response = self.child.process_calculate_size(requested_size, &mut LayoutContext {
                 text: ctx.text,
                 image: ctx.image,
                 env: inner,
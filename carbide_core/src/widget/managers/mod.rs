@@ -1,7 +1,9 @@
 mod theme_manager;
 mod font_size_manager;
 mod shortcut_manager;
+mod undo_manager;
 
 pub use theme_manager::*;
 pub use font_size_manager::*;
-pub use shortcut_manager::*;
\ No newline at end of file
+pub use shortcut_manager::*;
+pub use undo_manager::*;
\ No newline at end of file
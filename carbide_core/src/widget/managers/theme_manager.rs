@@ -154,7 +154,7 @@ impl<C: Widget> Layout for ThemeManager<C> {
         };
 
         EnvironmentColor::with_all(values, ctx.env, |inner| {
-            response = self.child.calculate_size(requested_size, &mut LayoutContext {
+            response = self.child.process_calculate_size(requested_size, &mut LayoutContext {
                 text: ctx.text,
                 image: ctx.image,
                 env: inner,
@@ -0,0 +1,283 @@
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::rc::{Rc, Weak};
+
+use crate::environment::EnvironmentKey;
+use crate::event::{Key, KeyboardEvent, KeyboardEventContext, KeyboardEventHandler, ModifierKey, OtherEvent, OtherEventContext, OtherEventHandler};
+use crate::identifiable::Identifiable;
+use crate::widget::managers::{ShortcutManager, ShortcutPressed};
+use crate::widget::{CommonWidget, Empty, Widget, WidgetId};
+use crate::ModifierWidgetImpl;
+
+/// Something with a history of changes that can be undone and redone, like a
+/// [HistoryState](crate::state::HistoryState).
+pub trait Undoable {
+    /// Undo the last change. Returns false if there was nothing to undo.
+    fn undo(&self) -> bool;
+
+    /// Redo the last undone change. Returns false if there was nothing to redo.
+    fn redo(&self) -> bool;
+}
+
+/// The undo manager keeps the order of changes made to the history states synced within it,
+/// such that the latest change can be undone regardless of which state it was made to. Clones of
+/// the manager share the same history.
+///
+/// The manager is placed in the environment using [UndoShortcuts], which also undoes and redoes
+/// changes when pressing ctrl+z and ctrl+shift+z.
+#[derive(Clone, Default)]
+pub struct UndoManager {
+    inner: Rc<RefCell<UndoStacks>>,
+}
+
+#[derive(Default)]
+struct UndoStacks {
+    undo: Vec<Weak<dyn Undoable>>,
+    redo: Vec<Weak<dyn Undoable>>,
+}
+
+impl UndoManager {
+    pub fn new() -> UndoManager {
+        UndoManager::default()
+    }
+
+    /// Register that a change has been made to the undoable. This clears the redo history.
+    pub fn push(&self, undoable: Weak<dyn Undoable>) {
+        let mut stacks = self.inner.borrow_mut();
+        stacks.undo.push(undoable);
+        stacks.redo.clear();
+    }
+
+    /// Undo the latest change. Changes to states that have been dropped are skipped.
+    pub fn undo(&self) -> bool {
+        loop {
+            let Some(next) = self.inner.borrow_mut().undo.pop() else {
+                return false;
+            };
+
+            if let Some(undoable) = next.upgrade() {
+                if undoable.undo() {
+                    self.inner.borrow_mut().redo.push(next);
+                    return true;
+                }
+            }
+        }
+    }
+
+    /// Register that the latest change of the undoable has been undone directly, rather than
+    /// through the manager, such that the manager keeps the order of the changes.
+    pub fn undone(&self, undoable: &Weak<dyn Undoable>) {
+        let mut stacks = self.inner.borrow_mut();
+
+        if let Some(index) = stacks.undo.iter().rposition(|x| x.ptr_eq(undoable)) {
+            let change = stacks.undo.remove(index);
+            stacks.redo.push(change);
+        }
+    }
+
+    /// Register that the latest undone change of the undoable has been redone directly, rather
+    /// than through the manager.
+    pub fn redone(&self, undoable: &Weak<dyn Undoable>) {
+        let mut stacks = self.inner.borrow_mut();
+
+        if let Some(index) = stacks.redo.iter().rposition(|x| x.ptr_eq(undoable)) {
+            let change = stacks.redo.remove(index);
+            stacks.undo.push(change);
+        }
+    }
+
+    /// Redo the latest undone change.
+    pub fn redo(&self) -> bool {
+        loop {
+            let Some(next) = self.inner.borrow_mut().redo.pop() else {
+                return false;
+            };
+
+            if let Some(undoable) = next.upgrade() {
+                if undoable.redo() {
+                    self.inner.borrow_mut().undo.push(next);
+                    return true;
+                }
+            }
+        }
+    }
+}
+
+impl Debug for UndoManager {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let stacks = self.inner.borrow();
+
+        f.debug_struct("UndoManager")
+            .field("undo", &stacks.undo.len())
+            .field("redo", &stacks.redo.len())
+            .finish()
+    }
+}
+
+impl EnvironmentKey for UndoManager {
+    type Value = UndoManager;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum UndoAction {
+    Undo,
+    Redo,
+}
+
+/// Undoes and redoes the changes of the [UndoManager] when the shortcuts are pressed. The
+/// shortcuts are not handled if a child, like a focused text input with its own history,
+/// prevents the default handling of the key.
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(KeyboardEvent, OtherEvent)]
+pub struct UndoShortcuts<C> where C: Widget {
+    id: WidgetId,
+    child: C,
+    manager: UndoManager,
+    pending: Option<UndoAction>,
+}
+
+impl UndoShortcuts<Empty> {
+    pub fn new<C: Widget>(manager: UndoManager, child: C) -> UndoShortcuts<C> {
+        UndoShortcuts {
+            id: WidgetId::new(),
+            child,
+            manager,
+            pending: None,
+        }
+    }
+}
+
+impl<C: Widget> KeyboardEventHandler for UndoShortcuts<C> {
+    fn process_keyboard_event(&mut self, event: &KeyboardEvent, ctx: &mut KeyboardEventContext) {
+        self.child.process_keyboard_event(event, ctx);
+
+        if !*ctx.is_current || *ctx.prevent_default {
+            return;
+        }
+
+        let KeyboardEvent::Press { no_modifier_key, modifiers, .. } = event else {
+            return;
+        };
+
+        let action = match no_modifier_key {
+            Key::Character(c) if c == "z" && (*modifiers == ModifierKey::CONTROL || *modifiers == ModifierKey::SUPER) => UndoAction::Undo,
+            Key::Character(c) if c == "z" && (*modifiers == ModifierKey::CTRL_SHIFT || *modifiers == ModifierKey::SHIFT_SUPER) => UndoAction::Redo,
+            Key::Character(c) if c == "y" && *modifiers == ModifierKey::CONTROL => UndoAction::Redo,
+            Key::Undo => UndoAction::Undo,
+            Key::Redo => UndoAction::Redo,
+            _ => return,
+        };
+
+        if let Some(manager) = ctx.env.get_mut::<ShortcutManager>() {
+            if manager.shortcut(self.id) {
+                self.pending = Some(action);
+            }
+        }
+    }
+}
+
+impl<C: Widget> OtherEventHandler for UndoShortcuts<C> {
+    fn process_other_event(&mut self, event: &OtherEvent, ctx: &mut OtherEventContext) {
+        if let Some(pressed) = event.value::<ShortcutPressed>() {
+            if pressed.0 == self.id {
+                match self.pending.take() {
+                    Some(UndoAction::Undo) => { self.manager.undo(); }
+                    Some(UndoAction::Redo) => { self.manager.redo(); }
+                    None => {}
+                }
+
+                return;
+            }
+        }
+
+        self.child.process_other_event(event, ctx);
+    }
+}
+
+impl<C: Widget> Identifiable for UndoShortcuts<C> {
+    type Id = WidgetId;
+
+    fn id(&self) -> WidgetId {
+        self.id
+    }
+}
+
+impl<C: Widget> CommonWidget for UndoShortcuts<C> {
+    ModifierWidgetImpl!(self, child: self.child);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::environment::Environment;
+    use crate::state::{HistoryState, LocalState, ReadState, State, StateSync};
+    use crate::widget::managers::UndoManager;
+
+    #[test]
+    fn undo_latest_change_across_states() {
+        let manager = UndoManager::new();
+        let mut env = Environment::new();
+
+        let mut first = HistoryState::new(LocalState::new(0));
+        let mut second = HistoryState::new(LocalState::new(0));
+
+        env.with::<UndoManager>(&manager, |env| {
+            first.sync(env);
+            second.sync(env);
+        });
+
+        first.set_value(1);
+        second.set_value(1);
+
+        assert!(manager.undo());
+        assert_eq!((*first.value(), *second.value()), (1, 0));
+
+        assert!(manager.undo());
+        assert_eq!((*first.value(), *second.value()), (0, 0));
+
+        assert!(!manager.undo());
+
+        assert!(manager.redo());
+        assert_eq!((*first.value(), *second.value()), (1, 0));
+
+        // Changes of dropped states are skipped
+        drop(first);
+        assert!(manager.redo());
+        assert_eq!(*second.value(), 1);
+
+        drop(second);
+        assert!(!manager.undo());
+    }
+    #[test]
+    fn undoing_state_directly_keeps_manager_order() {
+        let manager = UndoManager::new();
+        let mut env = Environment::new();
+
+        let mut first = HistoryState::new(LocalState::new(0));
+        let mut second = HistoryState::new(LocalState::new(0));
+
+        env.with::<UndoManager>(&manager, |env| {
+            first.sync(env);
+            second.sync(env);
+        });
+
+        first.set_value(1);
+        second.set_value(1);
+
+        assert!(first.undo());
+        assert_eq!((*first.value(), *second.value()), (0, 1));
+
+        // The change undone directly is no longer undone by the manager, but can be redone by it
+        assert!(manager.undo());
+        assert_eq!((*first.value(), *second.value()), (0, 0));
+        assert!(!manager.undo());
+
+        assert!(manager.redo());
+        assert!(manager.redo());
+        assert_eq!((*first.value(), *second.value()), (1, 1));
+
+        assert!(second.undo());
+        assert!(second.redo());
+        assert!(manager.undo());
+        assert_eq!((*first.value(), *second.value()), (1, 0));
+    }
+}
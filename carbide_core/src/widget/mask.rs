@@ -44,8 +44,8 @@ impl<M: Widget, W: Widget> WidgetSync for Mask<M, W> {
 
 impl<M: Widget, W: Widget> Layout for Mask<M, W> {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
-        self.child.calculate_size(requested_size, ctx);
-        self.mask.calculate_size(requested_size, ctx);
+        self.child.process_calculate_size(requested_size, ctx);
+        self.mask.process_calculate_size(requested_size, ctx);
         self.dimension = requested_size;
         requested_size
     }
@@ -16,6 +16,7 @@ pub use self::blur::*;
 pub use self::border::*;
 pub use self::clip::*;
 pub use self::clip_shape::*;
+pub use self::custom_layout::*;
 pub use self::empty::*;
 pub use self::environment_updating_new2::*;
 pub use self::environment_updating_new3::*;
@@ -26,10 +27,13 @@ pub use self::flexibility::*;
 pub use self::foreach::*;
 pub use self::frame::*;
 pub use self::geometry_reader::*;
+pub use self::grid::*;
+pub use self::grid_cell::*;
 pub use self::h_grid_lazy::*;
 pub use self::h_split::*;
 pub use self::h_stack::*;
 pub use self::h_stack_lazy::*;
+pub use self::h_wrap::*;
 pub use self::hidden::*;
 pub use self::hue_rotation::*;
 pub use self::if_else::*;
@@ -52,10 +56,12 @@ pub use self::shadow::*;
 pub use self::spacer::*;
 pub use self::text::*;
 pub use self::transform::*;
+pub(crate) use self::transitioned::Transitioned;
 pub use self::v_grid_lazy::*;
 pub use self::v_split::*;
 pub use self::v_stack::*;
 pub use self::v_stack_lazy::*;
+pub use self::v_wrap::*;
 pub use self::z_stack::*;
 pub use self::styled::*;
 
@@ -71,15 +77,19 @@ mod blur;
 mod border;
 mod clip;
 mod clip_shape;
+mod custom_layout;
 mod empty;
 mod filter;
 mod flag;
 mod flexibility;
 mod frame;
 mod geometry_reader;
+mod grid;
+mod grid_cell;
 mod h_grid_lazy;
 mod h_split;
 mod h_stack;
+mod h_wrap;
 mod hidden;
 mod if_else;
 mod image;
@@ -97,9 +107,11 @@ pub mod scroll;
 mod spacer;
 mod text;
 mod transform;
+mod transitioned;
 mod v_grid_lazy;
 mod v_split;
 mod v_stack;
+mod v_wrap;
 mod z_stack;
 mod aspect_ratio;
 mod on_key;
@@ -4,11 +4,13 @@ use crate::environment::EnvironmentKey;
 use crate::event::{AccessibilityEvent, AccessibilityEventContext, OtherEvent, KeyboardEvent, KeyboardEventContext, MouseEvent, MouseEventContext, OtherEventContext, WindowEvent, WindowEventContext, WindowEventHandler};
 use crate::lifecycle::InitializationContext;
 use crate::CommonWidgetImpl;
-use crate::draw::{Dimension, Position};
+use crate::animation::Transition;
+use crate::draw::{Dimension, Position, Rect};
+use crate::layout::{Layout, LayoutContext};
 use crate::environment::{Environment};
 use crate::event::{AccessibilityEventHandler, KeyboardEventHandler, MouseEventHandler, OtherEventHandler};
 use crate::lifecycle::{Initialize, Update, UpdateContext};
-use crate::widget::{AnyWidget, CommonWidget, Widget, WidgetId};
+use crate::widget::{AnyWidget, CommonWidget, LayoutDirection, Transitioned, Widget, WidgetId};
 use crate::widget::properties::WidgetKindSimple;
 
 #[derive(Debug, Clone)]
@@ -93,7 +95,7 @@ impl EnvironmentKey for NavigationRootKey {
 }
 
 #[derive(Debug, Clone, Widget)]
-#[carbide_exclude(Initialize, Update, MouseEvent, KeyboardEvent, OtherEvent, WindowEvent, AccessibilityEvent)]
+#[carbide_exclude(Initialize, Update, Layout, MouseEvent, KeyboardEvent, OtherEvent, WindowEvent, AccessibilityEvent)]
 pub struct NavigationStack<K> where K: EnvironmentKey<Value=NavigationManager> + Clone {
     #[id] id: WidgetId,
     position: Position,
@@ -101,7 +103,10 @@ pub struct NavigationStack<K> where K: EnvironmentKey<Value=NavigationManager> +
 
     key: PhantomData<K>,
     navigation_manager: NavigationManager,
-    current: Box<dyn AnyWidget>,
+    current: Transitioned<Box<dyn AnyWidget>>,
+    /// Pages that are transitioning out after being replaced by the current.
+    leaving: Vec<Transitioned<Box<dyn AnyWidget>>>,
+    transition: Option<Transition>,
 }
 
 impl NavigationStack<NavigationKey> {
@@ -114,7 +119,9 @@ impl NavigationStack<NavigationKey> {
             navigation_manager: NavigationManager {
                 stack: vec![StackItem::Current],
             },
-            current: initial.boxed(),
+            current: Transitioned::new(initial.boxed()),
+            leaving: vec![],
+            transition: None,
         }
     }
 
@@ -127,7 +134,9 @@ impl NavigationStack<NavigationKey> {
             navigation_manager: NavigationManager {
                 stack: vec![StackItem::Current],
             },
-            current: initial.boxed(),
+            current: Transitioned::new(initial.boxed()),
+            leaving: vec![],
+            transition: None,
         }
     }
 
@@ -140,15 +149,26 @@ impl NavigationStack<NavigationKey> {
             navigation_manager: NavigationManager {
                 stack: vec![StackItem::Current],
             },
-            current: initial.boxed(),
+            current: Transitioned::new(initial.boxed()),
+            leaving: vec![],
+            transition: None,
         }
     }
 }
 
 impl<K: EnvironmentKey<Value=NavigationManager> + Clone> NavigationStack<K> {
+    /// Transition between pages when pages are pushed or popped. The page being replaced is kept
+    /// until its transition has finished.
+    pub fn transition(mut self, transition: Transition) -> Self {
+        self.transition = Some(transition);
+        self
+    }
+
     fn with_navigation_manager(&mut self, env: &mut Environment, f: impl FnOnce(&mut Environment, &mut Box<dyn AnyWidget>)) {
+        self.leaving.retain(|page| !page.is_removed());
+
         env.with_mut::<K>(&mut self.navigation_manager, |env| {
-            f(env, &mut self.current)
+            f(env, self.current.inner_mut())
         });
 
         // Get a reference to the last stack item in the stack
@@ -162,9 +182,9 @@ impl<K: EnvironmentKey<Value=NavigationManager> + Clone> NavigationStack<K> {
             }
             StackItem::Other(last) => {
                 // replaces the last current element with the last element.
-                std::mem::swap(&mut self.current, last);
+                std::mem::swap(self.current.inner_mut(), last);
 
-                self.current.process_initialization(&mut InitializationContext {
+                self.current.inner_mut().process_initialization(&mut InitializationContext {
                     env,
                 })
             }
@@ -173,6 +193,14 @@ impl<K: EnvironmentKey<Value=NavigationManager> + Clone> NavigationStack<K> {
         // Last is now the previously current element
         let old_current = self.navigation_manager.stack.pop().unwrap();
 
+        if let (Some(transition), StackItem::Other(page)) = (&self.transition, &old_current) {
+            let mut leaving = Transitioned::new(page.clone());
+            leaving.remove(transition);
+
+            self.leaving.push(leaving);
+            self.current.insert(transition);
+        }
+
         // If any stack item has variant current, replace with the old_current.
         // At most 1 element could have variant current.
         let current = self.navigation_manager.stack.iter_mut().find(|a| matches!(a, StackItem::Current));
@@ -203,7 +231,12 @@ impl<K: EnvironmentKey<Value=NavigationManager> + Clone> Update for NavigationSt
                 image: ctx.image,
                 env,
             })
-        })
+        });
+
+        // The pages are updated without their transitions, so they are advanced here.
+        for page in self.leaving.iter_mut().chain(std::iter::once(&mut self.current)) {
+            page.advance(ctx.env);
+        }
     }
 }
 
@@ -275,6 +308,29 @@ impl<K: EnvironmentKey<Value=NavigationManager> + Clone> AccessibilityEventHandl
     }
 }
 
+impl<K: EnvironmentKey<Value=NavigationManager> + Clone> Layout for NavigationStack<K> {
+    fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        // The pages transitioning out are laid out in the same space as the current page.
+        for page in &mut self.leaving {
+            page.process_calculate_size(requested_size, ctx);
+        }
+
+        self.dimension = self.current.process_calculate_size(requested_size, ctx);
+        self.dimension
+    }
+
+    fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
+        let alignment = self.alignment().resolve(LayoutDirection::get(ctx.env));
+        let position = self.position;
+        let dimension = self.dimension;
+
+        for page in self.leaving.iter_mut().chain(std::iter::once(&mut self.current)) {
+            page.set_position(alignment.position(position, dimension, page.dimension()));
+            page.position_children(bounding_box, ctx);
+        }
+    }
+}
+
 impl<K: EnvironmentKey<Value=NavigationManager> + Clone> CommonWidget for NavigationStack<K> {
-    CommonWidgetImpl!(self, child: self.current, position: self.position, dimension: self.dimension);
+    CommonWidgetImpl!(self, child: [self.leaving, self.current], position: self.position, dimension: self.dimension);
 }
\ No newline at end of file
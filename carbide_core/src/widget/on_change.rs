@@ -53,7 +53,10 @@ impl<W: Widget, T: StateContract + PartialEq, S: ReadState<T=T>, F: Changed<T>>
 
 impl<W: Widget, T: StateContract + PartialEq, S: ReadState<T=T>, F: Changed<T>> WidgetSync for OnChange<W, T, S, F> {
     fn sync(&mut self, env: &mut Environment) {
-        self.state.sync(env);
+        // The value is only compared when the state reports a change
+        if !self.state.sync(env) && self.prev.is_some() {
+            return;
+        }
 
         if let Some(val) = &mut self.prev {
             if &*self.state.value() != val {
@@ -1,3 +1,4 @@
+use std::hash::Hash;
 use std::marker::PhantomData;
 use carbide::draw::Rect;
 use carbide::widget::properties::WidgetKindSimple;
@@ -9,10 +10,10 @@ use crate::CommonWidgetImpl;
 use crate::draw::{Dimension, Position};
 use crate::environment::EnvironmentKey;
 use crate::event::{KeyboardEvent, KeyboardEventContext, KeyboardEventHandler, MouseEvent, MouseEventContext, MouseEventHandler, OtherEventContext, OtherEventHandler, OtherEvent, WindowEventHandler, AccessibilityEventHandler};
-use crate::layout::{Layout, LayoutContext};
+use crate::layout::{Layout, LayoutCache, LayoutContext};
 use crate::render::{Render, RenderContext};
 use crate::lifecycle::{Initialize, Update, UpdateContext};
-use crate::widget::{AnyWidget, CommonWidget, Widget, WidgetId};
+use crate::widget::{AnyWidget, CommonWidget, LayoutDirection, Widget, WidgetId};
 
 #[derive(Debug)]
 pub enum OverlayAction {
@@ -115,18 +116,22 @@ impl<K: EnvironmentKey<Value=OverlayManager> + Clone, C: Widget> Initialize for
 
 impl<K: EnvironmentKey<Value=OverlayManager> + Clone, C: Widget> Update for Overlay<K, C> {
     fn process_update(&mut self, ctx: &mut UpdateContext) {
-        self.with(ctx.env, |env, inner| {
-            let inner_ctx = &mut UpdateContext {
-                text: ctx.text,
-                image: ctx.image,
-                env,
-            };
-
-            if let Some(overlay) = &mut inner.overlay {
-                overlay.process_update(inner_ctx);
-            }
+        LayoutCache::track(self.id, |children| {
+            self.with(ctx.env, |env, inner| {
+                let inner_ctx = &mut UpdateContext {
+                    text: ctx.text,
+                    image: ctx.image,
+                    env,
+                };
+
+                if let Some(overlay) = &mut inner.overlay {
+                    overlay.id().hash(children);
+                    overlay.process_update(inner_ctx);
+                }
 
-            inner.child.process_update(inner_ctx);
+                inner.child.id().hash(children);
+                inner.child.process_update(inner_ctx);
+            })
         })
     }
 }
@@ -248,15 +253,15 @@ impl<K: EnvironmentKey<Value=OverlayManager> + Clone, C: Widget> AccessibilityEv
 impl<K: EnvironmentKey<Value=OverlayManager> + Clone, C: Widget> Layout for Overlay<K, C> {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
         if let Some(overlay) = &mut self.overlay {
-            overlay.calculate_size(requested_size, ctx);
+            overlay.process_calculate_size(requested_size, ctx);
         }
 
-        self.dimension = self.child.calculate_size(requested_size, ctx);
+        self.dimension = self.child.process_calculate_size(requested_size, ctx);
         self.dimension
     }
 
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
-        let alignment = self.alignment();
+        let alignment = self.alignment().resolve(LayoutDirection::get(ctx.env));
         let position = self.position();
         let dimension = self.dimension();
 
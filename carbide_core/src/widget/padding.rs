@@ -3,7 +3,7 @@ use crate::CommonWidgetImpl;
 use crate::draw::{Alignment, Dimension, Position};
 use crate::layout::{Layout, LayoutContext};
 use crate::state::{IntoReadState, ReadState};
-use crate::widget::{AnyWidget, CommonWidget, Empty, Widget, WidgetId};
+use crate::widget::{AnyWidget, CommonWidget, Empty, LayoutDirection, Widget, WidgetId};
 use crate::widget::types::EdgeInsets;
 
 #[derive(Debug, Clone, Widget)]
@@ -48,7 +48,7 @@ impl<W: Widget, E: ReadState<T=EdgeInsets>> Layout for Padding<W, E> {
             requested_size.height - insets.top - insets.bottom,
         );
 
-        let child_dimensions = self.child.calculate_size(dimensions, ctx);
+        let child_dimensions = self.child.process_calculate_size(dimensions, ctx);
 
         self.dimension = Dimension::new(
             child_dimensions.width + insets.left + insets.right,
@@ -61,7 +61,7 @@ impl<W: Widget, E: ReadState<T=EdgeInsets>> Layout for Padding<W, E> {
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
         let insets = *self.edge_insets.value();
         let position = Position::new(
-            self.x() + insets.left,
+            self.x() + insets.left_in(LayoutDirection::get(ctx.env)),
             self.y() + insets.top,
         );
         let dimension = Dimension::new(
@@ -5,22 +5,47 @@ use carbide::draw::Rect;
 use crate::event::{WindowEvent, WindowEventContext};
 use carbide_macro::carbide_default_builder2;
 
+use crate::animation::{AnimationManager, Decay, VelocityTracker};
 use crate::color::Color;
 use crate::draw::{Alignment, Dimension, Position};
-use crate::environment::EnvironmentColor;
+use crate::environment::{EnvironmentColor, EnvironmentKey};
 use crate::event::{ModifierKey, MouseButton, MouseEvent, MouseEventContext, MouseEventHandler, WindowEventHandler};
 use crate::common::flags::WidgetFlag;
 use crate::CommonWidgetImpl;
 use crate::layout::{Layout, LayoutContext};
+use crate::lifecycle::{Update, UpdateContext};
 use crate::render::{Render, RenderContext};
 use crate::state::{LocalState, ReadState, State, StateExtNew};
-use crate::widget::{AnyWidget, Capsule, CommonWidget, Empty, Rectangle, Widget, WidgetExt, WidgetId};
+use crate::widget::{AnyWidget, Capsule, CommonWidget, Empty, LayoutDirection, Rectangle, Widget, WidgetExt, WidgetId};
 use crate::widget::properties::WidgetKindSimple;
 use crate::widget::scroll::style::{HorizontalScrollBarStyleKey, VerticalScrollBarStyleKey};
+use crate::time::*;
 use crate::widget::types::ScrollDirection;
 
+/// A rectangle that a child of a scroll wants to be visible. The closest scroll provides this
+/// while positioning its child. If a child sets it, for example to keep a text cursor in view
+/// while typing, the scroll adjusts its offset such that the rectangle is within its bounds.
+#[derive(Debug)]
+pub struct ScrollToVisible;
+
+impl EnvironmentKey for ScrollToVisible {
+    type Value = Option<Rect>;
+}
+
+/// The time without scroll events after which scrolling is considered ended, and a fling starts.
+const FLING_DELAY: Duration = Duration::from_millis(50);
+
+/// A running fling, continuing the scrolling with decaying velocity.
+#[derive(Debug, Clone)]
+struct Fling {
+    start: Instant,
+    from: Position,
+    x: Decay,
+    y: Decay,
+}
+
 #[derive(Debug, Clone, Widget)]
-#[carbide_exclude(Render, MouseEvent, WindowEvent, Layout)]
+#[carbide_exclude(Render, MouseEvent, WindowEvent, Layout, Update)]
 pub struct Scroll<W> where W: Widget<Kind=WidgetKindSimple> {
     #[id] id: WidgetId,
     child: W,
@@ -40,6 +65,12 @@ pub struct Scroll<W> where W: Widget<Kind=WidgetKindSimple> {
     vertical_background: Box<dyn AnyWidget>,
     vertical_background_hovered: LocalState<bool>,
     vertical_dragging: LocalState<bool>,
+
+    /// The friction of flings, or None if scrolling stops with the scroll events.
+    fling_friction: Option<f64>,
+    velocity_tracker: VelocityTracker,
+    last_scroll: Option<Instant>,
+    fling: Option<Fling>,
 }
 
 impl Scroll<Empty> {
@@ -63,6 +94,11 @@ impl Scroll<Empty> {
             vertical_background: Rectangle::new().fill(EnvironmentColor::Blue).frame(10.0, 10.0).boxed(),
             vertical_background_hovered: LocalState::new(false),
             vertical_dragging: LocalState::new(false),
+
+            fling_friction: None,
+            velocity_tracker: VelocityTracker::new(),
+            last_scroll: None,
+            fling: None,
         }
     }
 }
@@ -73,6 +109,14 @@ impl<W: Widget<Kind=WidgetKindSimple>> Scroll<W> {
         self
     }
 
+    /// Keep scrolling with the velocity of the scroll events after they stop, slowing down with
+    /// the friction like a [Decay]. This is useful for input devices like mouse wheels that do
+    /// not provide momentum scrolling themselves.
+    pub fn fling(mut self, friction: f64) -> Self {
+        self.fling_friction = Some(friction);
+        self
+    }
+
     fn keep_y_within_bounds(&mut self) {
         if self.scroll_offset.y > 0.0 {
             self.scroll_offset = Position::new(self.scroll_offset.x, 0.0);
@@ -102,6 +146,46 @@ impl<W: Widget<Kind=WidgetKindSimple>> Scroll<W> {
             self.scroll_offset = Position::new(0.0, self.scroll_offset.y);
         }
     }
+
+    /// Adjust the scroll offset to make the rectangle visible, if possible. Returns true if the
+    /// offset changed.
+    fn scroll_to_visible(&mut self, rect: Rect) -> bool {
+        let offset = self.scroll_offset;
+
+        if self.scroll_directions == ScrollDirection::Both || self.scroll_directions == ScrollDirection::Vertical {
+            let below = rect.position.y + rect.dimension.height - (self.y() + self.height());
+            let above = self.y() - rect.position.y;
+
+            // A rectangle taller than the scroll can not be fully visible, so its top edge is
+            // shown instead.
+            if rect.dimension.height > self.height() {
+                self.scroll_offset.y += above;
+            } else if below > 0.0 {
+                self.scroll_offset.y -= below;
+            } else if above > 0.0 {
+                self.scroll_offset.y += above;
+            }
+
+            self.keep_y_within_bounds();
+        }
+
+        if self.scroll_directions == ScrollDirection::Both || self.scroll_directions == ScrollDirection::Horizontal {
+            let right = rect.position.x + rect.dimension.width - (self.x() + self.width());
+            let left = self.x() - rect.position.x;
+
+            if rect.dimension.width > self.width() {
+                self.scroll_offset.x -= left;
+            } else if right > 0.0 {
+                self.scroll_offset.x += right;
+            } else if left > 0.0 {
+                self.scroll_offset.x -= left;
+            }
+
+            self.keep_x_within_bounds();
+        }
+
+        self.scroll_offset != offset
+    }
 }
 
 impl<W: Widget<Kind=WidgetKindSimple>> MouseEventHandler for Scroll<W> {
@@ -139,6 +223,14 @@ impl<W: Widget<Kind=WidgetKindSimple>> MouseEventHandler for Scroll<W> {
 
                     self.keep_x_within_bounds();
                 }
+
+                if self.fling_friction.is_some() {
+                    let now = Instant::now();
+
+                    self.fling = None;
+                    self.last_scroll = Some(now);
+                    self.velocity_tracker.add(now, self.scroll_offset);
+                }
             }
             MouseEvent::Release { .. } => {
                 *self.vertical_dragging.value_mut() = false;
@@ -149,6 +241,8 @@ impl<W: Widget<Kind=WidgetKindSimple>> MouseEventHandler for Scroll<W> {
                 *self.horizontal_background_hovered.value_mut() = self.horizontal_background.is_inside(*to);
             }
             MouseEvent::Press { button: MouseButton::Left, position: point, .. } => {
+                self.fling = None;
+
                 if self.vertical_background.is_inside(*point)
                     && !self.vertical_thumb.is_inside(*point)
                 {
@@ -233,6 +327,58 @@ impl<W: Widget<Kind=WidgetKindSimple>> MouseEventHandler for Scroll<W> {
     }
 }
 
+impl<W: Widget<Kind=WidgetKindSimple>> Update for Scroll<W> {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let Some(friction) = self.fling_friction else {
+            return;
+        };
+
+        let now = Instant::now();
+
+        // Start a fling with the velocity of the scrolling, once the scroll events have stopped.
+        if let Some(last_scroll) = self.last_scroll {
+            if now.duration_since(last_scroll) < FLING_DELAY {
+                if let Some(manager) = ctx.env.get_mut::<AnimationManager>() {
+                    manager.request_animation_frame();
+                }
+
+                return;
+            }
+
+            let velocity = self.velocity_tracker.velocity(last_scroll);
+
+            self.last_scroll = None;
+            self.velocity_tracker.reset();
+
+            if velocity != Position::new(0.0, 0.0) {
+                self.fling = Some(Fling {
+                    start: now,
+                    from: self.scroll_offset,
+                    x: Decay::new(velocity.x).friction(friction),
+                    y: Decay::new(velocity.y).friction(friction),
+                });
+            }
+        }
+
+        let Some(fling) = &self.fling else {
+            return;
+        };
+
+        let elapsed = now.duration_since(fling.start).as_secs_f64();
+        let settled = fling.x.is_settled(elapsed) && fling.y.is_settled(elapsed);
+
+        self.scroll_offset = fling.from + Position::new(fling.x.offset(elapsed), fling.y.offset(elapsed));
+        self.keep_x_within_bounds();
+        self.keep_y_within_bounds();
+
+        if settled {
+            self.fling = None;
+        } else if let Some(manager) = ctx.env.get_mut::<AnimationManager>() {
+            manager.request_animation_frame();
+        }
+    }
+}
+
 impl<W: Widget<Kind=WidgetKindSimple>> WindowEventHandler for Scroll<W> {
     fn handle_window_event(&mut self, _: &WindowEvent, _: &mut WindowEventContext) {
         self.keep_y_within_bounds();
@@ -242,7 +388,7 @@ impl<W: Widget<Kind=WidgetKindSimple>> WindowEventHandler for Scroll<W> {
 
 impl<W: Widget<Kind=WidgetKindSimple>> Layout for Scroll<W> {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
-        self.child.calculate_size(requested_size, ctx);
+        self.child.process_calculate_size(requested_size, ctx);
 
         self.keep_y_within_bounds();
         self.keep_x_within_bounds();
@@ -283,12 +429,12 @@ impl<W: Widget<Kind=WidgetKindSimple>> Layout for Scroll<W> {
                 - horizontal_height;
 
             self.vertical_thumb.set_height(height);
-            self.vertical_thumb.calculate_size(requested_size, ctx);
+            self.vertical_thumb.process_calculate_size(requested_size, ctx);
 
             self.vertical_background
                 .set_height(requested_size.height);
             self.vertical_background
-                .calculate_size(requested_size, ctx);
+                .process_calculate_size(requested_size, ctx);
         }
 
         if self.scroll_directions == ScrollDirection::Both
@@ -312,18 +458,33 @@ impl<W: Widget<Kind=WidgetKindSimple>> Layout for Scroll<W> {
             self.horizontal_thumb
                 .set_width(width);
             self.horizontal_thumb
-                .calculate_size(requested_size, ctx);
+                .process_calculate_size(requested_size, ctx);
 
             self.horizontal_background
                 .set_width(requested_size.width);
             self.horizontal_background
-                .calculate_size(requested_size, ctx);
+                .process_calculate_size(requested_size, ctx);
         }
 
         requested_size
     }
 
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
+        // Position again with the new offset, if the child requested a rectangle to be visible
+        // that was not. The rectangle requested while positioning again is ignored, such that
+        // the offset can not keep changing within a single layout.
+        if let Some(rect) = self.position_content(bounding_box, ctx) {
+            if self.scroll_to_visible(rect) {
+                self.position_content(bounding_box, ctx);
+            }
+        }
+    }
+}
+
+impl<W: Widget<Kind=WidgetKindSimple>> Scroll<W> {
+    /// Position the child and the scroll bars using the current offset. Returns the rectangle
+    /// the child requested to be visible, if any.
+    fn position_content(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) -> Option<Rect> {
         let position = self.position;
         let dimension = self.dimension;
 
@@ -336,14 +497,18 @@ impl<W: Widget<Kind=WidgetKindSimple>> Layout for Scroll<W> {
             child_position.y + self.scroll_offset.y,
         ));
 
-        // Position scrollbars
+        // Position scrollbars. In right to left layouts the vertical scroll bar is on the left.
+        let right_to_left = LayoutDirection::get(ctx.env).is_right_to_left();
+
+        let vertical_x = |width: f64| if right_to_left { 0.0 } else { dimension.width - width };
+
         self.vertical_thumb.set_position(
             self.position()
-                + Position::new(self.dimension.width - self.vertical_thumb.width(), 0.0),
+                + Position::new(vertical_x(self.vertical_thumb.width()), 0.0),
         );
         self.vertical_background.set_position(
             self.position()
-                + Position::new(self.dimension.width - self.vertical_background.width(), 0.0),
+                + Position::new(vertical_x(self.vertical_background.width()), 0.0),
         );
 
         let scroll_vertical_percent = if self.child.height() - self.height() != 0.0 {
@@ -398,10 +563,12 @@ impl<W: Widget<Kind=WidgetKindSimple>> Layout for Scroll<W> {
             0.0
         };
 
+        let horizontal_start = if right_to_left { vertical_width } else { 0.0 };
+
         self.horizontal_thumb.set_position(
             self.horizontal_thumb.position()
                 + Position::new(
-                    (self.width() - vertical_width - self.horizontal_thumb.width())
+                    horizontal_start + (self.width() - vertical_width - self.horizontal_thumb.width())
                         * scroll_horizontal_percent,
                     0.0,
                 ),
@@ -411,7 +578,18 @@ impl<W: Widget<Kind=WidgetKindSimple>> Layout for Scroll<W> {
         self.horizontal_thumb.position_children(bounding_box, ctx);
         self.vertical_background.position_children(bounding_box, ctx);
         self.horizontal_background.position_children(bounding_box, ctx);
-        self.child.position_children(bounding_box, ctx);
+
+        let mut visible = None;
+
+        ctx.env.with_mut::<ScrollToVisible>(&mut visible, |env| {
+            self.child.position_children(bounding_box, &mut LayoutContext {
+                text: ctx.text,
+                image: ctx.image,
+                env,
+            });
+        });
+
+        visible
     }
 }
 
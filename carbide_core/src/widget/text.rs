@@ -1,15 +1,17 @@
 use crate::accessibility::Accessibility;
 use crate::accessibility::AccessibilityContext;
 use crate::draw::{Dimension, Position};
-use crate::environment::{EnvironmentColor, EnvironmentFontSize};
-use crate::layout::{Layout, LayoutContext};
+use crate::environment::{EnvironmentColor, EnvironmentFontSize, EnvironmentKeyable};
+use crate::event::{MouseButton, MouseEvent, MouseEventContext, MouseEventHandler};
+use crate::layout::{Layout, LayoutContext, report_baseline};
 use crate::render::{Render, RenderContext, Style};
 use crate::scene::SceneManager;
-use crate::state::{IntoReadState, ReadState};
+use crate::state::{IntoReadState, Map1, ReadState};
 use crate::text::text_justify::TextJustify;
 use crate::text::text_wrap::{wrap_state, Wrap, WrapState};
-use crate::text::{FontStyle, FontWeight, TextDecoration, TextId, TextStyle};
-use crate::widget::{AnyWidget, CommonWidget, Widget, WidgetId, WidgetSync};
+use crate::text::highlight::Highlighter;
+use crate::text::{AttributedText, FontStyle, FontWeight, LinkHandler, TextDecoration, TextId, TextSpan, TextStyle};
+use crate::widget::{AnyWidget, CommonWidget, LayoutDirection, Widget, WidgetId, WidgetSync};
 use accesskit::{Node, Point, Rect, Role, Size};
 use carbide_macro::carbide_default_builder2;
 use std::fmt::Debug;
@@ -22,16 +24,20 @@ use crate::identifiable::Identifiable;
 ///
 /// If some horizontal dimension is given, the text will automatically wrap to the width and align
 /// in accordance with the produced **Alignment**.
+///
+/// Parts of the text can be styled differently using spans, see [Text::attributed]. Clicking a
+/// span with a link calls the [LinkHandler] in the environment with the link.
 #[derive(Debug, Clone, Widget)]
-#[carbide_exclude(Render, Layout, Accessibility)]
-pub struct Text<T, S, C, FS, FW, W>
+#[carbide_exclude(Render, Layout, Accessibility, MouseEvent)]
+pub struct Text<T, S, C, FS, FW, W, A>
 where
     T: ReadState<T=String>,
     S: ReadState<T=u32>,
     C: ReadState<T=Style>,
     FS: ReadState<T=FontStyle>,
     FW: ReadState<T=FontWeight>,
-    W: ReadState<T=Wrap>
+    W: ReadState<T=Wrap>,
+    A: ReadState<T=Vec<TextSpan>>,
 {
     #[id] id: WidgetId,
     text_id: TextId,
@@ -45,11 +51,13 @@ where
     font_style: FS,
     font_weight: FW,
     text_decoration: TextDecoration,
+    #[state] spans: A,
+    highlighter: Option<Box<dyn Highlighter>>,
 }
 
-impl Text<String, u32, Style, FontStyle, FontWeight, Wrap> {
+impl Text<String, u32, Style, FontStyle, FontWeight, Wrap, Vec<TextSpan>> {
 
-    pub fn new<T: IntoReadState<String>>(text: T) -> Text<T::Output, impl ReadState<T=u32>, impl ReadState<T=Style>, FontStyle, FontWeight, WrapState> {
+    pub fn new<T: IntoReadState<String>>(text: T) -> Text<T::Output, impl ReadState<T=u32>, impl ReadState<T=Style>, FontStyle, FontWeight, WrapState, Vec<TextSpan>> {
         let text = text.into_read_state();
 
         Text {
@@ -65,12 +73,23 @@ impl Text<String, u32, Style, FontStyle, FontWeight, Wrap> {
             font_style: FontStyle::Normal,
             font_weight: FontWeight::Normal,
             text_decoration: TextDecoration::None,
+            spans: vec![],
+            highlighter: None,
         }
     }
+
+    /// Create a text where the spans of the attributed text are styled on top of the style of the
+    /// widget.
+    pub fn attributed<T: IntoReadState<AttributedText>>(text: T) -> Text<impl ReadState<T=String>, impl ReadState<T=u32>, impl ReadState<T=Style>, FontStyle, FontWeight, WrapState, impl ReadState<T=Vec<TextSpan>>> {
+        let text = text.into_read_state();
+
+        Text::new(Map1::read_map(text.clone(), |text: &AttributedText| text.text.clone()))
+            .spans(Map1::read_map(text, |text: &AttributedText| text.spans.clone()))
+    }
 }
 
-impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2: ReadState<T=FontStyle>, FW2: ReadState<T=FontWeight>, W: ReadState<T=Wrap>> Text<T2, S2, C2, FS2, FW2, W> {
-    pub fn color<C: IntoReadState<Style>>(self, color: C) -> Text<T2, S2, C::Output, FS2, FW2, W> {
+impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2: ReadState<T=FontStyle>, FW2: ReadState<T=FontWeight>, W: ReadState<T=Wrap>, A: ReadState<T=Vec<TextSpan>>> Text<T2, S2, C2, FS2, FW2, W, A> {
+    pub fn color<C: IntoReadState<Style>>(self, color: C) -> Text<T2, S2, C::Output, FS2, FW2, W, A> {
         Text {
             id: self.id,
             position: self.position,
@@ -83,13 +102,15 @@ impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2:
             font_style: self.font_style,
             font_weight: self.font_weight,
             text_decoration: self.text_decoration,
+            spans: self.spans,
+            highlighter: self.highlighter,
             //internal_text: self.internal_text,
             //text_span_generator: self.text_span_generator,
             text_id: self.text_id
         }
     }
 
-    pub fn font_size<S: IntoReadState<u32>>(self, size: S) -> Text<T2, S::Output, C2, FS2, FW2, W> {
+    pub fn font_size<S: IntoReadState<u32>>(self, size: S) -> Text<T2, S::Output, C2, FS2, FW2, W, A> {
         Text {
             id: self.id,
             text_id: self.text_id,
@@ -103,6 +124,8 @@ impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2:
             font_style: self.font_style,
             font_weight: self.font_weight,
             text_decoration: self.text_decoration,
+            spans: self.spans,
+            highlighter: self.highlighter,
         }
     }
 
@@ -111,7 +134,7 @@ impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2:
         self
     }
 
-    pub fn font_weight<FW: IntoReadState<FontWeight>>(self, weight: FW) -> Text<T2, S2, C2, FS2, FW::Output, W> {
+    pub fn font_weight<FW: IntoReadState<FontWeight>>(self, weight: FW) -> Text<T2, S2, C2, FS2, FW::Output, W, A> {
         Text {
             id: self.id,
             text_id: self.text_id,
@@ -125,10 +148,12 @@ impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2:
             font_style: self.font_style,
             font_weight: weight.into_read_state(),
             text_decoration: self.text_decoration,
+            spans: self.spans,
+            highlighter: self.highlighter,
         }
     }
 
-    pub fn font_style<FS: IntoReadState<FontStyle>>(self, style: FS) -> Text<T2, S2, C2, FS::Output, FW2, W> {
+    pub fn font_style<FS: IntoReadState<FontStyle>>(self, style: FS) -> Text<T2, S2, C2, FS::Output, FW2, W, A> {
         Text {
             id: self.id,
             text_id: self.text_id,
@@ -142,19 +167,48 @@ impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2:
             font_style: style.into_read_state(),
             font_weight: self.font_weight,
             text_decoration: self.text_decoration,
+            spans: self.spans,
+            highlighter: self.highlighter,
         }
     }
 
     /// Take a given text element and make it render with the font weight: Bold
-    pub fn bold(self) -> Text<T2, S2, C2, FS2, FontWeight, W> {
+    pub fn bold(self) -> Text<T2, S2, C2, FS2, FontWeight, W, A> {
         self.font_weight(FontWeight::Bold)
     }
 
-    pub fn italic(self) -> Text<T2, S2, C2, FontStyle, FW2, W> {
+    pub fn italic(self) -> Text<T2, S2, C2, FontStyle, FW2, W, A> {
         self.font_style(FontStyle::Italic)
     }
 
-    pub fn wrap<W2: IntoReadState<Wrap>>(self, wrap: W2) -> Text<T2, S2, C2, FS2, FW2, W2::Output> {
+    /// Set the spans of the text. The ranges of the spans are byte ranges into the text.
+    pub fn spans<A2: IntoReadState<Vec<TextSpan>>>(self, spans: A2) -> Text<T2, S2, C2, FS2, FW2, W, A2::Output> {
+        Text {
+            id: self.id,
+            text_id: self.text_id,
+            position: self.position,
+            dimension: self.dimension,
+            wrap_mode: self.wrap_mode,
+            text: self.text,
+            font_size: self.font_size,
+            color: self.color,
+            family: self.family,
+            font_style: self.font_style,
+            font_weight: self.font_weight,
+            text_decoration: self.text_decoration,
+            spans: spans.into_read_state(),
+            highlighter: self.highlighter,
+        }
+    }
+
+    /// Style the text using the highlighter, for example to color source code. The highlighter is
+    /// only used when the text has no spans of its own.
+    pub fn highlight(mut self, highlighter: impl Highlighter + 'static) -> Self {
+        self.highlighter = Some(Box::new(highlighter));
+        self
+    }
+
+    pub fn wrap<W2: IntoReadState<Wrap>>(self, wrap: W2) -> Text<T2, S2, C2, FS2, FW2, W2::Output, A> {
         Text {
             id: self.id,
             text_id: self.text_id,
@@ -168,6 +222,8 @@ impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2:
             font_style: self.font_style,
             font_weight: self.font_weight,
             text_decoration: self.text_decoration,
+            spans: self.spans,
+            highlighter: self.highlighter,
         }
     }
 
@@ -200,6 +256,7 @@ impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2:
             text_decoration: self.text_decoration.clone(),
             color: None,
             wrap: *self.wrap_mode.value(),
+            direction: LayoutDirection::LeftToRight,
         }
     }
 
@@ -215,11 +272,38 @@ impl<T2: ReadState<T=String>, S2: ReadState<T=u32>, C2: ReadState<T=Style>, FS2:
     }
 }
 
-impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>> Layout for Text<T, S, C, FS, FW, W> {
+impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>, A: ReadState<T=Vec<TextSpan>>> Layout for Text<T, S, C, FS, FW, W, A> {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
         self.sync(ctx.env);
 
-        ctx.text.update(self.text_id, &self.text.value(), &self.get_style());
+        let mut spans = self.spans.value().clone();
+
+        if spans.is_empty() {
+            if let Some(highlighter) = &self.highlighter {
+                spans = highlighter.highlight(&self.text.value(), ctx.env);
+            }
+        }
+
+        let style = TextStyle {
+            direction: LayoutDirection::get(ctx.env),
+            ..self.get_style()
+        };
+
+        if spans.is_empty() {
+            ctx.text.update(self.text_id, &self.text.value(), &style);
+        } else {
+            // Links without a color of their own are shown in the link color of the environment.
+            let link_color = EnvironmentColor::Link.get(ctx.env);
+
+            for span in &mut spans {
+                if span.attributes.link.is_some() && span.attributes.color.is_none() {
+                    span.attributes.color = link_color;
+                }
+            }
+
+            ctx.text.update_attributed(self.text_id, &self.text.value(), &spans, &style);
+        }
+
         self.dimension = ctx.text.calculate_size(self.text_id, requested_size, ctx.env);
 
         self.dimension
@@ -230,11 +314,17 @@ impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: Rea
             .map(|a| a.scale_factor())
             .unwrap_or(1.0);
 
-        ctx.text.calculate_position(self.text_id, self.position.tolerance(1.0 / scale_factor), ctx.env)
+        ctx.text.calculate_position(self.text_id, self.position.tolerance(1.0 / scale_factor), ctx.env);
+
+        // Report the baseline, for parents aligning their children on it, like a Grid.
+        let position = self.position;
+        let text_id = self.text_id;
+        let text = &mut *ctx.text;
+        report_baseline(ctx.env, || text.first_baseline(text_id).map(|baseline| position.y + baseline));
     }
 }
 
-impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>> Render for Text<T, S, C, FS, FW, W> {
+impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>, A: ReadState<T=Vec<TextSpan>>> Render for Text<T, S, C, FS, FW, W, A> {
     fn render(&mut self, context: &mut RenderContext) {
         self.sync(context.env);
 
@@ -246,7 +336,33 @@ impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: Rea
     }
 }
 
-impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>> Accessibility for Text<T, S, C, FS, FW, W> {
+impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>, A: ReadState<T=Vec<TextSpan>>> MouseEventHandler for Text<T, S, C, FS, FW, W, A> {
+    fn handle_mouse_event(&mut self, event: &MouseEvent, ctx: &mut MouseEventContext) {
+        match event {
+            MouseEvent::Click(MouseButton::Left, position, _) => {
+                if !self.is_inside(*position) || self.spans.value().is_empty() {
+                    return;
+                }
+
+                let Some(index) = ctx.text.span_at(self.text_id, *position - self.position) else {
+                    return;
+                };
+
+                let Some(link) = self.spans.value().get(index).and_then(|span| span.attributes.link.clone()) else {
+                    return;
+                };
+
+                if let Some(handler) = ctx.env.get::<LinkHandler>().copied() {
+                    handler(&link, ctx.env);
+                    *ctx.consumed = true;
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>, A: ReadState<T=Vec<TextSpan>>> Accessibility for Text<T, S, C, FS, FW, W, A> {
     fn process_accessibility(&mut self, ctx: &mut AccessibilityContext) {
         self.sync(ctx.env);
 
@@ -287,7 +403,7 @@ impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: Rea
     }
 }
 
-impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>> CommonWidget for Text<T, S, C, FS, FW, W> {
+impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>, A: ReadState<T=Vec<TextSpan>>> CommonWidget for Text<T, S, C, FS, FW, W, A> {
     CommonWidgetImpl!(self, position: self.position, dimension: self.dimension, child: (), flexibility: 2);
 }
 
@@ -295,7 +411,7 @@ pub trait TextWidget: AnyWidget {
     fn text_id(&self) -> TextId;
 }
 
-impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>> TextWidget for Text<T, S, C, FS, FW, W> {
+impl<T: ReadState<T=String>, S: ReadState<T=u32>, C: ReadState<T=Style>, FS: ReadState<T=FontStyle>, FW: ReadState<T=FontWeight>, W: ReadState<T=Wrap>, A: ReadState<T=Vec<TextSpan>>> TextWidget for Text<T, S, C, FS, FW, W, A> {
     fn text_id(&self) -> TextId {
         self.text_id
     }
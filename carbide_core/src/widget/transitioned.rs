@@ -0,0 +1,321 @@
+use carbide::draw::Rect;
+
+use crate::animation::{AnimationManager, Transition};
+use crate::common::flags::WidgetFlag;
+use crate::draw::{Alignment, Dimension, Position};
+use crate::event::{KeyboardEvent, KeyboardEventContext, KeyboardEventHandler, MouseEvent, MouseEventContext, MouseEventHandler};
+use crate::environment::Environment;
+use crate::layout::{Layout, LayoutContext};
+use crate::lifecycle::{Update, UpdateContext};
+use crate::render::{Render, RenderContext};
+use crate::time::*;
+use crate::widget::{AnySequence, AnyWidget, CommonWidget, Widget, WidgetId};
+use crate::CommonWidgetImpl;
+
+/// Wraps content that is inserted and removed with a [Transition] by a container. The widget is
+/// always a proxy, so the container lays out and handles events for the content as if it was not
+/// wrapped. The effect of a running transition is applied by its [TransitionContent] child.
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Layout)]
+pub(crate) struct Transitioned<W> where W: Widget {
+    #[id] id: WidgetId,
+    content: TransitionContent<W>,
+}
+
+/// Applies the effect of a running transition to the content. While a transition is running the
+/// widget takes the place of the content in the container, so the effect is applied to each
+/// child. Otherwise, it is a proxy for the content.
+///
+/// The progress of the transition is advanced when the widget is updated, from the frame time of
+/// the [AnimationManager]. Content that is itself a proxy, like a nested
+/// [ForEach](crate::widget::ForEach), is shown and removed without the effect, because its
+/// children are owned by the proxy.
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Layout, Render, MouseEvent, KeyboardEvent, Update)]
+struct TransitionContent<W> where W: Widget {
+    #[id] id: WidgetId,
+    position: Position,
+    dimension: Dimension,
+    child: W,
+    running: Option<RunningTransition>,
+}
+
+#[derive(Debug, Clone)]
+struct RunningTransition {
+    transition: Transition,
+    removal: bool,
+    /// The progress the transition started at. This is non-zero when a running transition is reversed.
+    from: f64,
+    /// The frame time the transition started. This is set when the content is first updated.
+    start: Option<Instant>,
+    progress: f64,
+}
+
+impl<W: Widget> Transitioned<W> {
+    pub(crate) fn new(child: W) -> Transitioned<W> {
+        Transitioned {
+            id: WidgetId::new(),
+            content: TransitionContent {
+                id: WidgetId::new(),
+                position: Position::new(0.0, 0.0),
+                dimension: Dimension::new(0.0, 0.0),
+                child,
+                running: None,
+            },
+        }
+    }
+
+    pub(crate) fn inner(&self) -> &W {
+        &self.content.child
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut W {
+        &mut self.content.child
+    }
+
+    /// Start transitioning the content in.
+    pub(crate) fn insert(&mut self, transition: &Transition) {
+        self.begin(transition, false);
+    }
+
+    /// Start transitioning the content out. The container should keep the content until
+    /// [Self::is_removed] returns true.
+    pub(crate) fn remove(&mut self, transition: &Transition) {
+        self.begin(transition, true);
+    }
+
+    fn begin(&mut self, transition: &Transition, removal: bool) {
+        let from = match &self.content.running {
+            // Continue from the current point, if reversing a running transition.
+            Some(running) if running.removal != removal => 1.0 - running.progress,
+            Some(_) => return,
+            None => 0.0,
+        };
+
+        // Content that is a proxy is never updated itself, so it is shown or removed at once.
+        let progress = if self.content.child.is_proxy() || self.content.child.is_ignore() {
+            1.0
+        } else {
+            from
+        };
+
+        self.content.running = Some(RunningTransition {
+            transition: transition.clone(),
+            removal,
+            from,
+            start: None,
+            progress,
+        });
+
+        if progress >= 1.0 && !removal {
+            self.content.running = None;
+        }
+    }
+
+    /// Advance the running transition. This is used by containers that update the content
+    /// without updating this widget.
+    pub(crate) fn advance(&mut self, env: &mut Environment) {
+        self.content.advance(env);
+    }
+
+    /// Returns true while the content is transitioning out.
+    pub(crate) fn is_removing(&self) -> bool {
+        matches!(&self.content.running, Some(running) if running.removal && running.progress < 1.0)
+    }
+
+    /// Returns true when the content has finished transitioning out.
+    pub(crate) fn is_removed(&self) -> bool {
+        matches!(&self.content.running, Some(running) if running.removal && running.progress >= 1.0)
+    }
+}
+
+impl<W: Widget> Layout for Transitioned<W> {
+    fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        self.content.process_calculate_size(requested_size, ctx)
+    }
+
+    fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
+        self.content.position_children(bounding_box, ctx)
+    }
+}
+
+impl<W: Widget> CommonWidget for Transitioned<W> {
+    CommonWidgetImpl!(self, child: self.content, flag: WidgetFlag::PROXY);
+
+    fn position(&self) -> Position {
+        self.content.position
+    }
+
+    fn set_position(&mut self, position: Position) {
+        self.content.position = position;
+    }
+
+    fn dimension(&self) -> Dimension {
+        self.content.dimension
+    }
+
+    fn set_dimension(&mut self, dimension: Dimension) {
+        self.content.dimension = dimension;
+    }
+}
+
+impl<W: Widget> TransitionContent<W> {
+    /// Returns true while the content is transitioning out, after which it should no longer be interactive.
+    fn is_removal(&self) -> bool {
+        self.running.as_ref().is_some_and(|running| running.removal)
+    }
+
+    /// Set the progress of the running transition from the frame time of the current frame.
+    fn advance(&mut self, env: &mut Environment) {
+        let Some(running) = &mut self.running else {
+            return;
+        };
+
+        let now = env.get::<AnimationManager>()
+            .map(|manager| manager.frame_time())
+            .unwrap_or_else(Instant::now);
+
+        let start = *running.start.get_or_insert(now);
+        let duration = running.transition.get_duration().as_secs_f64();
+
+        running.progress = if duration > 0.0 {
+            (running.from + (now - start).as_secs_f64() / duration).min(1.0)
+        } else {
+            1.0
+        };
+
+        if running.progress >= 1.0 && !running.removal {
+            self.running = None;
+        } else {
+            // We request a frame even when the removal is finished, such that the container can remove the content.
+            AnimationManager::get(env, |manager| manager.request_animation_frame());
+        }
+    }
+}
+
+impl<W: Widget> Update for TransitionContent<W> {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        self.advance(ctx.env);
+    }
+}
+
+impl<W: Widget> Layout for TransitionContent<W> {
+    fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        let mut max_width: f64 = 0.0;
+        let mut max_height: f64 = 0.0;
+
+        self.child.foreach(&mut |child: &mut dyn AnyWidget| {
+            let chosen_size = child.process_calculate_size(requested_size, ctx);
+            max_width = max_width.max(chosen_size.width);
+            max_height = max_height.max(chosen_size.height);
+        });
+
+        self.dimension = Dimension::new(max_width, max_height);
+        self.dimension
+    }
+
+    fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
+        let position = self.position;
+        let dimension = self.dimension;
+
+        self.child.foreach(&mut |child: &mut dyn AnyWidget| {
+            child.set_position(Alignment::Center.position(position, dimension, child.dimension()));
+            child.position_children(bounding_box, ctx);
+        });
+    }
+}
+
+impl<W: Widget> Render for TransitionContent<W> {
+    fn render(&mut self, ctx: &mut RenderContext) {
+        let Some(running) = &self.running else {
+            self.child.foreach(&mut |child: &mut dyn AnyWidget| child.render(ctx));
+            return;
+        };
+
+        let bounding_box = Rect::new(self.position, self.dimension);
+        let child = &mut self.child;
+
+        running.transition.render(running.progress, running.removal, bounding_box, ctx, &mut |ctx| {
+            child.foreach(&mut |child: &mut dyn AnyWidget| child.render(ctx));
+        });
+    }
+}
+
+impl<W: Widget> MouseEventHandler for TransitionContent<W> {
+    fn process_mouse_event(&mut self, event: &MouseEvent, ctx: &mut MouseEventContext) {
+        // Content that is being removed should no longer be interactive.
+        if self.is_removal() {
+            return;
+        }
+
+        self.child.foreach(&mut |child: &mut dyn AnyWidget| {
+            child.process_mouse_event(event, ctx);
+        });
+    }
+}
+
+impl<W: Widget> KeyboardEventHandler for TransitionContent<W> {
+    fn process_keyboard_event(&mut self, event: &KeyboardEvent, ctx: &mut KeyboardEventContext) {
+        // Content that is being removed should no longer be interactive.
+        if self.is_removal() {
+            return;
+        }
+
+        self.child.foreach(&mut |child: &mut dyn AnyWidget| {
+            child.process_keyboard_event(event, ctx);
+        });
+    }
+}
+
+impl<W: Widget> CommonWidget for TransitionContent<W> {
+    CommonWidgetImpl!(self, child: self.child, position: self.position, dimension: self.dimension);
+
+    fn flag(&self) -> WidgetFlag {
+        if self.running.is_none() || self.child.is_proxy() || self.child.is_ignore() {
+            WidgetFlag::PROXY
+        } else if self.child.is_spacer() {
+            WidgetFlag::SPACER
+        } else {
+            WidgetFlag::EMPTY
+        }
+    }
+
+    fn alignment(&self) -> Alignment {
+        self.child.alignment()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::animation::Transition;
+    use crate::draw::Dimension;
+    use crate::identifiable::Identifiable;
+    use crate::state::{LocalState, State};
+    use crate::testing::TestHarness;
+    use crate::time::Duration;
+    use crate::widget::{IfElse, Rectangle, WidgetExt};
+
+    #[test]
+    fn transitions_advance_without_rendering() {
+        let mut shown = LocalState::new(true);
+
+        let details = Rectangle::new().frame(40.0, 20.0);
+        let id = details.id();
+
+        let mut harness = TestHarness::new(
+            IfElse::new(shown.clone())
+                .when_true(details)
+                .transition(Transition::opacity().duration(Duration::ZERO)),
+            Dimension::new(200.0, 200.0),
+        );
+
+        harness.update();
+        assert!(harness.bounding_box(id).is_some());
+
+        shown.set_value(false);
+        harness.update();
+        harness.update();
+
+        assert!(harness.bounding_box(id).is_none());
+    }
+}
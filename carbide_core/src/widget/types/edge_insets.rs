@@ -2,7 +2,12 @@ use crate::state::AnyReadState;
 
 use crate::draw::Scalar;
 use crate::state::{ConvertIntoRead, Map1, RMap1};
+use crate::widget::LayoutDirection;
 
+/// Insets from each edge of a rectangle. The `left` and `right` insets are given for left to
+/// right layouts, and are mirrored in right to left layouts, such that `left` is the inset from
+/// the leading edge. Use [left_in](Self::left_in) and [right_in](Self::right_in) to get the
+/// inset from an edge in a given layout direction.
 #[derive(Debug, Copy, Clone)]
 pub struct EdgeInsets {
     pub top: Scalar,
@@ -38,6 +43,22 @@ impl EdgeInsets {
             right: amount,
         }
     }
+
+    /// Returns the inset from the left edge when laid out in the given direction.
+    pub fn left_in(&self, direction: LayoutDirection) -> Scalar {
+        match direction {
+            LayoutDirection::LeftToRight => self.left,
+            LayoutDirection::RightToLeft => self.right,
+        }
+    }
+
+    /// Returns the inset from the right edge when laid out in the given direction.
+    pub fn right_in(&self, direction: LayoutDirection) -> Scalar {
+        match direction {
+            LayoutDirection::LeftToRight => self.right,
+            LayoutDirection::RightToLeft => self.left,
+        }
+    }
 }
 
 impl Into<EdgeInsets> for f64 {
@@ -1,6 +1,7 @@
 use std::fmt::{Display, Formatter};
 use std::ops::Div;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::OnceLock;
 
 use crate::common::utils::gaussian;
 
@@ -110,6 +111,23 @@ impl ImageFilter {
         }
     }
 
+    /// Applying this filter will multiply the image, including its alpha, by the given opacity.
+    /// The opacity is rounded to one of 256 steps, and the filters for the same step share a
+    /// [FilterId], such that animating the opacity does not create a new filter every frame.
+    pub fn opacity(opacity: f32) -> ImageFilter {
+        static OPACITY_FILTER_IDS: OnceLock<Vec<FilterId>> = OnceLock::new();
+
+        let step = (opacity.clamp(0.0, 1.0) * 255.0).round() as usize;
+        let ids = OPACITY_FILTER_IDS.get_or_init(|| (0..256).map(|_| FilterId::new()).collect());
+
+        ImageFilter {
+            id: ids[step],
+            filter: vec![
+                ImageFilterValue::new(0, 0, step as f32 / 255.0),
+            ],
+        }
+    }
+
     pub fn gaussian_blur_1d(sigma: f32) -> ImageFilter {
         assert!(sigma > 0.0);
         let mut entries = vec![];
@@ -0,0 +1,38 @@
+/// The size of a column or row in a [Grid](crate::widget::Grid).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridTrack {
+    /// A track of a fixed size.
+    Fixed(f64),
+    /// A track sharing the space left by the other tracks. The space is divided between the
+    /// flexible tracks in proportion to their fractions.
+    Flexible(f64),
+    /// A track sized to the largest of the children within it.
+    Auto,
+}
+
+/// The number of columns and rows a child of a [Grid](crate::widget::Grid) spans.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct GridSpan {
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl GridSpan {
+    pub fn new(columns: usize, rows: usize) -> GridSpan {
+        GridSpan {
+            columns: columns.max(1),
+            rows: rows.max(1),
+        }
+    }
+}
+
+/// How the cells of a row in a [Grid](crate::widget::Grid) are aligned vertically.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GridRowAlignment {
+    Top,
+    Center,
+    Bottom,
+    /// Align the cells such that the baselines of their first lines of text line up. Cells
+    /// without text are aligned to the top.
+    FirstBaseline,
+}
@@ -0,0 +1,40 @@
+use carbide_derive::StateValue;
+
+use crate::environment::{Environment, EnvironmentKey};
+
+/// The direction in which content is laid out horizontally. In right to left layouts, used for
+/// languages like Arabic and Hebrew, leading is the right side and trailing the left side.
+/// Horizontal stacks place their first child on the right, and paddings, splits and scroll
+/// bars are mirrored.
+///
+/// The direction is read from the environment, and can be set using
+/// [layout_direction](crate::widget::WidgetExt::layout_direction).
+#[derive(Copy, Clone, Debug, PartialEq, StateValue, Eq, Hash)]
+pub enum LayoutDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+impl Default for LayoutDirection {
+    fn default() -> Self {
+        LayoutDirection::LeftToRight
+    }
+}
+
+impl LayoutDirection {
+    /// Returns the direction from the environment, or left to right if none is specified.
+    pub fn get(env: &Environment) -> LayoutDirection {
+        env.get::<LayoutDirectionKey>().copied().unwrap_or_default()
+    }
+
+    pub fn is_right_to_left(&self) -> bool {
+        *self == LayoutDirection::RightToLeft
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct LayoutDirectionKey;
+
+impl EnvironmentKey for LayoutDirectionKey {
+    type Value = LayoutDirection;
+}
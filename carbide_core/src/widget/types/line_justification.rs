@@ -0,0 +1,11 @@
+/// How the children of each line in a wrapping layout, like
+/// [HWrap](crate::widget::HWrap), are placed along the main axis.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum LineJustification {
+    /// Place the children at the start of the line.
+    Start,
+    /// Place the children in the center of the line.
+    Center,
+    /// Distribute the remaining space of the line between the children.
+    SpaceBetween,
+}
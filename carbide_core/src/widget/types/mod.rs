@@ -2,20 +2,26 @@ pub use blur_type::*;
 pub use corner_radii::*;
 pub use cross_axis_alignment::*;
 pub use edge_insets::*;
+pub use layout_direction::*;
+pub use line_justification::*;
 pub use filter::*;
 pub use scale_mode::*;
 pub use scroll_direction::*;
 pub use shape_style::*;
 pub use split_type::*;
 pub use grid_item::*;
+pub use grid_track::*;
 
 mod blur_type;
 mod corner_radii;
 mod cross_axis_alignment;
 mod edge_insets;
+mod layout_direction;
+mod line_justification;
 mod filter;
 mod scale_mode;
 mod scroll_direction;
 mod shape_style;
 mod split_type;
 mod grid_item;
+mod grid_track;
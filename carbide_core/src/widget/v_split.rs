@@ -175,19 +175,19 @@ impl<S: State<T=f64>, L: Widget, T: Widget> Layout for VSplit<S, L, T> {
         };
 
         let top_size = Dimension::new(requested_size.width, requested_top_height);
-        let mut top = self.leading.calculate_size(top_size, ctx);
+        let mut top = self.leading.process_calculate_size(top_size, ctx);
 
         let bottom_size = Dimension::new(requested_size.width, requested_bottom_height);
-        let mut bottom = self.trailing.calculate_size(bottom_size, ctx);
+        let mut bottom = self.trailing.process_calculate_size(bottom_size, ctx);
 
         if top.height > requested_top_height {
             let bottom_size =
                 Dimension::new(requested_size.width, requested_size.height - top.height);
-            bottom = self.trailing.calculate_size(bottom_size, ctx);
+            bottom = self.trailing.process_calculate_size(bottom_size, ctx);
         } else if bottom.height > requested_bottom_height {
             let top_size =
                 Dimension::new(requested_size.width, requested_size.height - bottom.height);
-            top = self.leading.calculate_size(top_size, ctx);
+            top = self.leading.process_calculate_size(top_size, ctx);
         }
 
         self.set_dimension(Dimension::new(
@@ -64,7 +64,7 @@ impl<W: Sequence> Layout for LazyVStack<W> {
         if self.child_height_estimate.is_none() {
             // TODO: Calculate height estimate based on the a random selection of N children
             let child = self.children.index(0);
-            let chosen_size = child.calculate_size(requested_size, ctx);
+            let chosen_size = child.process_calculate_size(requested_size, ctx);
 
             self.child_heights.insert(child.id(), chosen_size.height);
 
@@ -113,7 +113,7 @@ impl<W: Sequence> Layout for LazyVStack<W> {
 
             let child = self.children.index(index);
 
-            let chosen_size = child.calculate_size(Dimension::new(
+            let chosen_size = child.process_calculate_size(Dimension::new(
                 width,
                 self.requested_height
             ), ctx);
@@ -0,0 +1,120 @@
+use carbide::draw::Rect;
+
+use crate::CommonWidgetImpl;
+use crate::draw::{Dimension, Position, Scalar};
+use crate::layout::{calculate_size_vwrap, Layout, LayoutContext, position_children_vwrap};
+use crate::widget::{CommonWidget, CrossAxisAlignment, LineJustification, Sequence, Widget, WidgetId};
+
+/// # VWrap
+/// A vertical flow layout. The children are placed top to bottom like in a [VStack](crate::widget::VStack),
+/// but when the next child would exceed the proposed height, it is placed in a new column to the
+/// right.
+///
+/// The children are measured with the size proposed to the wrap. `spacing` is the space between
+/// children in a column, and `line_spacing` the space between columns. Each column is as wide as
+/// its widest child, and the children are aligned within the column using the cross axis alignment.
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Layout)]
+pub struct VWrap<W> where W: Sequence
+{
+    #[id] id: WidgetId,
+    children: W,
+    position: Position,
+    dimension: Dimension,
+    spacing: Scalar,
+    line_spacing: Scalar,
+    cross_axis_alignment: CrossAxisAlignment,
+    justification: LineJustification,
+}
+
+impl<W: Sequence> VWrap<W> {
+    pub fn new(children: W) -> Self {
+        VWrap {
+            id: WidgetId::new(),
+            children,
+            position: Position::new(0.0, 0.0),
+            dimension: Dimension::new(100.0, 100.0),
+            spacing: 10.0,
+            line_spacing: 10.0,
+            cross_axis_alignment: CrossAxisAlignment::Center,
+            justification: LineJustification::Start,
+        }
+    }
+
+    pub fn cross_axis_alignment(mut self, alignment: CrossAxisAlignment) -> Self {
+        self.cross_axis_alignment = alignment;
+        self
+    }
+
+    /// Set how the children of each column are placed vertically. When the columns are justified
+    /// the wrap takes up the full proposed height.
+    pub fn justification(mut self, justification: LineJustification) -> Self {
+        self.justification = justification;
+        self
+    }
+
+    pub fn spacing(mut self, spacing: f64) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn line_spacing(mut self, line_spacing: f64) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+}
+
+impl<W: Sequence> Layout for VWrap<W> {
+    fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
+        let spacing = self.spacing;
+        let line_spacing = self.line_spacing;
+        let justification = self.justification;
+        calculate_size_vwrap(self, spacing, line_spacing, justification, requested_size, ctx);
+        self.dimension
+    }
+
+    fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
+        let spacing = self.spacing;
+        let line_spacing = self.line_spacing;
+        let cross_axis_alignment = self.cross_axis_alignment;
+        let justification = self.justification;
+        position_children_vwrap(self, spacing, line_spacing, cross_axis_alignment, justification, bounding_box, ctx)
+    }
+}
+
+impl<W: Sequence> CommonWidget for VWrap<W> {
+    CommonWidgetImpl!(self, child: self.children, position: self.position, dimension: self.dimension, flexibility: 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::draw::{Dimension, Position};
+    use crate::identifiable::Identifiable;
+    use crate::testing::TestHarness;
+    use crate::widget::{CommonWidget, VWrap, Rectangle, WidgetExt};
+
+    #[test]
+    fn children_wrap_onto_new_columns() {
+        let children = vec![
+            Rectangle::new().frame(20.0, 40.0),
+            Rectangle::new().frame(20.0, 40.0),
+            Rectangle::new().frame(20.0, 40.0),
+        ];
+
+        let ids = children.iter().map(|child| child.id()).collect::<Vec<_>>();
+
+        let mut harness = TestHarness::new(
+            VWrap::new(children).spacing(10.0).line_spacing(5.0).frame(100.0, 100.0),
+            Dimension::new(200.0, 200.0),
+        );
+
+        harness.update();
+
+        let first = harness.bounding_box(ids[0]).unwrap();
+        let second = harness.bounding_box(ids[1]).unwrap();
+        let third = harness.bounding_box(ids[2]).unwrap();
+
+        assert_eq!(second.position - first.position, Position::new(0.0, 50.0));
+        assert_eq!(third.position - first.position, Position::new(25.0, 0.0));
+    }
+}
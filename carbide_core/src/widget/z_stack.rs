@@ -9,7 +9,7 @@ use crate::CommonWidgetImpl;
 use crate::draw::{Dimension, Position, Alignment};
 use crate::layout::{Layout, LayoutContext};
 use crate::render::Render;
-use crate::widget::{AnyWidget, CommonWidget, Widget, WidgetId, Sequence};
+use crate::widget::{AnyWidget, CommonWidget, LayoutDirection, Widget, WidgetId, Sequence};
 
 /// A basic, non-interactive rectangle shape widget.
 #[derive(Debug, Clone, Widget)]
@@ -64,7 +64,7 @@ impl<W: Sequence> Layout for ZStack<W> {
                 requested_size.width.max(max_width),
                 requested_size.height.max(max_height),
             );
-            let chosen_size = child.calculate_size(new_requested_size, ctx);
+            let chosen_size = child.process_calculate_size(new_requested_size, ctx);
 
             if chosen_size.width > max_width {
                 max_width = chosen_size.width;
@@ -80,7 +80,7 @@ impl<W: Sequence> Layout for ZStack<W> {
     }
 
     fn position_children(&mut self, bounding_box: Rect, ctx: &mut LayoutContext) {
-        let alignment = self.alignment();
+        let alignment = self.alignment().resolve(LayoutDirection::get(ctx.env));
         let position = self.position;
         let dimension = self.dimension;
 
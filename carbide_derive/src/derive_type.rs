@@ -90,7 +90,7 @@ impl DeriveType {
             DeriveType::ApplicationEvent => application_event_token_stream(ident, generics, wheres),
             DeriveType::OtherEvent => other_event_token_stream(ident, generics, wheres),
             DeriveType::AccessibilityEvent => accessibility_event_token_stream(ident, generics, wheres),
-            DeriveType::WidgetSync => widget_sync_token_stream(ident, generics, wheres, state_idents),
+            DeriveType::WidgetSync => widget_sync_token_stream(ident, generics, wheres, state_idents, id_idents),
             DeriveType::Id => id_token_stream(ident, generics, wheres, id_idents),
             DeriveType::Render => render_token_stream(ident, generics, wheres),
             DeriveType::Focusable => focusable_token_stream(ident, generics, wheres),
@@ -187,13 +187,47 @@ fn widget_sync_token_stream(
     generics: &Generics,
     wheres: &Option<WhereClause>,
     state_idents: &Vec<Ident>,
+    id_idents: &Vec<Ident>,
 ) -> TokenStream {
+    // Widgets with an id sync their states through the state inspector, which records the widget
+    // as reading the states when it is recording. Changed states are reported to the layout
+    // cache, such that cached sizes of the subtrees containing the widget are recalculated.
+    if let (Some(id), false) = (id_idents.first(), state_idents.is_empty()) {
+        return quote! {
+            #[automatically_derived]
+            impl #generics carbide::widget::WidgetSync for #ident #generics #wheres {
+                fn sync(&mut self, env: &mut carbide::environment::Environment) {
+                    use carbide::state::StateSync;
+                    let id = self.#id;
+                    let changed = carbide::state::StateInspector::with_widget(id, std::any::type_name::<Self>(), || {
+                        false #(| self.#state_idents.sync(env))*
+                    });
+
+                    if changed {
+                        carbide::layout::LayoutCache::state_changed();
+                    }
+                }
+            }
+        };
+    }
+
+    if state_idents.is_empty() {
+        return quote! {
+            #[automatically_derived]
+            impl #generics carbide::widget::WidgetSync for #ident #generics #wheres {}
+        };
+    }
+
     quote! {
         #[automatically_derived]
         impl #generics carbide::widget::WidgetSync for #ident #generics #wheres {
             fn sync(&mut self, env: &mut carbide::environment::Environment) {
                 use carbide::state::StateSync;
-                #(self.#state_idents.sync(env);)*
+                let changed = false #(| self.#state_idents.sync(env))*;
+
+                if changed {
+                    carbide::layout::LayoutCache::state_changed();
+                }
             }
         }
     }
@@ -14,11 +14,7 @@ pub fn impl_state_value(ast: &syn::DeriveInput) -> proc_macro2::TokenStream {
     // Additional where: T: StateContract + Add<U>
 
     quote! {
-        impl #generics #crate_name::state::StateSync for #struct_ident #generics #wheres {
-            fn sync(&mut self, _env: &mut #crate_name::environment::Environment) -> bool {
-                true
-            }
-        }
+        impl #generics #crate_name::state::StateSync for #struct_ident #generics #wheres {}
         impl #generics #crate_name::state::AnyReadState for #struct_ident #generics #wheres {
             type T = #struct_ident #generics;
             fn value_dyn(&self) -> #crate_name::state::ValueRef<#struct_ident #generics> {
@@ -37,8 +37,11 @@ impl ColorDialogStyle for NativeStyle {
 
 #[cfg(not(target_os = "macos"))]
 impl ColorDialogStyle for NativeStyle {
+    #[allow(unused_variables)]
     fn open(&self, color: Box<dyn AnyState<T=Color>>, show_alpha: Box<dyn AnyReadState<T=bool>>, env: &mut Environment) {
-        todo!()
+        // There is no native dialog on this platform, so nothing is shown. Dialogs drawn by
+        // carbide are provided by the PlainStyle in carbide_controls.
+        eprintln!("No native color dialog on this platform, the dialog is not shown. Wrap the window content in carbide_controls::controls_overlay to use the dialogs drawn by carbide.");
     }
 }
 
@@ -13,6 +13,10 @@ impl FileType {
         }
     }
 
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
     pub fn extensions(&self) -> &[&str] {
         &self.extension
     }
@@ -6,6 +6,8 @@ use crate::save_dialog::style::SaveDialogStyle;
 use crate::NativeStyle;
 use carbide::asynchronous::AsyncContext;
 use carbide::draw::AutomaticStyle;
+#[cfg(not(target_os = "macos"))]
+use carbide::SpawnTask;
 use carbide::environment::Environment;
 use carbide_core::environment::EnvironmentKey;
 use dyn_clone::{clone_trait_object, DynClone};
@@ -42,8 +44,15 @@ impl OpenDialogStyle for NativeStyle {
 
 #[cfg(not(target_os = "macos"))]
 impl OpenDialogStyle for NativeStyle {
+    #[allow(unused_variables)]
     fn open(&self, title: Option<String>, message: Option<String>, prompt: Option<String>, multiple_selection: bool, show_hidden_files: bool, selection_type: OpenPanelSelectionType, path: Option<PathBuf>, file_types: &[FileType], f: Box<dyn Fn(Result<Option<Vec<PathBuf>>, RecvError>, &mut AsyncContext) + 'static>, env: &mut Environment) {
-        todo!()
+        // There is no native dialog on this platform, so the dialog is reported as cancelled.
+        // Dialogs drawn by carbide are provided by the PlainStyle in carbide_controls.
+        eprintln!("No native open dialog on this platform, the dialog is cancelled. Wrap the window content in carbide_controls::controls_overlay to use the dialogs drawn by carbide.");
+        let (sender, receiver) = oneshot::channel();
+        let _ = sender.send(None);
+
+        receiver.spawn(f)
     }
 }
 
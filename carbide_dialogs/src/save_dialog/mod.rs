@@ -1,4 +1,4 @@
 mod save_dialog;
-pub(crate) mod style;
+pub mod style;
 
 pub use save_dialog::*;
@@ -4,6 +4,8 @@ use crate::save_dialog::style::macos::MacOSNativeSaveDialogStyle;
 use crate::{FileType, NativeStyle};
 use carbide::asynchronous::AsyncContext;
 use carbide::draw::AutomaticStyle;
+#[cfg(not(target_os = "macos"))]
+use carbide::SpawnTask;
 use carbide::environment::{Environment, EnvironmentKey};
 use dyn_clone::{clone_trait_object, DynClone};
 use oneshot::RecvError;
@@ -41,8 +43,15 @@ impl SaveDialogStyle for NativeStyle {
 
 #[cfg(not(target_os = "macos"))]
 impl SaveDialogStyle for NativeStyle {
+    #[allow(unused_variables)]
     fn open(&self, title: Option<String>, message: Option<String>, prompt: Option<String>, default_file_name: Option<String>, show_hidden_files: bool, path: Option<PathBuf>, file_types: &[FileType], f: Box<dyn Fn(Result<Option<PathBuf>, RecvError>, &mut AsyncContext) + 'static>, env: &mut Environment) {
-        todo!()
+        // There is no native dialog on this platform, so the dialog is reported as cancelled.
+        // Dialogs drawn by carbide are provided by the PlainStyle in carbide_controls.
+        eprintln!("No native save dialog on this platform, the dialog is cancelled. Wrap the window content in carbide_controls::controls_overlay to use the dialogs drawn by carbide.");
+        let (sender, receiver) = oneshot::channel();
+        let _ = sender.send(None);
+
+        receiver.spawn(f)
     }
 }
 
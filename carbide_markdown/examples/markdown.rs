@@ -0,0 +1,46 @@
+use carbide_core::draw::Dimension;
+use carbide_core::widget::WidgetExt;
+use carbide_markdown::Markdown;
+use carbide_wgpu::{Application, Window};
+
+const DOCUMENT: &str = r##"# Release notes
+
+This release adds a **markdown** widget, which can show *emphasis*, `inline code` and [links](https://github.com/HolgerGottChristensen/carbide).
+
+## Changes
+
+1. Headings use the environment font sizes
+2. Lists can be nested
+    - Like this
+3. Tables are supported
+
+> Block quotes are indented and shown with a secondary color.
+
+```rust
+let markdown = Markdown::new("# Hello world!");
+```
+
+| Widget   | Crate            |
+|----------|:----------------:|
+| Markdown | carbide_markdown |
+| Table    | carbide_table    |
+
+---
+
+~~Hand built stacks of text~~ are no longer needed.
+"##;
+
+fn main() {
+    let mut application = Application::new();
+
+    application.set_scene(
+        Window::new(
+            "Markdown example - Carbide",
+            Dimension::new(600.0, 700.0),
+            Markdown::new(DOCUMENT)
+                .padding(20.0)
+        )
+    );
+
+    application.launch()
+}
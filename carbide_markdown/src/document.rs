@@ -0,0 +1,310 @@
+use std::mem;
+
+use carbide::text::{AttributedText, TextAttributes, TextSpan};
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+
+/// The family used for inline code and code blocks.
+pub(crate) const CODE_FAMILY: &str = "monospace";
+
+/// A block of a markdown document. The inline content of the blocks is converted to attributed
+/// text, such that emphasis, inline code and links are spans of the text.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Block {
+    Heading(HeadingLevel, AttributedText),
+    Paragraph(AttributedText),
+    Code {
+        language: Option<String>,
+        code: String,
+    },
+    Quote(Vec<Block>),
+    List {
+        /// The number of the first item for ordered lists, and None for bullet lists.
+        start: Option<u64>,
+        items: Vec<Vec<Block>>,
+    },
+    Table {
+        alignments: Vec<Alignment>,
+        head: Vec<AttributedText>,
+        rows: Vec<Vec<AttributedText>>,
+    },
+    Image {
+        url: String,
+        description: String,
+    },
+    Rule,
+}
+
+/// Parse the markdown source into a list of blocks.
+pub(crate) fn parse(source: &str) -> Vec<Block> {
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let mut events = Parser::new_ext(source, options);
+
+    blocks(&mut events, None)
+}
+
+/// Collect blocks until the end tag is reached, or there are no more events.
+fn blocks<'a>(events: &mut impl Iterator<Item=Event<'a>>, end: Option<TagEnd>) -> Vec<Block> {
+    let mut blocks = vec![];
+    let mut inline = Inline::new(true);
+
+    while let Some(event) = events.next() {
+        match event {
+            Event::End(tag) if Some(tag) == end => break,
+            Event::Start(Tag::Paragraph) | Event::End(TagEnd::Paragraph) => {
+                inline.finish(&mut blocks);
+            }
+            Event::Start(Tag::Heading { level, .. }) => {
+                inline.finish(&mut blocks);
+                blocks.push(Block::Heading(level, text(events, TagEnd::Heading(level))));
+            }
+            Event::Start(Tag::BlockQuote(kind)) => {
+                inline.finish(&mut blocks);
+                blocks.push(Block::Quote(self::blocks(events, Some(TagEnd::BlockQuote(kind)))));
+            }
+            Event::Start(Tag::CodeBlock(kind)) => {
+                inline.finish(&mut blocks);
+                blocks.push(code(events, kind));
+            }
+            Event::Start(Tag::List(start)) => {
+                inline.finish(&mut blocks);
+                blocks.push(list(events, start));
+            }
+            Event::Start(Tag::Table(alignments)) => {
+                inline.finish(&mut blocks);
+                blocks.push(table(events, alignments));
+            }
+            Event::Rule => {
+                inline.finish(&mut blocks);
+                blocks.push(Block::Rule);
+            }
+            // Items in tight lists contain their text without being wrapped in a paragraph.
+            event => inline.event(event, &mut blocks),
+        }
+    }
+
+    inline.finish(&mut blocks);
+
+    blocks
+}
+
+/// Collect the inline content until the end tag as a single text. Images are replaced by
+/// their description.
+fn text<'a>(events: &mut impl Iterator<Item=Event<'a>>, end: TagEnd) -> AttributedText {
+    let mut inline = Inline::new(false);
+    let mut blocks = vec![];
+
+    for event in events.by_ref() {
+        if event == Event::End(end) {
+            break;
+        }
+
+        inline.event(event, &mut blocks);
+    }
+
+    inline.text
+}
+
+fn code<'a>(events: &mut impl Iterator<Item=Event<'a>>, kind: CodeBlockKind) -> Block {
+    let language = match kind {
+        CodeBlockKind::Fenced(info) => info.split_whitespace().next().map(|language| language.to_string()),
+        CodeBlockKind::Indented => None,
+    };
+
+    let mut code = String::new();
+
+    for event in events.by_ref() {
+        match event {
+            Event::Text(text) => code.push_str(&text),
+            Event::End(TagEnd::CodeBlock) => break,
+            _ => {}
+        }
+    }
+
+    Block::Code {
+        language,
+        code: code.trim_end_matches('\n').to_string(),
+    }
+}
+
+fn list<'a>(events: &mut impl Iterator<Item=Event<'a>>, start: Option<u64>) -> Block {
+    let mut items = vec![];
+
+    while let Some(event) = events.next() {
+        match event {
+            Event::Start(Tag::Item) => items.push(blocks(events, Some(TagEnd::Item))),
+            Event::End(TagEnd::List(_)) => break,
+            _ => {}
+        }
+    }
+
+    Block::List { start, items }
+}
+
+fn table<'a>(events: &mut impl Iterator<Item=Event<'a>>, alignments: Vec<Alignment>) -> Block {
+    let mut head = vec![];
+    let mut rows: Vec<Vec<AttributedText>> = vec![];
+
+    while let Some(event) = events.next() {
+        match event {
+            // The cells of the head are not contained in a row, and the body starts after the head.
+            Event::Start(Tag::TableRow) => rows.push(vec![]),
+            Event::Start(Tag::TableCell) => {
+                let cell = text(events, TagEnd::TableCell);
+
+                match rows.last_mut() {
+                    Some(row) => row.push(cell),
+                    None => head.push(cell),
+                }
+            }
+            Event::End(TagEnd::Table) => break,
+            _ => {}
+        }
+    }
+
+    Block::Table { alignments, head, rows }
+}
+
+/// Builds attributed text from inline events. The attributes of nested inline tags, like a link
+/// within emphasis, are combined.
+struct Inline {
+    text: AttributedText,
+    attributes: Vec<TextAttributes>,
+    /// If true, images become image blocks. Otherwise their description is part of the text.
+    images: bool,
+    /// The url and description of the image currently being parsed.
+    image: Option<(String, String)>,
+}
+
+impl Inline {
+    fn new(images: bool) -> Inline {
+        Inline {
+            text: AttributedText::new(),
+            attributes: vec![],
+            images,
+            image: None,
+        }
+    }
+
+    fn event(&mut self, event: Event, blocks: &mut Vec<Block>) {
+        match event {
+            Event::Text(text) => {
+                match &mut self.image {
+                    Some((_, description)) => description.push_str(&text),
+                    None => self.push(&text, self.attributes.last().cloned()),
+                }
+            }
+            Event::Code(code) => {
+                let attributes = self.attributes.last().cloned().unwrap_or_default().family(CODE_FAMILY);
+                self.push(&code, Some(attributes));
+            }
+            Event::SoftBreak => self.push(" ", self.attributes.last().cloned()),
+            Event::HardBreak => self.push("\n", self.attributes.last().cloned()),
+            Event::TaskListMarker(checked) => self.push(if checked { "☑ " } else { "☐ " }, None),
+            Event::Start(Tag::Emphasis) => self.start(|attributes| attributes.italic()),
+            Event::Start(Tag::Strong) => self.start(|attributes| attributes.bold()),
+            Event::Start(Tag::Strikethrough) => self.start(|attributes| attributes.strike_through()),
+            Event::Start(Tag::Link { dest_url, .. }) => self.start(|attributes| attributes.link(dest_url.to_string()).underline()),
+            Event::End(TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough | TagEnd::Link) => {
+                self.attributes.pop();
+            }
+            Event::Start(Tag::Image { dest_url, .. }) if self.images => {
+                self.finish(blocks);
+                self.image = Some((dest_url.to_string(), String::new()));
+            }
+            Event::End(TagEnd::Image) if self.images => {
+                if let Some((url, description)) = self.image.take() {
+                    blocks.push(Block::Image { url, description });
+                }
+            }
+            // Html is not supported, and is left out of the document.
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, f: impl FnOnce(TextAttributes) -> TextAttributes) {
+        let attributes = self.attributes.last().cloned().unwrap_or_default();
+        self.attributes.push(f(attributes));
+    }
+
+    fn push(&mut self, text: &str, attributes: Option<TextAttributes>) {
+        let start = self.text.text.len();
+        self.text.text.push_str(text);
+
+        if let Some(attributes) = attributes {
+            self.text.spans.push(TextSpan {
+                range: start..self.text.text.len(),
+                attributes,
+            });
+        }
+    }
+
+    /// Add the text collected so far as a paragraph, if it is not empty.
+    fn finish(&mut self, blocks: &mut Vec<Block>) {
+        let text = mem::take(&mut self.text);
+
+        if !text.text.trim().is_empty() {
+            blocks.push(Block::Paragraph(text));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use carbide::text::{FontWeight, TextAttributes};
+    use pulldown_cmark::HeadingLevel;
+
+    use crate::document::{parse, Block, CODE_FAMILY};
+
+    #[test]
+    fn parse_blocks() {
+        let blocks = parse("# Release notes\n\nSome **bold** and `code` with a [link](https://example.com).\n\n- One\n- Two\n\n```rust\nfn main() {}\n```\n\n| A | B |\n|---|---|\n| 1 | 2 |\n");
+
+        assert_eq!(blocks.len(), 5);
+
+        match &blocks[0] {
+            Block::Heading(level, text) => {
+                assert_eq!(*level, HeadingLevel::H1);
+                assert_eq!(text.text, "Release notes");
+            }
+            block => panic!("Expected a heading, got: {:?}", block),
+        }
+
+        match &blocks[1] {
+            Block::Paragraph(text) => {
+                assert_eq!(text.text, "Some bold and code with a link.");
+                assert_eq!(text.spans.len(), 3);
+                assert_eq!(text.spans[0].attributes.font_weight, Some(FontWeight::Bold));
+                assert_eq!(text.spans[1].attributes, TextAttributes::new().family(CODE_FAMILY));
+                assert_eq!(text.link_at(27), Some("https://example.com"));
+            }
+            block => panic!("Expected a paragraph, got: {:?}", block),
+        }
+
+        match &blocks[2] {
+            Block::List { start, items } => {
+                assert_eq!(*start, None);
+                assert_eq!(items.len(), 2);
+            }
+            block => panic!("Expected a list, got: {:?}", block),
+        }
+
+        assert_eq!(blocks[3], Block::Code { language: Some("rust".to_string()), code: "fn main() {}".to_string() });
+
+        match &blocks[4] {
+            Block::Table { head, rows, .. } => {
+                assert_eq!(head.len(), 2);
+                assert_eq!(rows.len(), 1);
+                assert_eq!(rows[0][1].text, "2");
+            }
+            block => panic!("Expected a table, got: {:?}", block),
+        }
+    }
+
+    #[test]
+    fn images_split_paragraphs() {
+        let blocks = parse("Before ![A cat](cat.png) after");
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[1], Block::Image { url: "cat.png".to_string(), description: "A cat".to_string() });
+    }
+}
@@ -0,0 +1,9 @@
+//! A widget for displaying CommonMark documents, such as release notes and help content.
+//! Tables, strikethrough and task lists from GitHub flavored markdown are supported as well.
+
+mod document;
+mod markdown;
+
+pub use markdown::Markdown;
+
+extern crate carbide_core as carbide;
@@ -0,0 +1,192 @@
+use carbide::CommonWidgetImpl;
+use carbide::draw::{Dimension, ImageId, Position, Scalar};
+use carbide::environment::{EnvironmentColor, EnvironmentFontSize};
+use carbide::lifecycle::{Update, UpdateContext};
+use carbide::state::{IntoReadState, ReadState};
+use carbide::text::AttributedText;
+use carbide::text::highlight::Language;
+use carbide::text::text_wrap::Wrap;
+use carbide::widget::{AnyWidget, CommonWidget, CrossAxisAlignment, EdgeInsets, Empty, HStack, Image, Rectangle, RoundedRectangle, Spacer, Text, VStack, WidgetExt, WidgetId};
+use carbide_derive::Widget;
+use pulldown_cmark::{Alignment, HeadingLevel};
+
+use crate::document::{parse, Block, CODE_FAMILY};
+
+const BLOCK_SPACING: Scalar = 10.0;
+const ITEM_SPACING: Scalar = 4.0;
+const COLUMN_SPACING: Scalar = 16.0;
+const QUOTE_INDENT: Scalar = 12.0;
+const CODE_PADDING: Scalar = 8.0;
+
+/// Displays a markdown document. Headings use the environment font sizes, and the inline
+/// content of paragraphs is shown as attributed text, such that links can be clicked.
+///
+/// The widgets of the document are rebuilt when the source changes.
+///
+/// ```ignore
+/// Markdown::new("# Release notes\n\nThis release adds **markdown** support.")
+/// ```
+#[derive(Debug, Clone, Widget)]
+#[carbide_exclude(Update)]
+pub struct Markdown<S> where S: ReadState<T=String> {
+    #[id] id: WidgetId,
+    position: Position,
+    dimension: Dimension,
+    #[state] source: S,
+    /// The source the content was built from.
+    built: Option<String>,
+    content: Box<dyn AnyWidget>,
+}
+
+impl Markdown<String> {
+    pub fn new<S: IntoReadState<String>>(source: S) -> Markdown<S::Output> {
+        Markdown {
+            id: WidgetId::new(),
+            position: Position::origin(),
+            dimension: Dimension::new(0.0, 0.0),
+            source: source.into_read_state(),
+            built: None,
+            content: Empty::new().boxed(),
+        }
+    }
+}
+
+impl<S: ReadState<T=String>> Update for Markdown<S> {
+    fn update(&mut self, _ctx: &mut UpdateContext) {
+        let source = self.source.value();
+
+        if self.built.as_deref() != Some(source.as_str()) {
+            self.content = blocks(&parse(&source));
+            self.built = Some(source.clone());
+        }
+    }
+}
+
+impl<S: ReadState<T=String>> CommonWidget for Markdown<S> {
+    CommonWidgetImpl!(self, child: self.content, position: self.position, dimension: self.dimension);
+}
+
+fn blocks(blocks: &[Block]) -> Box<dyn AnyWidget> {
+    VStack::new(blocks.iter().map(block).collect::<Vec<_>>())
+        .cross_axis_alignment(CrossAxisAlignment::Start)
+        .spacing(BLOCK_SPACING)
+        .boxed()
+}
+
+fn block(block: &Block) -> Box<dyn AnyWidget> {
+    match block {
+        Block::Heading(level, text) => {
+            Text::attributed(text.clone())
+                .font_size(heading_size(*level))
+                .bold()
+                .boxed()
+        }
+        Block::Paragraph(text) => Text::attributed(text.clone()).boxed(),
+        Block::Code { language, code } => {
+            let mut text = Text::new(code.clone())
+                .family(CODE_FAMILY.to_string())
+                .wrap(Wrap::None);
+
+            // Code in a known language is highlighted
+            if let Some(language) = language.as_deref().and_then(Language::from_name) {
+                text = text.highlight(language);
+            }
+
+            HStack::new((
+                text,
+                Spacer::new(),
+            )).padding(CODE_PADDING)
+                .background(RoundedRectangle::new(4.0).fill(EnvironmentColor::SecondarySystemBackground))
+                .boxed()
+        }
+        Block::Quote(children) => {
+            blocks(children)
+                .foreground_color(EnvironmentColor::SecondaryLabel)
+                .padding(EdgeInsets::single(0.0, 0.0, QUOTE_INDENT, 0.0))
+                .background(HStack::new((
+                    Rectangle::new().fill(EnvironmentColor::Separator).frame_fixed_width(3.0),
+                    Spacer::new(),
+                )))
+                .boxed()
+        }
+        Block::List { start, items } => {
+            let items = items.iter().enumerate().map(|(index, item)| {
+                let marker = match start {
+                    Some(start) => format!("{}.", start + index as u64),
+                    None => "•".to_string(),
+                };
+
+                HStack::new((
+                    Text::new(marker),
+                    blocks(item),
+                )).cross_axis_alignment(CrossAxisAlignment::Start)
+                    .spacing(6.0)
+                    .boxed()
+            }).collect::<Vec<_>>();
+
+            VStack::new(items)
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .spacing(ITEM_SPACING)
+                .boxed()
+        }
+        Block::Table { alignments, head, rows } => {
+            let columns = rows.iter()
+                .map(|row| row.len())
+                .fold(head.len(), usize::max);
+
+            // The table is laid out as columns, such that the cells of a column have the same width.
+            // Cells do not wrap, to keep the rows the same height across the columns.
+            let columns = (0..columns).map(|column| {
+                let alignment = match alignments.get(column) {
+                    Some(Alignment::Center) => CrossAxisAlignment::Center,
+                    Some(Alignment::Right) => CrossAxisAlignment::End,
+                    _ => CrossAxisAlignment::Start,
+                };
+
+                let mut cells = vec![
+                    Text::attributed(cell(head.get(column))).bold().wrap(Wrap::None).boxed()
+                ];
+
+                for row in rows {
+                    cells.push(Text::attributed(cell(row.get(column))).wrap(Wrap::None).boxed());
+                }
+
+                VStack::new(cells)
+                    .cross_axis_alignment(alignment)
+                    .spacing(ITEM_SPACING)
+                    .boxed()
+            }).collect::<Vec<_>>();
+
+            HStack::new(columns)
+                .cross_axis_alignment(CrossAxisAlignment::Start)
+                .spacing(COLUMN_SPACING)
+                .boxed()
+        }
+        Block::Image { url, .. } => Image::new(ImageId::new(url.clone())).boxed(),
+        Block::Rule => {
+            Rectangle::new()
+                .fill(EnvironmentColor::Separator)
+                .frame_fixed_height(1.0)
+                .boxed()
+        }
+    }
+}
+
+fn heading_size(level: HeadingLevel) -> EnvironmentFontSize {
+    match level {
+        HeadingLevel::H1 => EnvironmentFontSize::LargeTitle,
+        HeadingLevel::H2 => EnvironmentFontSize::Title,
+        HeadingLevel::H3 => EnvironmentFontSize::Title2,
+        HeadingLevel::H4 => EnvironmentFontSize::Title3,
+        HeadingLevel::H5 => EnvironmentFontSize::Headline,
+        HeadingLevel::H6 => EnvironmentFontSize::Subhead,
+    }
+}
+
+/// Empty cells are replaced by a space, such that they have the height of a line.
+fn cell(text: Option<&AttributedText>) -> AttributedText {
+    match text {
+        Some(text) if !text.is_empty() => text.clone(),
+        _ => AttributedText::from(" "),
+    }
+}
@@ -202,8 +202,8 @@ impl<Id: ReadState<T=VideoId> + Clone> KeyboardEventHandler for VideoPlayer<Id>
 
 impl<Id: ReadState<T=VideoId> + Clone> Layout for VideoPlayer<Id> {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
-        let res = self.video.calculate_size(requested_size, ctx);
-        self.video_overlay.calculate_size(res, ctx);
+        let res = self.video.process_calculate_size(requested_size, ctx);
+        self.video_overlay.process_calculate_size(res, ctx);
 
         self.dimension = res;
         res
@@ -36,9 +36,9 @@ impl Layout for Toolbar {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
         let toolbar_height = 31.0;
 
-        self.bar.calculate_size(Dimension::new(requested_size.width, toolbar_height), ctx);
+        self.bar.process_calculate_size(Dimension::new(requested_size.width, toolbar_height), ctx);
 
-        self.child.calculate_size(Dimension::new(requested_size.width, requested_size.height - toolbar_height), ctx);
+        self.child.process_calculate_size(Dimension::new(requested_size.width, requested_size.height - toolbar_height), ctx);
 
         self.set_dimension(requested_size);
         requested_size
@@ -102,7 +102,7 @@ impl WindowEventHandler for WidgetViewer {
 
 impl Layout for WidgetViewer {
     fn calculate_size(&mut self, requested_size: Dimension, ctx: &mut LayoutContext) -> Dimension {
-        self.child.value_mut().calculate_size(requested_size, ctx);
+        self.child.value_mut().process_calculate_size(requested_size, ctx);
         self.dimension = requested_size;
         requested_size
     }